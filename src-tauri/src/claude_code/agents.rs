@@ -0,0 +1,128 @@
+use serde_json::Value;
+
+use super::{gemini, lockfile};
+
+/// Which coding-agent CLI a tracked session belongs to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentKind {
+    #[default]
+    Claude,
+    Gemini,
+}
+
+impl AgentKind {
+    /// Parse the `agent` query param on `/hooks` (absent means Claude Code).
+    pub fn from_param(raw: &str) -> Option<Self> {
+        match raw {
+            "claude" => Some(Self::Claude),
+            "gemini" => Some(Self::Gemini),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Gemini => "gemini",
+        }
+    }
+}
+
+/// Everything maiTerm needs to integrate with one agent CLI: registering our
+/// MCP server, installing lifecycle hooks, mapping hook payloads onto the
+/// Claude Code event names the rest of the app understands, and resuming.
+pub trait AgentAdapter: Sync {
+    fn kind(&self) -> AgentKind;
+
+    fn display_name(&self) -> &'static str;
+
+    /// Whether the CLI has a config dir on this machine. Uninstalled agents are
+    /// skipped at startup so we don't create config for tools the user lacks.
+    fn is_installed(&self) -> bool;
+
+    fn register_mcp(&self, port: u16, auth: &str) -> Result<(), String>;
+
+    fn unregister_mcp(&self) -> Result<(), String>;
+
+    fn install_hooks(&self, port: u16, auth: &str) -> Result<(), String>;
+
+    fn remove_hooks(&self, port: u16, auth: &str) -> Result<(), String>;
+
+    /// Remove hooks left behind by dead maiTerm instances, as `(port, auth)`.
+    fn remove_stale_hooks(&self, stale: &[(u16, String)]) -> Result<(), String>;
+
+    /// Rewrite a hook payload in place so `hook_event_name` (and any typed
+    /// fields the handler reads) use Claude Code's vocabulary.
+    fn normalize_hook_event(&self, _event: &mut Value) {}
+
+    /// Whether hook requests carry the tab ID, so SessionStart alone captures
+    /// the session (Claude Code instead reports it through `initSession`).
+    fn captures_session_from_hooks(&self) -> bool {
+        false
+    }
+
+    /// Whether the agent reads our response to PreToolUse/PermissionRequest.
+    fn accepts_hook_decisions(&self) -> bool {
+        false
+    }
+
+    /// Shell command that resumes `session_id`.
+    fn resume_command(&self, session_id: &str) -> String;
+}
+
+pub struct ClaudeCode;
+
+impl AgentAdapter for ClaudeCode {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Claude
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    // Always registered, matching the behavior from before other agents existed
+    fn is_installed(&self) -> bool {
+        true
+    }
+
+    fn register_mcp(&self, port: u16, auth: &str) -> Result<(), String> {
+        lockfile::write_mcp_settings(port, auth)
+    }
+
+    fn unregister_mcp(&self) -> Result<(), String> {
+        lockfile::remove_mcp_settings()
+    }
+
+    fn install_hooks(&self, port: u16, auth: &str) -> Result<(), String> {
+        lockfile::write_hook_settings(port, auth)
+    }
+
+    fn remove_hooks(&self, port: u16, auth: &str) -> Result<(), String> {
+        lockfile::remove_hook_settings(port, auth)
+    }
+
+    fn remove_stale_hooks(&self, stale: &[(u16, String)]) -> Result<(), String> {
+        lockfile::cleanup_stale_hooks(stale)
+    }
+
+    fn accepts_hook_decisions(&self) -> bool {
+        true
+    }
+
+    fn resume_command(&self, session_id: &str) -> String {
+        format!("claude --resume {}", session_id)
+    }
+}
+
+/// Every supported agent, Claude Code first.
+pub static ADAPTERS: &[&dyn AgentAdapter] = &[&ClaudeCode, &gemini::GeminiCli];
+
+pub fn adapter(kind: AgentKind) -> &'static dyn AgentAdapter {
+    ADAPTERS
+        .iter()
+        .copied()
+        .find(|a| a.kind() == kind)
+        .unwrap_or(&ClaudeCode)
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::agents::{AgentAdapter, AgentKind};
+use super::lockfile::{
+    collect_live_lockfile_ports, entry_contains_auth, entry_matches_url, extract_hook_port,
+    hook_url_marker, mcp_server_key,
+};
+
+/// Gemini CLI lifecycle events we hook, each mapped onto its Claude Code equivalent.
+const HOOK_EVENTS: &[(&str, &str)] = &[
+    ("SessionStart", "SessionStart"),
+    ("SessionEnd", "SessionEnd"),
+    ("BeforeAgent", "UserPromptSubmit"),
+    ("AfterAgent", "Stop"),
+    ("BeforeTool", "PreToolUse"),
+    ("AfterTool", "PostToolUse"),
+    ("Notification", "Notification"),
+    ("PreCompress", "PreCompact"),
+];
+
+/// Gemini CLI hook timeouts are in milliseconds.
+const HOOK_TIMEOUT_MS: u64 = 5000;
+
+pub struct GeminiCli;
+
+fn gemini_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".gemini"))
+}
+
+/// Path to Gemini CLI user settings: ~/.gemini/settings.json
+fn gemini_settings_path() -> Option<PathBuf> {
+    gemini_dir().map(|d| d.join("settings.json"))
+}
+
+/// Read settings, refusing to continue on a file we can't parse (Gemini
+/// accepts comments we'd otherwise drop by rewriting it from scratch).
+fn read_settings(path: &Path) -> Result<Value, String> {
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let raw = fs::read_to_string(path).map_err(|e| format!("Cannot read settings: {}", e))?;
+    let settings: Value = serde_json::from_str(&raw).map_err(|e| format!("Cannot parse settings: {}", e))?;
+    if !settings.is_object() {
+        return Err("settings.json is not an object".to_string());
+    }
+    Ok(settings)
+}
+
+fn write_settings(path: &Path, settings: &Value) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {:?}: {}", dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.aiterm-tmp");
+    fs::write(&tmp, &json).map_err(|e| format!("Cannot write settings tmp: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Cannot update settings: {}", e)
+    })
+}
+
+/// Command hook that POSTs the event JSON from stdin to our server, tagged with
+/// the tab it runs in. Gemini CLI only has command hooks and parses their
+/// stdout, so the response is discarded. Gated on $AITERM_PORT like the Claude
+/// SessionStart hook to prevent dev/prod cross-talk.
+fn hook_command(port: u16, auth: &str) -> String {
+    format!(
+        "[ -n \"$AITERM_TAB_ID\" ] && \
+         {{ [ \"$AITERM_PORT\" = \"{port}\" ] || [ -z \"$AITERM_PORT\" ]; }} && \
+         curl -s -o /dev/null -m 2 -X POST -H 'Content-Type: application/json' \
+         -H 'x-claude-code-ide-authorization: {auth}' --data-binary @- \
+         \"{url}?agent=gemini&tab_id=$AITERM_TAB_ID\" || true",
+        port = port,
+        auth = auth,
+        url = hook_url_marker(port),
+    )
+}
+
+/// Drop hook entries matching `remove` and any event arrays left empty.
+/// Returns whether anything changed.
+fn strip_hooks(settings: &mut Value, remove: impl Fn(&Value) -> bool) -> bool {
+    let Some(hooks_map) = settings.get_mut("hooks").and_then(|v| v.as_object_mut()) else {
+        return false;
+    };
+    let mut changed = false;
+    for entries in hooks_map.values_mut() {
+        if let Some(arr) = entries.as_array_mut() {
+            let before = arr.len();
+            arr.retain(|entry| !remove(entry));
+            changed |= arr.len() != before;
+        }
+    }
+    hooks_map.retain(|_, v| v.as_array().map(|a| !a.is_empty()).unwrap_or(true));
+    if hooks_map.is_empty() {
+        settings.as_object_mut().unwrap().remove("hooks");
+    }
+    changed
+}
+
+impl AgentAdapter for GeminiCli {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Gemini
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Gemini CLI"
+    }
+
+    fn is_installed(&self) -> bool {
+        gemini_dir().is_some_and(|d| d.is_dir())
+    }
+
+    fn register_mcp(&self, port: u16, auth: &str) -> Result<(), String> {
+        let path = gemini_settings_path().ok_or("Could not determine home directory")?;
+        let mut settings = read_settings(&path)?;
+        let servers = settings
+            .as_object_mut()
+            .unwrap()
+            .entry("mcpServers")
+            .or_insert(serde_json::json!({}));
+        servers[mcp_server_key()] = serde_json::json!({
+            "httpUrl": format!("http://127.0.0.1:{}/mcp", port),
+            "headers": {
+                "x-claude-code-ide-authorization": auth
+            }
+        });
+        write_settings(&path, &settings)?;
+        log::info!("Registered {} MCP server in ~/.gemini/settings.json (port {})", mcp_server_key(), port);
+        Ok(())
+    }
+
+    fn unregister_mcp(&self) -> Result<(), String> {
+        let path = gemini_settings_path().ok_or("Could not determine home directory")?;
+        if !path.exists() {
+            return Ok(());
+        }
+        let mut settings = read_settings(&path)?;
+        let Some(servers) = settings.get_mut("mcpServers").and_then(|v| v.as_object_mut()) else {
+            return Ok(());
+        };
+        if servers.remove(mcp_server_key()).is_none() {
+            return Ok(());
+        }
+        if servers.is_empty() {
+            settings.as_object_mut().unwrap().remove("mcpServers");
+        }
+        write_settings(&path, &settings)?;
+        log::info!("Removed {} MCP server from ~/.gemini/settings.json", mcp_server_key());
+        Ok(())
+    }
+
+    fn install_hooks(&self, port: u16, auth: &str) -> Result<(), String> {
+        let path = gemini_settings_path().ok_or("Could not determine home directory")?;
+        let mut settings = read_settings(&path)?;
+
+        // Replace our previous entries and sweep ones whose instance is gone
+        let hooks_url = hook_url_marker(port);
+        let live_ports = collect_live_lockfile_ports();
+        strip_hooks(&mut settings, |entry| {
+            entry_matches_url(entry, &hooks_url)
+                || extract_hook_port(entry).is_some_and(|p| !live_ports.contains(&p) && p != port)
+        });
+
+        let entry = serde_json::json!({
+            "hooks": [{
+                "name": "maiterm",
+                "type": "command",
+                "command": hook_command(port, auth),
+                "timeout": HOOK_TIMEOUT_MS
+            }]
+        });
+        let hooks_obj = settings
+            .as_object_mut()
+            .unwrap()
+            .entry("hooks")
+            .or_insert(serde_json::json!({}));
+        let hooks_map = hooks_obj.as_object_mut().ok_or("settings.json hooks is not an object")?;
+        for (event, _) in HOOK_EVENTS {
+            let arr = hooks_map.entry(*event).or_insert(serde_json::json!([]));
+            if let Some(arr) = arr.as_array_mut() {
+                arr.push(entry.clone());
+            }
+        }
+
+        write_settings(&path, &settings)?;
+        log::info!("Registered Gemini CLI hooks in ~/.gemini/settings.json (port {})", port);
+        Ok(())
+    }
+
+    fn remove_hooks(&self, _port: u16, auth: &str) -> Result<(), String> {
+        self.remove_stale_hooks(&[(0, auth.to_string())])
+    }
+
+    fn remove_stale_hooks(&self, stale: &[(u16, String)]) -> Result<(), String> {
+        let path = gemini_settings_path().ok_or("Could not determine home directory")?;
+        if !path.exists() {
+            return Ok(());
+        }
+        let mut settings = read_settings(&path)?;
+        let changed = strip_hooks(&mut settings, |entry| {
+            stale.iter().any(|(_, auth)| entry_contains_auth(entry, auth))
+        });
+        if !changed {
+            return Ok(());
+        }
+        write_settings(&path, &settings)?;
+        log::info!("Removed maiTerm hooks from ~/.gemini/settings.json");
+        Ok(())
+    }
+
+    fn normalize_hook_event(&self, event: &mut Value) {
+        let Some(obj) = event.as_object_mut() else { return };
+        let name = obj.get("hook_event_name").and_then(|v| v.as_str()).unwrap_or("");
+        if let Some((_, claude_name)) = HOOK_EVENTS.iter().find(|(gemini, _)| *gemini == name) {
+            obj.insert("hook_event_name".into(), (*claude_name).into());
+        }
+        // Gemini only notifies about permission prompts
+        if obj.get("notification_type").and_then(|v| v.as_str()) == Some("ToolPermission") {
+            obj.insert("notification_type".into(), "permission_prompt".into());
+        }
+    }
+
+    fn captures_session_from_hooks(&self) -> bool {
+        true
+    }
+
+    fn resume_command(&self, session_id: &str) -> String {
+        format!("gemini --resume {}", session_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_renamed_to_claude_vocabulary() {
+        let mut event = serde_json::json!({
+            "hook_event_name": "Notification",
+            "notification_type": "ToolPermission",
+            "session_id": "abc",
+        });
+        GeminiCli.normalize_hook_event(&mut event);
+        assert_eq!(event["hook_event_name"], "Notification");
+        assert_eq!(event["notification_type"], "permission_prompt");
+
+        let mut event = serde_json::json!({ "hook_event_name": "AfterAgent" });
+        GeminiCli.normalize_hook_event(&mut event);
+        assert_eq!(event["hook_event_name"], "Stop");
+
+        let mut event = serde_json::json!({ "hook_event_name": "BeforeModel" });
+        GeminiCli.normalize_hook_event(&mut event);
+        assert_eq!(event["hook_event_name"], "BeforeModel");
+    }
+
+    #[test]
+    fn hook_entries_are_recognized_for_cleanup() {
+        let entry = serde_json::json!({
+            "hooks": [{ "type": "command", "command": hook_command(43210, "secret") }]
+        });
+        assert_eq!(extract_hook_port(&entry), Some(43210));
+        assert!(entry_matches_url(&entry, &hook_url_marker(43210)));
+        assert!(entry_contains_auth(&entry, "secret"));
+
+        let mut settings = serde_json::json!({
+            "hooks": {
+                "AfterAgent": [entry, { "hooks": [{ "type": "command", "command": "notify-send done" }] }],
+                "SessionEnd": [entry_for_other_instance()],
+            }
+        });
+        assert!(strip_hooks(&mut settings, |e| entry_contains_auth(e, "secret")));
+        assert_eq!(settings["hooks"]["AfterAgent"].as_array().unwrap().len(), 1);
+        assert_eq!(settings["hooks"]["SessionEnd"].as_array().unwrap().len(), 1);
+    }
+
+    fn entry_for_other_instance() -> Value {
+        serde_json::json!({
+            "hooks": [{ "type": "command", "command": hook_command(43211, "other") }]
+        })
+    }
+}
@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::server::request_frontend_with_timeout;
+use crate::state::workspace::{HookDecision, HookPolicyRule};
+use crate::state::AppState;
+
+/// Timeout registered on the decision hooks in `~/.claude/settings.json`.
+/// Prompt waits are capped below it so Claude never gives up on us first.
+pub const HOOK_DECISION_TIMEOUT_SECS: u64 = 600;
+const MAX_PROMPT_SECS: u64 = HOOK_DECISION_TIMEOUT_SECS - 30;
+
+/// What a rule's pattern is matched against: the field that identifies the
+/// call for well-known tools, otherwise the whole input as JSON.
+pub fn subject(tool_name: &str, tool_input: Option<&Value>) -> String {
+    let Some(input) = tool_input else {
+        return String::new();
+    };
+    let field = match tool_name {
+        "Bash" => "command",
+        "Read" | "Write" | "Edit" | "MultiEdit" => "file_path",
+        "NotebookEdit" => "notebook_path",
+        "WebFetch" => "url",
+        "WebSearch" => "query",
+        "Glob" | "Grep" => "pattern",
+        _ => "",
+    };
+    input
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| input.to_string())
+}
+
+/// Tool name match with `*` wildcards; an empty pattern matches any tool.
+fn tool_matches(pattern: &str, tool: &str) -> bool {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return true;
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == tool;
+    }
+    let Some(mut rest) = tool.strip_prefix(first) else {
+        return false;
+    };
+    for middle in &parts[1..parts.len() - 1] {
+        match rest.find(middle) {
+            Some(idx) => rest = &rest[idx + middle.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn pattern_matches(rule: &HookPolicyRule, subject: &str) -> bool {
+    let Some(pattern) = rule.pattern.as_deref().filter(|p| !p.is_empty()) else {
+        return true;
+    };
+    match regex::Regex::new(pattern) {
+        Ok(re) => re.is_match(subject),
+        Err(e) => {
+            log::warn!("Hook policy rule {}: invalid pattern '{}': {}", rule.id, pattern, e);
+            false
+        }
+    }
+}
+
+fn strictness(decision: HookDecision) -> u8 {
+    match decision {
+        HookDecision::Deny => 3,
+        HookDecision::Prompt => 2,
+        HookDecision::Ask => 1,
+        HookDecision::Allow => 0,
+    }
+}
+
+/// The strictest enabled rule matching a call (deny, then prompt, ask, allow;
+/// the first listed wins a tie).
+pub fn evaluate<'a>(
+    rules: &'a [HookPolicyRule],
+    tool_name: &str,
+    subject: &str,
+    workspace_id: Option<&str>,
+) -> Option<&'a HookPolicyRule> {
+    rules
+        .iter()
+        .filter(|r| r.enabled)
+        .filter(|r| r.workspaces.is_empty() || workspace_id.is_some_and(|id| r.workspaces.iter().any(|w| w == id)))
+        .filter(|r| tool_matches(&r.tool, tool_name))
+        .filter(|r| pattern_matches(r, subject))
+        .min_by_key(|r| Reverse(strictness(r.decision)))
+}
+
+/// Answer a `PreToolUse` or `PermissionRequest` hook from the policy rules.
+/// `None` means no rule applies and the hook stays observe-only, leaving the
+/// call to Claude's own permission settings.
+pub async fn decide(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    event: &str,
+    session_id: &str,
+    tab_id: Option<&str>,
+    tool_name: &str,
+    tool_input: Option<&Value>,
+) -> Option<Value> {
+    let subject = subject(tool_name, tool_input);
+    let (rule, prompt_timeout) = {
+        let app_data = state.app_data.read();
+        let workspace_id = tab_id.and_then(|id| app_data.find_tab(id)).map(|(ws, _)| ws.id.clone());
+        let prefs = &app_data.preferences;
+        let rule = evaluate(&prefs.hook_policy_rules, tool_name, &subject, workspace_id.as_deref())?.clone();
+        (rule, prefs.hook_prompt_timeout)
+    };
+
+    let (decision, reason) = match rule.decision {
+        // Prompted at PreToolUse; a later permission request for the same
+        // call goes to Claude's own dialog rather than asking twice.
+        HookDecision::Prompt if event != "PreToolUse" => return None,
+        HookDecision::Prompt => {
+            prompt_user(state, app_handle, session_id, tab_id, tool_name, &subject, prompt_timeout).await
+        }
+        decision => (
+            decision,
+            rule.reason.clone().unwrap_or_else(|| "maiTerm hook policy".to_string()),
+        ),
+    };
+
+    log::info!(
+        "Claude hook: {} {} '{}' -> {:?} (rule {})",
+        event,
+        tool_name,
+        subject.chars().take(80).collect::<String>(),
+        decision,
+        rule.id
+    );
+    response(event, decision, &reason)
+}
+
+/// Ask the window owning the tab (Allow/Deny toast). Dismissing the toast or
+/// not answering in time defers to Claude's permission prompt.
+async fn prompt_user(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    session_id: &str,
+    tab_id: Option<&str>,
+    tool_name: &str,
+    subject: &str,
+    timeout_secs: u32,
+) -> (HookDecision, String) {
+    let secs = u64::from(timeout_secs).clamp(1, MAX_PROMPT_SECS);
+    let arguments = serde_json::json!({
+        "tabId": tab_id,
+        "sessionId": session_id,
+        "toolName": tool_name,
+        "subject": subject,
+        "timeout": secs,
+    });
+    let answer = request_frontend_with_timeout(
+        state,
+        app_handle,
+        "hookPermissionRequest",
+        arguments,
+        Duration::from_secs(secs),
+    )
+    .await;
+
+    match answer.ok().as_ref().and_then(|v| v.get("decision")).and_then(|d| d.as_str()) {
+        Some("allow") => (HookDecision::Allow, "Approved in maiTerm".to_string()),
+        Some("deny") => (HookDecision::Deny, "Denied in maiTerm".to_string()),
+        _ => (HookDecision::Ask, "Not answered in maiTerm".to_string()),
+    }
+}
+
+/// Hook output in the shape Claude expects for `event`.
+fn response(event: &str, decision: HookDecision, reason: &str) -> Option<Value> {
+    match event {
+        "PreToolUse" => {
+            let permission = match decision {
+                HookDecision::Allow => "allow",
+                HookDecision::Deny => "deny",
+                HookDecision::Ask | HookDecision::Prompt => "ask",
+            };
+            Some(serde_json::json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PreToolUse",
+                    "permissionDecision": permission,
+                    "permissionDecisionReason": reason,
+                }
+            }))
+        }
+        // Claude is already about to ask, so only a definite answer is useful
+        "PermissionRequest" => {
+            let behavior = match decision {
+                HookDecision::Allow => serde_json::json!({ "behavior": "allow" }),
+                HookDecision::Deny => serde_json::json!({ "behavior": "deny", "message": reason }),
+                HookDecision::Ask | HookDecision::Prompt => return None,
+            };
+            Some(serde_json::json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PermissionRequest",
+                    "decision": behavior,
+                }
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, tool: &str, pattern: Option<&str>, decision: HookDecision) -> HookPolicyRule {
+        HookPolicyRule {
+            id: id.into(),
+            tool: tool.into(),
+            pattern: pattern.map(Into::into),
+            decision,
+            workspaces: Vec::new(),
+            reason: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn tool_globs() {
+        assert!(tool_matches("", "Bash"));
+        assert!(tool_matches("Bash", "Bash"));
+        assert!(!tool_matches("Bash", "BashOutput"));
+        assert!(tool_matches("mcp__*", "mcp__maiterm__runInTab"));
+        assert!(tool_matches("*Edit", "MultiEdit"));
+        assert!(tool_matches("mcp__*__run*", "mcp__maiterm__runInTab"));
+        assert!(!tool_matches("mcp__*__run*", "mcp__maiterm__createTab"));
+        assert!(!tool_matches("a*a", "a"));
+    }
+
+    #[test]
+    fn strictest_matching_rule_wins() {
+        let mut rules = vec![
+            rule("allow-git", "Bash", Some(r"^git "), HookDecision::Allow),
+            rule("no-force", "Bash", Some(r"git push .*(--force|-f\b)"), HookDecision::Deny),
+            rule("ask-edits", "*Edit", None, HookDecision::Prompt),
+            rule("bad", "Bash", Some("("), HookDecision::Deny),
+        ];
+        let verdict = |rules: &[HookPolicyRule], tool: &str, subject: &str, ws: Option<&str>| {
+            evaluate(rules, tool, subject, ws).map(|r| r.id.clone())
+        };
+
+        assert_eq!(verdict(&rules, "Bash", "git status", None).as_deref(), Some("allow-git"));
+        assert_eq!(verdict(&rules, "Bash", "git push origin main --force", None).as_deref(), Some("no-force"));
+        assert_eq!(verdict(&rules, "Edit", "/tmp/x", None).as_deref(), Some("ask-edits"));
+        assert_eq!(verdict(&rules, "Bash", "ls", None), None);
+
+        rules[1].workspaces = vec!["ws-1".into()];
+        assert_eq!(verdict(&rules, "Bash", "git push -f", Some("ws-2")).as_deref(), Some("allow-git"));
+        assert_eq!(verdict(&rules, "Bash", "git push -f", Some("ws-1")).as_deref(), Some("no-force"));
+        rules[1].enabled = false;
+        assert_eq!(verdict(&rules, "Bash", "git push -f", Some("ws-1")).as_deref(), Some("allow-git"));
+    }
+
+    #[test]
+    fn subjects_and_responses() {
+        let input = serde_json::json!({ "command": "rm -rf /", "description": "oops" });
+        assert_eq!(subject("Bash", Some(&input)), "rm -rf /");
+        assert_eq!(subject("mcp__x__y", Some(&serde_json::json!({ "a": 1 }))), r#"{"a":1}"#);
+
+        let pre = response("PreToolUse", HookDecision::Deny, "no").unwrap();
+        assert_eq!(pre["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(pre["hookSpecificOutput"]["permissionDecisionReason"], "no");
+
+        let req = response("PermissionRequest", HookDecision::Allow, "ok").unwrap();
+        assert_eq!(req["hookSpecificOutput"]["decision"]["behavior"], "allow");
+        assert_eq!(response("PermissionRequest", HookDecision::Ask, "ok"), None);
+    }
+}
@@ -9,7 +9,7 @@ pub const STATUSLINE_SETUP_SCRIPT: &str =
 pub const STATUSLINE_PAYLOAD_SCRIPT: &str =
     include_str!("../../resources/maiterm-skill/bin/statusline-command.sh");
 
-fn mcp_server_key() -> &'static str {
+pub(super) fn mcp_server_key() -> &'static str {
     if cfg!(debug_assertions) { "maiterm-dev" } else { "maiterm" }
 }
 
@@ -73,15 +73,16 @@ pub fn write_lockfile(port: u16, auth: &str, workspace_folders: Vec<String>, hoo
     fs::write(&lock_path, json).map_err(|e| format!("Failed to write lock file: {}", e))?;
     log::info!("Wrote Claude Code lock file at {:?}", lock_path);
 
-    // Also register as a named MCP server so Claude exposes our full tool list
-    if let Err(e) = write_mcp_settings(port, auth) {
-        log::warn!("Failed to write MCP settings: {}", e);
-    }
-
-    // Register hooks in ~/.claude/settings.json (gated on preference)
-    if hooks_enabled {
-        if let Err(e) = write_hook_settings(port, auth) {
-            log::warn!("Failed to write hook settings: {}", e);
+    // Register our MCP server (and hooks, gated on preference) with every
+    // installed agent CLI so it exposes our full tool list
+    for agent in super::agents::ADAPTERS.iter().filter(|a| a.is_installed()) {
+        if let Err(e) = agent.register_mcp(port, auth) {
+            log::warn!("Failed to write {} MCP settings: {}", agent.display_name(), e);
+        }
+        if hooks_enabled {
+            if let Err(e) = agent.install_hooks(port, auth) {
+                log::warn!("Failed to write {} hook settings: {}", agent.display_name(), e);
+            }
         }
     }
 
@@ -95,8 +96,10 @@ pub fn write_lockfile(port: u16, auth: &str, workspace_folders: Vec<String>, hoo
 
 pub fn delete_lockfile(port: u16, auth: &str) {
     // Clean up hooks first (needs both port and auth)
-    if let Err(e) = remove_hook_settings(port, auth) {
-        log::warn!("Failed to remove hook settings: {}", e);
+    for agent in super::agents::ADAPTERS {
+        if let Err(e) = agent.remove_hooks(port, auth) {
+            log::warn!("Failed to remove {} hook settings: {}", agent.display_name(), e);
+        }
     }
     if let Some(dir) = ide_lock_dir() {
         let lock_path = dir.join(format!("{}.lock", port));
@@ -107,8 +110,10 @@ pub fn delete_lockfile(port: u16, auth: &str) {
         }
     }
 
-    if let Err(e) = remove_mcp_settings() {
-        log::warn!("Failed to remove MCP settings: {}", e);
+    for agent in super::agents::ADAPTERS {
+        if let Err(e) = agent.unregister_mcp() {
+            log::warn!("Failed to remove {} MCP settings: {}", agent.display_name(), e);
+        }
     }
 
     remove_aiterm_skill();
@@ -160,7 +165,7 @@ fn put_mcp_entry(path: &PathBuf, entry: serde_json::Value) -> Result<(), String>
 
 /// Write an `mcpServers.aiterm` entry into ~/.claude.json so Claude
 /// Code CLI exposes our full tool list (not filtered by IDE name).
-fn write_mcp_settings(port: u16, auth: &str) -> Result<(), String> {
+pub(super) fn write_mcp_settings(port: u16, auth: &str) -> Result<(), String> {
     let path = claude_settings_path().ok_or("Could not determine home directory")?;
     put_mcp_entry(&path, expected_mcp_entry(port, auth))?;
     log::info!("Registered {} MCP server in ~/.claude.json (port {})", mcp_server_key(), port);
@@ -201,7 +206,7 @@ pub fn ensure_mcp_settings(port: u16, auth: &str) -> Result<bool, String> {
 }
 
 /// Remove the MCP server entry from ~/.claude.json on shutdown.
-fn remove_mcp_settings() -> Result<(), String> {
+pub(super) fn remove_mcp_settings() -> Result<(), String> {
     let path = claude_settings_path().ok_or("Could not determine home directory")?;
     if !path.exists() {
         return Ok(());
@@ -236,7 +241,7 @@ fn remove_mcp_settings() -> Result<(), String> {
 }
 
 /// Build the hook URL marker used to identify our hook entries.
-fn hook_url_marker(port: u16) -> String {
+pub(super) fn hook_url_marker(port: u16) -> String {
     format!("http://127.0.0.1:{}/hooks", port)
 }
 
@@ -244,10 +249,11 @@ fn hook_url_marker(port: u16) -> String {
 ///
 /// Registers:
 /// - SessionStart (command) — reads $AITERM_TAB_ID, POSTs to our server, injects tab ID context
-/// - SessionEnd, Notification, Stop (http) — lightweight event notifications
+/// - SessionEnd, Notification, Stop, tool and subagent events (http) — lightweight event notifications
+/// - PreToolUse, PermissionRequest (http) — may answer with a hook policy decision
 ///
 /// We identify our entries by matching the hook URL, so we don't clobber user hooks.
-fn write_hook_settings(port: u16, auth: &str) -> Result<(), String> {
+pub(super) fn write_hook_settings(port: u16, auth: &str) -> Result<(), String> {
     let path = claude_user_settings_path().ok_or("Could not determine home directory")?;
 
     let mut settings: serde_json::Value = if path.exists() {
@@ -286,6 +292,21 @@ You MUST immediately call the {mcp_key} initSession tool with this tabId and ses
         }])
     };
 
+    // Decision hooks may wait on a prompt in the maiTerm UI
+    let decision_hook = |url: &str| -> serde_json::Value {
+        serde_json::json!([{
+            "matcher": "",
+            "hooks": [{
+                "type": "http",
+                "url": url,
+                "headers": {
+                    "x-claude-code-ide-authorization": auth
+                },
+                "timeout": super::hook_policy::HOOK_DECISION_TIMEOUT_SECS
+            }]
+        }])
+    };
+
     let our_hooks = serde_json::json!({
         "SessionStart": [
             // Command hook: echo tab ID into Claude's context + background curl for tab mapping
@@ -313,8 +334,12 @@ You MUST immediately call the {mcp_key} initSession tool with this tabId and ses
         "Notification": http_hook(&hooks_url),
         "Stop": http_hook(&hooks_url),
         "UserPromptSubmit": http_hook(&hooks_url),
-        "PreToolUse": http_hook(&hooks_url),
+        "PreToolUse": decision_hook(&hooks_url),
+        "PermissionRequest": decision_hook(&hooks_url),
         "PostToolUse": http_hook(&hooks_url),
+        "PostToolUseFailure": http_hook(&hooks_url),
+        "SubagentStart": http_hook(&hooks_url),
+        "SubagentStop": http_hook(&hooks_url),
         "PreCompact": http_hook(&hooks_url)
     });
 
@@ -408,7 +433,7 @@ You MUST immediately call the {mcp_key} initSession tool with this tabId and ses
 }
 
 /// Collect ports from all live lockfiles (PIDs that are still running).
-fn collect_live_lockfile_ports() -> Vec<u16> {
+pub(super) fn collect_live_lockfile_ports() -> Vec<u16> {
     let Some(dir) = ide_lock_dir() else { return vec![] };
     let Ok(entries) = fs::read_dir(&dir) else { return vec![] };
     let mut ports = Vec::new();
@@ -437,7 +462,7 @@ fn collect_live_lockfile_ports() -> Vec<u16> {
 }
 
 /// Extract the port number from an maiTerm hook entry's URL/command.
-fn extract_hook_port(entry: &serde_json::Value) -> Option<u16> {
+pub(super) fn extract_hook_port(entry: &serde_json::Value) -> Option<u16> {
     if let Some(hooks) = entry.get("hooks").and_then(|v| v.as_array()) {
         for hook in hooks {
             // HTTP hooks
@@ -485,7 +510,7 @@ fn extract_port_from_url(s: &str) -> Option<u16> {
 }
 
 /// Check if a hook entry contains a URL matching our server.
-fn entry_matches_url(entry: &serde_json::Value, url_pattern: &str) -> bool {
+pub(super) fn entry_matches_url(entry: &serde_json::Value, url_pattern: &str) -> bool {
     if let Some(hooks) = entry.get("hooks").and_then(|v| v.as_array()) {
         for hook in hooks {
             // HTTP hooks: check url field
@@ -506,7 +531,7 @@ fn entry_matches_url(entry: &serde_json::Value, url_pattern: &str) -> bool {
 }
 
 /// Remove our hook entries from ~/.claude/settings.json on shutdown.
-fn remove_hook_settings(port: u16, auth: &str) -> Result<(), String> {
+pub(super) fn remove_hook_settings(port: u16, auth: &str) -> Result<(), String> {
     let path = claude_user_settings_path().ok_or("Could not determine home directory")?;
     if !path.exists() {
         return Ok(());
@@ -573,7 +598,7 @@ fn remove_hook_settings(port: u16, auth: &str) -> Result<(), String> {
 }
 
 /// Check if a hook entry contains a specific auth token (used for cleanup).
-fn entry_contains_auth(entry: &serde_json::Value, auth: &str) -> bool {
+pub(super) fn entry_contains_auth(entry: &serde_json::Value, auth: &str) -> bool {
     if let Some(hooks) = entry.get("hooks").and_then(|v| v.as_array()) {
         for hook in hooks {
             if let Some(headers) = hook.get("headers").and_then(|v| v.as_object()) {
@@ -771,14 +796,16 @@ pub fn cleanup_stale_lockfiles() {
 
     // Clean up hooks and allowedHttpHookUrls for all dead servers
     if !stale_ports.is_empty() {
-        if let Err(e) = cleanup_stale_hooks(&stale_ports) {
-            log::warn!("Failed to clean stale hooks: {}", e);
+        for agent in super::agents::ADAPTERS {
+            if let Err(e) = agent.remove_stale_hooks(&stale_ports) {
+                log::warn!("Failed to clean stale {} hooks: {}", agent.display_name(), e);
+            }
         }
     }
 }
 
 /// Remove hook entries and allowedHttpHookUrls for a list of dead (port, auth) pairs.
-fn cleanup_stale_hooks(stale_ports: &[(u16, String)]) -> Result<(), String> {
+pub(super) fn cleanup_stale_hooks(stale_ports: &[(u16, String)]) -> Result<(), String> {
     let path = claude_user_settings_path().ok_or("Could not determine home directory")?;
     if !path.exists() {
         return Ok(());
@@ -1,3 +1,13 @@
+pub mod agents;
+pub mod gemini;
+pub mod hook_policy;
 pub mod lockfile;
+pub mod prompts;
 pub mod protocol;
+pub mod resources;
+pub mod scopes;
 pub mod server;
+pub mod tab_runner;
+pub mod tokens;
+pub mod transcript;
+pub mod usage;
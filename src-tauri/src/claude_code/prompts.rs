@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::state::workspace::WorkspaceNote;
+use crate::state::AppState;
+use crate::terminal::triggers::interpolate_variables;
+
+/// Variables filled in from the caller's tab and workspace. Any other
+/// `%name` in a template is offered to the client as a prompt argument.
+const CONTEXT_VARIABLES: &[&str] = &[
+    "tabName",
+    "workspaceName",
+    "cwd",
+    "gitBranch",
+    "failedCommand",
+    "failedExitCode",
+    "failedOutput",
+];
+
+/// Lines of a failed command's output kept for `%failedOutput` (the tail).
+const FAILED_OUTPUT_LINES: usize = 200;
+
+/// How long `%gitBranch` may wait on `git` before it's left empty.
+const GIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// A prompt served over MCP, from `Preferences.prompt_templates` or a
+/// workspace note.
+#[derive(Debug, PartialEq)]
+struct PromptDef {
+    name: String,
+    description: Option<String>,
+    template: String,
+}
+
+/// A workspace note defines a prompt when its first non-empty line is a
+/// `/name` command (optionally as a Markdown heading); the rest of that line
+/// is the description and the remaining lines are the template:
+///
+/// ```text
+/// # /summarize-build Summarize the failing build
+/// `%failedCommand` exited with %failedExitCode:
+/// %failedOutput
+/// ```
+fn note_prompt(note: &WorkspaceNote) -> Option<PromptDef> {
+    let mut lines = note.content.lines().skip_while(|l| l.trim().is_empty());
+    let header = lines.next()?.trim_start_matches('#').trim();
+    let (name, description) = match header.strip_prefix('/')?.split_once(char::is_whitespace) {
+        Some((name, description)) => (name, Some(description.trim().to_string())),
+        None => (header.strip_prefix('/')?, None),
+    };
+    let name = prompt_name(name)?;
+    let template = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    if template.is_empty() {
+        return None;
+    }
+    Some(PromptDef { name, description: description.filter(|d| !d.is_empty()), template })
+}
+
+/// MCP prompt names become slash commands, so keep them to `[a-z0-9_-]`.
+fn prompt_name(raw: &str) -> Option<String> {
+    let name: String = raw
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let name = name.trim_matches('-').to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Preference templates, overridden by same-named prompts from the caller's
+/// workspace notes.
+fn prompt_defs(state: &AppState, tab_id: Option<&str>) -> Vec<PromptDef> {
+    let app_data = state.app_data.read();
+    let mut defs: Vec<PromptDef> = app_data
+        .preferences
+        .prompt_templates
+        .iter()
+        .filter_map(|t| {
+            Some(PromptDef {
+                name: prompt_name(&t.name)?,
+                description: t.description.clone().filter(|d| !d.is_empty()),
+                template: t.template.clone(),
+            })
+        })
+        .collect();
+
+    if let Some((workspace, _)) = tab_id.and_then(|id| app_data.find_tab(id)) {
+        for def in workspace.workspace_notes.iter().filter_map(note_prompt) {
+            defs.retain(|d| d.name != def.name);
+            defs.push(def);
+        }
+    }
+    defs
+}
+
+/// `%name` references in a template, in order of first use.
+fn referenced_variables(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (idx, _) in template.match_indices('%') {
+        let after = &template[idx + 1..];
+        let len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..len];
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// `prompts/list` for a caller in `tab_id` (if the connection has affinity).
+pub fn list(state: &AppState, tab_id: Option<&str>) -> Value {
+    let prompts: Vec<Value> = prompt_defs(state, tab_id)
+        .iter()
+        .map(|def| {
+            let arguments: Vec<Value> = referenced_variables(&def.template)
+                .into_iter()
+                .filter(|name| !CONTEXT_VARIABLES.contains(&name.as_str()))
+                .map(|name| {
+                    serde_json::json!({
+                        "name": name,
+                        "description": "Defaults to the tab's trigger variable of the same name",
+                        "required": false,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": def.name,
+                "description": def.description,
+                "arguments": arguments,
+            })
+        })
+        .collect();
+    serde_json::json!({ "prompts": prompts })
+}
+
+/// `prompts/get`: the template interpolated with the given arguments, then
+/// the tab's trigger variables, then tab/workspace context.
+pub async fn get(state: &Arc<AppState>, tab_id: Option<&str>, name: &str, arguments: Option<&Value>) -> Result<Value, String> {
+    let def = prompt_defs(state, tab_id)
+        .into_iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| format!("Unknown prompt: {}", name))?;
+
+    let mut variables = match tab_id {
+        Some(id) => context_variables(state, id).await,
+        None => HashMap::new(),
+    };
+    if let Some(id) = tab_id {
+        if let Some((_, tab)) = state.app_data.read().find_tab(id) {
+            variables.extend(tab.trigger_variables.clone());
+        }
+    }
+    if let Some(args) = arguments.and_then(|a| a.as_object()) {
+        for (key, value) in args {
+            let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+            variables.insert(key.clone(), value);
+        }
+    }
+
+    let text = interpolate_variables(&def.template, &variables, false);
+    Ok(serde_json::json!({
+        "description": def.description,
+        "messages": [{ "role": "user", "content": { "type": "text", "text": text } }],
+    }))
+}
+
+/// Values for `CONTEXT_VARIABLES`; each is set (possibly empty) so a template
+/// never shows a raw `%gitBranch` just because the tab isn't in a repo.
+async fn context_variables(state: &Arc<AppState>, tab_id: &str) -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = CONTEXT_VARIABLES.iter().map(|n| (n.to_string(), String::new())).collect();
+
+    let last_cwd = {
+        let app_data = state.app_data.read();
+        let Some((workspace, tab)) = app_data.find_tab(tab_id) else {
+            return vars;
+        };
+        vars.insert("tabName".into(), tab.name.clone());
+        vars.insert("workspaceName".into(), workspace.name.clone());
+        tab.last_cwd.clone()
+    };
+
+    let pty_id = state.tab_pty_map.read().get(tab_id).cloned();
+    let cwd = pty_id
+        .as_deref()
+        .and_then(|id| crate::pty::get_pty_info(state, id).ok())
+        .and_then(|info| info.cwd)
+        .or(last_cwd);
+    if let Some(cwd) = cwd {
+        if let Some(branch) = git_branch(&cwd).await {
+            vars.insert("gitBranch".into(), branch);
+        }
+        vars.insert("cwd".into(), cwd);
+    }
+
+    // Most recent command that exited non-zero and is still in the buffer
+    if let Some(terminal) = pty_id.and_then(|id| state.terminal_registry.get(&id)) {
+        let handle = terminal.lock();
+        let failed = handle
+            .blocks
+            .blocks()
+            .filter(|b| matches!(b.exit_code, Some(code) if code != 0))
+            .filter_map(|b| Some((b, handle.blocks.output_text(&handle.term, b)?)))
+            .last();
+        if let Some((block, output)) = failed {
+            let lines: Vec<&str> = output.lines().collect();
+            let tail = lines[lines.len().saturating_sub(FAILED_OUTPUT_LINES)..].join("\n");
+            vars.insert("failedCommand".into(), block.command.clone().unwrap_or_default());
+            vars.insert("failedExitCode".into(), block.exit_code.unwrap_or_default().to_string());
+            vars.insert("failedOutput".into(), tail.trim_end().to_string());
+        }
+    }
+    vars
+}
+
+/// The checked-out branch in `cwd`, or `None` outside a repo or on timeout.
+async fn git_branch(cwd: &str) -> Option<String> {
+    let output = tokio::time::timeout(
+        GIT_TIMEOUT,
+        tokio::process::Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !branch.is_empty()).then_some(branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(content: &str) -> WorkspaceNote {
+        WorkspaceNote {
+            id: "n1".into(),
+            content: content.into(),
+            mode: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn notes_with_a_slash_heading_define_prompts() {
+        let def = note_prompt(&note("\n# /Summarize-Build  Summarize the failing build\n\n`%failedCommand` failed:\n%failedOutput\n"));
+        assert_eq!(
+            def,
+            Some(PromptDef {
+                name: "summarize-build".into(),
+                description: Some("Summarize the failing build".into()),
+                template: "`%failedCommand` failed:\n%failedOutput".into(),
+            })
+        );
+        assert_eq!(note_prompt(&note("/review\nReview the diff on %gitBranch")).unwrap().description, None);
+        assert_eq!(note_prompt(&note("# Meeting notes\n/not-a-prompt")), None);
+        assert_eq!(note_prompt(&note("/empty\n\n")), None);
+    }
+
+    #[test]
+    fn referenced_variables_are_unique_and_ordered() {
+        assert_eq!(
+            referenced_variables("%ticket on %gitBranch: %ticket, 100% done"),
+            vec!["ticket".to_string(), "gitBranch".to_string()]
+        );
+    }
+}
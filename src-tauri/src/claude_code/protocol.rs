@@ -48,7 +48,7 @@ pub fn tool_list_response() -> Value {
     // Tools are built in batches to stay under the serde_json::json! macro recursion limit (128).
     // Each batch is a small Vec<Value> that gets extended into the final tools array.
 
-    let mut tools: Vec<Value> = Vec::with_capacity(42);
+    let mut tools: Vec<Value> = Vec::with_capacity(46);
 
     // Batch 1: Session, info, notification, logs, document tools
     tools.extend(serde_json::json!([
@@ -464,9 +464,22 @@ pub fn tool_list_response() -> Value {
         },
         {
             "name": "getClaudeSessions",
-            "description": "Get all active Claude Code sessions across all tabs. Returns session IDs, states (active/waiting_input/waiting_permission/stopped), current tool being executed, model, working directory, and tab/workspace names. Use this for multi-agent coordination — check if Claude is running in other tabs before starting work, avoid conflicting edits, or wait for another session to finish.",
+            "description": "Get all active Claude Code sessions across all tabs. Returns session IDs, states (active/waiting_input/waiting_permission/stopped), current tool being executed, number of running subagents, the last tool failure (if the most recent tool call failed), model, token usage and estimated cost so far (from the local transcript; absent for sessions running over SSH), working directory, and tab/workspace names. Use this for multi-agent coordination — check if Claude is running in other tabs before starting work, avoid conflicting edits, or wait for another session to finish.",
             "inputSchema": { "type": "object", "properties": {}, "required": [] }
         },
+        {
+            "name": "getHookTimeline",
+            "description": "Get the recorded Claude Code hook events for a tab or session, oldest first: SessionStart/End, UserPromptSubmit, PreToolUse/PostToolUse/PostToolUseFailure (with tool input, error, any maiTerm policy decision, and durationMs for each completed tool call), PermissionRequest, SubagentStart/SubagentStop, Notification, Stop and PreCompact. Events are kept for 30 days. Defaults to your own tab; use getClaudeSessions to find other sessions. Use this to audit what an agent did, e.g. overnight.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "targetTabId": { "type": "string", "description": "Tab whose sessions to return. Defaults to your own tab." },
+                    "sessionId": { "type": "string", "description": "Return one session's events instead of a tab's." },
+                    "sinceMinutes": { "type": "number", "description": "Only events from the last N minutes." },
+                    "limit": { "type": "number", "description": "Maximum events to return, keeping the most recent (default: 200, max: 1000)." }
+                }
+            }
+        },
         {
             "name": "listArchivedTabs",
             "description": "List archived (suspended) tabs for a workspace. Returns tab IDs, display names, archived dates, and restore context (CWD, SSH command, auto-resume info). Use this to discover old sessions that can be restored. Use listWorkspaces first to find workspaces with archived tabs (archivedTabCount > 0).",
@@ -491,29 +504,101 @@ pub fn tool_list_response() -> Value {
         },
         {
             "name": "sendToBridgedAgent",
-            "description": "Send a message to the peer AI agent you are bridged with (running in another maiTerm pane, e.g. an expert on a related codebase). Use this to ask questions, request research, or share context. The recipient's reply arrives later as a new turn in your own prompt — this is asynchronous, so finish your current turn after sending. maiTerm automatically stamps your identity (tab, workspace, cwd) on the message so the recipient knows it's from you, a peer agent, NOT from a human operator. Only works once a bridge has been established (the human connects two sessions via the Agent Bridge picker). If your conversation is complete, simply stop sending — do not reply just to acknowledge.",
+            "description": "Send a message to the peer AI agents in your Agent Bridge group (running in other maiTerm panes, e.g. experts on related codebases). Use this to ask questions, request research, or share context. Omit `to` to broadcast to every other member, or set it to one member's role, tab name, or tab id for a direct message. Replies arrive later as new turns in your own prompt — this is asynchronous, so finish your current turn after sending. maiTerm automatically stamps your identity (tab, role, workspace, cwd) on the message so recipients know it's from you, a peer agent, NOT from a human operator. Only works once a bridge has been established (the human connects sessions via the Agent Bridge picker). If your conversation is complete, simply stop sending — do not reply just to acknowledge.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "message": { "type": "string", "description": "The message to send to the bridged agent. Be explicit: state who you are and why you're asking on first contact, then your question or information." }
+                    "message": { "type": "string", "description": "The message to send. Be explicit: state who you are and why you're asking on first contact, then your question or information." },
+                    "to": { "type": "string", "description": "Recipient: a member's role (e.g. \"backend\"), tab name, or tab id. Omit to broadcast to the whole group." }
                 },
                 "required": ["message"]
             }
         },
         {
             "name": "getBridgedAgent",
-            "description": "Check whether you are currently bridged to a peer AI agent and, if so, who. Returns the bridged agent's tab name, workspace, and working directory, or indicates that no bridge is active. Use this to discover the context of the agent you can reach via sendToBridgedAgent.",
+            "description": "Check whether you are in an Agent Bridge group and, if so, with whom. Returns the group name, your own role, and each other member's tab name, role, working directory, and whether it is online — or indicates that no bridge is active. Use this to decide whom to address via sendToBridgedAgent.",
             "inputSchema": { "type": "object", "properties": {}, "required": [] }
         }
     ]).as_array().unwrap().clone());
 
+    // Batch 5: Driving other terminal tabs
+    tools.extend(serde_json::json!([
+        {
+            "name": "runInTab",
+            "description": "Run a shell command in ANOTHER terminal tab (e.g. a visible tab hosting a dev server or test runner) and wait for the result. Types the command at the tab's shell prompt, then returns when the command finishes (requires shell integration / OSC 133 marks — the result includes the exit code), when the output matches `pattern`, or when `timeout` expires. Returns status ('finished', 'matched' or 'timeout'), the output text (tail, at most maxLines), and exitCode when finished. Fails if the tab is already running a command — use waitForOutput for that. Use listWorkspaces to find tab IDs.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "targetTabId": { "type": "string", "description": "The terminal tab to run the command in. Must not be your own tab." },
+                    "command": { "type": "string", "description": "The command line to type and execute" },
+                    "pattern": { "type": "string", "description": "Optional regex. Return as soon as new output matches it (e.g. 'ready in \\d+ms' for a dev server that keeps running). Needed to finish early in shells without shell integration." },
+                    "timeout": { "type": "number", "description": "Seconds to wait (default: 60, max: 600). On timeout the output so far is returned and the command keeps running." },
+                    "maxLines": { "type": "number", "description": "Maximum output lines to return, keeping the last ones (default: 200)." }
+                },
+                "required": ["targetTabId", "command"]
+            }
+        },
+        {
+            "name": "waitForOutput",
+            "description": "Wait on ANOTHER terminal tab without typing anything: until its running command finishes (OSC 133), until new output matches `pattern`, or until `timeout`. Returns status ('finished', 'matched', 'timeout', or 'idle' when nothing is running and no pattern was given), the output text, and exitCode when finished. Use after runInTab timed out or matched early, or to wait for a long-running build in a tab the user started.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "targetTabId": { "type": "string", "description": "The terminal tab to watch. Must not be your own tab." },
+                    "pattern": { "type": "string", "description": "Optional regex matched against output that arrives after this call." },
+                    "timeout": { "type": "number", "description": "Seconds to wait (default: 60, max: 600)." },
+                    "maxLines": { "type": "number", "description": "Maximum output lines to return, keeping the last ones (default: 200)." }
+                },
+                "required": ["targetTabId"]
+            }
+        }
+    ]).as_array().unwrap().clone());
+
+    // Batch 6: Building layouts — new terminal tabs and splits
+    tools.extend(serde_json::json!([
+        {
+            "name": "createTab",
+            "description": "Create a new terminal tab and return its tabId. By default it opens in your own pane; pass paneId or workspaceId (uses that workspace's active pane) to place it elsewhere in your window. Optionally set the starting directory, run an initial command, or start a sibling Claude Code agent with a prompt (startClaude/prompt) — use this to fan work out to visible agents. Combine with runInTab/waitForOutput to drive the new tab.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "paneId": { "type": "string", "description": "Pane to add the tab to (see listWorkspaces). Defaults to your own pane." },
+                    "workspaceId": { "type": "string", "description": "Workspace to add the tab to (its active pane). Ignored when paneId is set." },
+                    "name": { "type": "string", "description": "Tab name. Pinned — shell titles won't replace it." },
+                    "cwd": { "type": "string", "description": "Starting directory. Defaults to the directory of sibling terminals." },
+                    "command": { "type": "string", "description": "Command typed once the shell starts. Not re-run when the tab is restored." },
+                    "startClaude": { "type": "boolean", "description": "Start `claude` in the new tab. Cannot be combined with command." },
+                    "prompt": { "type": "string", "description": "Initial prompt for the new Claude session (implies startClaude)." }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "splitPane",
+            "description": "Split a pane and open a new terminal tab in the new half; returns the new tabId and paneId. Defaults to splitting your own pane side by side. Accepts the same cwd / command / startClaude / prompt options as createTab.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "paneId": { "type": "string", "description": "Pane to split (see listWorkspaces). Defaults to your own pane." },
+                    "direction": { "type": "string", "enum": ["horizontal", "vertical"], "description": "'horizontal' places the new pane to the right (default), 'vertical' below." },
+                    "name": { "type": "string", "description": "Name for the new tab." },
+                    "cwd": { "type": "string", "description": "Starting directory. Defaults to the split pane's current directory." },
+                    "command": { "type": "string", "description": "Command typed once the shell starts. Not re-run when the tab is restored." },
+                    "startClaude": { "type": "boolean", "description": "Start `claude` in the new tab. Cannot be combined with command." },
+                    "prompt": { "type": "string", "description": "Initial prompt for the new Claude session (implies startClaude)." }
+                },
+                "required": []
+            }
+        }
+    ]).as_array().unwrap().clone());
+
     serde_json::json!({ "tools": tools })
 }
 
 pub fn initialize_response() -> Value {
     serde_json::json!({
         "protocolVersion": "2025-03-26",
-        "capabilities": { "tools": {} },
+        "capabilities": { "tools": {}, "resources": { "subscribe": true }, "prompts": {} },
         "serverInfo": { "name": crate::APP_DISPLAY_NAME, "version": crate::APP_VERSION },
         "instructions": format!(
             "You are running inside a maiTerm terminal tab. At the start of every session (new, resume, compact, clear), \
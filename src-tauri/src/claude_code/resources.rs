@@ -0,0 +1,372 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde_json::Value;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use crate::state::workspace::{AppData, TabType};
+use crate::state::AppState;
+use crate::terminal::export::export_text;
+use crate::terminal::handle::restore_headless_terminal;
+
+const SCHEME: &str = "maiterm://";
+
+/// An MCP resource exposed by maiTerm, addressed by a `maiterm://` URI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceUri {
+    /// `maiterm://tab/<tabId>/scrollback` — a terminal tab's buffer as plain text.
+    Scrollback(String),
+    /// `maiterm://tab/<tabId>/notes` — a tab's notes scratchpad.
+    TabNotes(String),
+    /// `maiterm://tab/<tabId>/editor` — an editor tab's buffer, unsaved edits included.
+    Editor(String),
+    /// `maiterm://workspace/<workspaceId>/notes/<noteId>` — one workspace note.
+    WorkspaceNote { workspace_id: String, note_id: String },
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let parts: Vec<&str> = uri.strip_prefix(SCHEME)?.split('/').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        match parts.as_slice() {
+            ["tab", tab_id, "scrollback"] => Some(Self::Scrollback(tab_id.to_string())),
+            ["tab", tab_id, "notes"] => Some(Self::TabNotes(tab_id.to_string())),
+            ["tab", tab_id, "editor"] => Some(Self::Editor(tab_id.to_string())),
+            ["workspace", workspace_id, "notes", note_id] => Some(Self::WorkspaceNote {
+                workspace_id: workspace_id.to_string(),
+                note_id: note_id.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ResourceUri {
+    /// The tab or workspace this resource belongs to, as tool-style
+    /// arguments for the MCP scope check.
+    pub fn scope_target(&self) -> Value {
+        match self {
+            Self::Scrollback(tab_id) | Self::TabNotes(tab_id) | Self::Editor(tab_id) => {
+                serde_json::json!({ "tabId": tab_id })
+            }
+            Self::WorkspaceNote { workspace_id, .. } => serde_json::json!({ "workspaceId": workspace_id }),
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scrollback(tab_id) => write!(f, "{}tab/{}/scrollback", SCHEME, tab_id),
+            Self::TabNotes(tab_id) => write!(f, "{}tab/{}/notes", SCHEME, tab_id),
+            Self::Editor(tab_id) => write!(f, "{}tab/{}/editor", SCHEME, tab_id),
+            Self::WorkspaceNote { workspace_id, note_id } => {
+                write!(f, "{}workspace/{}/notes/{}", SCHEME, workspace_id, note_id)
+            }
+        }
+    }
+}
+
+/// `resources/list`: scrollback for every terminal tab that has a live or
+/// saved buffer, non-empty tab notes, open editor buffers and workspace notes,
+/// limited to those `visible` lets through (the caller's MCP scope).
+pub fn list(state: &AppState, visible: impl Fn(&AppData, &ResourceUri) -> bool) -> Value {
+    let live_tabs: HashSet<String> = state.tab_pty_map.read().keys().cloned().collect();
+    let app_data = state.app_data.read();
+    let mut resources = Vec::new();
+    let mut push = |uri: ResourceUri, name: String, description: String, mime_type: &str| {
+        if visible(&app_data, &uri) {
+            resources.push(entry(uri, name, description, mime_type));
+        }
+    };
+
+    for ws in app_data.windows.iter().flat_map(|win| &win.workspaces) {
+        for tab in ws.panes.iter().flat_map(|p| &p.tabs) {
+            match tab.tab_type {
+                TabType::Terminal => {
+                    if live_tabs.contains(&tab.id) || state.scrollback_db.has(&tab.id).unwrap_or(false) {
+                        push(
+                            ResourceUri::Scrollback(tab.id.clone()),
+                            format!("{} (scrollback)", tab.name),
+                            format!("Terminal output of tab '{}' in workspace '{}'", tab.name, ws.name),
+                            "text/plain",
+                        );
+                    }
+                }
+                TabType::Editor => {
+                    if let Some(file) = &tab.editor_file {
+                        push(
+                            ResourceUri::Editor(tab.id.clone()),
+                            format!("{} (editor)", tab.name),
+                            format!("Editor buffer for {}, including unsaved edits", file.file_path),
+                            "text/plain",
+                        );
+                    }
+                }
+                TabType::Diff | TabType::Playback | TabType::Transcript => {}
+            }
+            if tab.notes.as_deref().is_some_and(|n| !n.trim().is_empty()) {
+                push(
+                    ResourceUri::TabNotes(tab.id.clone()),
+                    format!("{} (notes)", tab.name),
+                    format!("Notes of tab '{}' in workspace '{}'", tab.name, ws.name),
+                    "text/markdown",
+                );
+            }
+        }
+        for note in &ws.workspace_notes {
+            push(
+                ResourceUri::WorkspaceNote { workspace_id: ws.id.clone(), note_id: note.id.clone() },
+                note_title(&note.content),
+                format!("Workspace note in '{}'", ws.name),
+                "text/markdown",
+            );
+        }
+    }
+
+    serde_json::json!({ "resources": resources })
+}
+
+fn entry(uri: ResourceUri, name: String, description: String, mime_type: &str) -> Value {
+    serde_json::json!({
+        "uri": uri.to_string(),
+        "name": name,
+        "description": description,
+        "mimeType": mime_type,
+    })
+}
+
+/// First non-empty line of a note (heading markers stripped), capped at 60 chars.
+fn note_title(content: &str) -> String {
+    let line = content
+        .lines()
+        .map(|l| l.trim_start_matches('#').trim())
+        .find(|l| !l.is_empty())
+        .unwrap_or("Untitled note");
+    match line.char_indices().nth(60) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+/// `resources/read`. Editor buffers are asked of the frontend so unsaved
+/// edits are included; everything else is read from backend state.
+pub async fn read(state: &Arc<AppState>, app_handle: &AppHandle, uri: &str) -> Result<Value, String> {
+    let resource = ResourceUri::parse(uri).ok_or_else(|| format!("Unknown resource URI: {}", uri))?;
+    let (text, mime_type) = match &resource {
+        ResourceUri::Scrollback(tab_id) => (read_scrollback(state, tab_id)?, "text/plain"),
+        ResourceUri::TabNotes(tab_id) => {
+            let app_data = state.app_data.read();
+            let (_, tab) = app_data.find_tab(tab_id).ok_or_else(|| format!("Tab not found: {}", tab_id))?;
+            (tab.notes.clone().unwrap_or_default(), "text/markdown")
+        }
+        ResourceUri::WorkspaceNote { workspace_id, note_id } => {
+            let app_data = state.app_data.read();
+            let note = app_data
+                .windows
+                .iter()
+                .flat_map(|win| &win.workspaces)
+                .find(|ws| &ws.id == workspace_id)
+                .and_then(|ws| ws.workspace_notes.iter().find(|n| &n.id == note_id))
+                .ok_or_else(|| format!("Workspace note not found: {}", note_id))?;
+            (note.content.clone(), "text/markdown")
+        }
+        ResourceUri::Editor(tab_id) => (read_editor(state, app_handle, tab_id).await?, "text/plain"),
+    };
+    Ok(serde_json::json!({
+        "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }]
+    }))
+}
+
+/// The live buffer when the tab has a running terminal, else its saved scrollback.
+fn read_scrollback(state: &AppState, tab_id: &str) -> Result<String, String> {
+    let pty_id = state.tab_pty_map.read().get(tab_id).cloned();
+    if let Some(terminal) = pty_id.and_then(|id| state.terminal_registry.get(&id)) {
+        let text = export_text(&terminal.lock().term);
+        return Ok(text);
+    }
+    let scrollback = state
+        .scrollback_db
+        .load(tab_id)?
+        .ok_or_else(|| format!("Tab '{}' has no running terminal or saved scrollback", tab_id))?;
+    let term = restore_headless_terminal(&scrollback, state.scrollback_db.saved_size(tab_id)?);
+    Ok(export_text(&term))
+}
+
+/// The open editor's content; falls back to the file on disk when the editor
+/// isn't mounted (e.g. its workspace hasn't been visited since launch).
+async fn read_editor(state: &Arc<AppState>, app_handle: &AppHandle, tab_id: &str) -> Result<String, String> {
+    let file = {
+        let app_data = state.app_data.read();
+        let (_, tab) = app_data.find_tab(tab_id).ok_or_else(|| format!("Tab not found: {}", tab_id))?;
+        tab.editor_file.clone().ok_or_else(|| format!("Tab '{}' is not an editor tab", tab_id))?
+    };
+    let buffer = super::server::request_frontend(
+        state,
+        app_handle,
+        "readEditorBuffer",
+        serde_json::json!({ "tabId": tab_id }),
+    )
+    .await?;
+    if let Some(content) = buffer.get("content").and_then(|v| v.as_str()) {
+        return Ok(content.to_string());
+    }
+    if file.is_remote {
+        return Err(format!("Remote file {} is not loaded in an editor", file.file_path));
+    }
+    std::fs::read_to_string(&file.file_path).map_err(|e| format!("Failed to read {}: {}", file.file_path, e))
+}
+
+/// `resources/subscribe` state: which URIs each push-capable connection
+/// (WebSocket or legacy SSE) watches. Changes are collected by
+/// `mark_changed` and sent in batches by `flush`, so a busy terminal yields
+/// one notification per flush rather than one per output chunk.
+#[derive(Default)]
+pub struct ResourceSubscriptions {
+    subscribers: HashMap<String, Subscriber>,
+    changed: HashSet<String>,
+    /// Whether anyone subscribed, readable without the lock (`AppState::resources_watched`).
+    watched: Arc<AtomicBool>,
+}
+
+struct Subscriber {
+    tx: mpsc::UnboundedSender<String>,
+    uris: HashSet<String>,
+}
+
+impl ResourceSubscriptions {
+    pub fn subscribe(&mut self, connection_id: &str, tx: &mpsc::UnboundedSender<String>, uri: &str) {
+        let subscriber = self
+            .subscribers
+            .entry(connection_id.to_string())
+            .or_insert_with(|| Subscriber { tx: tx.clone(), uris: HashSet::new() });
+        subscriber.uris.insert(uri.to_string());
+        self.update_watched();
+    }
+
+    pub fn unsubscribe(&mut self, connection_id: &str, uri: &str) {
+        if let Some(subscriber) = self.subscribers.get_mut(connection_id) {
+            subscriber.uris.remove(uri);
+            if subscriber.uris.is_empty() {
+                self.subscribers.remove(connection_id);
+            }
+        }
+        self.update_watched();
+    }
+
+    pub fn remove_connection(&mut self, connection_id: &str) {
+        self.subscribers.remove(connection_id);
+        self.update_watched();
+    }
+
+    /// Flag that is set while there are subscribers, for hot paths (PTY
+    /// output) that shouldn't take the lock for every chunk.
+    pub fn watched(&self) -> Arc<AtomicBool> {
+        self.watched.clone()
+    }
+
+    fn update_watched(&self) {
+        self.watched.store(!self.subscribers.is_empty(), Ordering::Relaxed);
+    }
+
+    /// Whether no connection is subscribed to anything.
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Queue `notifications/resources/updated` for `uri` if anyone watches it.
+    pub fn mark_changed(&mut self, uri: &ResourceUri) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        let uri = uri.to_string();
+        if self.subscribers.values().any(|s| s.uris.contains(&uri)) {
+            self.changed.insert(uri);
+        }
+    }
+
+    /// Send the queued notifications and drop subscribers whose transport closed.
+    pub fn flush(&mut self) {
+        for uri in self.changed.drain() {
+            let notification = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": { "uri": uri },
+            })
+            .to_string();
+            for subscriber in self.subscribers.values().filter(|s| s.uris.contains(&uri)) {
+                let _ = subscriber.tx.send(notification.clone());
+            }
+        }
+        self.subscribers.retain(|_, s| !s.tx.is_closed());
+        self.update_watched();
+    }
+}
+
+/// Record a change to `uri` for subscribed clients.
+pub fn mark_changed(state: &AppState, uri: ResourceUri) {
+    state.resource_subscriptions.lock().mark_changed(&uri);
+}
+
+/// Called for every chunk of PTY output; only takes the lock while someone
+/// is subscribed.
+pub fn scrollback_changed(state: &AppState, tab_id: &str) {
+    if state.resources_watched.load(Ordering::Relaxed) {
+        mark_changed(state, ResourceUri::Scrollback(tab_id.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uris_round_trip() {
+        for uri in [
+            "maiterm://tab/t1/scrollback",
+            "maiterm://tab/t1/notes",
+            "maiterm://tab/t1/editor",
+            "maiterm://workspace/w1/notes/n1",
+        ] {
+            assert_eq!(ResourceUri::parse(uri).unwrap().to_string(), uri);
+        }
+        assert_eq!(ResourceUri::parse("maiterm://tab//notes"), None);
+        assert_eq!(ResourceUri::parse("maiterm://tab/t1/other"), None);
+        assert_eq!(ResourceUri::parse("file:///tab/t1/notes"), None);
+    }
+
+    #[test]
+    fn changes_are_batched_per_subscriber() {
+        let (tx_a, mut rx_a) = mpsc::unbounded_channel();
+        let (tx_b, mut rx_b) = mpsc::unbounded_channel();
+        let mut subs = ResourceSubscriptions::default();
+        let watched = subs.watched();
+        subs.subscribe("a", &tx_a, "maiterm://tab/t1/scrollback");
+        assert!(watched.load(Ordering::Relaxed));
+        subs.subscribe("b", &tx_b, "maiterm://tab/t1/notes");
+
+        for _ in 0..3 {
+            subs.mark_changed(&ResourceUri::Scrollback("t1".into()));
+        }
+        subs.mark_changed(&ResourceUri::Scrollback("t2".into()));
+        subs.flush();
+
+        let sent: Value = serde_json::from_str(&rx_a.try_recv().unwrap()).unwrap();
+        assert_eq!(sent["method"], "notifications/resources/updated");
+        assert_eq!(sent["params"]["uri"], "maiterm://tab/t1/scrollback");
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_b.try_recv().is_err());
+
+        drop(rx_b);
+        subs.flush();
+        assert!(!subs.subscribers.contains_key("b"));
+        subs.unsubscribe("a", "maiterm://tab/t1/scrollback");
+        assert!(subs.is_empty());
+        assert!(!watched.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::tokens::TokenOwner;
+use crate::pty::clipboard::ssh_host;
+use crate::state::app_state::SshTunnel;
+use crate::state::workspace::{AppData, McpScope};
+use crate::state::AppState;
+
+/// Tools that only read state. Anything not listed here or in `ADMIN_TOOLS`
+/// (including tools added later) counts as a write.
+const READ_TOOLS: &[&str] = &[
+    "initSession", "getOpenEditors", "getWorkspaceFolders", "getDiagnostics", "readLogs",
+    "checkDocumentDirty", "getCurrentSelection", "getLatestSelection", "listWindows",
+    "listWorkspaces", "getTabNotes", "listWorkspaceNotes", "readWorkspaceNote", "getTabContext",
+    "getActiveTab", "getTriggerVariables", "getAutoResume", "findNotes", "getPreferences",
+    "getClaudeSessions", "getHookTimeline", "listArchivedTabs", "getBridgedAgent", "waitForOutput",
+    // MCP prompt and resource methods, checked against the tab or workspace they address
+    "prompts/list", "prompts/get", "resources/list", "resources/read", "resources/subscribe",
+];
+
+/// Tools that change the app as a whole rather than a tab or workspace.
+const ADMIN_TOOLS: &[&str] = &["setPreference", "createBackup"];
+
+/// Tools whose answer spans every window and workspace, so they need `Full`.
+const APP_WIDE_TOOLS: &[&str] = &[
+    "readLogs", "listWindows", "listWorkspaces", "findNotes", "getPreferences",
+    "getClaudeSessions", "getActiveTab",
+];
+
+/// Tools that fall back to the active workspace when `workspaceId` is omitted.
+/// Scoped callers get their own workspace instead.
+const WORKSPACE_DEFAULT_TOOLS: &[&str] = &[
+    "listWorkspaceNotes", "readWorkspaceNote", "writeWorkspaceNote", "deleteWorkspaceNote",
+    "moveNote", "listArchivedTabs", "restoreArchivedTab", "createTab",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Access {
+    Read,
+    Write,
+    Admin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Reach {
+    OwnTab,
+    Workspace,
+    All,
+}
+
+/// What a caller may do: which tools, and on which tabs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grant {
+    access: Access,
+    reach: Reach,
+}
+
+impl Grant {
+    pub const FULL: Grant = Grant { access: Access::Admin, reach: Reach::All };
+
+    fn of(scope: McpScope) -> Self {
+        let (access, reach) = match scope {
+            McpScope::ReadOnly => (Access::Read, Reach::Workspace),
+            McpScope::OwnTab => (Access::Write, Reach::OwnTab),
+            McpScope::Workspace => (Access::Write, Reach::Workspace),
+            McpScope::Full => (Access::Admin, Reach::All),
+        };
+        Grant { access, reach }
+    }
+
+    /// Narrow this grant by `scope` (e.g. a host scope and a workspace scope).
+    fn restrict(self, scope: McpScope) -> Self {
+        let other = Self::of(scope);
+        Grant {
+            access: self.access.min(other.access),
+            reach: self.reach.min(other.reach),
+        }
+    }
+
+    /// Name for the audit log, matching the `McpScope` names where one applies.
+    pub fn label(&self) -> &'static str {
+        match (self.access, self.reach) {
+            (Access::Admin, Reach::All) => "full",
+            (Access::Read, Reach::OwnTab) => "read_only+own_tab",
+            (Access::Read, _) => "read_only",
+            (_, Reach::OwnTab) => "own_tab",
+            _ => "workspace",
+        }
+    }
+}
+
+/// Outcome of checking one tool call against the caller's scope.
+pub struct Authorization {
+    pub grant: Grant,
+    /// SSH host the caller was reached through, if any
+    pub host: Option<String>,
+    /// Why the call was refused
+    pub denied: Option<String>,
+}
+
+/// Work out the caller's grant and check `tool` against it. `owner` is whose
+/// auth token the connection presented, which tells remote callers apart;
+/// `caller_tab` is the tab it acts as (see `caller_tab`). Scoped callers get `workspaceId` filled in where the tool would
+/// otherwise pick the active workspace.
+pub fn authorize(
+    state: &AppState,
+    owner: &TokenOwner,
+    caller_tab: Option<&str>,
+    tool: &str,
+    arguments: &mut Value,
+) -> Authorization {
+    let host = match owner {
+        TokenOwner::Local => None,
+        TokenOwner::Host(host_key) => {
+            Some(ssh_host(&format!("ssh {}", host_key)).unwrap_or_else(|| host_key.to_lowercase()))
+        }
+    };
+    let session_tab = arguments
+        .get("sessionId")
+        .and_then(|v| v.as_str())
+        .filter(|_| tool != "initSession")
+        .map(|sid| state.claude_sessions.read().get(sid).map(|s| s.tab_id.clone()));
+
+    let app_data = state.app_data.read();
+    let prefs = &app_data.preferences;
+    let mut grant = Grant::FULL;
+    if let Some(host) = &host {
+        let scope = prefs.mcp_host_scopes.get(host).copied().unwrap_or(prefs.mcp_remote_scope);
+        grant = grant.restrict(scope);
+    }
+    let caller_ws = caller_tab.and_then(|tab| workspace_of_tab(&app_data, tab));
+    if let Some(scope) = caller_ws.and_then(|ws| prefs.mcp_workspace_scopes.get(ws)) {
+        grant = grant.restrict(*scope);
+    }
+
+    if grant.reach != Reach::All && WORKSPACE_DEFAULT_TOOLS.contains(&tool) {
+        if let (Some(ws), Some(obj)) = (caller_ws, arguments.as_object_mut()) {
+            if obj.get("workspaceId").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).is_none() {
+                obj.insert("workspaceId".to_string(), Value::String(ws.to_string()));
+            }
+        }
+    }
+
+    let denied = check(&app_data, grant, caller_tab, tool, arguments, session_tab).err();
+    Authorization { grant, host, denied }
+}
+
+/// Whether `owner` may bind its connection to `tab_id` in `initSession`. A
+/// host's token only covers the tabs its tunnel serves, so a remote caller
+/// can't take on a local tab's (or another host's) identity and scope.
+pub fn may_bind_tab(tunnels: &HashMap<String, SshTunnel>, owner: &TokenOwner, tab_id: &str) -> bool {
+    match owner {
+        TokenOwner::Local => true,
+        TokenOwner::Host(host_key) => tunnels.get(host_key).is_some_and(|t| t.tab_ids.contains(tab_id)),
+    }
+}
+
+/// Whether a hook event from `owner` naming `tab_id` is accepted. A host
+/// must name a tab its tunnel serves; only local hooks may leave the tab out
+/// (a session waiting for `initSession`), which a local agent could claim.
+pub fn may_post_hook(tunnels: &HashMap<String, SshTunnel>, owner: &TokenOwner, tab_id: Option<&str>) -> bool {
+    match tab_id {
+        Some(tab_id) => may_bind_tab(tunnels, owner, tab_id),
+        None => matches!(owner, TokenOwner::Local),
+    }
+}
+
+/// The tab a tool call acts as: the connection's `bound` tab, else the
+/// `tabId` it names. A host's connection can't claim a tab outside its
+/// tunnel by skipping `initSession`, so such a call is refused.
+pub fn caller_tab(
+    tunnels: &HashMap<String, SshTunnel>,
+    owner: &TokenOwner,
+    bound: Option<&str>,
+    arguments: &Value,
+) -> Result<Option<String>, String> {
+    if let Some(tab) = bound {
+        return Ok(Some(tab.to_string()));
+    }
+    let Some(claimed) = arguments.get("tabId").and_then(|v| v.as_str()).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    if !may_bind_tab(tunnels, owner, claimed) {
+        return Err(format!("Tab '{}' is not served by this SSH host's MCP tunnel", claimed));
+    }
+    Ok(Some(claimed.to_string()))
+}
+
+/// Whether `grant` allows `tool` on the target named in `arguments`; used to
+/// filter listings entry by entry.
+pub fn permits(app_data: &AppData, grant: Grant, caller_tab: Option<&str>, tool: &str, arguments: &Value) -> bool {
+    check(app_data, grant, caller_tab, tool, arguments, None).is_ok()
+}
+
+/// `session_tab` is `Some` when the call names a `sessionId`, holding the tab
+/// that session runs in if it's known.
+fn check(
+    app_data: &AppData,
+    grant: Grant,
+    caller_tab: Option<&str>,
+    tool: &str,
+    arguments: &Value,
+    session_tab: Option<Option<String>>,
+) -> Result<(), String> {
+    let access = if READ_TOOLS.contains(&tool) {
+        Access::Read
+    } else if ADMIN_TOOLS.contains(&tool) {
+        Access::Admin
+    } else {
+        Access::Write
+    };
+    if access > grant.access {
+        return Err(format!("'{}' is not allowed in MCP scope '{}'", tool, grant.label()));
+    }
+    if grant.reach == Reach::All {
+        return Ok(());
+    }
+    if APP_WIDE_TOOLS.contains(&tool) {
+        return Err(format!(
+            "'{}' reads every workspace and is not allowed in MCP scope '{}'",
+            tool,
+            grant.label()
+        ));
+    }
+
+    let arg = |key: &str| arguments.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let caller_ws = caller_tab.and_then(|tab| workspace_of_tab(app_data, tab));
+    let out_of_scope = || {
+        Err(format!(
+            "'{}' targets a tab outside MCP scope '{}' (the caller's own {})",
+            tool,
+            grant.label(),
+            if grant.reach == Reach::OwnTab { "tab" } else { "workspace" }
+        ))
+    };
+
+    let mut tabs: Vec<&str> = ["tabId", "targetTabId"].iter().filter_map(|k| arg(k)).collect();
+    if let Some(found) = &session_tab {
+        match found {
+            Some(tab) => tabs.push(tab),
+            None => return out_of_scope(),
+        }
+    }
+    for tab in tabs {
+        let allowed = match grant.reach {
+            Reach::OwnTab => caller_tab == Some(tab),
+            _ => caller_ws.is_some() && workspace_of_tab(app_data, tab) == caller_ws,
+        };
+        if !allowed {
+            return out_of_scope();
+        }
+    }
+
+    let mut workspaces: Vec<Option<&str>> = Vec::new();
+    if let Some(ws) = arg("workspaceId") {
+        workspaces.push(Some(ws));
+    }
+    if let Some(pane) = arg("paneId") {
+        workspaces.push(workspace_of_pane(app_data, pane));
+    }
+    for ws in workspaces {
+        if grant.reach == Reach::OwnTab || ws.is_none() || ws != caller_ws {
+            return out_of_scope();
+        }
+    }
+    Ok(())
+}
+
+/// Workspace holding `tab_id`, open or archived.
+fn workspace_of_tab<'a>(app_data: &'a AppData, tab_id: &str) -> Option<&'a str> {
+    app_data
+        .windows
+        .iter()
+        .flat_map(|w| &w.workspaces)
+        .find(|ws| {
+            ws.panes.iter().any(|p| p.tabs.iter().any(|t| t.id == tab_id))
+                || ws.archived_tabs.iter().any(|t| t.id == tab_id)
+        })
+        .map(|ws| ws.id.as_str())
+}
+
+fn workspace_of_pane<'a>(app_data: &'a AppData, pane_id: &str) -> Option<&'a str> {
+    app_data
+        .windows
+        .iter()
+        .flat_map(|w| &w.workspaces)
+        .find(|ws| ws.panes.iter().any(|p| p.id == pane_id))
+        .map(|ws| ws.id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn scopes_narrow_to_the_weakest_part() {
+        let host = Grant::FULL.restrict(McpScope::ReadOnly);
+        assert_eq!(host.label(), "read_only");
+        assert_eq!(host.restrict(McpScope::OwnTab).label(), "read_only+own_tab");
+        assert_eq!(Grant::FULL.restrict(McpScope::Workspace).restrict(McpScope::Full).label(), "workspace");
+        assert_eq!(Grant::FULL.restrict(McpScope::Full), Grant::FULL);
+    }
+
+    #[test]
+    fn tools_are_checked_by_access_and_target() {
+        let app_data: AppData = serde_json::from_value(json!({
+            "windows": [{
+                "id": "w1",
+                "label": "main",
+                "workspaces": [
+                    {
+                        "id": "ws1",
+                        "name": "One",
+                        "panes": [{ "id": "p1", "name": "", "tabs": [tab("t1"), tab("t2")] }],
+                        "archived_tabs": [tab("t3")]
+                    },
+                    {
+                        "id": "ws2",
+                        "name": "Two",
+                        "panes": [{ "id": "p2", "name": "", "tabs": [tab("t4")] }]
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+        let call = |scope: McpScope, tool: &str, args: Value| {
+            check(&app_data, Grant::FULL.restrict(scope), Some("t1"), tool, &args, None).is_ok()
+        };
+
+        assert!(call(McpScope::Full, "setPreference", json!({ "key": "font_size" })));
+        assert!(!call(McpScope::Workspace, "setPreference", json!({ "key": "font_size" })));
+        assert!(!call(McpScope::Workspace, "listWorkspaces", json!({})));
+
+        assert!(call(McpScope::Workspace, "runInTab", json!({ "targetTabId": "t2" })));
+        assert!(call(McpScope::Workspace, "restoreArchivedTab", json!({ "tabId": "t3" })));
+        assert!(call(McpScope::Workspace, "splitPane", json!({ "paneId": "p1" })));
+        assert!(!call(McpScope::Workspace, "runInTab", json!({ "targetTabId": "t4" })));
+        assert!(!call(McpScope::Workspace, "createTab", json!({ "workspaceId": "ws2" })));
+        assert!(!call(McpScope::Workspace, "switchTab", json!({ "tabId": "missing" })));
+
+        assert!(call(McpScope::OwnTab, "setTabNotes", json!({ "tabId": "t1" })));
+        assert!(!call(McpScope::OwnTab, "waitForOutput", json!({ "tabId": "t1", "targetTabId": "t2" })));
+        assert!(!call(McpScope::OwnTab, "listWorkspaceNotes", json!({ "workspaceId": "ws1" })));
+
+        assert!(call(McpScope::ReadOnly, "waitForOutput", json!({ "targetTabId": "t2" })));
+        assert!(!call(McpScope::ReadOnly, "setTabNotes", json!({ "tabId": "t1" })));
+        assert!(!call(McpScope::ReadOnly, "someFutureTool", json!({})));
+
+        assert!(call(McpScope::ReadOnly, "resources/read", json!({ "tabId": "t2" })));
+        assert!(!call(McpScope::ReadOnly, "resources/read", json!({ "tabId": "t4" })));
+        assert!(!call(McpScope::ReadOnly, "resources/subscribe", json!({ "workspaceId": "ws2" })));
+        assert!(!call(McpScope::OwnTab, "resources/read", json!({ "tabId": "t2" })));
+        assert!(call(McpScope::OwnTab, "prompts/get", json!({})));
+
+        let grant = Grant::FULL.restrict(McpScope::Workspace);
+        let timeline = json!({ "tabId": "t1", "sessionId": "s" });
+        assert!(check(&app_data, grant, Some("t1"), "getHookTimeline", &timeline, Some(Some("t2".into()))).is_ok());
+        assert!(check(&app_data, grant, Some("t1"), "getHookTimeline", &timeline, Some(None)).is_err());
+    }
+
+    #[test]
+    fn hosts_only_bind_their_own_tabs() {
+        let tunnels = tunnels();
+        let host = |key: &str| TokenOwner::Host(key.to_string());
+
+        assert!(may_bind_tab(&tunnels, &host("dev@box"), "t1"));
+        assert!(!may_bind_tab(&tunnels, &host("dev@box"), "t2"));
+        assert!(!may_bind_tab(&tunnels, &host("other"), "t1"));
+        assert!(may_bind_tab(&tunnels, &TokenOwner::Local, "t2"));
+    }
+
+    #[test]
+    fn unbound_hosts_cannot_claim_local_tabs() {
+        let tunnels = tunnels();
+        let host = TokenOwner::Host("dev@box".into());
+        let local_tab = json!({ "tabId": "t2" });
+
+        assert!(caller_tab(&tunnels, &host, None, &local_tab).is_err());
+        assert_eq!(caller_tab(&tunnels, &host, None, &json!({ "tabId": "t1" })), Ok(Some("t1".into())));
+        assert_eq!(caller_tab(&tunnels, &host, Some("t1"), &local_tab), Ok(Some("t1".into())));
+        assert_eq!(caller_tab(&tunnels, &host, None, &json!({})), Ok(None));
+        assert_eq!(caller_tab(&tunnels, &TokenOwner::Local, None, &local_tab), Ok(Some("t2".into())));
+    }
+
+    #[test]
+    fn host_hooks_only_reach_their_own_tabs() {
+        let tunnels = tunnels();
+        let host = TokenOwner::Host("dev@box".into());
+
+        assert!(may_post_hook(&tunnels, &host, Some("t1")));
+        assert!(!may_post_hook(&tunnels, &host, Some("t2")));
+        assert!(!may_post_hook(&tunnels, &host, None));
+        assert!(may_post_hook(&tunnels, &TokenOwner::Local, Some("t2")));
+        assert!(may_post_hook(&tunnels, &TokenOwner::Local, None));
+    }
+
+    /// One tunnel, to `dev@box`, serving tab `t1`.
+    fn tunnels() -> HashMap<String, SshTunnel> {
+        let tunnel = SshTunnel {
+            pid: 1,
+            remote_port: 2222,
+            host_key: "dev@box".into(),
+            tab_ids: ["t1".to_string()].into_iter().collect(),
+            ssh_args: "dev@box".into(),
+            local_port: 1234,
+            muxed: false,
+            health: Default::default(),
+        };
+        HashMap::from([("dev@box".to_string(), tunnel)])
+    }
+
+    fn tab(id: &str) -> Value {
+        json!({ "id": id, "name": id })
+    }
+}
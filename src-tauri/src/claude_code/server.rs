@@ -22,6 +22,11 @@ use tokio::sync::{mpsc, oneshot};
 
 use super::lockfile::{cleanup_stale_lockfiles, ensure_mcp_settings, write_lockfile};
 use super::protocol::{initialize_response, tool_list_response, JsonRpcRequest, JsonRpcResponse};
+use super::agents::{self, AgentKind};
+use super::scopes::{self, Authorization};
+use super::tokens::{generate_token, TokenOwner};
+use super::{hook_policy, prompts, resources, tab_runner};
+use crate::state::mcp_audit_db::NewMcpAuditEntry;
 use crate::state::AppState;
 
 const PING_INTERVAL: Duration = Duration::from_secs(30);
@@ -31,6 +36,9 @@ const SSE_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
 /// The file is co-owned by the `claude` CLI, which can clobber our entry; this
 /// heals any drift within one tick. Idempotent — only writes when it differs.
 const MCP_REASSERT_INTERVAL: Duration = Duration::from_secs(30);
+/// How often queued `notifications/resources/updated` are sent. Also the
+/// most a busy terminal's scrollback subscribers get notified.
+const RESOURCE_NOTIFY_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Per-SSE-session sender: receives raw JSON strings, which the SSE stream wraps as data events.
 type SseSessions = Arc<parking_lot::RwLock<HashMap<String, mpsc::UnboundedSender<String>>>>;
@@ -43,7 +51,6 @@ type ConnectionTabMap = Arc<parking_lot::RwLock<HashMap<String, String>>>;
 struct ServerState {
     app_handle: AppHandle,
     state: Arc<AppState>,
-    expected_auth: String,
     sse_sessions: SseSessions,
     /// Maps connection IDs (SSE session, WS id, or "streamable-http") to tab IDs.
     connection_tabs: ConnectionTabMap,
@@ -56,11 +63,11 @@ struct ServerState {
 
 /// Result of the synchronous server preparation step. Holds the bound TCP
 /// listener (std form — converted to tokio inside `serve_server`) along with
-/// the port and auth token that were already written into `~/.claude.json`.
+/// the port that was already written into `~/.claude.json`. The auth token
+/// lives in `AppState::mcp_tokens`, since it rotates.
 pub struct ServerSetup {
     pub std_listener: std::net::TcpListener,
     pub port: u16,
-    pub auth: String,
 }
 
 /// Synchronous prep that must complete before the frontend is allowed to
@@ -98,14 +105,11 @@ pub fn prepare_server(state: &Arc<AppState>) -> Option<ServerSetup> {
     };
     let port = std_listener.local_addr().ok()?.port();
 
-    let auth: String = rng
-        .sample_iter(&rand::distributions::Alphanumeric)
-        .take(32)
-        .map(char::from)
-        .collect();
+    let auth = generate_token();
 
     *state.claude_code_port.write() = Some(port);
     *state.claude_code_auth.write() = Some(auth.clone());
+    state.mcp_tokens.lock().set_local(auth.clone());
 
     let workspace_folders = collect_workspace_folders(state);
     let hooks_enabled = state.app_data.read().preferences.claude_code_hooks;
@@ -115,7 +119,59 @@ pub fn prepare_server(state: &Arc<AppState>) -> Option<ServerSetup> {
 
     log::info!("Claude Code IDE server bound on http://127.0.0.1:{}", port);
 
-    Some(ServerSetup { std_listener, port, auth })
+    Some(ServerSetup { std_listener, port })
+}
+
+/// Replace the local auth token and rewrite the lock file and every agent's
+/// MCP and hook settings with it. The old token stays valid for `grace_ms`
+/// so running sessions, which read it at startup, aren't cut off at once.
+pub fn rotate_local_token(state: &Arc<AppState>, grace_ms: i64) -> Result<(), String> {
+    let port = (*state.claude_code_port.read()).ok_or("MCP server is not running")?;
+    let auth = state
+        .mcp_tokens
+        .lock()
+        .rotate(&TokenOwner::Local, grace_ms)
+        .ok_or("MCP server is not running")?;
+    *state.claude_code_auth.write() = Some(auth.clone());
+    let hooks_enabled = state.app_data.read().preferences.claude_code_hooks;
+    write_lockfile(port, &auth, collect_workspace_folders(state), hooks_enabled)?;
+    log::info!("Rotated local MCP auth token");
+    Ok(())
+}
+
+/// Replace the token of `host_key`'s SSH tunnel. The frontend re-runs the
+/// remote setup on `mcp-host-token-rotated` to install the new one.
+pub fn rotate_host_token(state: &AppState, app_handle: &AppHandle, host_key: &str, grace_ms: i64) -> Result<(), String> {
+    state
+        .mcp_tokens
+        .lock()
+        .rotate(&TokenOwner::Host(host_key.to_string()), grace_ms)
+        .ok_or_else(|| format!("No MCP token issued for {}", host_key))?;
+    let _ = app_handle.emit("mcp-host-token-rotated", host_key);
+    log::info!("Rotated MCP auth token for {}", host_key);
+    Ok(())
+}
+
+/// Rotate every token older than `mcp_local_token_rotation_hours` (the local
+/// token) or `mcp_token_rotation_hours` (SSH hosts). Rotated-out tokens are
+/// accepted for one more period. Local rotation is off by default: local
+/// sessions read the token once at startup and can outlive any grace period.
+fn rotate_due_tokens(state: &Arc<AppState>, app_handle: &AppHandle) {
+    let (local_ms, host_ms) = {
+        let prefs = &state.app_data.read().preferences;
+        let ms = |hours: u32| hours as i64 * 60 * 60 * 1000;
+        (ms(prefs.mcp_local_token_rotation_hours), ms(prefs.mcp_token_rotation_hours))
+    };
+    let due = state.mcp_tokens.lock().due_for_rotation(local_ms, host_ms);
+    for owner in due {
+        let result = match &owner {
+            TokenOwner::Local => rotate_local_token(state, local_ms),
+            TokenOwner::Host(host_key) => rotate_host_token(state, app_handle, host_key, host_ms),
+        };
+        if let Err(e) = result {
+            log::warn!("MCP token rotation failed: {}", e);
+        }
+    }
 }
 
 /// Start serving the axum router on the pre-bound listener. Runs forever
@@ -137,20 +193,25 @@ pub async fn serve_server(app_handle: AppHandle, state: Arc<AppState>, setup: Se
     // Graceful shutdown signal — sender stored in AppState, triggered on app exit
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::watch::channel(false);
     let mut reassert_shutdown = shutdown_tx.subscribe();
+    let mut notify_shutdown = shutdown_tx.subscribe();
     *state.claude_code_shutdown.lock() = Some(shutdown_tx);
 
     // Periodically re-assert our `~/.claude.json` entry so a clobber by the
     // co-owning `claude` CLI self-heals instead of leaving Claude Code dialing a
-    // dead port until the next app restart. Runs until graceful shutdown.
+    // dead port until the next app restart. The same tick rotates tokens that
+    // have outlived their rotation period. Runs until graceful shutdown.
     let reassert_port = setup.port;
-    let reassert_auth = setup.auth.clone();
+    let reassert_state = state.clone();
+    let reassert_app = app_handle.clone();
     tauri::async_runtime::spawn(async move {
         let mut ticker = tokio::time::interval(MCP_REASSERT_INTERVAL);
         ticker.tick().await; // consume the immediate first tick
         loop {
             tokio::select! {
                 _ = ticker.tick() => {
-                    if let Err(e) = ensure_mcp_settings(reassert_port, &reassert_auth) {
+                    rotate_due_tokens(&reassert_state, &reassert_app);
+                    let auth = reassert_state.claude_code_auth.read().clone().unwrap_or_default();
+                    if let Err(e) = ensure_mcp_settings(reassert_port, &auth) {
                         log::warn!("MCP settings re-assert failed: {}", e);
                     }
                 }
@@ -164,13 +225,28 @@ pub async fn serve_server(app_handle: AppHandle, state: Arc<AppState>, setup: Se
         log::debug!("MCP settings re-assert loop stopped");
     });
 
+    // Deliver queued notifications/resources/updated to subscribed clients.
+    let notify_state = state.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(RESOURCE_NOTIFY_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => notify_state.resource_subscriptions.lock().flush(),
+                res = notify_shutdown.changed() => {
+                    if res.is_err() || *notify_shutdown.borrow() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
     let sse_sessions: SseSessions = Arc::new(parking_lot::RwLock::new(HashMap::new()));
     let connection_tabs: ConnectionTabMap = Arc::new(parking_lot::RwLock::new(HashMap::new()));
 
     let server_state = ServerState {
         app_handle,
         state,
-        expected_auth: setup.auth,
         sse_sessions,
         connection_tabs,
         connection_count: Arc::new(AtomicUsize::new(0)),
@@ -186,6 +262,8 @@ pub async fn serve_server(app_handle: AppHandle, state: Arc<AppState>, setup: Se
         .route("/message", post(sse_message_handler))
         // Claude Code hooks — lifecycle events from hook scripts
         .route("/hooks", post(hooks_handler))
+        // Tunnel health probe — proves a forwarded port reaches this process
+        .route("/health", get(health_handler))
         .with_state(server_state);
 
     log::info!("Claude Code IDE server listening on http://127.0.0.1:{}", setup.port);
@@ -262,6 +340,10 @@ fn preference_meta() -> Vec<(&'static str, PrefMeta)> {
         ("shell_integration", PrefMeta { description: "Enable OSC 133 shell integration for command detection", ptype: "boolean", category: "Terminal", read_only: false }),
         ("file_link_action", PrefMeta { description: "How file links in the terminal are activated", ptype: "string", category: "Terminal", read_only: false }),
         ("windows_shell", PrefMeta { description: "Default shell on Windows", ptype: "string", category: "Terminal", read_only: false }),
+        ("osc52_write_policy", PrefMeta { description: "Let programs copy to the clipboard via OSC 52 (always, ask, never)", ptype: "string", category: "Terminal", read_only: false }),
+        ("osc52_read_policy", PrefMeta { description: "Let programs read the clipboard via OSC 52 (always, ask, never)", ptype: "string", category: "Terminal", read_only: false }),
+        ("osc52_max_bytes", PrefMeta { description: "Largest OSC 52 clipboard payload in bytes", ptype: "number", category: "Terminal", read_only: false }),
+        ("osc52_host_policies", PrefMeta { description: "Per-SSH-host OSC 52 clipboard policy overrides", ptype: "object", category: "Terminal", read_only: true }),
         ("theme", PrefMeta { description: "Color theme ID (built-in or custom)", ptype: "string", category: "Appearance", read_only: false }),
         ("auto_save_interval", PrefMeta { description: "Auto-save interval in seconds (0 to disable)", ptype: "number", category: "General", read_only: false }),
         ("restore_session", PrefMeta { description: "Restore tabs and workspaces on app restart", ptype: "boolean", category: "General", read_only: false }),
@@ -293,6 +375,15 @@ fn preference_meta() -> Vec<(&'static str, PrefMeta)> {
         ("claude_code_ide_ssh", PrefMeta { description: "Enable MCP bridge over SSH (reverse tunnel for remote Claude Code)", ptype: "boolean", category: "Integration", read_only: false }),
         ("claude_code_hooks", PrefMeta { description: "Enable hooks integration (session lifecycle events, tab indicators)", ptype: "boolean", category: "Integration", read_only: false }),
         ("claude_code_auto_resume", PrefMeta { description: "Enable hooks-based auto-resume (programmatic session ID capture)", ptype: "boolean", category: "Integration", read_only: false }),
+        ("hook_policy_rules", PrefMeta { description: "Allow/deny/ask/prompt rules for Claude tool calls (list of {id, tool, pattern, decision, workspaces, reason, enabled}); the strictest match wins; not settable by Claude", ptype: "array", category: "Integration", read_only: true }),
+        ("mcp_remote_scope", PrefMeta { description: "MCP scope for clients reached through an SSH tunnel (read_only, own_tab, workspace, full); not settable by Claude", ptype: "string", category: "Integration", read_only: true }),
+        ("mcp_host_scopes", PrefMeta { description: "Per-SSH-host overrides of mcp_remote_scope; not settable by Claude", ptype: "object", category: "Integration", read_only: true }),
+        ("mcp_workspace_scopes", PrefMeta { description: "MCP scope for clients running in a workspace, keyed by workspace ID; not settable by Claude", ptype: "object", category: "Integration", read_only: true }),
+        ("mcp_token_rotation_hours", PrefMeta { description: "Hours after which SSH hosts' MCP auth tokens are replaced (0 = never); not settable by Claude", ptype: "number", category: "Integration", read_only: true }),
+        ("mcp_local_token_rotation_hours", PrefMeta { description: "Hours after which the local MCP auth token is replaced (0 = never); not settable by Claude", ptype: "number", category: "Integration", read_only: true }),
+        ("hook_prompt_timeout", PrefMeta { description: "Seconds to wait for an answer to a \"prompt\" hook rule before deferring to Claude's own prompt", ptype: "number", category: "Integration", read_only: false }),
+        ("claude_model_prices", PrefMeta { description: "USD per million tokens for Claude usage costs (list of {model, input, output, cache_write, cache_read}); the first entry whose model is contained in the session's model id applies", ptype: "array", category: "Integration", read_only: false }),
+        ("prompt_templates", PrefMeta { description: "MCP prompt templates served as slash commands (list of {id, name, description, template}; %variables are interpolated)", ptype: "array", category: "Integration", read_only: false }),
         ("backup_directory", PrefMeta { description: "Backup directory path (null = scheduled backups disabled)", ptype: "string", category: "Backup", read_only: false }),
         ("backup_interval", PrefMeta { description: "Scheduled backup interval (off, hourly, daily, weekly, monthly)", ptype: "string", category: "Backup", read_only: false }),
         ("backup_exclude_scrollback", PrefMeta { description: "Exclude terminal scrollback from backups", ptype: "boolean", category: "Backup", read_only: false }),
@@ -307,7 +398,7 @@ fn preference_meta() -> Vec<(&'static str, PrefMeta)> {
 
 /// Handle tools that can be resolved entirely on the backend without frontend involvement.
 /// Returns Some(result) if handled, None if the tool should be forwarded to the frontend.
-fn handle_backend_tool(tool_name: &str, arguments: &Value, state: &Arc<AppState>, app_handle: &AppHandle) -> Option<Value> {
+async fn handle_backend_tool(tool_name: &str, arguments: &Value, state: &Arc<AppState>, app_handle: &AppHandle) -> Option<Value> {
     match tool_name {
         "listWindows" => {
             let app_data = state.app_data.read();
@@ -476,6 +567,8 @@ fn handle_backend_tool(tool_name: &str, arguments: &Value, state: &Arc<AppState>
             Some(serde_json::json!({ "success": true, "path": path_str, "excludedScrollback": exclude_scrollback }))
         }
         "getClaudeSessions" => {
+            // Read transcripts before taking the session lock
+            let usage = super::usage::tracked_session_usage(state);
             let sessions = state.claude_sessions.read();
             let app_data = state.app_data.read();
 
@@ -504,15 +597,117 @@ fn handle_backend_tool(tool_name: &str, arguments: &Value, state: &Arc<AppState>
                     "cwd": info.cwd,
                     "toolName": info.tool_name,
                     "model": info.model,
+                    "subagents": info.subagents,
+                    "lastToolFailure": info.last_tool_failure,
+                    "agent": info.agent,
+                    "resumeCommand": agents::adapter(info.agent).resume_command(sid),
+                    "usage": usage.get(sid),
                 })
             }).collect();
 
             Some(serde_json::json!({ "sessions": entries, "count": entries.len() }))
         }
+        "getHookTimeline" => {
+            let session_id = arguments.get("sessionId").and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+            let tab_id = arguments
+                .get("targetTabId")
+                .or_else(|| arguments.get("tabId"))
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty());
+            if session_id.is_none() && tab_id.is_none() {
+                return Some(serde_json::json!({ "error": "Provide sessionId or targetTabId (or call initSession first)" }));
+            }
+            let since = arguments
+                .get("sinceMinutes")
+                .and_then(|v| v.as_f64())
+                .map(|minutes| crate::state::now_ms() - (minutes * 60_000.0) as i64);
+            let limit = arguments.get("limit").and_then(|v| v.as_u64()).unwrap_or(200).min(1000) as usize;
+
+            let query = crate::state::hook_event_db::TimelineQuery {
+                // An explicit session wins over the caller's own tab
+                tab_id: if session_id.is_some() { None } else { tab_id.map(str::to_string) },
+                session_id: session_id.map(str::to_string),
+                since,
+                limit,
+            };
+            match state.hook_event_db.timeline(&query) {
+                Ok(events) => {
+                    let entries: Vec<Value> = events.iter().map(|e| {
+                        serde_json::json!({
+                            "time": e.time,
+                            "event": e.event,
+                            "sessionId": e.session_id,
+                            "tabId": e.tab_id,
+                            "toolName": e.tool_name,
+                            "detail": e.detail,
+                            "decision": e.decision,
+                            "durationMs": e.duration_ms,
+                        })
+                    }).collect();
+                    Some(serde_json::json!({ "events": entries, "count": entries.len() }))
+                }
+                Err(e) => Some(serde_json::json!({ "error": e })),
+            }
+        }
+        "runInTab" => Some(tab_runner::run_in_tab(state, arguments).await),
+        "waitForOutput" => Some(tab_runner::wait_for_output(state, arguments).await),
         _ => None,
     }
 }
 
+/// Hand a request to the frontend window that owns `arguments.tabId` (or all
+/// windows) as a `claude-code-tool` event and wait for `claude_code_respond`.
+pub(crate) async fn request_frontend(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    tool_name: &str,
+    arguments: Value,
+) -> Result<Value, String> {
+    request_frontend_with_timeout(state, app_handle, tool_name, arguments, RESPONSE_TIMEOUT).await
+}
+
+/// `request_frontend` with a caller-chosen wait (e.g. a hook prompt's timeout).
+pub(crate) async fn request_frontend_with_timeout(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    tool_name: &str,
+    arguments: Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel::<Value>();
+    state
+        .claude_code_pending
+        .write()
+        .insert(request_id.clone(), tx);
+
+    // Emit to the specific window that owns the tab (avoids race
+    // when preferences/help windows also listen for the event)
+    let target = resolve_target_window(state, &arguments);
+    let payload = serde_json::json!({
+        "request_id": request_id,
+        "tool": tool_name,
+        "arguments": arguments,
+    });
+    if let Some(label) = target {
+        let _ = app_handle.emit_to(&label, "claude-code-tool", payload);
+    } else {
+        let _ = app_handle.emit("claude-code-tool", payload);
+    }
+
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(_)) => {
+            state.claude_code_pending.write().remove(&request_id);
+            Err("Tool handler disconnected".to_string())
+        }
+        Err(_) => {
+            state.claude_code_pending.write().remove(&request_id);
+            Err("Tool response timeout".to_string())
+        }
+    }
+}
+
 fn collect_workspace_folders(_state: &Arc<AppState>) -> Vec<String> {
     let mut folders = Vec::new();
     if let Some(home) = dirs::home_dir() {
@@ -565,15 +760,15 @@ async fn ws_upgrade_handler(
         .unwrap_or("")
         .to_string();
 
-    if auth != srv.expected_auth {
+    let Some(owner) = srv.state.mcp_tokens.lock().identify(&auth) else {
         log::warn!("Claude Code WS connection rejected: invalid auth");
         return StatusCode::UNAUTHORIZED.into_response();
-    }
+    };
 
-    ws.on_upgrade(move |socket| handle_ws_connection(socket, srv))
+    ws.on_upgrade(move |socket| handle_ws_connection(socket, srv, auth, owner))
 }
 
-async fn handle_ws_connection(socket: WebSocket, srv: ServerState) {
+async fn handle_ws_connection(socket: WebSocket, srv: ServerState, auth: String, owner: TokenOwner) {
     let ws_connection_id = format!("ws-{}", uuid::Uuid::new_v4());
     log::debug!("Claude Code WS client connected ({})", &ws_connection_id[..11]);
     connection_inc(&srv);
@@ -585,13 +780,26 @@ async fn handle_ws_connection(socket: WebSocket, srv: ServerState) {
     let (mut ws_write, mut ws_read) = socket.split();
     let mut ping_interval = tokio::time::interval(PING_INTERVAL);
     ping_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // Requests run as tasks answering over response_tx, so a long one
+    // (waitForOutput, a permission prompt) doesn't hold up pings or other
+    // requests. Aborted when the socket goes away.
+    let mut requests = tokio::task::JoinSet::new();
 
     loop {
         tokio::select! {
             msg = ws_read.next() => {
                 match msg {
                     Some(Ok(WsMessage::Text(text))) => {
-                        handle_message(&text, &srv.app_handle, &srv.state, &srv.connection_tabs, &ws_connection_id, &response_tx).await;
+                        // The token is checked once at upgrade; drop the socket if it was revoked since
+                        if srv.state.mcp_tokens.lock().identify(&auth).is_none() {
+                            log::info!("Claude Code WS connection closed: auth token revoked");
+                            break;
+                        }
+                        let (srv, connection_id, owner, response_tx) =
+                            (srv.clone(), ws_connection_id.clone(), owner.clone(), response_tx.clone());
+                        requests.spawn(async move {
+                            handle_message(&text, &srv.app_handle, &srv.state, &srv.connection_tabs, &connection_id, &owner, &response_tx).await;
+                        });
                     }
                     Some(Ok(WsMessage::Ping(data))) => {
                         let _ = ws_write.send(WsMessage::Pong(data)).await;
@@ -612,6 +820,7 @@ async fn handle_ws_connection(socket: WebSocket, srv: ServerState) {
                     break;
                 }
             }
+            Some(_) = requests.join_next(), if !requests.is_empty() => {}
             response = response_rx.recv() => {
                 if let Some(json) = response {
                     if ws_write.send(WsMessage::Text(json.into())).await.is_err() {
@@ -622,8 +831,10 @@ async fn handle_ws_connection(socket: WebSocket, srv: ServerState) {
         }
     }
 
+    requests.abort_all();
     connection_dec(&srv);
     srv.connection_tabs.write().remove(&ws_connection_id);
+    srv.state.resource_subscriptions.lock().remove_connection(&ws_connection_id);
     // Only clear the global notify channel if we still own it — a newer
     // transport session may have overwritten it while we were running.
     {
@@ -651,9 +862,9 @@ async fn streamable_http_handler(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    if auth != srv.expected_auth {
+    let Some(owner) = srv.state.mcp_tokens.lock().identify(auth) else {
         return StatusCode::UNAUTHORIZED.into_response();
-    }
+    };
 
     // Connection affinity for streamable HTTP. The client echoes the Mcp-Session-Id we
     // assign on `initialize`; once it does, every request from that agent maps to the
@@ -667,7 +878,7 @@ async fn streamable_http_handler(
     let (connection_id, assigned_sid) = derive_streamable_connection_id(incoming_sid, is_initialize);
 
     // Process the JSON-RPC message and get the response
-    let response_json = process_message(&body, &srv.app_handle, &srv.state, &srv.connection_tabs, &connection_id).await;
+    let response_json = process_message(&body, &srv.app_handle, &srv.state, &srv.connection_tabs, &connection_id, &owner, None).await;
 
     match response_json {
         Some(json) => {
@@ -725,7 +936,7 @@ async fn sse_get_handler(State(srv): State<ServerState>, headers: HeaderMap) ->
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    if auth != srv.expected_auth {
+    if srv.state.mcp_tokens.lock().identify(auth).is_none() {
         log::warn!("Claude Code SSE connection rejected: invalid auth");
         return StatusCode::UNAUTHORIZED.into_response();
     }
@@ -791,7 +1002,9 @@ async fn sse_get_handler(State(srv): State<ServerState>, headers: HeaderMap) ->
             }
         }
         cleanup_srv.sse_sessions.write().remove(&cleanup_session_id);
-        cleanup_srv.connection_tabs.write().remove(&format!("sse-{}", cleanup_session_id));
+        let connection_id = format!("sse-{}", cleanup_session_id);
+        cleanup_srv.connection_tabs.write().remove(&connection_id);
+        cleanup_srv.state.resource_subscriptions.lock().remove_connection(&connection_id);
         connection_dec(&cleanup_srv);
         // Only clear the global notify channel if this session still owns it —
         // a newer session may have overwritten it while we were running.
@@ -830,9 +1043,9 @@ async fn sse_message_handler(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    if auth != srv.expected_auth {
+    let Some(owner) = srv.state.mcp_tokens.lock().identify(auth) else {
         return StatusCode::UNAUTHORIZED.into_response();
-    }
+    };
 
     let tx = srv.sse_sessions.read().get(&params.session_id).cloned();
     let Some(tx) = tx else {
@@ -840,7 +1053,7 @@ async fn sse_message_handler(
     };
 
     let connection_id = format!("sse-{}", params.session_id);
-    handle_message(&body, &srv.app_handle, &srv.state, &srv.connection_tabs, &connection_id, &tx).await;
+    handle_message(&body, &srv.app_handle, &srv.state, &srv.connection_tabs, &connection_id, &owner, &tx).await;
     StatusCode::OK.into_response()
 }
 
@@ -849,13 +1062,17 @@ async fn sse_message_handler(
 /// Process one JSON-RPC message and return the response as a raw JSON string.
 /// Returns `None` for notifications (no id) that don't require a response.
 /// `connection_id` identifies the transport connection (SSE session, WS, or streamable-http)
-/// for tab affinity tracking.
+/// for tab affinity tracking, and `owner` whose auth token it presented. `push_tx` is the
+/// connection's server→client channel, present only for transports that can carry
+/// notifications (WS and legacy SSE).
 async fn process_message(
     text: &str,
     app_handle: &AppHandle,
     state: &Arc<AppState>,
     connection_tabs: &ConnectionTabMap,
     connection_id: &str,
+    owner: &TokenOwner,
+    push_tx: Option<&mpsc::UnboundedSender<String>>,
 ) -> Option<String> {
     let req: JsonRpcRequest = match serde_json::from_str(text) {
         Ok(r) => r,
@@ -877,6 +1094,71 @@ async fn process_message(
             let resp = JsonRpcResponse::success(id, tool_list_response());
             Some(serde_json::to_string(&resp).unwrap())
         }
+        "prompts/list" | "prompts/get" | "resources/list" | "resources/read" | "resources/subscribe" => {
+            // Read access to the tab or workspace addressed, checked and
+            // audited like a read-only tool call
+            let caller_tab = connection_tabs.read().get(connection_id).cloned();
+            let params = req.params.clone().unwrap_or_else(|| serde_json::json!({}));
+            let uri = params.get("uri").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            let resource = resources::ResourceUri::parse(&uri);
+            let mut target = match &resource {
+                Some(resource) => resource.scope_target(),
+                None => serde_json::json!({}),
+            };
+            if req.method.starts_with("resources/") && req.method != "resources/list" && resource.is_none() {
+                let e = format!("Unknown resource URI: {}", uri);
+                record_audit(state, connection_id, caller_tab.as_deref(), None, &req.method, &params, Some(&e));
+                let resp = JsonRpcResponse::error(id, -32002, e);
+                return Some(serde_json::to_string(&resp).unwrap());
+            }
+            let auth = scopes::authorize(state, owner, caller_tab.as_deref(), &req.method, &mut target);
+            if let Some(reason) = &auth.denied {
+                log::warn!("MCP scope: {}", reason);
+                record_audit(state, connection_id, caller_tab.as_deref(), Some(&auth), &req.method, &params, Some(reason));
+                let resp = JsonRpcResponse::error(
+                    id,
+                    -32603,
+                    format!("Permission denied: {}. The user can widen MCP access in Preferences.", reason),
+                );
+                return Some(serde_json::to_string(&resp).unwrap());
+            }
+
+            let outcome = match req.method.as_str() {
+                "prompts/list" => Ok(prompts::list(state, caller_tab.as_deref())),
+                "prompts/get" => {
+                    let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                    prompts::get(state, caller_tab.as_deref(), name, params.get("arguments"))
+                        .await
+                        .map_err(|e| (-32602, e))
+                }
+                "resources/list" => Ok(resources::list(state, |app_data, resource| {
+                    scopes::permits(app_data, auth.grant, caller_tab.as_deref(), "resources/read", &resource.scope_target())
+                })),
+                "resources/read" => resources::read(state, app_handle, &uri).await.map_err(|e| (-32002, e)),
+                _ => match push_tx {
+                    Some(tx) => {
+                        state.resource_subscriptions.lock().subscribe(connection_id, tx, &uri);
+                        Ok(serde_json::json!({}))
+                    }
+                    // Streamable HTTP answers each POST and closes; there is no
+                    // stream left open to deliver notifications on.
+                    None => Err((-32601, "resources/subscribe needs a WebSocket or SSE connection".to_string())),
+                },
+            };
+            let error = outcome.as_ref().err().map(|(_, e)| e.as_str());
+            record_audit(state, connection_id, caller_tab.as_deref(), Some(&auth), &req.method, &params, error);
+            let resp = match outcome {
+                Ok(result) => JsonRpcResponse::success(id, result),
+                Err((code, e)) => JsonRpcResponse::error(id, code, e),
+            };
+            Some(serde_json::to_string(&resp).unwrap())
+        }
+        "resources/unsubscribe" => {
+            let uri = req.params.as_ref().and_then(|p| p.get("uri")).and_then(|v| v.as_str()).unwrap_or("");
+            state.resource_subscriptions.lock().unsubscribe(connection_id, uri);
+            let resp = JsonRpcResponse::success(id, serde_json::json!({}));
+            Some(serde_json::to_string(&resp).unwrap())
+        }
         "tools/call" => {
             if let Some(params) = req.params {
                 let tool_name = params
@@ -889,12 +1171,18 @@ async fn process_message(
                     .cloned()
                     .unwrap_or(Value::Object(serde_json::Map::new()));
 
+                // Every call lands in the MCP audit log, refused ones included
+                let audit = |tab_id: Option<&str>, auth: Option<&Authorization>, arguments: &Value, error: Option<&str>| {
+                    record_audit(state, connection_id, tab_id, auth, &tool_name, arguments, error);
+                };
+
                 // ── initSession: register connection → tab affinity ──
                 if tool_name == "initSession" {
                     let tab_id = arguments.get("tabId").and_then(|v| v.as_str()).unwrap_or("").to_string();
                     let session_id = arguments.get("sessionId").and_then(|v| v.as_str()).unwrap_or("").to_string();
 
                     if tab_id.is_empty() {
+                        audit(None, None, &arguments, Some("tabId is required"));
                         let resp = JsonRpcResponse::success(
                             id,
                             serde_json::json!({
@@ -924,6 +1212,7 @@ async fn process_message(
                              and use '{}' tools.",
                             tab_id, this_server, other_server
                         );
+                        audit(Some(tab_id.as_str()), None, &arguments, Some("Tab not found"));
                         let resp = JsonRpcResponse::success(
                             id,
                             serde_json::json!({
@@ -934,6 +1223,20 @@ async fn process_message(
                         return Some(serde_json::to_string(&resp).unwrap());
                     }
 
+                    if !scopes::may_bind_tab(&state.ssh_tunnels.read(), owner, &tab_id) {
+                        let msg = format!("Tab '{}' is not served by this SSH host's MCP tunnel", tab_id);
+                        log::warn!("MCP initSession: {}", msg);
+                        audit(Some(tab_id.as_str()), None, &arguments, Some(msg.as_str()));
+                        let resp = JsonRpcResponse::success(
+                            id,
+                            serde_json::json!({
+                                "content": [{ "type": "text", "text": format!("Error: {}", msg) }],
+                                "isError": true
+                            }),
+                        );
+                        return Some(serde_json::to_string(&resp).unwrap());
+                    }
+
                     // Store connection → tab affinity
                     connection_tabs.write().insert(connection_id.to_string(), tab_id.clone());
                     log::debug!("initSession: connection {} → tab {} (claude session: {})",
@@ -954,11 +1257,14 @@ async fn process_message(
                                 session_id.clone(),
                                 ClaudeSessionInfo {
                                     tab_id: tab_id.clone(),
+                                    agent: existing.as_ref().map_or(AgentKind::Claude, |e| e.agent),
                                     cwd: existing.as_ref().and_then(|e| e.cwd.clone()),
                                     state: ClaudeSessionState::Active,
                                     tool_name: existing.as_ref().and_then(|e| e.tool_name.clone()),
-                                    model: existing.and_then(|e| e.model),
+                                    model: existing.as_ref().and_then(|e| e.model.clone()),
                                     connection_id: Some(connection_id.to_string()),
+                                    subagents: existing.as_ref().map_or(0, |e| e.subagents),
+                                    last_tool_failure: existing.and_then(|e| e.last_tool_failure),
                                 },
                             );
                         }
@@ -977,11 +1283,14 @@ async fn process_message(
                                     pending_sid.clone(),
                                     ClaudeSessionInfo {
                                         tab_id: tab_id.clone(),
+                                        agent: AgentKind::Claude,
                                         cwd: pending_cwd,
                                         state: ClaudeSessionState::Active,
                                         tool_name: None,
                                         model: None,
                                         connection_id: Some(connection_id.to_string()),
+                                        subagents: 0,
+                                        last_tool_failure: None,
                                     },
                                 );
                                 log::debug!("initSession: linked pending session {} → tab {}",
@@ -998,12 +1307,15 @@ async fn process_message(
                     // Emit event so frontend can set claudeSessionId trigger variable
                     // (unconditional — session ID is useful for triggers beyond auto-resume)
                     if !session_id.is_empty() {
+                        let agent = state.claude_sessions.read().get(&session_id).map(|s| s.agent).unwrap_or_default();
                         let _ = app_handle.emit("claude-init-session", serde_json::json!({
                             "tab_id": &tab_id,
                             "session_id": &session_id,
+                            "agent": agent,
                         }));
                     }
 
+                    audit(Some(tab_id.as_str()), None, &arguments, None);
                     let resp = JsonRpcResponse::success(
                         id,
                         serde_json::json!({
@@ -1029,7 +1341,9 @@ async fn process_message(
                 // connection_tabs (orphaned by SSE disconnect). If exactly one orphaned
                 // session exists, it's the one reconnecting. Falls back to the simpler
                 // "exactly 1 active session" heuristic if connection_ids aren't set.
-                if affinity_tab.is_none() {
+                // Host connections must bind through initSession: guessing could hand a
+                // remote caller a local agent's tab.
+                if affinity_tab.is_none() && matches!(owner, TokenOwner::Local) {
                     let sessions = state.claude_sessions.read();
                     let ct = connection_tabs.read();
 
@@ -1095,11 +1409,12 @@ async fn process_message(
                         "getOpenEditors", "getWorkspaceFolders", "getDiagnostics",
                         "sendNotification", "readLogs", "listWindows", "listWorkspaces",
                         "getPreferences", "setPreference", "createBackup",
-                        "getClaudeSessions",
+                        "getClaudeSessions", "getHookTimeline",
                     ];
                     if !global_tools.contains(&tool_name.as_str())
                         && arguments.get("tabId").and_then(|v| v.as_str()).map_or(true, |s| s.is_empty())
                     {
+                        audit(None, None, &arguments, Some("Session not initialized"));
                         let resp = JsonRpcResponse::success(
                             id,
                             serde_json::json!({
@@ -1126,6 +1441,7 @@ async fn process_message(
                             tab_id, this_server, other_server
                         );
                         log::warn!("MCP tool guard: {}", err_msg);
+                        audit(Some(tab_id), None, &arguments, Some(err_msg.as_str()));
                         let resp = JsonRpcResponse::success(
                             id,
                             serde_json::json!({
@@ -1137,70 +1453,66 @@ async fn process_message(
                     }
                 }
 
-                // Backend-only tools: handle directly without emitting to frontend
-                if let Some(result) = handle_backend_tool(&tool_name, &arguments, state, app_handle) {
-                    let content_text = serde_json::to_string(&result).unwrap_or_default();
+                // ── Scope check: remote callers and scoped workspaces only get
+                // the tools and tabs their MCP scope allows ──
+                let caller_tab = match scopes::caller_tab(&state.ssh_tunnels.read(), owner, affinity_tab.as_deref(), &arguments) {
+                    Ok(tab) => tab,
+                    Err(msg) => {
+                        log::warn!("MCP scope: {}", msg);
+                        audit(None, None, &arguments, Some(msg.as_str()));
+                        let resp = JsonRpcResponse::success(
+                            id,
+                            serde_json::json!({
+                                "content": [{ "type": "text", "text": format!("Error: {}. Call initSession first.", msg) }],
+                                "isError": true
+                            }),
+                        );
+                        return Some(serde_json::to_string(&resp).unwrap());
+                    }
+                };
+                let auth = scopes::authorize(state, owner, caller_tab.as_deref(), &tool_name, &mut arguments);
+                if let Some(reason) = &auth.denied {
+                    log::warn!("MCP scope: {}", reason);
+                    audit(caller_tab.as_deref(), Some(&auth), &arguments, Some(reason.as_str()));
                     let resp = JsonRpcResponse::success(
                         id,
                         serde_json::json!({
-                            "content": [{ "type": "text", "text": content_text }]
+                            "content": [{ "type": "text", "text": format!(
+                                "Permission denied: {}. The user can widen MCP access in Preferences.", reason
+                            ) }],
+                            "isError": true
                         }),
                     );
-                    Some(serde_json::to_string(&resp).unwrap())
-                } else {
-                    // Frontend-handled tools: emit to the correct window
-                    let request_id = uuid::Uuid::new_v4().to_string();
-                    let (tx, rx) = oneshot::channel::<Value>();
-                    state
-                        .claude_code_pending
-                        .write()
-                        .insert(request_id.clone(), tx);
-
-                    let payload = serde_json::json!({
-                        "request_id": request_id,
-                        "tool": tool_name,
-                        "arguments": arguments,
-                    });
+                    return Some(serde_json::to_string(&resp).unwrap());
+                }
 
-                    // Emit to the specific window that owns the tab (avoids race
-                    // when preferences/help windows also listen for the event)
-                    if let Some(label) = resolve_target_window(state, &arguments) {
-                        let _ = app_handle.emit_to(&label, "claude-code-tool", payload);
-                    } else {
-                        let _ = app_handle.emit("claude-code-tool", payload);
-                    }
+                // Backend-only tools: handle directly without emitting to frontend
+                let audit_args = arguments.clone();
+                let backend = handle_backend_tool(&tool_name, &arguments, state, app_handle).await;
+                let outcome = match backend {
+                    Some(result) => Ok(result),
+                    // Frontend-handled tools: emit to the correct window
+                    None => request_frontend(state, app_handle, &tool_name, arguments).await,
+                };
+                let error = match &outcome {
+                    Ok(result) => result.get("error").and_then(|v| v.as_str()).map(String::from),
+                    Err(e) => Some(e.clone()),
+                };
+                audit(caller_tab.as_deref(), Some(&auth), &audit_args, error.as_deref());
 
-                    match tokio::time::timeout(RESPONSE_TIMEOUT, rx).await {
-                        Ok(Ok(result)) => {
-                            let content_text = serde_json::to_string(&result).unwrap_or_default();
-                            let resp = JsonRpcResponse::success(
-                                id,
-                                serde_json::json!({
-                                    "content": [{ "type": "text", "text": content_text }]
-                                }),
-                            );
-                            Some(serde_json::to_string(&resp).unwrap())
-                        }
-                        Ok(Err(_)) => {
-                            state.claude_code_pending.write().remove(&request_id);
-                            let resp = JsonRpcResponse::error(
-                                id,
-                                -32603,
-                                "Tool handler disconnected".to_string(),
-                            );
-                            Some(serde_json::to_string(&resp).unwrap())
-                        }
-                        Err(_) => {
-                            state.claude_code_pending.write().remove(&request_id);
-                            let resp = JsonRpcResponse::error(
-                                id,
-                                -32603,
-                                "Tool response timeout".to_string(),
-                            );
-                            Some(serde_json::to_string(&resp).unwrap())
-                        }
+                let resp = match outcome {
+                    Ok(result) => {
+                        let content_text = serde_json::to_string(&result).unwrap_or_default();
+                        JsonRpcResponse::success(
+                            id,
+                            serde_json::json!({
+                                "content": [{ "type": "text", "text": content_text }]
+                            }),
+                        )
                     }
-                }
+                    Err(e) => JsonRpcResponse::error(id, -32603, e),
+                };
+                Some(serde_json::to_string(&resp).unwrap())
             } else {
                 let resp = JsonRpcResponse::error(id, -32602, "Missing params".to_string());
                 Some(serde_json::to_string(&resp).unwrap())
@@ -1221,6 +1533,48 @@ async fn process_message(
     }
 }
 
+/// Write one MCP audit row; `auth` is `None` when the call was refused
+/// before its scope was worked out.
+fn record_audit(
+    state: &AppState,
+    connection_id: &str,
+    tab_id: Option<&str>,
+    auth: Option<&Authorization>,
+    tool: &str,
+    arguments: &Value,
+    error: Option<&str>,
+) {
+    let status = match (auth.and_then(|a| a.denied.as_ref()), error) {
+        (Some(_), _) => "denied",
+        (None, Some(_)) => "error",
+        (None, None) => "ok",
+    };
+    let result = state.mcp_audit_db.record(NewMcpAuditEntry {
+        connection_id,
+        tab_id,
+        host: auth.and_then(|a| a.host.as_deref()),
+        tool,
+        arguments,
+        scope: auth.map(|a| a.grant.label()),
+        status,
+        error,
+    });
+    if let Err(e) = result {
+        log::warn!("MCP audit: {}", e);
+    }
+}
+
+/// What GET /health answers. Unauthenticated, so it names the process
+/// without revealing anything; the tunnel supervisor compares it with its
+/// own to tell our server from whatever else might hold a remote port.
+pub fn health_signature() -> String {
+    format!("{} {}", crate::APP_DISPLAY_NAME, std::process::id())
+}
+
+async fn health_handler() -> String {
+    health_signature()
+}
+
 // ─── Claude Code Hooks ──────────────────────────────────────────────────────
 
 /// Handle POST /hooks — receives Claude Code hook events.
@@ -1232,18 +1586,30 @@ async fn hooks_handler(
     Query(params): Query<HashMap<String, String>>,
     body: String,
 ) -> Response {
+    // Timeline time is arrival time: decision hooks are recorded after the answer
+    let received_ms = crate::state::now_ms();
     let auth = headers
         .get("x-claude-code-ide-authorization")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
-    if auth != srv.expected_auth {
+    let Some(owner) = srv.state.mcp_tokens.lock().identify(auth) else {
         return StatusCode::UNAUTHORIZED.into_response();
-    }
+    };
 
-    let event: Value = match serde_json::from_str(&body) {
+    // Hooks from other agent CLIs say which one sent them; absent means Claude Code
+    let adapter = match params.get("agent") {
+        Some(raw) => match AgentKind::from_param(raw) {
+            Some(kind) => agents::adapter(kind),
+            None => return StatusCode::BAD_REQUEST.into_response(),
+        },
+        None => agents::adapter(AgentKind::Claude),
+    };
+
+    let mut event: Value = match serde_json::from_str(&body) {
         Ok(v) => v,
         Err(_) => return StatusCode::BAD_REQUEST.into_response(),
     };
+    adapter.normalize_hook_event(&mut event);
 
     let hook_event_name = event
         .get("hook_event_name")
@@ -1255,7 +1621,7 @@ async fn hooks_handler(
         .unwrap_or("")
         .to_string();
 
-    log::debug!("Claude hook: received '{}' session={}", hook_event_name, &session_id[..session_id.len().min(8)]);
+    log::debug!("{} hook: received '{}' session={}", adapter.display_name(), hook_event_name, &session_id[..session_id.len().min(8)]);
 
     // tab_id comes from query param (set by the command hook script from $AITERM_TAB_ID)
     // Validate it actually exists — it may be stale after HMR reload or tab recreation.
@@ -1285,6 +1651,37 @@ async fn hooks_handler(
         }
     });
 
+    // Resolved before the match: SessionEnd forgets the session
+    let session_tab = srv.state.claude_sessions.read().get(&session_id).map(|s| s.tab_id.clone());
+
+    // A host's hooks may only speak for tabs its tunnel serves, through the tab
+    // they name and through the session they report on
+    let permitted = {
+        let tunnels = srv.state.ssh_tunnels.read();
+        scopes::may_post_hook(&tunnels, &owner, tab_id_from_param.as_deref())
+            && session_tab.as_deref().is_none_or(|tab| scopes::may_bind_tab(&tunnels, &owner, tab))
+    };
+    if !permitted {
+        log::warn!(
+            "{} hook: '{}' for tab {:?} refused, not served by the caller's SSH tunnel",
+            adapter.display_name(),
+            hook_event_name,
+            session_tab.as_deref().or(tab_id_from_param.as_deref())
+        );
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let timeline_tab = session_tab.or_else(|| tab_id_from_param.clone());
+
+    // Usage is read from Claude Code's JSONL transcripts only
+    if adapter.kind() == AgentKind::Claude {
+        if let Some(path) = event.get("transcript_path").and_then(|v| v.as_str()) {
+            srv.state.claude_usage.lock().set_transcript_path(&session_id, path);
+        }
+    }
+
+    // Decision events may answer with a JSON body; everything else is observe-only
+    let mut reply: Option<Value> = None;
+
     match hook_event_name {
         "SessionStart" => {
             let tab_id = tab_id_from_param.clone().unwrap_or_default();
@@ -1298,14 +1695,27 @@ async fn hooks_handler(
                     session_id.clone(),
                     ClaudeSessionInfo {
                         tab_id: tab_id.clone(),
+                        agent: adapter.kind(),
                         cwd: cwd.clone(),
                         state: ClaudeSessionState::Active,
                         tool_name: None,
                         model: model.clone(),
                         connection_id: None,
+                        subagents: 0,
+                        last_tool_failure: None,
                     },
                 );
-                log::info!("Claude hook: session {} started for tab {}", session_id, tab_id);
+                drop(sessions);
+                log::info!("{} hook: session {} started for tab {}", adapter.display_name(), session_id, tab_id);
+
+                // No initSession round-trip for these agents: capture the session here
+                if adapter.captures_session_from_hooks() {
+                    let _ = srv.app_handle.emit("claude-init-session", serde_json::json!({
+                        "tab_id": &tab_id,
+                        "session_id": &session_id,
+                        "agent": adapter.kind(),
+                    }));
+                }
             } else if !session_id.is_empty() {
                 // No tab_id yet — buffer for initSession to pick up
                 let mut pending = srv.state.pending_hook_sessions.write();
@@ -1322,6 +1732,7 @@ async fn hooks_handler(
                 "tab_id": if tab_id.is_empty() { None } else { Some(&tab_id) },
                 "cwd": event.get("cwd"),
                 "source": source,
+                "agent": adapter.kind(),
             }));
         }
 
@@ -1391,6 +1802,7 @@ async fn hooks_handler(
                 if let Some(session) = sessions.get_mut(&session_id) {
                     session.state = ClaudeSessionState::Stopped;
                     session.tool_name = None;
+                    session.subagents = subagent_count(hook_event_name, session.subagents);
                 }
             }
 
@@ -1414,6 +1826,8 @@ async fn hooks_handler(
                 let mut sessions = srv.state.claude_sessions.write();
                 if let Some(session) = sessions.get_mut(&session_id) {
                     session.state = ClaudeSessionState::Active;
+                    session.last_tool_failure = None;
+                    session.subagents = subagent_count(hook_event_name, session.subagents);
                 }
             }
 
@@ -1455,6 +1869,58 @@ async fn hooks_handler(
                 "tool_name": tool_name,
                 "tool_input": event.get("tool_input"),
             }));
+
+            if adapter.accepts_hook_decisions() {
+                reply = hook_policy::decide(
+                    &srv.state,
+                    &srv.app_handle,
+                    hook_event_name,
+                    &session_id,
+                    tab_id.as_deref(),
+                    &tool_name,
+                    event.get("tool_input"),
+                )
+                .await;
+            }
+
+            // A denied call never reaches PostToolUse, so clear the tool here
+            let denied = reply
+                .as_ref()
+                .is_some_and(|r| r["hookSpecificOutput"]["permissionDecision"] == "deny");
+            if denied {
+                if let Some(session) = srv.state.claude_sessions.write().get_mut(&session_id) {
+                    session.tool_name = None;
+                }
+            }
+        }
+
+        "PermissionRequest" => {
+            let tab_id = {
+                let sessions = srv.state.claude_sessions.read();
+                sessions.get(&session_id).map(|s| s.tab_id.clone())
+            }
+            .or(tab_id_from_param);
+
+            let tool_name = event
+                .get("tool_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            log::debug!("Claude hook: PermissionRequest tool='{}' session={} (tab {:?})",
+                tool_name, &session_id[..session_id.len().min(8)], tab_id);
+            if adapter.accepts_hook_decisions() {
+                reply = hook_policy::decide(
+                    &srv.state,
+                    &srv.app_handle,
+                    hook_event_name,
+                    &session_id,
+                    tab_id.as_deref(),
+                    &tool_name,
+                    event.get("tool_input"),
+                )
+                .await;
+            }
         }
 
         "PostToolUse" => {
@@ -1475,6 +1941,7 @@ async fn hooks_handler(
                 let mut sessions = srv.state.claude_sessions.write();
                 if let Some(session) = sessions.get_mut(&session_id) {
                     session.tool_name = None;
+                    session.last_tool_failure = None;
                 }
             }
 
@@ -1488,6 +1955,72 @@ async fn hooks_handler(
             }));
         }
 
+        "PostToolUseFailure" => {
+            let tab_id = {
+                let sessions = srv.state.claude_sessions.read();
+                sessions.get(&session_id).map(|s| s.tab_id.clone())
+            }
+            .or(tab_id_from_param);
+
+            let tool_name = event
+                .get("tool_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let error = event.get("error").and_then(|v| v.as_str()).map(String::from);
+            let interrupted = event.get("is_interrupt").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            // Tool finished unsuccessfully — clear it and remember the failure
+            // (a user interrupt isn't one)
+            if !session_id.is_empty() {
+                use crate::state::app_state::ToolFailure;
+                let mut sessions = srv.state.claude_sessions.write();
+                if let Some(session) = sessions.get_mut(&session_id) {
+                    session.tool_name = None;
+                    session.last_tool_failure = (!interrupted).then(|| ToolFailure {
+                        tool_name: tool_name.clone(),
+                        error: error.clone(),
+                    });
+                }
+            }
+
+            log::debug!("Claude hook: PostToolUseFailure tool='{}' session={} (tab {:?})",
+                tool_name, &session_id[..session_id.len().min(8)], tab_id);
+            let _ = srv.app_handle.emit("claude-hook-tool-failure", serde_json::json!({
+                "session_id": session_id,
+                "tab_id": tab_id,
+                "tool_name": tool_name,
+                "error": error,
+                "is_interrupt": interrupted,
+            }));
+        }
+
+        "SubagentStart" | "SubagentStop" => {
+            let starting = hook_event_name == "SubagentStart";
+            let (tab_id, subagents) = {
+                let mut sessions = srv.state.claude_sessions.write();
+                match sessions.get_mut(&session_id) {
+                    Some(session) => {
+                        session.subagents = subagent_count(hook_event_name, session.subagents);
+                        (Some(session.tab_id.clone()), session.subagents)
+                    }
+                    None => (None, 0),
+                }
+            };
+            let tab_id = tab_id.or(tab_id_from_param);
+
+            log::debug!("Claude hook: {} type={:?} session={} (tab {:?}, {} running)",
+                hook_event_name, event.get("agent_type"), &session_id[..session_id.len().min(8)], tab_id, subagents);
+            let name = if starting { "claude-hook-subagent-start" } else { "claude-hook-subagent-stop" };
+            let _ = srv.app_handle.emit(name, serde_json::json!({
+                "session_id": session_id,
+                "tab_id": tab_id,
+                "agent_id": event.get("agent_id"),
+                "agent_type": event.get("agent_type"),
+                "subagents": subagents,
+            }));
+        }
+
         "PreCompact" => {
             let tab_id = {
                 let sessions = srv.state.claude_sessions.read();
@@ -1515,7 +2048,81 @@ async fn hooks_handler(
         }
     }
 
-    StatusCode::OK.into_response()
+    if !session_id.is_empty() && !hook_event_name.is_empty() {
+        record_hook_event(
+            &srv.state,
+            &event,
+            hook_event_name,
+            &session_id,
+            timeline_tab.as_deref(),
+            reply.as_ref(),
+            received_ms,
+        );
+    }
+
+    match reply {
+        Some(body) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            body.to_string(),
+        )
+            .into_response(),
+        None => StatusCode::OK.into_response(),
+    }
+}
+
+/// Running-subagent count after a hook event. A turn ending (Stop), a new
+/// prompt or the session ending means none of its subagents are still
+/// running, which also recovers a count left behind by a missed SubagentStop.
+fn subagent_count(hook_event_name: &str, current: u32) -> u32 {
+    match hook_event_name {
+        "SubagentStart" => current + 1,
+        // Saturating: a SubagentStop may outlive a restart that reset the count
+        "SubagentStop" => current.saturating_sub(1),
+        "Stop" | "SessionEnd" | "UserPromptSubmit" => 0,
+        _ => current,
+    }
+}
+
+/// Event fields kept in the hook timeline's `detail`, when present.
+const TIMELINE_DETAIL_FIELDS: &[&str] = &[
+    "tool_input", "prompt", "notification_type", "title", "message", "body",
+    "reason", "source", "cwd", "model", "trigger", "error", "is_interrupt",
+    "agent_id", "agent_type",
+];
+
+/// Persist a hook event (and any policy decision we answered with) to the timeline.
+fn record_hook_event(
+    state: &Arc<AppState>,
+    event: &Value,
+    hook_event_name: &str,
+    session_id: &str,
+    tab_id: Option<&str>,
+    reply: Option<&Value>,
+    received_ms: i64,
+) {
+    let detail: serde_json::Map<String, Value> = TIMELINE_DETAIL_FIELDS
+        .iter()
+        .filter_map(|&key| event.get(key).filter(|v| !v.is_null()).map(|v| (key.to_string(), v.clone())))
+        .collect();
+    let decision = reply.and_then(|r| {
+        let output = &r["hookSpecificOutput"];
+        output["permissionDecision"].as_str().or(output["decision"]["behavior"].as_str())
+    });
+
+    let result = state.hook_event_db.record(crate::state::hook_event_db::NewHookEvent {
+        session_id,
+        tab_id,
+        event: hook_event_name,
+        tool_name: event.get("tool_name").and_then(|v| v.as_str()),
+        tool_use_id: event.get("tool_use_id").and_then(|v| v.as_str()),
+        detail: (!detail.is_empty()).then_some(Value::Object(detail)),
+        decision,
+        ts: received_ms,
+    });
+    if let Err(e) = result {
+        log::warn!("Claude hook: {}", e);
+    }
 }
 
 /// Channel-based wrapper: process a message and send the response to a channel.
@@ -1526,16 +2133,17 @@ async fn handle_message(
     state: &Arc<AppState>,
     connection_tabs: &ConnectionTabMap,
     connection_id: &str,
+    owner: &TokenOwner,
     response_tx: &mpsc::UnboundedSender<String>,
 ) {
-    if let Some(json) = process_message(text, app_handle, state, connection_tabs, connection_id).await {
+    if let Some(json) = process_message(text, app_handle, state, connection_tabs, connection_id, owner, Some(response_tx)).await {
         let _ = response_tx.send(json);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::derive_streamable_connection_id;
+    use super::{derive_streamable_connection_id, subagent_count};
 
     // Regression for the Agent Bridge "bridge dropped" bug: sessionless streamable-HTTP
     // requests used to collapse onto one shared "streamable-http" key, so two local
@@ -1568,4 +2176,25 @@ mod tests {
         let (c3, _) = derive_streamable_connection_id(Some(""), false);
         assert_ne!(c3, "mcp-");
     }
+
+    #[test]
+    fn subagent_count_resets_when_the_turn_ends() {
+        let events = [
+            ("SubagentStart", 1),
+            ("SubagentStart", 2),
+            ("PreToolUse", 2),
+            ("SubagentStop", 1),
+            ("Stop", 0),
+            ("SubagentStop", 0),
+            ("SubagentStart", 1),
+            ("UserPromptSubmit", 0),
+            ("SubagentStart", 1),
+            ("SessionEnd", 0),
+        ];
+        let mut count = 0;
+        for (event, expected) in events {
+            count = subagent_count(event, count);
+            assert_eq!(count, expected, "after {}", event);
+        }
+    }
 }
@@ -0,0 +1,336 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::term::{Term, TermMode};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::state::AppState;
+use crate::terminal::blocks::BlockTracker;
+
+/// How often the target terminal is checked while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_TIMEOUT_SECS: f64 = 60.0;
+const MAX_TIMEOUT_SECS: f64 = 600.0;
+/// Output lines returned when the caller doesn't set `maxLines` (the tail is kept).
+const DEFAULT_MAX_LINES: usize = 200;
+
+/// What a wait watches for in the target terminal.
+struct Watch {
+    /// Absolute line the pattern is searched (and timeout output read) from.
+    text_from: u64,
+    /// Track the first command block whose output starts at or after this
+    /// line — its 133;D ends the wait.
+    command_from: Option<u64>,
+    pattern: Option<Regex>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Progress {
+    Running,
+    /// The tracked command finished (OSC 133;D).
+    Finished { command: Option<String>, exit_code: Option<i32>, output: String },
+    /// The pattern appeared in new output.
+    Matched { matched: String, output: String },
+}
+
+fn poll<T: EventListener>(term: &Term<T>, blocks: &BlockTracker, watch: &Watch) -> Progress {
+    let block = watch.command_from.and_then(|from| {
+        blocks.blocks().find(|b| b.output_start.is_some_and(|start| start >= from))
+    });
+    if let Some(block) = block.filter(|b| b.output_end.is_some()) {
+        return Progress::Finished {
+            command: block.command.clone(),
+            exit_code: block.exit_code,
+            output: blocks.output_text(term, block).unwrap_or_default(),
+        };
+    }
+    if let Some(pattern) = &watch.pattern {
+        let output = output_so_far(term, blocks, watch);
+        if let Some(m) = pattern.find(&output) {
+            return Progress::Matched { matched: m.as_str().to_string(), output };
+        }
+    }
+    Progress::Running
+}
+
+fn output_so_far<T: EventListener>(term: &Term<T>, blocks: &BlockTracker, watch: &Watch) -> String {
+    blocks
+        .text_between(term, watch.text_from, blocks.cursor_line(term) + 1)
+        .unwrap_or_default()
+}
+
+/// The last `max_lines` lines of `text`, and whether anything was cut.
+fn tail_lines(text: &str, max_lines: usize) -> (String, bool) {
+    let lines: Vec<&str> = text.trim_end().lines().map(str::trim_end).collect();
+    let start = lines.len().saturating_sub(max_lines);
+    (lines[start..].join("\n"), start > 0)
+}
+
+/// Handle `runInTab`: type a command into another tab's shell and wait for
+/// it to finish (OSC 133;D), for `pattern` to appear, or for the timeout.
+pub async fn run_in_tab(state: &Arc<AppState>, arguments: &Value) -> Value {
+    let Some(command) = arguments.get("command").and_then(|v| v.as_str()) else {
+        return serde_json::json!({ "error": "Missing required parameter: command" });
+    };
+    let target = match target_tab(arguments) {
+        Ok(t) => t,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+    let pattern = match parse_pattern(arguments) {
+        Ok(p) => p,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+    let pty_id = match pty_for_tab(state, target) {
+        Ok(p) => p,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+
+    let watch = {
+        let Some(terminal) = state.terminal_registry.get(&pty_id) else {
+            return serde_json::json!({ "error": "Terminal not found" });
+        };
+        let handle = terminal.lock();
+        if handle.term.mode().contains(TermMode::ALT_SCREEN) {
+            return serde_json::json!({ "error": "The tab is running a full-screen program; runInTab only types into a shell prompt." });
+        }
+        if let Some(running) = handle.blocks.blocks().last().filter(|b| b.output_start.is_some() && b.output_end.is_none()) {
+            return serde_json::json!({ "error": format!(
+                "The tab is busy running `{}`. Use waitForOutput to wait for it to finish first.",
+                running.command.as_deref().unwrap_or("a command")
+            ) });
+        }
+        // Output starts below the line the command is typed on
+        let from = handle.blocks.cursor_line(&handle.term) + 1;
+        Watch { text_from: from, command_from: Some(from), pattern }
+    };
+
+    if let Err(e) = crate::pty::write_pty(state, &pty_id, format!("{}\r", command).as_bytes()) {
+        return serde_json::json!({ "error": format!("Failed to write to tab: {}", e) });
+    }
+    log::debug!("runInTab: {} → tab {}", command, &target[..target.len().min(8)]);
+
+    wait(state, &pty_id, watch, arguments).await
+}
+
+/// Handle `waitForOutput`: wait on another tab without typing anything —
+/// for its running command to finish, for `pattern` to appear in new
+/// output, or for the timeout.
+pub async fn wait_for_output(state: &Arc<AppState>, arguments: &Value) -> Value {
+    let target = match target_tab(arguments) {
+        Ok(t) => t,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+    let pattern = match parse_pattern(arguments) {
+        Ok(p) => p,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+    let pty_id = match pty_for_tab(state, target) {
+        Ok(p) => p,
+        Err(e) => return serde_json::json!({ "error": e }),
+    };
+
+    let watch = {
+        let Some(terminal) = state.terminal_registry.get(&pty_id) else {
+            return serde_json::json!({ "error": "Terminal not found" });
+        };
+        let handle = terminal.lock();
+        let running = handle.blocks.blocks().last().filter(|b| b.output_end.is_none()).and_then(|b| b.output_start);
+        if running.is_none() && pattern.is_none() {
+            let last = handle.blocks.blocks().filter(|b| b.output_end.is_some()).last();
+            return serde_json::json!({
+                "status": "idle",
+                "shellIntegration": handle.blocks.blocks().next().is_some(),
+                "lastCommand": last.and_then(|b| b.command.clone()),
+                "lastExitCode": last.and_then(|b| b.exit_code),
+            });
+        }
+        Watch { text_from: handle.blocks.cursor_line(&handle.term), command_from: running, pattern }
+    };
+
+    wait(state, &pty_id, watch, arguments).await
+}
+
+async fn wait(state: &Arc<AppState>, pty_id: &str, watch: Watch, arguments: &Value) -> Value {
+    let timeout = arguments
+        .get("timeout")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(0.0, MAX_TIMEOUT_SECS);
+    let max_lines = arguments
+        .get("maxLines")
+        .and_then(|v| v.as_u64())
+        .map(|n| n.max(1) as usize)
+        .unwrap_or(DEFAULT_MAX_LINES);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs_f64(timeout);
+
+    loop {
+        // The terminal lock is never held across the sleep
+        let (progress, shell_integration, timed_out_output) = {
+            let Some(terminal) = state.terminal_registry.get(pty_id) else {
+                return serde_json::json!({ "status": "exited", "error": "The tab's terminal exited while waiting." });
+            };
+            let handle = terminal.lock();
+            let progress = poll(&handle.term, &handle.blocks, &watch);
+            let timed_out = progress == Progress::Running && tokio::time::Instant::now() >= deadline;
+            let shell_integration = handle.blocks.blocks().next().is_some();
+            let output = timed_out.then(|| output_so_far(&handle.term, &handle.blocks, &watch));
+            (progress, shell_integration, output)
+        };
+
+        match progress {
+            Progress::Finished { command, exit_code, output } => {
+                let (output, truncated) = tail_lines(&output, max_lines);
+                return serde_json::json!({
+                    "status": "finished",
+                    "command": command,
+                    "exitCode": exit_code,
+                    "output": output,
+                    "truncated": truncated,
+                });
+            }
+            Progress::Matched { matched, output } => {
+                let (output, truncated) = tail_lines(&output, max_lines);
+                return serde_json::json!({
+                    "status": "matched",
+                    "match": matched,
+                    "output": output,
+                    "truncated": truncated,
+                });
+            }
+            Progress::Running => {}
+        }
+
+        if let Some(output) = timed_out_output {
+            let (output, truncated) = tail_lines(&output, max_lines);
+            return serde_json::json!({
+                "status": "timeout",
+                "output": output,
+                "truncated": truncated,
+                // Without OSC 133 marks only a pattern can end the wait early
+                "shellIntegration": shell_integration,
+            });
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// `targetTabId` — `tabId` is the caller's own tab (injected from the
+/// connection's initSession).
+fn target_tab(arguments: &Value) -> Result<&str, String> {
+    let target = arguments
+        .get("targetTabId")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .ok_or("Missing required parameter: targetTabId")?;
+    if arguments.get("tabId").and_then(|v| v.as_str()) == Some(target) {
+        return Err("targetTabId is your own tab. Pick another terminal tab (see listWorkspaces).".to_string());
+    }
+    Ok(target)
+}
+
+fn parse_pattern(arguments: &Value) -> Result<Option<Regex>, String> {
+    arguments
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|p| Regex::new(p).map_err(|e| format!("Invalid pattern: {}", e)))
+        .transpose()
+}
+
+fn pty_for_tab(state: &AppState, tab_id: &str) -> Result<String, String> {
+    state.tab_pty_map.read().get(tab_id).cloned().ok_or_else(|| {
+        format!(
+            "Tab '{}' has no running terminal (it may be suspended or not a terminal tab). Use switchTab to activate it first.",
+            tab_id
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    struct Shell {
+        term: Term<VoidListener>,
+        blocks: BlockTracker,
+        processor: Processor,
+    }
+
+    impl Shell {
+        fn new() -> Self {
+            Self {
+                term: Term::new(Config::default(), &TermSize::new(40, 10), VoidListener),
+                blocks: BlockTracker::new(1000),
+                processor: Processor::default(),
+            }
+        }
+
+        fn feed(&mut self, data: &str, mark: Option<(char, Option<i32>)>) {
+            self.blocks.advance(&mut self.term, &mut self.processor, data.as_bytes());
+            if let Some((cmd, exit_code)) = mark {
+                self.blocks.mark(&self.term, cmd, exit_code, 0, 0);
+            }
+            self.blocks.end_of_chunk(&self.term, 0);
+        }
+
+        fn prompt(&mut self) {
+            self.feed("", Some(('A', None)));
+            self.feed("$ ", None);
+        }
+    }
+
+    #[test]
+    fn run_finishes_on_command_end_mark() {
+        let mut shell = Shell::new();
+        shell.prompt();
+        shell.feed("ls\r\n", Some(('B', None)));
+        shell.feed("old\r\n", Some(('D', Some(0))));
+        shell.prompt();
+
+        let from = shell.blocks.cursor_line(&shell.term) + 1;
+        let watch = Watch { text_from: from, command_from: Some(from), pattern: None };
+        assert_eq!(poll(&shell.term, &shell.blocks, &watch), Progress::Running);
+
+        shell.feed("cargo test\r\n", Some(('B', None)));
+        shell.feed("running 3 tests\r\n", None);
+        assert_eq!(poll(&shell.term, &shell.blocks, &watch), Progress::Running);
+        shell.feed("FAILED\r\n", Some(('D', Some(101))));
+        shell.prompt();
+
+        match poll(&shell.term, &shell.blocks, &watch) {
+            Progress::Finished { command, exit_code, output } => {
+                assert_eq!(command.as_deref(), Some("cargo test"));
+                assert_eq!(exit_code, Some(101));
+                assert_eq!(tail_lines(&output, 1), ("FAILED".to_string(), true));
+            }
+            other => panic!("expected Finished, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn pattern_matches_only_new_output() {
+        let mut shell = Shell::new();
+        shell.feed("ready (old)\r\n", None);
+        let watch = Watch {
+            text_from: shell.blocks.cursor_line(&shell.term),
+            command_from: None,
+            pattern: Some(Regex::new(r"ready in \d+ms").unwrap()),
+        };
+        assert_eq!(poll(&shell.term, &shell.blocks, &watch), Progress::Running);
+
+        shell.feed("compiling...\r\nready in 42ms\r\n", None);
+        match poll(&shell.term, &shell.blocks, &watch) {
+            Progress::Matched { matched, output } => {
+                assert_eq!(matched, "ready in 42ms");
+                assert!(!output.contains("old"));
+            }
+            other => panic!("expected Matched, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::state::now_ms;
+
+/// Who an MCP auth token was issued to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenOwner {
+    /// Clients on this machine (the token in the lock file and `~/.claude.json`)
+    Local,
+    /// Clients on the far end of the SSH tunnel for this host key (`user@host`)
+    Host(String),
+}
+
+struct IssuedToken {
+    token: String,
+    issued_ms: i64,
+    last_used_ms: Option<i64>,
+}
+
+impl IssuedToken {
+    fn new(token: String) -> Self {
+        Self {
+            token,
+            issued_ms: now_ms(),
+            last_used_ms: None,
+        }
+    }
+}
+
+/// A rotated-out token still accepted until `expires_ms`, so clients that
+/// read the old one at startup keep working for a while.
+struct RetiredToken {
+    token: String,
+    owner: TokenOwner,
+    expires_ms: i64,
+}
+
+/// What the UI shows about an issued token; never the token itself.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct McpTokenInfo {
+    /// `None` for the local token
+    pub host_key: Option<String>,
+    /// First characters of the token, to tell rotations apart
+    pub prefix: String,
+    pub issued_ms: i64,
+    pub last_used_ms: Option<i64>,
+    /// Rotated-out tokens of this owner that are still accepted
+    pub retired: usize,
+}
+
+/// Every auth token the MCP server accepts: one for local clients and one per
+/// SSH tunnel, issued when the tunnel comes up and revoked when it goes away.
+#[derive(Default)]
+pub struct McpTokens {
+    local: Option<IssuedToken>,
+    hosts: HashMap<String, IssuedToken>,
+    retired: Vec<RetiredToken>,
+}
+
+impl McpTokens {
+    pub fn set_local(&mut self, token: String) {
+        self.local = Some(IssuedToken::new(token));
+    }
+
+    /// The owner of `auth`, or `None` if it isn't a live token. Records the use.
+    pub fn identify(&mut self, auth: &str) -> Option<TokenOwner> {
+        if auth.is_empty() {
+            return None;
+        }
+        let now = now_ms();
+        if let Some(local) = self.local.as_mut().filter(|t| t.token == auth) {
+            local.last_used_ms = Some(now);
+            return Some(TokenOwner::Local);
+        }
+        if let Some((host_key, issued)) = self.hosts.iter_mut().find(|(_, t)| t.token == auth) {
+            issued.last_used_ms = Some(now);
+            return Some(TokenOwner::Host(host_key.clone()));
+        }
+        self.retired.retain(|r| r.expires_ms > now);
+        self.retired.iter().find(|r| r.token == auth).map(|r| r.owner.clone())
+    }
+
+    /// The token for `host_key`'s tunnel, issuing one if it has none.
+    pub fn issue_host(&mut self, host_key: &str) -> String {
+        self.hosts
+            .entry(host_key.to_string())
+            .or_insert_with(|| IssuedToken::new(generate_token()))
+            .token
+            .clone()
+    }
+
+    pub fn host(&self, host_key: &str) -> Option<String> {
+        self.hosts.get(host_key).map(|t| t.token.clone())
+    }
+
+    /// When `host_key`'s current token was last presented.
+    pub fn host_last_used(&self, host_key: &str) -> Option<i64> {
+        self.hosts.get(host_key).and_then(|t| t.last_used_ms)
+    }
+
+    /// Stop accepting `host_key`'s token, including rotated-out ones.
+    pub fn revoke_host(&mut self, host_key: &str) -> bool {
+        let owner = TokenOwner::Host(host_key.to_string());
+        self.retired.retain(|r| r.owner != owner);
+        self.hosts.remove(host_key).is_some()
+    }
+
+    /// Replace `owner`'s token, keeping the old one valid for `grace_ms`.
+    /// Returns the new token, or `None` if `owner` has none to rotate.
+    pub fn rotate(&mut self, owner: &TokenOwner, grace_ms: i64) -> Option<String> {
+        let slot = match owner {
+            TokenOwner::Local => self.local.as_mut()?,
+            TokenOwner::Host(host_key) => self.hosts.get_mut(host_key)?,
+        };
+        let old = std::mem::replace(slot, IssuedToken::new(generate_token()));
+        let token = slot.token.clone();
+        if grace_ms > 0 {
+            self.retired.push(RetiredToken {
+                token: old.token,
+                owner: owner.clone(),
+                expires_ms: now_ms() + grace_ms,
+            });
+        }
+        Some(token)
+    }
+
+    /// Owners whose current token is older than the max age for its kind
+    /// (0 = never rotate).
+    pub fn due_for_rotation(&self, local_max_age_ms: i64, host_max_age_ms: i64) -> Vec<TokenOwner> {
+        let now = now_ms();
+        let due = |t: &IssuedToken, max_age_ms: i64| max_age_ms != 0 && t.issued_ms < now - max_age_ms;
+        let local = self
+            .local
+            .iter()
+            .filter(|t| due(t, local_max_age_ms))
+            .map(|_| TokenOwner::Local);
+        let hosts = self
+            .hosts
+            .iter()
+            .filter(|(_, t)| due(t, host_max_age_ms))
+            .map(|(host_key, _)| TokenOwner::Host(host_key.clone()));
+        local.chain(hosts).collect()
+    }
+
+    pub fn list(&self) -> Vec<McpTokenInfo> {
+        let now = now_ms();
+        let info = |host_key: Option<&String>, t: &IssuedToken| {
+            let owner = host_key.map_or(TokenOwner::Local, |h| TokenOwner::Host(h.clone()));
+            McpTokenInfo {
+                host_key: host_key.cloned(),
+                prefix: t.token.chars().take(4).collect(),
+                issued_ms: t.issued_ms,
+                last_used_ms: t.last_used_ms,
+                retired: self.retired.iter().filter(|r| r.owner == owner && r.expires_ms > now).count(),
+            }
+        };
+        let mut hosts: Vec<McpTokenInfo> = self.hosts.iter().map(|(h, t)| info(Some(h), t)).collect();
+        hosts.sort_by(|a, b| a.host_key.cmp(&b.host_key));
+        self.local.iter().map(|t| info(None, t)).chain(hosts).collect()
+    }
+}
+
+/// A fresh 32-character alphanumeric token.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_identify_rotate_and_revoke() {
+        let mut tokens = McpTokens::default();
+        assert_eq!(tokens.identify(""), None);
+        tokens.set_local("local-token".into());
+        let host = tokens.issue_host("me@box");
+        assert_eq!(tokens.issue_host("me@box"), host);
+        assert_eq!(tokens.identify("local-token"), Some(TokenOwner::Local));
+        assert_eq!(tokens.identify(&host), Some(TokenOwner::Host("me@box".into())));
+        assert_eq!(tokens.identify("guess"), None);
+
+        // The old token keeps working through the grace period
+        let owner = TokenOwner::Host("me@box".into());
+        let rotated = tokens.rotate(&owner, 60_000).unwrap();
+        assert_ne!(rotated, host);
+        assert_eq!(tokens.identify(&host), Some(owner.clone()));
+        assert_eq!(tokens.identify(&rotated), Some(owner.clone()));
+        assert_eq!(tokens.list()[1].retired, 1);
+
+        // ...but not without one
+        let local = tokens.rotate(&TokenOwner::Local, 0).unwrap();
+        assert_eq!(tokens.identify("local-token"), None);
+        assert_eq!(tokens.identify(&local), Some(TokenOwner::Local));
+
+        assert!(tokens.revoke_host("me@box"));
+        assert_eq!(tokens.identify(&host), None);
+        assert_eq!(tokens.identify(&rotated), None);
+        assert!(tokens.rotate(&owner, 0).is_none());
+        assert!(tokens.due_for_rotation(60_000, 60_000).is_empty());
+        assert_eq!(tokens.due_for_rotation(-1, -1), vec![TokenOwner::Local]);
+
+        // Local rotation is off independently of host rotation
+        tokens.issue_host("me@box");
+        assert_eq!(tokens.due_for_rotation(0, -1), vec![TokenOwner::Host("me@box".into())]);
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::usage::{locate_transcript, project_dir_name};
+use crate::commands::editor::{extract_user_host, scp_download};
+use crate::state::hook_event_db::clip_strings;
+use crate::state::workspace::TranscriptSource;
+
+/// Transcripts bigger than this aren't loaded (long sessions run to tens of MB).
+const MAX_TRANSCRIPT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Tool results and tool input strings are cut to this many chars; a `Read`
+/// of a large file would otherwise dominate the view.
+const MAX_TOOL_TEXT: usize = 20_000;
+
+/// A Claude session conversation, parsed for the Transcript tab.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeTranscript {
+    pub session_id: String,
+    /// Local path, or `user@host:path` for a remote session
+    pub location: String,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// One rendered item. `sidechain` marks messages of subagents (Task tool).
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TranscriptEntry {
+    User {
+        timestamp: Option<String>,
+        text: String,
+        sidechain: bool,
+    },
+    Assistant {
+        timestamp: Option<String>,
+        model: Option<String>,
+        text: String,
+        sidechain: bool,
+    },
+    Thinking {
+        timestamp: Option<String>,
+        text: String,
+        sidechain: bool,
+    },
+    ToolCall {
+        timestamp: Option<String>,
+        id: String,
+        name: String,
+        input: Value,
+        result: Option<ToolResult>,
+        sidechain: bool,
+    },
+    /// Compaction summary standing in for the earlier conversation
+    Summary {
+        timestamp: Option<String>,
+        text: String,
+    },
+}
+
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// Parse a transcript JSONL. Tool results are attached to the call they
+/// answer; lines that aren't conversation (snapshots, meta messages) are skipped.
+pub fn parse(content: &str) -> Vec<TranscriptEntry> {
+    let mut entries = Vec::new();
+    let mut calls: HashMap<String, usize> = HashMap::new();
+
+    for line in content.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let timestamp = value.get("timestamp").and_then(|t| t.as_str()).map(str::to_string);
+        let sidechain = value.get("isSidechain").and_then(|v| v.as_bool()).unwrap_or(false);
+        let message = value.get("message");
+        let content = message.and_then(|m| m.get("content"));
+
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("summary") => {
+                if let Some(text) = value.get("summary").and_then(|s| s.as_str()) {
+                    entries.push(TranscriptEntry::Summary { timestamp, text: text.to_string() });
+                }
+            }
+            Some("user") if value.get("isMeta").and_then(|v| v.as_bool()) != Some(true) => {
+                let mut texts = Vec::new();
+                match content {
+                    Some(Value::String(text)) => texts.push(text.clone()),
+                    Some(Value::Array(blocks)) => {
+                        for block in blocks {
+                            match block.get("type").and_then(|t| t.as_str()) {
+                                Some("text") => texts.extend(block.get("text").and_then(|t| t.as_str()).map(str::to_string)),
+                                Some("image") => texts.push("[image]".to_string()),
+                                Some("tool_result") => {
+                                    let id = block.get("tool_use_id").and_then(|v| v.as_str()).unwrap_or("");
+                                    if let Some(&idx) = calls.get(id) {
+                                        if let TranscriptEntry::ToolCall { result, .. } = &mut entries[idx] {
+                                            *result = Some(ToolResult {
+                                                text: clip(result_text(block.get("content"))),
+                                                is_error: block.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false),
+                                            });
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                let text = texts.join("\n\n");
+                if text.trim().is_empty() {
+                    continue;
+                }
+                if value.get("isCompactSummary").and_then(|v| v.as_bool()) == Some(true) {
+                    entries.push(TranscriptEntry::Summary { timestamp, text });
+                } else {
+                    entries.push(TranscriptEntry::User { timestamp, text, sidechain });
+                }
+            }
+            Some("assistant") => {
+                let model = message.and_then(|m| m.get("model")).and_then(|m| m.as_str()).map(str::to_string);
+                for block in content.and_then(|c| c.as_array()).into_iter().flatten() {
+                    let text = |key: &str| block.get(key).and_then(|t| t.as_str()).unwrap_or("").to_string();
+                    match block.get("type").and_then(|t| t.as_str()) {
+                        Some("text") if !text("text").trim().is_empty() => entries.push(TranscriptEntry::Assistant {
+                            timestamp: timestamp.clone(),
+                            model: model.clone(),
+                            text: text("text"),
+                            sidechain,
+                        }),
+                        Some("thinking") if !text("thinking").trim().is_empty() => entries.push(TranscriptEntry::Thinking {
+                            timestamp: timestamp.clone(),
+                            text: text("thinking"),
+                            sidechain,
+                        }),
+                        Some("tool_use") => {
+                            let id = text("id");
+                            let mut input = block.get("input").cloned().unwrap_or(Value::Null);
+                            clip_strings(&mut input, MAX_TOOL_TEXT);
+                            calls.insert(id.clone(), entries.len());
+                            entries.push(TranscriptEntry::ToolCall {
+                                timestamp: timestamp.clone(),
+                                id,
+                                name: text("name"),
+                                input,
+                                result: None,
+                                sidechain,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// `tool_result` content is a string or a list of text/image blocks.
+fn result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .map(|b| match b.get("type").and_then(|t| t.as_str()) {
+                Some("text") => b.get("text").and_then(|t| t.as_str()).unwrap_or("").to_string(),
+                Some("image") => "[image]".to_string(),
+                _ => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn clip(text: String) -> String {
+    let count = text.chars().count();
+    if count <= MAX_TOOL_TEXT {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_TOOL_TEXT).collect();
+    format!("{}… [{} chars]", cut, count)
+}
+
+/// Load and parse the transcript of `source`: from `~/.claude/projects`, or
+/// over SCP when the session ran on an SSH host. Blocking.
+pub fn read(source: &TranscriptSource) -> Result<ClaudeTranscript, String> {
+    let session_id = &source.session_id;
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid Claude session id: {}", session_id));
+    }
+
+    let (location, content) = match source.ssh_command.as_deref() {
+        Some(ssh_command) => {
+            let user_host = extract_user_host(ssh_command)?;
+            let remote_path = locate_remote(&user_host, session_id, source.cwd.as_deref())?;
+            let local_path = scp_download(&user_host, &remote_path)?;
+            let content = read_capped(&local_path);
+            let _ = std::fs::remove_file(&local_path);
+            (format!("{}:{}", user_host, remote_path), content?)
+        }
+        None => {
+            let path = locate_transcript(session_id, source.cwd.as_deref())
+                .ok_or_else(|| format!("No transcript found for session {} in ~/.claude/projects", session_id))?;
+            let content = read_capped(&path)?;
+            (path.to_string_lossy().into_owned(), content)
+        }
+    };
+
+    Ok(ClaudeTranscript {
+        session_id: session_id.clone(),
+        location,
+        entries: parse(&content),
+    })
+}
+
+fn read_capped(path: &std::path::Path) -> Result<String, String> {
+    let size = std::fs::metadata(path).map_err(|e| format!("Cannot stat transcript: {}", e))?.len();
+    if size > MAX_TRANSCRIPT_BYTES {
+        return Err(format!("FILE_TOO_LARGE:{:.1}", size as f64 / (1024.0 * 1024.0)));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot read transcript: {}", e))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Absolute path of the session's transcript on the SSH host: the project
+/// folder for `cwd` if it has it, else any project folder.
+fn locate_remote(user_host: &str, session_id: &str, cwd: Option<&str>) -> Result<String, String> {
+    let preferred = cwd
+        .map(|cwd| format!("f=~/.claude/projects/{}/{}.jsonl; [ -f \"$f\" ] && echo \"$f\" && exit 0; ", project_dir_name(cwd), session_id))
+        .unwrap_or_default();
+    let cmd = format!("{}ls -1 ~/.claude/projects/*/{}.jsonl 2>/dev/null | head -n 1", preferred, session_id);
+    let output = std::process::Command::new("ssh")
+        .arg("-o").arg("BatchMode=yes")
+        .arg("-o").arg("ConnectTimeout=10")
+        .arg(user_host)
+        .arg(&cmd)
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || path.is_empty() {
+        return Err(format!("No transcript found for session {} on {}", session_id, user_host));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_turns_tool_calls_and_results() {
+        let lines = [
+            r#"{"type":"summary","summary":"Fix the build","leafUuid":"x"}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"<local-command-caveat>"}}"#,
+            r#"{"type":"user","timestamp":"t1","message":{"role":"user","content":"Run the tests"}}"#,
+            r#"{"type":"assistant","timestamp":"t2","message":{"model":"claude-sonnet-4-5","content":[{"type":"thinking","thinking":"Plan"},{"type":"text","text":"Running them."}]}}"#,
+            r#"{"type":"assistant","timestamp":"t3","message":{"model":"claude-sonnet-4-5","content":[{"type":"tool_use","id":"tu1","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","timestamp":"t4","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"tu1","is_error":true,"content":[{"type":"text","text":"1 failed"}]}]}}"#,
+            r#"{"type":"file-history-snapshot","snapshot":{}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"content":[{"type":"text","text":"sub"}]}}"#,
+            "not json",
+        ];
+        let entries = parse(&lines.join("\n"));
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0], TranscriptEntry::Summary { timestamp: None, text: "Fix the build".into() });
+        assert_eq!(
+            entries[1],
+            TranscriptEntry::User { timestamp: Some("t1".into()), text: "Run the tests".into(), sidechain: false }
+        );
+        assert!(matches!(&entries[2], TranscriptEntry::Thinking { text, .. } if text == "Plan"));
+        assert!(matches!(&entries[3], TranscriptEntry::Assistant { model: Some(m), .. } if m == "claude-sonnet-4-5"));
+        match &entries[4] {
+            TranscriptEntry::ToolCall { name, input, result, .. } => {
+                assert_eq!(name, "Bash");
+                assert_eq!(input["command"], "cargo test");
+                assert_eq!(result, &Some(ToolResult { text: "1 failed".into(), is_error: true }));
+            }
+            other => panic!("expected a tool call, got {:?}", other),
+        }
+        assert!(matches!(&entries[5], TranscriptEntry::Assistant { sidechain: true, .. }));
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use crate::state::workspace::ModelPrice;
+use crate::state::AppState;
+
+/// Token counts for a session (or a sum of sessions) and their cost under
+/// `Preferences.claude_model_prices`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// USD; models missing from the price table count as zero
+    pub cost_usd: f64,
+}
+
+impl TokenUsage {
+    fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens == 0
+    }
+}
+
+/// Usage of one API response in a transcript.
+struct UsageEntry {
+    /// Unix time in milliseconds (0 if the line had no timestamp)
+    ts: i64,
+    model: String,
+    tokens: TokenUsage,
+}
+
+/// A session transcript read up to `offset`. Claude writes one line per
+/// content block of a response, each repeating the response's usage, so
+/// entries are keyed by message id and the last line wins.
+struct Transcript {
+    path: PathBuf,
+    offset: u64,
+    by_message: HashMap<String, usize>,
+    entries: Vec<UsageEntry>,
+}
+
+impl Transcript {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            by_message: HashMap::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Read complete lines appended since the last call.
+    fn read_new(&mut self) {
+        let Ok(mut file) = File::open(&self.path) else {
+            return;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            // Rewritten rather than appended to: start over
+            *self = Transcript::new(std::mem::take(&mut self.path));
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return;
+        }
+        let mut buf = Vec::new();
+        if file.take(len - self.offset).read_to_end(&mut buf).is_err() {
+            return;
+        }
+        // A trailing partial line is picked up once Claude finishes writing it
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+            return;
+        };
+        for line in buf[..end].split(|&b| b == b'\n') {
+            self.ingest(line);
+        }
+        self.offset += end as u64 + 1;
+    }
+
+    fn ingest(&mut self, line: &[u8]) {
+        let Ok(value) = serde_json::from_slice::<Value>(line) else {
+            return;
+        };
+        if value.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            return;
+        }
+        let Some(message) = value.get("message") else {
+            return;
+        };
+        let Some(usage) = message.get("usage") else {
+            return;
+        };
+        let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let entry = UsageEntry {
+            ts: value
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(parse_timestamp)
+                .unwrap_or(0),
+            model: message.get("model").and_then(|m| m.as_str()).unwrap_or("").to_string(),
+            tokens: TokenUsage {
+                input_tokens: count("input_tokens"),
+                output_tokens: count("output_tokens"),
+                cache_creation_tokens: count("cache_creation_input_tokens"),
+                cache_read_tokens: count("cache_read_input_tokens"),
+                cost_usd: 0.0,
+            },
+        };
+        let id = message.get("id").or_else(|| value.get("uuid")).and_then(|v| v.as_str());
+        match id {
+            Some(id) => match self.by_message.get(id) {
+                Some(&idx) => self.entries[idx] = entry,
+                None => {
+                    self.by_message.insert(id.to_string(), self.entries.len());
+                    self.entries.push(entry);
+                }
+            },
+            None => self.entries.push(entry),
+        }
+    }
+
+    fn total(&self, since: Option<i64>, prices: &[ModelPrice]) -> TokenUsage {
+        let mut total = TokenUsage::default();
+        for entry in self.entries.iter().filter(|e| since.is_none_or(|s| e.ts >= s)) {
+            let mut tokens = entry.tokens;
+            tokens.cost_usd = price_for(prices, &entry.model).map_or(0.0, |p| cost(&tokens, p));
+            total.add(&tokens);
+        }
+        total
+    }
+}
+
+/// Session transcripts under `~/.claude/projects`, tailed incrementally so
+/// repeated queries only parse what Claude appended since the last one.
+#[derive(Default)]
+pub struct UsageTracker {
+    /// `transcript_path` reported by hooks, by session id
+    paths: HashMap<String, PathBuf>,
+    transcripts: HashMap<String, Transcript>,
+}
+
+impl UsageTracker {
+    pub fn set_transcript_path(&mut self, session_id: &str, path: &str) {
+        if !session_id.is_empty() && path.ends_with(".jsonl") {
+            self.paths.insert(session_id.to_string(), PathBuf::from(path));
+        }
+    }
+
+    /// Take a session's open transcript out to read it with the tracker
+    /// unlocked, or the transcript path hooks reported when none is open yet.
+    fn checkout(&mut self, session_id: &str) -> (Option<Transcript>, Option<PathBuf>) {
+        match self.transcripts.remove(session_id) {
+            Some(transcript) => (Some(transcript), None),
+            None => (None, self.paths.get(session_id).cloned()),
+        }
+    }
+}
+
+/// Tokens each of `sessions` (id, cwd) used at or after `since` (unix ms),
+/// skipping those whose transcript isn't on this machine (e.g. sessions
+/// running over SSH). Transcripts are read with the tracker unlocked, so
+/// hooks recording a transcript path never wait on file I/O.
+pub fn sessions_usage(
+    tracker: &Mutex<UsageTracker>,
+    sessions: Vec<(String, Option<String>)>,
+    since: Option<i64>,
+    prices: &[ModelPrice],
+) -> Vec<(String, TokenUsage)> {
+    let checked_out: Vec<_> = {
+        let mut tracker = tracker.lock();
+        sessions
+            .into_iter()
+            .map(|(id, cwd)| {
+                let (transcript, path) = tracker.checkout(&id);
+                (id, cwd, transcript, path)
+            })
+            .collect()
+    };
+
+    let mut usage = Vec::new();
+    let mut read = Vec::new();
+    for (id, cwd, transcript, path) in checked_out {
+        let transcript = transcript.or_else(|| {
+            path.filter(|p| p.is_file())
+                .or_else(|| locate_transcript(&id, cwd.as_deref()))
+                .map(Transcript::new)
+        });
+        let Some(mut transcript) = transcript else {
+            continue;
+        };
+        transcript.read_new();
+        usage.push((id.clone(), transcript.total(since, prices)));
+        read.push((id, transcript));
+    }
+
+    let mut tracker = tracker.lock();
+    for (id, transcript) in read {
+        // A concurrent reader may have opened it meanwhile; either is current
+        tracker.transcripts.entry(id).or_insert(transcript);
+    }
+    usage
+}
+
+/// `~/.claude/projects/<project>/<session_id>.jsonl`, trying the directory
+/// Claude derives from `cwd` before scanning every project.
+pub(crate) fn locate_transcript(session_id: &str, cwd: Option<&str>) -> Option<PathBuf> {
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    let projects = dirs::home_dir()?.join(".claude").join("projects");
+    let file = format!("{}.jsonl", session_id);
+    if let Some(path) = cwd.map(|cwd| projects.join(project_dir_name(cwd)).join(&file)) {
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    std::fs::read_dir(&projects)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(&file))
+        .find(|path| path.is_file())
+}
+
+/// Claude Code's project directory name: the cwd with everything but ASCII
+/// letters and digits replaced by `-`.
+pub(crate) fn project_dir_name(cwd: &str) -> String {
+    cwd.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect()
+}
+
+/// The first price whose `model` is contained in the model id.
+fn price_for<'a>(prices: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+    prices.iter().find(|p| !p.model.is_empty() && model.contains(&p.model))
+}
+
+fn cost(tokens: &TokenUsage, price: &ModelPrice) -> f64 {
+    (tokens.input_tokens as f64 * price.input
+        + tokens.output_tokens as f64 * price.output
+        + tokens.cache_creation_tokens as f64 * price.cache_write
+        + tokens.cache_read_tokens as f64 * price.cache_read)
+        / 1_000_000.0
+}
+
+/// Unix milliseconds from a transcript timestamp (`2025-06-01T12:34:56.789Z`).
+fn parse_timestamp(ts: &str) -> Option<i64> {
+    let (date, time) = ts.strip_suffix('Z')?.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (date.next()??, date.next()??, date.next()??);
+    let (hms, frac) = time.split_once('.').unwrap_or((time, "0"));
+    let mut hms = hms.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hh, mm, ss) = (hms.next()??, hms.next()??, hms.next()??);
+    let millis: i64 = format!("{:0<3}", frac).get(..3)?.parse().ok()?;
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    Some(((days_from_civil(y, m, d) * 24 + hh) * 60 + mm) * 60_000 + ss * 1000 + millis)
+}
+
+/// Days since the Unix epoch for a proleptic Gregorian date (Howard Hinnant).
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Usage of every tracked session, for `getClaudeSessions`.
+pub fn tracked_session_usage(state: &AppState) -> HashMap<String, TokenUsage> {
+    let sessions: Vec<(String, Option<String>)> = state
+        .claude_sessions
+        .read()
+        .iter()
+        .map(|(id, info)| (id.clone(), info.cwd.clone()))
+        .collect();
+    let prices = state.app_data.read().preferences.claude_model_prices.clone();
+    sessions_usage(&state.claude_usage, sessions, None, &prices).into_iter().collect()
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub session_id: String,
+    pub tab_id: Option<String>,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabUsage {
+    pub tab_id: String,
+    /// `None` once the tab has been closed
+    pub tab_name: Option<String>,
+    pub workspace_id: Option<String>,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUsage {
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub usage: TokenUsage,
+}
+
+/// Usage totals per session, tab and workspace, most expensive first.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub since: Option<i64>,
+    pub total: TokenUsage,
+    pub workspaces: Vec<WorkspaceUsage>,
+    pub tabs: Vec<TabUsage>,
+    pub sessions: Vec<SessionUsage>,
+}
+
+/// Usage at or after `since` (unix ms) of the sessions seen by hooks in that
+/// window (the hook timeline keeps 30 days) plus the ones tracked now.
+pub fn report(state: &AppState, since: Option<i64>) -> Result<UsageReport, String> {
+    let mut sessions: HashMap<String, (Option<String>, Option<String>)> = state
+        .hook_event_db
+        .sessions_since(since.unwrap_or(0))?
+        .into_iter()
+        .map(|(id, tab_id)| (id, (tab_id, None)))
+        .collect();
+    for (id, info) in state.claude_sessions.read().iter() {
+        sessions.insert(id.clone(), (Some(info.tab_id.clone()).filter(|t| !t.is_empty()), info.cwd.clone()));
+    }
+    let prices = state.app_data.read().preferences.claude_model_prices.clone();
+
+    let (tab_ids, cwds): (HashMap<String, Option<String>>, Vec<(String, Option<String>)>) = sessions
+        .into_iter()
+        .map(|(id, (tab_id, cwd))| ((id.clone(), tab_id), (id, cwd)))
+        .unzip();
+    let mut session_usage: Vec<SessionUsage> = sessions_usage(&state.claude_usage, cwds, since, &prices)
+        .into_iter()
+        .filter(|(_, usage)| !usage.is_empty())
+        .map(|(session_id, usage)| SessionUsage { tab_id: tab_ids[&session_id].clone(), session_id, usage })
+        .collect();
+
+    let mut total = TokenUsage::default();
+    let mut tabs: HashMap<String, TabUsage> = HashMap::new();
+    let mut workspaces: HashMap<String, WorkspaceUsage> = HashMap::new();
+    {
+        let app_data = state.app_data.read();
+        for session in &session_usage {
+            total.add(&session.usage);
+            let Some(tab_id) = &session.tab_id else {
+                continue;
+            };
+            let found = app_data.find_tab(tab_id);
+            let tab = tabs.entry(tab_id.clone()).or_insert_with(|| TabUsage {
+                tab_id: tab_id.clone(),
+                tab_name: found.map(|(_, tab)| tab.name.clone()),
+                workspace_id: found.map(|(ws, _)| ws.id.clone()),
+                usage: TokenUsage::default(),
+            });
+            tab.usage.add(&session.usage);
+            if let Some((ws, _)) = found {
+                workspaces
+                    .entry(ws.id.clone())
+                    .or_insert_with(|| WorkspaceUsage {
+                        workspace_id: ws.id.clone(),
+                        workspace_name: ws.name.clone(),
+                        usage: TokenUsage::default(),
+                    })
+                    .usage
+                    .add(&session.usage);
+            }
+        }
+    }
+
+    let by_cost = |a: &TokenUsage, b: &TokenUsage| b.cost_usd.total_cmp(&a.cost_usd);
+    session_usage.sort_by(|a, b| by_cost(&a.usage, &b.usage));
+    let mut tabs: Vec<TabUsage> = tabs.into_values().collect();
+    tabs.sort_by(|a, b| by_cost(&a.usage, &b.usage));
+    let mut workspaces: Vec<WorkspaceUsage> = workspaces.into_values().collect();
+    workspaces.sort_by(|a, b| by_cost(&a.usage, &b.usage));
+
+    Ok(UsageReport {
+        since,
+        total,
+        workspaces,
+        tabs,
+        sessions: session_usage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_transcript_timestamps() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00.000Z"), Some(0));
+        assert_eq!(parse_timestamp("2025-06-01T12:34:56.789Z"), Some(1_748_781_296_789));
+        assert_eq!(parse_timestamp("2024-02-29T00:00:01Z"), Some(1_709_164_801_000));
+        assert_eq!(parse_timestamp("2025-13-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn tails_transcripts_and_prices_usage() {
+        let path = std::env::temp_dir().join(format!("maiterm-usage-{}.jsonl", uuid::Uuid::new_v4()));
+        let line = |id: &str, model: &str, ts: &str, output: u64| {
+            serde_json::json!({
+                "type": "assistant",
+                "timestamp": ts,
+                "message": {
+                    "id": id,
+                    "model": model,
+                    "usage": {
+                        "input_tokens": 1000,
+                        "output_tokens": output,
+                        "cache_creation_input_tokens": 2000,
+                        "cache_read_input_tokens": 10_000,
+                    },
+                },
+            })
+            .to_string()
+        };
+        // Two content blocks of one response, a user line, and a partial line
+        let first = format!(
+            "{}\n{}\n{}\n{}",
+            line("m1", "claude-sonnet-4-5", "2025-06-01T00:00:00.000Z", 10),
+            line("m1", "claude-sonnet-4-5", "2025-06-01T00:00:00.000Z", 500),
+            r#"{"type":"user","message":{"role":"user","content":"hi"}}"#,
+            &line("m2", "claude-opus-4-1", "2025-06-02T00:00:00.000Z", 100)[..40],
+        );
+        std::fs::write(&path, &first).unwrap();
+
+        let prices = vec![ModelPrice {
+            model: "sonnet".into(),
+            input: 3.0,
+            output: 15.0,
+            cache_write: 3.75,
+            cache_read: 0.3,
+        }];
+        let mut tracker = UsageTracker::default();
+        tracker.set_transcript_path("s1", path.to_str().unwrap());
+        let tracker = Mutex::new(tracker);
+        let session_usage = |since| sessions_usage(&tracker, vec![("s1".into(), None)], since, &prices)[0].1;
+        let usage = session_usage(None);
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.cache_read_tokens), (1000, 500, 10_000));
+        // 1000 * 3 + 500 * 15 + 2000 * 3.75 + 10000 * 0.3 per million
+        assert!((usage.cost_usd - 0.021).abs() < 1e-9);
+
+        // Finish the partial line; the unpriced model adds tokens but no cost
+        let rest = &line("m2", "claude-opus-4-1", "2025-06-02T00:00:00.000Z", 100)[40..];
+        std::fs::write(&path, format!("{}{}\n", first, rest)).unwrap();
+        let usage = session_usage(None);
+        assert_eq!(usage.output_tokens, 600);
+        assert!((usage.cost_usd - 0.021).abs() < 1e-9);
+
+        let since = parse_timestamp("2025-06-01T12:00:00Z");
+        assert_eq!(session_usage(since).output_tokens, 100);
+        assert_eq!(tracker.lock().transcripts["s1"].entries.len(), 2);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn project_dir_names_match_claude() {
+        assert_eq!(project_dir_name("/Users/me/src/my.app"), "-Users-me-src-my-app");
+    }
+}
@@ -2,6 +2,14 @@ use std::sync::Arc;
 use serde_json::Value;
 use tauri::State;
 
+use crate::claude_code::resources::{self, ResourceUri};
+use crate::claude_code::server;
+use crate::claude_code::tokens::McpTokenInfo;
+use crate::claude_code::transcript::{self, ClaudeTranscript};
+use crate::claude_code::usage::{self, UsageReport};
+use crate::state::hook_event_db::{HookEvent, TimelineQuery};
+use crate::state::mcp_audit_db::McpAuditEntry;
+use crate::state::workspace::TranscriptSource;
 use crate::state::AppState;
 
 /// Called by the frontend to send a tool response back to Claude CLI.
@@ -35,3 +43,80 @@ pub fn claude_code_notify_selection(
         Ok(())
     }
 }
+
+/// Called (debounced) by the frontend when an editor buffer changes, so MCP
+/// clients subscribed to its `maiterm://tab/<id>/editor` resource are notified.
+#[tauri::command]
+pub fn claude_code_editor_changed(state: State<'_, Arc<AppState>>, tab_id: String) {
+    resources::mark_changed(&state, ResourceUri::Editor(tab_id));
+}
+
+/// Recorded hook events for a tab or session, oldest first (the most recent
+/// `limit`, default 500). `since` is unix time in milliseconds.
+#[tauri::command]
+pub fn get_hook_timeline(
+    state: State<'_, Arc<AppState>>,
+    tab_id: Option<String>,
+    session_id: Option<String>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<HookEvent>, String> {
+    state.hook_event_db.timeline(&TimelineQuery {
+        tab_id,
+        session_id,
+        since,
+        limit: limit.unwrap_or(500).min(5000),
+    })
+}
+
+/// Recorded MCP tool calls, most recent first (at most `limit`, default 200),
+/// optionally only those from `tab_id` or at or after `since` (unix ms).
+#[tauri::command]
+pub fn get_mcp_audit_log(
+    state: State<'_, Arc<AppState>>,
+    tab_id: Option<String>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<McpAuditEntry>, String> {
+    state.mcp_audit_db.recent(tab_id.as_deref(), since, limit.unwrap_or(200).min(5000))
+}
+
+/// Tokens the MCP server accepts: the local one first, then one per SSH host.
+#[tauri::command]
+pub fn list_mcp_tokens(state: State<'_, Arc<AppState>>) -> Vec<McpTokenInfo> {
+    state.mcp_tokens.lock().list()
+}
+
+/// Replace the local token (`host_key` = None) or a host's token right away.
+/// Unlike scheduled rotation the old token stops working immediately, so
+/// sessions holding it have to reconnect.
+#[tauri::command]
+pub fn rotate_mcp_token(
+    app: tauri::AppHandle,
+    state: State<'_, Arc<AppState>>,
+    host_key: Option<String>,
+) -> Result<(), String> {
+    match host_key {
+        Some(host_key) => server::rotate_host_token(&state, &app, &host_key, 0),
+        None => server::rotate_local_token(&state, 0),
+    }
+}
+
+/// Claude token usage and cost per session, tab and workspace at or after
+/// `since` (unix time in milliseconds), read from session transcripts.
+#[tauri::command]
+pub async fn get_claude_usage(state: State<'_, Arc<AppState>>, since: Option<i64>) -> Result<UsageReport, String> {
+    let app_state = state.inner().clone();
+    // The first read of a long transcript can take a moment
+    tauri::async_runtime::spawn_blocking(move || usage::report(&app_state, since))
+        .await
+        .map_err(|e| format!("Usage task failed to run: {}", e))?
+}
+
+/// Load a Claude session transcript for a Transcript tab, locally or over SCP.
+#[tauri::command]
+pub async fn read_claude_transcript(source: TranscriptSource) -> Result<ClaudeTranscript, String> {
+    tauri::async_runtime::spawn_blocking(move || transcript::read(&source))
+        .await
+        .map_err(|e| format!("Transcript task failed to run: {}", e))?
+}
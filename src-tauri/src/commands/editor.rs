@@ -114,22 +114,7 @@ pub async fn scp_read_file_base64(
     let remote_path = expand_remote_tilde(&user_host, &remote_path);
 
     // Download via SCP
-    let temp_dir = std::env::temp_dir();
-    let temp_name = format!("aiterm-scp-{}", uuid::Uuid::new_v4());
-    let local_path = temp_dir.join(&temp_name);
-
-    let output = std::process::Command::new("scp")
-        .arg("-o").arg("BatchMode=yes")
-        .arg("-o").arg("ConnectTimeout=10")
-        .arg(format!("{}:{}", user_host, remote_path))
-        .arg(local_path.to_str().unwrap())
-        .output()
-        .map_err(|e| format!("Failed to run scp: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("SCP download failed: {}", stderr.trim()));
-    }
+    let local_path = scp_download(&user_host, &remote_path)?;
 
     let metadata = std::fs::metadata(&local_path).map_err(|e| format!("Cannot stat file: {}", e))?;
     if metadata.len() > 20 * 1024 * 1024 {
@@ -200,22 +185,7 @@ pub async fn scp_read_file(
     }
 
     // Pre-checks passed — download via SCP
-    let temp_dir = std::env::temp_dir();
-    let temp_name = format!("aiterm-scp-{}", uuid::Uuid::new_v4());
-    let local_path = temp_dir.join(&temp_name);
-
-    let output = std::process::Command::new("scp")
-        .arg("-o").arg("BatchMode=yes")
-        .arg("-o").arg("ConnectTimeout=10")
-        .arg(format!("{}:{}", user_host, remote_path))
-        .arg(local_path.to_str().unwrap())
-        .output()
-        .map_err(|e| format!("Failed to run scp: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("SCP download failed: {}", stderr.trim()));
-    }
+    let local_path = scp_download(&user_host, &remote_path)?;
 
     let content = std::fs::read_to_string(&local_path)
         .map_err(|e| format!("Cannot read downloaded file: {}", e))?;
@@ -689,6 +659,28 @@ fn shell_quote(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
 }
 
+/// Copy a remote file to a new temp file via SCP. The caller removes it.
+pub(crate) fn scp_download(user_host: &str, remote_path: &str) -> Result<std::path::PathBuf, String> {
+    let temp_dir = std::env::temp_dir();
+    let temp_name = format!("aiterm-scp-{}", uuid::Uuid::new_v4());
+    let local_path = temp_dir.join(&temp_name);
+
+    let output = std::process::Command::new("scp")
+        .arg("-o").arg("BatchMode=yes")
+        .arg("-o").arg("ConnectTimeout=10")
+        .arg(format!("{}:{}", user_host, remote_path))
+        .arg(local_path.to_str().unwrap())
+        .output()
+        .map_err(|e| format!("Failed to run scp: {}", e))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&local_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("SCP download failed: {}", stderr.trim()));
+    }
+    Ok(local_path)
+}
+
 /// Expand `~` and `~username` prefixes on a remote host via SSH.
 /// SCP in SFTP mode doesn't support `~user` paths, so we resolve them first.
 fn expand_remote_tilde(user_host: &str, path: &str) -> String {
@@ -811,7 +803,7 @@ pub async fn get_file_mtime(path: String) -> Result<u64, String> {
 
 /// Extract user@host from an SSH command string.
 /// Handles formats like "ssh user@host", "ssh -o Foo=bar user@host", etc.
-fn extract_user_host(ssh_command: &str) -> Result<String, String> {
+pub(crate) fn extract_user_host(ssh_command: &str) -> Result<String, String> {
     let parts: Vec<&str> = ssh_command.split_whitespace().collect();
 
     // Find the user@host part (first argument that contains @ and isn't a flag value)
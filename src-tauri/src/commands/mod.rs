@@ -1,8 +1,10 @@
 pub mod claude_code;
 pub mod editor;
+pub mod port_forward;
 pub mod scheduler;
 pub mod ssh_tunnel;
 pub mod system;
 pub mod terminal;
+pub mod triggers;
 pub mod window;
 pub mod workspace;
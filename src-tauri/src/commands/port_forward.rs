@@ -0,0 +1,546 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::process::{Child, ChildStderr};
+
+use crate::commands::ssh_tunnel::{is_process_alive, kill_process};
+use crate::pty::clipboard::ssh_host;
+use crate::state::app_state::{ActivePortForward, PortForwardState};
+use crate::state::workspace::{PortForward, PortForwardKind};
+use crate::state::{now_ms, save_state, AppData, AppState};
+
+/// Live status of one forward on one SSH destination, as listed to the UI
+/// and emitted (all of them) as `port-forwards-changed`.
+#[derive(Clone, serde::Serialize)]
+pub struct PortForwardStatus {
+    pub forward: PortForward,
+    pub host_key: String,
+    pub state: PortForwardState,
+    pub error: Option<String>,
+    pub uptime_secs: Option<i64>,
+    pub tab_ids: Vec<String>,
+}
+
+/// A forward's ssh process once the forward is confirmed (or handed to a
+/// ControlMaster, in which case the process has already exited).
+struct Spawned {
+    child: Child,
+    pid: u32,
+    muxed: bool,
+    stderr: Option<Lines<BufReader<ChildStderr>>>,
+}
+
+/// Tell us a tab's SSH session came up (`ssh_args` = its cleaned args) or
+/// ended (`None`). Starts the tab's and its workspace's forwards for that
+/// destination, joining ones other tabs already run, and lets go of the rest.
+#[tauri::command]
+pub async fn sync_port_forwards(
+    app: AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    tab_id: String,
+    ssh_args: Option<String>,
+) -> Result<Vec<PortForwardStatus>, String> {
+    {
+        let mut sessions = state.port_forward_sessions.write();
+        match ssh_args {
+            Some(args) => sessions.insert(tab_id.clone(), args),
+            None => sessions.remove(&tab_id),
+        };
+    }
+    reconcile_tab(state.inner(), &app, &tab_id);
+    Ok(statuses(&state)
+        .into_iter()
+        .filter(|s| s.tab_ids.contains(&tab_id))
+        .collect())
+}
+
+/// Attach a forward to a tab (`tab_id`) or to the whole workspace, and start
+/// it on the SSH sessions it applies to.
+#[tauri::command]
+pub fn add_port_forward(
+    window: tauri::Window,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    workspace_id: String,
+    tab_id: Option<String>,
+    mut forward: PortForward,
+) -> Result<PortForward, String> {
+    forward.validate()?;
+    forward.host = forward.host.map(|h| h.trim().to_lowercase()).filter(|h| !h.is_empty());
+    forward.label = forward.label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if forward.id.is_empty() {
+        forward.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let label = window.label().to_string();
+    let affected = {
+        let mut app_data = state.app_data.write();
+        let win = app_data.window_mut(&label).ok_or("Window not found")?;
+        let workspace = win.workspaces.iter_mut().find(|w| w.id == workspace_id).ok_or("Workspace not found")?;
+        let tab_ids = workspace_tab_ids(&workspace.panes, tab_id.as_deref());
+        let forwards = match &tab_id {
+            Some(tab_id) => {
+                &mut workspace
+                    .panes
+                    .iter_mut()
+                    .flat_map(|p| &mut p.tabs)
+                    .find(|t| &t.id == tab_id)
+                    .ok_or("Tab not found")?
+                    .port_forwards
+            }
+            None => &mut workspace.port_forwards,
+        };
+        if let Some(existing) = forwards.iter().find(|f| f.ssh_flag() == forward.ssh_flag() && f.host == forward.host) {
+            return Err(format!("{} is already forwarded{}", existing.ssh_flag().1, describe_host(&existing.host)));
+        }
+        forwards.push(forward.clone());
+        save_state(&app_data)?;
+        tab_ids
+    };
+
+    for tab_id in affected {
+        reconcile_tab(state.inner(), &app, &tab_id);
+    }
+    Ok(forward)
+}
+
+/// Detach a forward from a tab or workspace, stopping it wherever no other
+/// tab still wants it.
+#[tauri::command]
+pub fn remove_port_forward(
+    window: tauri::Window,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    workspace_id: String,
+    tab_id: Option<String>,
+    forward_id: String,
+) -> Result<(), String> {
+    let label = window.label().to_string();
+    let affected = {
+        let mut app_data = state.app_data.write();
+        let win = app_data.window_mut(&label).ok_or("Window not found")?;
+        let workspace = win.workspaces.iter_mut().find(|w| w.id == workspace_id).ok_or("Workspace not found")?;
+        let tab_ids = workspace_tab_ids(&workspace.panes, tab_id.as_deref());
+        match &tab_id {
+            Some(tab_id) => {
+                if let Some(tab) = workspace.panes.iter_mut().flat_map(|p| &mut p.tabs).find(|t| &t.id == tab_id) {
+                    tab.port_forwards.retain(|f| f.id != forward_id);
+                }
+            }
+            None => workspace.port_forwards.retain(|f| f.id != forward_id),
+        }
+        save_state(&app_data)?;
+        tab_ids
+    };
+
+    for tab_id in affected {
+        reconcile_tab(state.inner(), &app, &tab_id);
+    }
+    Ok(())
+}
+
+/// Every running (or failed) forward with its status.
+#[tauri::command]
+pub fn list_port_forwards(state: tauri::State<'_, Arc<AppState>>) -> Vec<PortForwardStatus> {
+    statuses(&state)
+}
+
+/// Stop every forward (called on app exit).
+pub fn kill_all_port_forwards(state: &Arc<AppState>) {
+    let forwards: Vec<ActivePortForward> = state.port_forwards.write().drain().map(|(_, f)| f).collect();
+    for forward in forwards {
+        stop_forward(&forward);
+        log::info!("Stopped port forward {} on {} on shutdown", forward.forward.ssh_flag().1, forward.host_key);
+    }
+}
+
+/// Bring the forwards running for `tab_id` in line with what it currently
+/// wants. Starting happens in the background; progress arrives as
+/// `port-forwards-changed`.
+fn reconcile_tab(state: &Arc<AppState>, app: &AppHandle, tab_id: &str) {
+    let ssh_args = state.port_forward_sessions.read().get(tab_id).cloned();
+    let wanted: Vec<(String, PortForward)> = match &ssh_args {
+        Some(ssh_args) => {
+            let host = ssh_host(&format!("ssh {}", ssh_args));
+            forwards_for_tab(&state.app_data.read(), tab_id)
+                .into_iter()
+                .filter(|f| f.host.is_none() || f.host == host)
+                .map(|f| (forward_key(ssh_args, &f), f))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let (stopped, to_start) = reconcile_entries(&mut state.port_forwards.write(), tab_id, ssh_args.as_deref(), wanted);
+
+    for forward in &stopped {
+        stop_forward(forward);
+        log::info!("Stopped port forward {} on {}", forward.forward.ssh_flag().1, forward.host_key);
+    }
+    for (key, forward) in to_start {
+        // Also reached from sync commands, which run outside the Tokio runtime
+        tauri::async_runtime::spawn(start_forward(state.clone(), app.clone(), key, forward));
+    }
+    emit_changed(app, state);
+}
+
+/// Ref-count `tab_id` into the forwards it `wanted` (keyed by `forward_key`)
+/// on `ssh_args` and out of all others. Returns the forwards no tab wants any
+/// more, already removed from `active`, and the ones to (re)start.
+fn reconcile_entries(
+    active: &mut HashMap<String, ActivePortForward>,
+    tab_id: &str,
+    ssh_args: Option<&str>,
+    wanted: Vec<(String, PortForward)>,
+) -> (Vec<ActivePortForward>, Vec<(String, PortForward)>) {
+    for (key, forward) in active.iter_mut() {
+        if !wanted.iter().any(|(k, _)| k == key) {
+            forward.tab_ids.remove(tab_id);
+        }
+    }
+    let idle: Vec<String> = active.iter().filter(|(_, f)| f.tab_ids.is_empty()).map(|(k, _)| k.clone()).collect();
+    let stopped: Vec<ActivePortForward> = idle.iter().filter_map(|key| active.remove(key)).collect();
+
+    let mut to_start = Vec::new();
+    for (key, forward) in wanted {
+        match active.get_mut(&key) {
+            Some(existing) => {
+                existing.tab_ids.insert(tab_id.to_string());
+                existing.forward.label = forward.label.clone();
+                // Coming back into a session is the cue to retry
+                if existing.state == PortForwardState::Failed {
+                    existing.state = PortForwardState::Starting;
+                    existing.error = None;
+                    to_start.push((key, forward));
+                }
+            }
+            None => {
+                let mut tab_ids = HashSet::new();
+                tab_ids.insert(tab_id.to_string());
+                active.insert(key.clone(), ActivePortForward {
+                    pid: 0,
+                    host_key: ssh_args.unwrap_or_default().to_string(),
+                    forward: forward.clone(),
+                    tab_ids,
+                    state: PortForwardState::Starting,
+                    error: None,
+                    up_since_ms: 0,
+                    muxed: false,
+                });
+                to_start.push((key, forward));
+            }
+        }
+    }
+    (stopped, to_start)
+}
+
+/// The forwards a tab wants: its own, then its workspace's.
+fn forwards_for_tab(app_data: &AppData, tab_id: &str) -> Vec<PortForward> {
+    let Some((workspace, tab)) = app_data.find_tab(tab_id) else {
+        return Vec::new();
+    };
+    let mut forwards = tab.port_forwards.clone();
+    for forward in &workspace.port_forwards {
+        if !forwards.iter().any(|f| f.ssh_flag() == forward.ssh_flag()) {
+            forwards.push(forward.clone());
+        }
+    }
+    forwards
+}
+
+/// Tabs whose forwards change when `tab_id`'s (or, for None, the
+/// workspace's) list does.
+fn workspace_tab_ids(panes: &[crate::state::Pane], tab_id: Option<&str>) -> Vec<String> {
+    match tab_id {
+        Some(tab_id) => vec![tab_id.to_string()],
+        None => panes.iter().flat_map(|p| &p.tabs).map(|t| t.id.clone()).collect(),
+    }
+}
+
+/// Identity of a running forward: the same flag over the same destination
+/// is one ssh process, however many tabs want it.
+fn forward_key(ssh_args: &str, forward: &PortForward) -> String {
+    let (flag, spec) = forward.ssh_flag();
+    format!("{} {} {}", flag, spec, ssh_args)
+}
+
+fn describe_host(host: &Option<String>) -> String {
+    host.as_ref().map(|h| format!(" for {}", h)).unwrap_or_default()
+}
+
+async fn start_forward(state: Arc<AppState>, app: AppHandle, key: String, forward: PortForward) {
+    let Some(ssh_args) = state.port_forwards.read().get(&key).map(|f| f.host_key.clone()) else {
+        return;
+    };
+    let result = spawn_forward(&ssh_args, &forward).await;
+
+    let mut active = state.port_forwards.write();
+    // Every tab left (or another start won) while we were connecting
+    let Some(entry) = active.get_mut(&key).filter(|f| f.state == PortForwardState::Starting) else {
+        drop(active);
+        match result {
+            Ok(spawned) if spawned.muxed => cancel_muxed(&ssh_args, &forward),
+            Ok(mut spawned) => {
+                let _ = spawned.child.start_kill();
+            }
+            Err(_) => {}
+        }
+        return;
+    };
+    match result {
+        Ok(spawned) => {
+            log::info!("Port forward up: {} on {}{}", forward.ssh_flag().1, ssh_args, if spawned.muxed { " (ControlMaster)" } else { "" });
+            entry.pid = spawned.pid;
+            entry.muxed = spawned.muxed;
+            entry.state = PortForwardState::Up;
+            entry.up_since_ms = now_ms();
+            drop(active);
+            if !spawned.muxed {
+                watch_forward(state.clone(), app.clone(), key, spawned);
+            }
+        }
+        Err(e) => {
+            log::warn!("Port forward {} on {} failed: {}", forward.ssh_flag().1, ssh_args, e);
+            entry.state = PortForwardState::Failed;
+            entry.error = Some(e);
+            drop(active);
+        }
+    }
+    emit_changed(&app, &state);
+}
+
+/// Spawn `ssh -N <flag> <spec> {ssh_args}` and wait until ssh confirms the
+/// forward, or hands it to a ControlMaster and exits cleanly.
+async fn spawn_forward(ssh_args: &str, forward: &PortForward) -> Result<Spawned, String> {
+    let (flag, spec) = forward.ssh_flag();
+    // -v for the confirmation lines; ExitOnForwardFailure so a taken port
+    // fails the process instead of leaving a session that forwards nothing
+    let mut cmd_args: Vec<String> = vec![
+        "-N".to_string(),
+        "-v".to_string(),
+        "-o".to_string(), "ExitOnForwardFailure=yes".to_string(),
+        "-o".to_string(), "ServerAliveInterval=15".to_string(),
+        "-o".to_string(), "ServerAliveCountMax=3".to_string(),
+        flag.to_string(),
+        spec,
+    ];
+    for arg in ssh_args.split_whitespace() {
+        cmd_args.push(arg.to_string());
+    }
+
+    log::info!("Starting port forward: ssh {}", cmd_args.join(" "));
+
+    let mut child = tokio::process::Command::new("ssh")
+        .args(&cmd_args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn ssh: {}", e))?;
+    let pid = child.id().ok_or("Failed to get ssh PID")?;
+    let mut stderr = BufReader::new(child.stderr.take().ok_or("Failed to capture stderr")?).lines();
+
+    let mut last_error = None;
+    let confirmed = tokio::time::timeout(Duration::from_secs(15), async {
+        while let Ok(Some(line)) = stderr.next_line().await {
+            log::debug!("Port forward stderr: {}", line);
+            if confirms_forward(&line, forward.kind, forward.listen_port) {
+                return true;
+            }
+            if !line.starts_with("debug") {
+                last_error = Some(line);
+            }
+        }
+        false
+    })
+    .await;
+
+    match confirmed {
+        Ok(true) => Ok(Spawned { child, pid, muxed: false, stderr: Some(stderr) }),
+        Ok(false) => {
+            // stderr closed: either a mux client that handed the forward to
+            // the master, or ssh giving up
+            let status = tokio::time::timeout(Duration::from_secs(5), child.wait()).await;
+            if matches!(status, Ok(Ok(s)) if s.success()) {
+                return Ok(Spawned { child, pid, muxed: true, stderr: None });
+            }
+            let _ = child.start_kill();
+            Err(last_error.unwrap_or_else(|| "ssh exited before the forward was set up".to_string()))
+        }
+        Err(_) => {
+            let _ = child.start_kill();
+            Err("Timed out waiting for ssh to set up the forward (15s)".to_string())
+        }
+    }
+}
+
+/// Follow a forward's ssh process, marking it failed if it dies. A clean
+/// exit means a ControlMaster took the forward over.
+fn watch_forward(state: Arc<AppState>, app: AppHandle, key: String, spawned: Spawned) {
+    let Spawned { mut child, pid, stderr, .. } = spawned;
+    tauri::async_runtime::spawn(async move {
+        let mut last_error = None;
+        if let Some(mut stderr) = stderr {
+            while let Ok(Some(line)) = stderr.next_line().await {
+                if !line.starts_with("debug") {
+                    last_error = Some(line);
+                }
+            }
+        }
+        let exit_ok = child.wait().await.map(|s| s.success()).unwrap_or(false);
+
+        let mut active = state.port_forwards.write();
+        // Stopped, or replaced by a retry
+        let Some(entry) = active.get_mut(&key).filter(|f| f.pid == pid) else {
+            return;
+        };
+        if exit_ok {
+            entry.muxed = true;
+            return;
+        }
+        log::info!("Port forward {} on {} exited", entry.forward.ssh_flag().1, entry.host_key);
+        entry.state = PortForwardState::Failed;
+        entry.error = Some(last_error.unwrap_or_else(|| "ssh exited".to_string()));
+        drop(active);
+        emit_changed(&app, &state);
+    });
+}
+
+/// Kill a forward's ssh process, or cancel it on the ControlMaster holding it.
+fn stop_forward(forward: &ActivePortForward) {
+    if forward.muxed {
+        cancel_muxed(&forward.host_key, &forward.forward);
+    } else if forward.pid != 0 && is_process_alive(forward.pid) {
+        kill_process(forward.pid);
+    }
+}
+
+/// Ask the ControlMaster for `ssh_args` to drop a forward it took over from
+/// our ssh client; it outlives us otherwise.
+fn cancel_muxed(ssh_args: &str, forward: &PortForward) {
+    let (flag, spec) = forward.ssh_flag();
+    let _ = std::process::Command::new("ssh")
+        .args(["-O", "cancel", flag, spec.as_str()])
+        .args(ssh_args.split_whitespace())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+}
+
+/// Whether a line of `ssh -v` output confirms the forward is listening.
+fn confirms_forward(line: &str, kind: PortForwardKind, listen_port: u16) -> bool {
+    match kind {
+        // "debug1: Local forwarding listening on 127.0.0.1 port 5432."
+        PortForwardKind::Local | PortForwardKind::Dynamic => {
+            line.contains("Local forwarding listening on")
+                && line.trim_end_matches('.').ends_with(&format!(" port {}", listen_port))
+        }
+        PortForwardKind::Remote => line.contains(&format!("remote forward success for: listen {},", listen_port)),
+    }
+}
+
+fn statuses(state: &AppState) -> Vec<PortForwardStatus> {
+    let now = now_ms();
+    let active = state.port_forwards.read();
+    let mut list: Vec<PortForwardStatus> = active
+        .values()
+        .map(|f| {
+            let mut tab_ids: Vec<String> = f.tab_ids.iter().cloned().collect();
+            tab_ids.sort();
+            PortForwardStatus {
+                forward: f.forward.clone(),
+                host_key: f.host_key.clone(),
+                state: f.state,
+                error: f.error.clone(),
+                uptime_secs: (f.state == PortForwardState::Up).then(|| (now - f.up_since_ms) / 1000),
+                tab_ids,
+            }
+        })
+        .collect();
+    list.sort_by(|a, b| (&a.host_key, a.forward.listen_port).cmp(&(&b.host_key, b.forward.listen_port)));
+    list
+}
+
+fn emit_changed(app: &AppHandle, state: &AppState) {
+    let _ = app.emit("port-forwards-changed", statuses(state));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forward(kind: PortForwardKind, listen_port: u16, target: &str) -> PortForward {
+        PortForward {
+            id: "f1".into(),
+            kind,
+            listen_port,
+            target: target.into(),
+            host: None,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn forward_flags_and_validation() {
+        assert_eq!(forward(PortForwardKind::Local, 5432, "db:5432").ssh_flag(), ("-L", "5432:db:5432".to_string()));
+        assert_eq!(forward(PortForwardKind::Remote, 8080, "localhost:3000").ssh_flag(), ("-R", "8080:localhost:3000".to_string()));
+        assert_eq!(forward(PortForwardKind::Dynamic, 1080, "").ssh_flag(), ("-D", "1080".to_string()));
+
+        assert!(forward(PortForwardKind::Local, 5432, "db:5432").validate().is_ok());
+        assert!(forward(PortForwardKind::Local, 5432, "[::1]:5432").validate().is_ok());
+        assert!(forward(PortForwardKind::Dynamic, 1080, "").validate().is_ok());
+        assert!(forward(PortForwardKind::Local, 0, "db:5432").validate().is_err());
+        assert!(forward(PortForwardKind::Local, 5432, "db").validate().is_err());
+        assert!(forward(PortForwardKind::Remote, 8080, ":3000").validate().is_err());
+        assert!(forward(PortForwardKind::Remote, 8080, "localhost:http").validate().is_err());
+    }
+
+    #[test]
+    fn forward_confirmation_from_ssh_output() {
+        let local = "debug1: Local forwarding listening on 127.0.0.1 port 5432.";
+        assert!(confirms_forward(local, PortForwardKind::Local, 5432));
+        assert!(confirms_forward(local, PortForwardKind::Dynamic, 5432));
+        assert!(!confirms_forward(local, PortForwardKind::Local, 543));
+        assert!(!confirms_forward(local, PortForwardKind::Remote, 5432));
+
+        let remote = "debug1: remote forward success for: listen 8080, connect localhost:3000";
+        assert!(confirms_forward(remote, PortForwardKind::Remote, 8080));
+        assert!(!confirms_forward(remote, PortForwardKind::Remote, 80));
+        assert!(!confirms_forward("Error: remote port forwarding failed for listen port 8080", PortForwardKind::Remote, 8080));
+    }
+
+    #[test]
+    fn forwards_are_ref_counted_by_tab() {
+        let db = forward(PortForwardKind::Local, 5432, "db:5432");
+        let wanted = || vec![(forward_key("dev@box", &db), db.clone())];
+        let mut active = HashMap::new();
+
+        let (stopped, to_start) = reconcile_entries(&mut active, "t1", Some("dev@box"), wanted());
+        assert!(stopped.is_empty());
+        assert_eq!(to_start.len(), 1);
+        assert_eq!(active.len(), 1);
+
+        // A second tab on the same destination joins the running forward
+        let (_, to_start) = reconcile_entries(&mut active, "t2", Some("dev@box"), wanted());
+        assert!(to_start.is_empty());
+        assert_eq!(active.values().next().unwrap().tab_ids.len(), 2);
+
+        let (stopped, _) = reconcile_entries(&mut active, "t1", None, Vec::new());
+        assert!(stopped.is_empty());
+
+        // Rejoining a failed forward retries it
+        active.values_mut().next().unwrap().state = PortForwardState::Failed;
+        let (_, to_start) = reconcile_entries(&mut active, "t1", Some("dev@box"), wanted());
+        assert_eq!(to_start.len(), 1);
+        assert_eq!(active.values().next().unwrap().state, PortForwardState::Starting);
+
+        reconcile_entries(&mut active, "t1", None, Vec::new());
+        let (stopped, _) = reconcile_entries(&mut active, "t2", None, Vec::new());
+        assert_eq!(stopped.len(), 1);
+        assert!(active.is_empty());
+    }
+}
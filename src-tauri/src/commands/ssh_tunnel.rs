@@ -1,20 +1,59 @@
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tauri::Emitter;
+use tauri::{AppHandle, Emitter};
 
-use crate::state::AppState;
+use crate::claude_code::server::health_signature;
+use crate::state::app_state::{SshTunnel, TunnelHealth};
+use crate::state::{now_ms, AppState};
+
+/// How often the supervisor wakes to probe tunnels and retry restarts.
+const SUPERVISOR_TICK: Duration = Duration::from_secs(5);
+/// How often an idle tunnel is probed end to end. A tunnel whose host token
+/// was used more recently than this is known to work and isn't probed.
+const PROBE_INTERVAL_MS: i64 = 60_000;
+/// Restart delays double from the second attempt, up to the max.
+const BACKOFF_MIN_MS: i64 = 5_000;
+const BACKOFF_MAX_MS: i64 = 5 * 60_000;
 
 #[derive(serde::Serialize)]
 pub struct SshTunnelInfo {
     pub tunnel_id: String,
     pub remote_port: u16,
     pub host_key: String,
+    /// MCP token issued to this host, for the remote MCP config
+    pub auth_token: String,
+    /// False while the supervisor is restarting the forward
+    pub up: bool,
+}
+
+/// Emitted as `ssh-tunnel-status` when the supervisor finds a tunnel down
+/// or brings it back up.
+#[derive(Clone, serde::Serialize)]
+pub struct SshTunnelStatus {
+    pub host_key: String,
+    pub up: bool,
+    pub remote_port: u16,
+    /// The forward came back on another remote port, so the remote setup
+    /// has to be re-run
+    pub port_changed: bool,
+    pub error: Option<String>,
+}
+
+/// A freshly spawned `ssh -N -R` and the remote port it forwards.
+struct Forward {
+    child: tokio::process::Child,
+    pid: u32,
+    remote_port: u16,
 }
 
 /// Start a reverse SSH tunnel to expose the local MCP server on a remote host.
 /// Spawns `ssh -N -o ExitOnForwardFailure=yes -R 0:127.0.0.1:{local_port} {ssh_args}`.
 /// Parses the allocated remote port from stderr output.
-/// Returns the tunnel info including the allocated remote port.
+/// Returns the tunnel info including the allocated remote port and the MCP
+/// token issued to the host (shared by every tab on it). The tunnel is then
+/// kept up by the supervisor until its last tab detaches.
 #[tauri::command]
 pub async fn start_ssh_tunnel(
     app: tauri::AppHandle,
@@ -24,24 +63,379 @@ pub async fn start_ssh_tunnel(
     tab_id: String,
     local_port: u16,
 ) -> Result<SshTunnelInfo, String> {
-    // Check if tunnel already exists for this host
+    // Join the existing tunnel for this host. It's reused even when its
+    // process is gone: ControlMaster forwards outlive their ssh client, and
+    // a forward that's down is the supervisor's to restart.
     {
         let mut tunnels = state.ssh_tunnels.write();
         if let Some(tunnel) = tunnels.get_mut(&host_key) {
-            // Verify the process is still alive
-            if is_process_alive(tunnel.pid) {
-                tunnel.tab_ids.insert(tab_id);
-                return Ok(SshTunnelInfo {
-                    tunnel_id: host_key.clone(),
-                    remote_port: tunnel.remote_port,
-                    host_key,
-                });
+            tunnel.tab_ids.insert(tab_id);
+            return Ok(SshTunnelInfo {
+                tunnel_id: host_key.clone(),
+                remote_port: tunnel.remote_port,
+                auth_token: state.mcp_tokens.lock().issue_host(&host_key),
+                up: tunnel.health.down_since_ms.is_none(),
+                host_key,
+            });
+        }
+    }
+
+    let forward = spawn_forward(&ssh_args, local_port, 0).await?;
+    let remote_port = forward.remote_port;
+
+    log::info!("SSH tunnel established: {} → remote port {}", host_key, remote_port);
+
+    // Store the tunnel (don't store the Child — we track by PID)
+    {
+        let mut tunnels = state.ssh_tunnels.write();
+        let mut tab_ids = std::collections::HashSet::new();
+        tab_ids.insert(tab_id);
+        let now = now_ms();
+        tunnels.insert(host_key.clone(), SshTunnel {
+            pid: forward.pid,
+            remote_port,
+            host_key: host_key.clone(),
+            tab_ids,
+            ssh_args,
+            local_port,
+            muxed: false,
+            health: TunnelHealth {
+                up_since_ms: now,
+                last_check_ms: now,
+                ..Default::default()
+            },
+        });
+    }
+
+    watch_forward(state.inner().clone(), app.clone(), host_key.clone(), forward.child, forward.pid);
+    ensure_supervisor(state.inner(), &app);
+
+    Ok(SshTunnelInfo {
+        tunnel_id: host_key.clone(),
+        remote_port,
+        auth_token: state.mcp_tokens.lock().issue_host(&host_key),
+        up: true,
+        host_key,
+    })
+}
+
+/// Remove a tab from a tunnel's ref count. Kills the tunnel and revokes the
+/// host's MCP token if no tabs remain.
+#[tauri::command]
+pub async fn detach_ssh_tunnel(
+    state: tauri::State<'_, Arc<AppState>>,
+    host_key: String,
+    tab_id: String,
+) -> Result<(), String> {
+    let removed = {
+        let mut tunnels = state.ssh_tunnels.write();
+        match tunnels.get_mut(&host_key) {
+            Some(tunnel) => {
+                tunnel.tab_ids.remove(&tab_id);
+                if tunnel.tab_ids.is_empty() {
+                    tunnels.remove(&host_key)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    };
+
+    if let Some(tunnel) = removed {
+        stop_forward(&tunnel);
+        state.mcp_tokens.lock().revoke_host(&host_key);
+        log::info!("Killed SSH tunnel for {} (pid {})", host_key, tunnel.pid);
+    }
+
+    Ok(())
+}
+
+/// Revoke `host_key`'s MCP token and close its tunnel, cutting the remote
+/// host off until the bridge is enabled again (by reconnecting SSH).
+/// Emits `mcp-host-token-revoked` so the frontend marks its bridges failed
+/// instead of re-enabling them.
+#[tauri::command]
+pub fn revoke_mcp_host_token(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    host_key: String,
+) -> Result<(), String> {
+    let revoked = state.mcp_tokens.lock().revoke_host(&host_key);
+    // Removing the entry also keeps the supervisor from restarting it. A
+    // ControlMaster-muxed forward outlives the kill, but anything reaching
+    // us through it now fails auth.
+    let tunnel = state.ssh_tunnels.write().remove(&host_key);
+    if !revoked && tunnel.is_none() {
+        return Err(format!("No MCP token issued for {}", host_key));
+    }
+    if let Some(tunnel) = tunnel {
+        stop_forward(&tunnel);
+    }
+    let _ = app.emit("mcp-host-token-revoked", &host_key);
+    log::info!("Revoked MCP token for {}", host_key);
+    Ok(())
+}
+
+/// Get info about an active tunnel for a host.
+#[tauri::command]
+pub fn get_ssh_tunnel(
+    state: tauri::State<'_, Arc<AppState>>,
+    host_key: String,
+) -> Option<SshTunnelInfo> {
+    let tunnels = state.ssh_tunnels.read();
+    tunnels.get(&host_key).map(|t| SshTunnelInfo {
+        tunnel_id: t.host_key.clone(),
+        remote_port: t.remote_port,
+        host_key: t.host_key.clone(),
+        auth_token: state.mcp_tokens.lock().host(&t.host_key).unwrap_or_default(),
+        up: t.health.down_since_ms.is_none(),
+    })
+}
+
+// ─── Supervisor ─────────────────────────────────────────────────────────────
+
+/// Start the supervisor task if it isn't running.
+fn ensure_supervisor(state: &Arc<AppState>, app: &AppHandle) {
+    if !state.tunnel_supervisor_running.swap(true, Ordering::SeqCst) {
+        tokio::spawn(supervise_tunnels(state.clone(), app.clone()));
+    }
+}
+
+/// Keep every tunnel working end to end: probe idle ones through a separate
+/// ssh connection and restart those that are down, backing off between
+/// attempts. Hosts are handled concurrently, so one unreachable host doesn't
+/// hold up the others. Stops when the last tunnel is gone.
+async fn supervise_tunnels(state: Arc<AppState>, app: AppHandle) {
+    loop {
+        tokio::time::sleep(SUPERVISOR_TICK).await;
+
+        let now = now_ms();
+        // (host_key, whether it's due for a restart rather than a check)
+        let due: Vec<(String, bool)> = {
+            let tunnels = state.ssh_tunnels.read();
+            if tunnels.is_empty() {
+                state.tunnel_supervisor_running.store(false, Ordering::SeqCst);
+                log::info!("SSH tunnel supervisor: no tunnels remaining, stopping");
+                return;
             }
-            // Process died — remove stale entry and create new tunnel
-            tunnels.remove(&host_key);
+            tunnels.values().filter_map(|t| match t.health.down_since_ms {
+                Some(_) => (now >= t.health.retry_at_ms).then(|| (t.host_key.clone(), true)),
+                None => (now - t.health.last_check_ms >= PROBE_INTERVAL_MS).then(|| (t.host_key.clone(), false)),
+            }).collect()
+        };
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (host_key, restart) in due {
+            let (state, app) = (state.clone(), app.clone());
+            tasks.spawn(async move {
+                if restart {
+                    restart_tunnel(&state, &app, &host_key).await;
+                } else {
+                    check_tunnel(&state, &app, &host_key).await;
+                }
+            });
+        }
+        // Finish the round before the next tick so a host is never handled twice at once
+        while tasks.join_next().await.is_some() {}
+    }
+}
+
+async fn check_tunnel(state: &Arc<AppState>, app: &AppHandle, host_key: &str) {
+    let Some((ssh_args, remote_port)) = state.ssh_tunnels.read().get(host_key).map(|t| (t.ssh_args.clone(), t.remote_port)) else {
+        return;
+    };
+
+    // Remote clients getting through since the last check prove the forward works
+    let last_used = state.mcp_tokens.lock().host_last_used(host_key).unwrap_or(0);
+    let result = if now_ms() - last_used < PROBE_INTERVAL_MS {
+        Ok(true)
+    } else {
+        probe_forward(&ssh_args, remote_port).await
+    };
+
+    let mut tunnels = state.ssh_tunnels.write();
+    // Detached or restarted while we were probing
+    let Some(tunnel) = tunnels.get_mut(host_key).filter(|t| t.remote_port == remote_port) else {
+        return;
+    };
+    tunnel.health.last_check_ms = now_ms();
+    match result {
+        Ok(verified) => {
+            if !verified && !tunnel.health.unverified {
+                log::info!("SSH tunnel to {}: host has no curl, wget or bash, health is unverified", host_key);
+            }
+            tunnel.health.unverified = !verified;
+        }
+        Err(e) => {
+            log::warn!("SSH tunnel to {} failed its health check: {}", host_key, e);
+            let status = mark_down(tunnel, e);
+            drop(tunnels);
+            if let Some(status) = status {
+                let _ = app.emit("ssh-tunnel-status", status);
+            }
+        }
+    }
+}
+
+/// Replace a tunnel's forward. The old remote port is asked for first so
+/// remote shells that exported `AITERM_PORT` keep working; only if it's
+/// taken does the server pick a new one.
+async fn restart_tunnel(state: &Arc<AppState>, app: &AppHandle, host_key: &str) {
+    let Some((ssh_args, local_port, old_port, old_pid, muxed)) = state.ssh_tunnels.read().get(host_key).map(|t| {
+        (t.ssh_args.clone(), t.local_port, t.remote_port, t.pid, t.muxed)
+    }) else {
+        return;
+    };
+    // Make sure a hung client lets go of the old port before asking for it again
+    if !muxed && is_process_alive(old_pid) {
+        kill_process(old_pid);
+    }
+
+    log::info!("Restarting SSH tunnel for {}", host_key);
+    let result = match spawn_forward(&ssh_args, local_port, old_port).await {
+        Ok(forward) => Ok(forward),
+        Err(e) => {
+            log::info!("Remote port {} unavailable for {} ({}), asking for a new one", old_port, host_key, e);
+            spawn_forward(&ssh_args, local_port, 0).await
+        }
+    };
+
+    let mut tunnels = state.ssh_tunnels.write();
+    let Some(tunnel) = tunnels.get_mut(host_key) else {
+        // Detached while we were restarting
+        if let Ok(forward) = result {
+            kill_process(forward.pid);
+        }
+        return;
+    };
+    match result {
+        Ok(forward) => {
+            let now = now_ms();
+            tunnel.pid = forward.pid;
+            tunnel.remote_port = forward.remote_port;
+            tunnel.muxed = false;
+            tunnel.health.up_since_ms = now;
+            tunnel.health.down_since_ms = None;
+            tunnel.health.last_check_ms = now;
+            tunnel.health.restarts += 1;
+            tunnel.health.consecutive_failures = 0;
+            drop(tunnels);
+
+            log::info!("SSH tunnel restored: {} → remote port {}", host_key, forward.remote_port);
+            watch_forward(state.clone(), app.clone(), host_key.to_string(), forward.child, forward.pid);
+            let _ = app.emit("ssh-tunnel-status", SshTunnelStatus {
+                host_key: host_key.to_string(),
+                up: true,
+                remote_port: forward.remote_port,
+                port_changed: forward.remote_port != old_port,
+                error: None,
+            });
         }
+        Err(e) => {
+            log::warn!("SSH tunnel restart failed for {}: {}", host_key, e);
+            mark_down(tunnel, e);
+        }
+    }
+}
+
+/// Record a failure and schedule the next restart. Returns the status to
+/// emit if the tunnel was up until now.
+fn mark_down(tunnel: &mut SshTunnel, error: String) -> Option<SshTunnelStatus> {
+    let now = now_ms();
+    let health = &mut tunnel.health;
+    let was_up = health.down_since_ms.is_none();
+    health.down_since_ms.get_or_insert(now);
+    health.failures += 1;
+    health.consecutive_failures += 1;
+    health.retry_at_ms = now + backoff_ms(health.consecutive_failures);
+    health.last_error = Some(error.clone());
+    was_up.then(|| SshTunnelStatus {
+        host_key: tunnel.host_key.clone(),
+        up: false,
+        remote_port: tunnel.remote_port,
+        port_changed: false,
+        error: Some(error),
+    })
+}
+
+/// Delay before restart attempt `failures`: the first is immediate, then
+/// 5s, 10s, 20s, ... up to five minutes.
+fn backoff_ms(failures: u32) -> i64 {
+    if failures <= 1 {
+        return 0;
+    }
+    BACKOFF_MIN_MS
+        .saturating_mul(1 << (failures - 2).min(16))
+        .min(BACKOFF_MAX_MS)
+}
+
+/// Fetch `/health` through the remote end of the forward over a separate
+/// ssh connection. This checks the whole path: the host is reachable, the
+/// remote port listens, and it leads back to this process. A ControlMaster
+/// keeps dead forwards' listeners open, so a bare connect isn't enough.
+/// Fetched with curl, wget or bash's `/dev/tcp`; `Ok(false)` when the host
+/// has none of them and the forward couldn't be verified.
+async fn probe_forward(ssh_args: &str, remote_port: u16) -> Result<bool, String> {
+    let mut cmd_args: Vec<String> = vec![
+        "-o".to_string(), "BatchMode=yes".to_string(),
+        "-o".to_string(), "ConnectTimeout=10".to_string(),
+        "-T".to_string(),
+    ];
+    for arg in ssh_args.split_whitespace() {
+        cmd_args.push(arg.to_string());
+    }
+    cmd_args.push(probe_command(remote_port));
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(20),
+        tokio::process::Command::new("ssh")
+            .args(&cmd_args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .output()
+    ).await
+        .map_err(|_| "Health check timed out (20s)".to_string())?
+        .map_err(|e| format!("Failed to spawn health check: {}", e))?;
+
+    if output.status.code() == Some(255) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Host unreachable: {}", stderr.trim()));
+    }
+    probe_verdict(&String::from_utf8_lossy(&output.stdout), &health_signature())
+        .ok_or_else(|| format!("Remote port {} doesn't reach this maiTerm", remote_port))
+}
+
+/// Remote shell command fetching `/health` through the forward, with the
+/// first of curl, wget and bash the host has. Prints `unverified` if none.
+fn probe_command(remote_port: u16) -> String {
+    let url = format!("http://127.0.0.1:{}/health", remote_port);
+    format!(
+        "if command -v curl >/dev/null 2>&1; then curl -s -m 5 {url}; \
+         elif command -v wget >/dev/null 2>&1; then wget -q -T 5 -O - {url}; \
+         elif command -v bash >/dev/null 2>&1; then bash -c 'exec 3<>/dev/tcp/127.0.0.1/{port} \
+         && printf \"GET /health HTTP/1.0\\r\\n\\r\\n\" >&3 && cat <&3'; \
+         else echo unverified; fi",
+        url = url,
+        port = remote_port,
+    )
+}
+
+/// `Some(true)` when the probe's output ends with this process's health
+/// signature (the body follows the headers when fetched over `/dev/tcp`),
+/// `Some(false)` when the host couldn't fetch it, None otherwise.
+fn probe_verdict(stdout: &str, signature: &str) -> Option<bool> {
+    match stdout.lines().map(str::trim).rfind(|line| !line.is_empty()) {
+        Some("unverified") => Some(false),
+        Some(last) => (last == signature).then_some(true),
+        None => None,
     }
+}
 
+/// Spawn `ssh -N -R {requested}:127.0.0.1:{local_port} {ssh_args}` and wait
+/// until the forward is up. `requested` 0 lets the server pick the port.
+async fn spawn_forward(ssh_args: &str, local_port: u16, requested: u16) -> Result<Forward, String> {
     // Build SSH command args
     // ssh_args is already cleaned (e.g. "user@host" or "-p 2222 user@host")
     let mut cmd_args: Vec<String> = Vec::new();
@@ -52,11 +446,17 @@ pub async fn start_ssh_tunnel(
     cmd_args.push("-v".to_string());
     cmd_args.push("-o".to_string());
     cmd_args.push("ExitOnForwardFailure=yes".to_string());
+    // Exit when the connection silently dies (e.g. across a laptop sleep)
+    // instead of hanging on, so the supervisor can restart it
+    cmd_args.push("-o".to_string());
+    cmd_args.push("ServerAliveInterval=15".to_string());
+    cmd_args.push("-o".to_string());
+    cmd_args.push("ServerAliveCountMax=3".to_string());
     // No ControlMaster=no — let SSH multiplex over the user's existing control
     // socket if they have ControlMaster auto. This gives free auth for password/
     // passphrase users whose session is already authenticated.
     cmd_args.push("-R".to_string());
-    cmd_args.push(format!("0:127.0.0.1:{}", local_port));
+    cmd_args.push(format!("{}:127.0.0.1:{}", requested, local_port));
 
     // Add the user's SSH args
     for arg in ssh_args.split_whitespace() {
@@ -80,124 +480,104 @@ pub async fn start_ssh_tunnel(
     // connections print "Allocated port ..." to stdout instead.
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    let remote_port = parse_allocated_port(stdout, stderr).await?;
-
-    log::info!("SSH tunnel established: {} → remote port {}", host_key, remote_port);
+    let remote_port = match parse_allocated_port(stdout, stderr, requested).await {
+        Ok(port) => port,
+        Err(e) => {
+            // A mux client doesn't report fixed-port forwards at all; it just
+            // exits 0 once the master holds the forward
+            let handed_off = requested != 0
+                && matches!(tokio::time::timeout(Duration::from_secs(5), child.wait()).await, Ok(Ok(s)) if s.success());
+            if !handed_off {
+                let _ = child.start_kill();
+                return Err(e);
+            }
+            requested
+        }
+    };
 
-    // Store the tunnel (don't store the Child — we track by PID)
-    {
-        let mut tunnels = state.ssh_tunnels.write();
-        let mut tab_ids = std::collections::HashSet::new();
-        tab_ids.insert(tab_id);
-        tunnels.insert(host_key.clone(), crate::state::app_state::SshTunnel {
-            pid,
-            remote_port,
-            host_key: host_key.clone(),
-            tab_ids,
-        });
-    }
+    Ok(Forward { child, pid, remote_port })
+}
 
-    // Spawn background task to monitor the process and clean up on exit.
-    // Note: ControlMaster mux clients exit immediately after setting up the
-    // forwarding (the master holds it). Don't remove tunnel state if the
-    // process exits with code 0 — the forwarding is still alive in the master.
-    let state_clone = state.inner().clone();
-    let hk = host_key.clone();
-    let app_clone = app.clone();
+/// Follow a forward's ssh process. A clean exit means a ControlMaster took
+/// the forward over; an error exit means it's gone and needs a restart.
+fn watch_forward(state: Arc<AppState>, app: AppHandle, host_key: String, mut child: tokio::process::Child, pid: u32) {
     tokio::spawn(async move {
         let status = child.wait().await;
         let exit_ok = status.map(|s| s.success()).unwrap_or(false);
+        let mut tunnels = state.ssh_tunnels.write();
+        // Detached, revoked, or already replaced by a restart
+        let Some(tunnel) = tunnels.get_mut(&host_key).filter(|t| t.pid == pid) else {
+            return;
+        };
         if exit_ok {
-            log::info!("SSH tunnel process exited cleanly for {} (likely ControlMaster mux)", hk);
+            log::info!("SSH tunnel process exited cleanly for {} (likely ControlMaster mux)", host_key);
+            tunnel.muxed = true;
         } else {
-            log::info!("SSH tunnel process exited with error for {}", hk);
-            let tab_ids: Vec<String> = {
-                let mut tunnels = state_clone.ssh_tunnels.write();
-                if let Some(tunnel) = tunnels.remove(&hk) {
-                    tunnel.tab_ids.into_iter().collect()
-                } else {
-                    vec![]
-                }
-            };
-            // Notify frontend so bridge indicators update in real-time
-            for tid in &tab_ids {
-                let _ = app_clone.emit(&format!("ssh-tunnel-down-{}", tid), ());
+            log::info!("SSH tunnel process exited with error for {}", host_key);
+            let status = mark_down(tunnel, "ssh tunnel process exited".to_string());
+            drop(tunnels);
+            if let Some(status) = status {
+                let _ = app.emit("ssh-tunnel-status", status);
             }
         }
     });
-
-    Ok(SshTunnelInfo {
-        tunnel_id: host_key.clone(),
-        remote_port,
-        host_key,
-    })
 }
 
-/// Remove a tab from a tunnel's ref count. Kills the tunnel if no tabs remain.
-#[tauri::command]
-pub async fn detach_ssh_tunnel(
-    state: tauri::State<'_, Arc<AppState>>,
-    host_key: String,
-    tab_id: String,
-) -> Result<(), String> {
-    let should_kill = {
-        let mut tunnels = state.ssh_tunnels.write();
-        if let Some(tunnel) = tunnels.get_mut(&host_key) {
-            tunnel.tab_ids.remove(&tab_id);
-            if tunnel.tab_ids.is_empty() {
-                let pid = tunnel.pid;
-                tunnels.remove(&host_key);
-                Some(pid)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    };
-
-    if let Some(pid) = should_kill {
-        kill_process(pid);
-        log::info!("Killed SSH tunnel for {} (pid {})", host_key, pid);
+/// Kill a tunnel's ssh process, unless it already handed the forward to a
+/// ControlMaster (its pid may have been reused since).
+fn stop_forward(tunnel: &SshTunnel) {
+    if !tunnel.muxed && is_process_alive(tunnel.pid) {
+        kill_process(tunnel.pid);
     }
-
-    Ok(())
-}
-
-/// Get info about an active tunnel for a host.
-#[tauri::command]
-pub fn get_ssh_tunnel(
-    state: tauri::State<'_, Arc<AppState>>,
-    host_key: String,
-) -> Option<SshTunnelInfo> {
-    let tunnels = state.ssh_tunnels.read();
-    tunnels.get(&host_key).map(|t| SshTunnelInfo {
-        tunnel_id: t.host_key.clone(),
-        remote_port: t.remote_port,
-        host_key: t.host_key.clone(),
-    })
 }
 
 /// Kill all SSH tunnels (called on app exit).
 pub fn kill_all_tunnels(state: &Arc<AppState>) {
-    let tunnels: Vec<(String, u32)> = {
+    let tunnels: Vec<SshTunnel> = {
         let mut map = state.ssh_tunnels.write();
-        let items: Vec<_> = map.drain().map(|(k, t)| (k, t.pid)).collect();
+        let items: Vec<_> = map.drain().map(|(_, t)| t).collect();
         items
     };
-    for (host_key, pid) in tunnels {
-        kill_process(pid);
-        log::info!("Killed SSH tunnel for {} on shutdown", host_key);
+    for tunnel in tunnels {
+        stop_forward(&tunnel);
+        log::info!("Killed SSH tunnel for {} on shutdown", tunnel.host_key);
     }
 }
 
+/// Per-tunnel health for `get_app_diagnostics`.
+pub fn tunnel_diagnostics(state: &AppState) -> Vec<serde_json::Value> {
+    let now = now_ms();
+    let tunnels = state.ssh_tunnels.read();
+    tunnels.values().map(|t| {
+        let tab_ids: Vec<&str> = t.tab_ids.iter().map(|s| s.as_str()).collect();
+        let h = &t.health;
+        serde_json::json!({
+            "host_key": t.host_key,
+            "remote_port": t.remote_port,
+            "pid": t.pid,
+            "alive": is_process_alive(t.pid),
+            "muxed": t.muxed,
+            "up": h.down_since_ms.is_none(),
+            "unverified": h.unverified,
+            "uptime_secs": if h.down_since_ms.is_none() { (now - h.up_since_ms) / 1000 } else { 0 },
+            "down_secs": h.down_since_ms.map(|since| (now - since) / 1000),
+            "last_check_secs_ago": (now - h.last_check_ms) / 1000,
+            "restarts": h.restarts,
+            "failures": h.failures,
+            "last_error": h.last_error,
+            "tab_ids": tab_ids,
+        })
+    }).collect()
+}
+
 /// Get the local MCP server port (needed by frontend to construct tunnel).
 #[tauri::command]
 pub fn get_mcp_port(state: tauri::State<'_, Arc<AppState>>) -> Option<u16> {
     *state.claude_code_port.read()
 }
 
-/// Get the MCP auth token (needed by frontend to write remote lockfile).
+/// Get the local MCP auth token. Remote hosts get their own from
+/// `start_ssh_tunnel` instead.
 #[tauri::command]
 pub fn get_mcp_auth(state: tauri::State<'_, Arc<AppState>>) -> Option<String> {
     state.claude_code_auth.read().clone()
@@ -276,25 +656,34 @@ pub async fn ssh_run_setup(
     Ok(())
 }
 
-/// Parse "Allocated port NNNNN for remote forward" from SSH output.
+/// The remote port a line of `ssh -v` output reports as forwarded: "Allocated
+/// port N" for a server-picked port, or the debug line confirming `requested`.
+fn forwarded_port(line: &str, requested: u16) -> Option<u16> {
+    if let Some(port) = line
+        .strip_prefix("Allocated port ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|s| s.parse::<u16>().ok())
+    {
+        return Some(port);
+    }
+    let confirmed = format!("remote forward success for: listen {},", requested);
+    (requested != 0 && line.contains(&confirmed)).then_some(requested)
+}
+
+/// Parse the forwarded remote port from SSH output (see `forwarded_port`).
 /// Reads both stdout and stderr concurrently — direct connections print to
 /// stderr, but ControlMaster-multiplexed connections print to stdout.
 /// Times out after 15 seconds.
 async fn parse_allocated_port(
     stdout: tokio::process::ChildStdout,
     stderr: tokio::process::ChildStderr,
+    requested: u16,
 ) -> Result<u16, String> {
     use tokio::io::{AsyncBufReadExt, BufReader};
 
     let mut stdout_lines = BufReader::new(stdout).lines();
     let mut stderr_lines = BufReader::new(stderr).lines();
 
-    fn try_parse_port(line: &str) -> Option<u16> {
-        line.strip_prefix("Allocated port ")
-            .and_then(|rest| rest.split_whitespace().next())
-            .and_then(|s| s.parse::<u16>().ok())
-    }
-
     let timeout = tokio::time::Duration::from_secs(15);
     match tokio::time::timeout(timeout, async {
         let mut stdout_done = false;
@@ -308,7 +697,7 @@ async fn parse_allocated_port(
                     match result {
                         Ok(Some(line)) => {
                             log::debug!("SSH tunnel stdout: {}", line);
-                            if let Some(port) = try_parse_port(&line) {
+                            if let Some(port) = forwarded_port(&line, requested) {
                                 return Ok(port);
                             }
                         }
@@ -320,7 +709,7 @@ async fn parse_allocated_port(
                     match result {
                         Ok(Some(line)) => {
                             log::debug!("SSH tunnel stderr: {}", line);
-                            if let Some(port) = try_parse_port(&line) {
+                            if let Some(port) = forwarded_port(&line, requested) {
                                 return Ok(port);
                             }
                         }
@@ -336,18 +725,13 @@ async fn parse_allocated_port(
     }
 }
 
-/// Check if a tunnel process is alive (used by diagnostics).
-pub fn is_tunnel_alive(pid: u32) -> bool {
-    is_process_alive(pid)
-}
-
 #[cfg(unix)]
-fn is_process_alive(pid: u32) -> bool {
+pub(crate) fn is_process_alive(pid: u32) -> bool {
     unsafe { libc::kill(pid as i32, 0) == 0 }
 }
 
 #[cfg(windows)]
-fn is_process_alive(pid: u32) -> bool {
+pub(crate) fn is_process_alive(pid: u32) -> bool {
     use std::process::Command;
     Command::new("tasklist")
         .args(["/FI", &format!("PID eq {}", pid), "/NH"])
@@ -357,13 +741,49 @@ fn is_process_alive(pid: u32) -> bool {
 }
 
 #[cfg(unix)]
-fn kill_process(pid: u32) {
+pub(crate) fn kill_process(pid: u32) {
     unsafe { libc::kill(pid as i32, libc::SIGTERM); }
 }
 
 #[cfg(windows)]
-fn kill_process(pid: u32) {
+pub(crate) fn kill_process(pid: u32) {
     let _ = std::process::Command::new("taskkill")
         .args(["/PID", &pid.to_string(), "/F"])
         .output();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_port_from_ssh_output() {
+        assert_eq!(forwarded_port("Allocated port 43210 for remote forward to 127.0.0.1:5555", 0), Some(43210));
+        let confirmed = "debug1: remote forward success for: listen 43210, connect 127.0.0.1:5555";
+        assert_eq!(forwarded_port(confirmed, 43210), Some(43210));
+        assert_eq!(forwarded_port(confirmed, 4321), None);
+        assert_eq!(forwarded_port(confirmed, 0), None);
+        assert_eq!(forwarded_port("debug1: remote forward failure for: listen 43210, connect 127.0.0.1:5555", 43210), None);
+    }
+
+    #[test]
+    fn probe_output_must_end_with_the_signature() {
+        assert_eq!(probe_verdict("maiTerm 42", "maiTerm 42"), Some(true));
+        let raw = "HTTP/1.0 200 OK\r\ncontent-type: text/plain\r\ncontent-length: 10\r\n\r\nmaiTerm 42";
+        assert_eq!(probe_verdict(raw, "maiTerm 42"), Some(true));
+        assert_eq!(probe_verdict("unverified\n", "maiTerm 42"), Some(false));
+        assert_eq!(probe_verdict("maiTerm 7", "maiTerm 42"), None);
+        assert_eq!(probe_verdict("", "maiTerm 42"), None);
+        assert!(probe_command(43210).contains("/dev/tcp/127.0.0.1/43210"));
+    }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff_ms(1), 0);
+        assert_eq!(backoff_ms(2), 5_000);
+        assert_eq!(backoff_ms(3), 10_000);
+        assert_eq!(backoff_ms(7), 160_000);
+        assert_eq!(backoff_ms(8), BACKOFF_MAX_MS);
+        assert_eq!(backoff_ms(u32::MAX), BACKOFF_MAX_MS);
+    }
+}
@@ -1,10 +1,19 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 
 use crate::pty;
-use crate::state::AppState;
-use crate::terminal::handle::TermDimensions;
-use crate::terminal::render::{self, TerminalFrame};
+use crate::state::workspace::ClipboardPolicy;
+use crate::state::{save_state, AppState};
+use crate::terminal::ansi::strip_ansi;
+use crate::terminal::asciicast::{self, PlaybackControl, Recording};
+use crate::terminal::blocks::CommandBlock;
+use crate::terminal::export;
+use crate::terminal::handle::restore_headless_terminal;
+use crate::terminal::palette::{TerminalColors, ThemePalette};
+use crate::terminal::render::TerminalFrame;
 use crate::terminal::search;
 use crate::terminal::serialize;
 use alacritty_terminal::grid::{Dimensions, Scroll};
@@ -206,10 +215,10 @@ pub fn scroll_terminal(
     pty_id: String,
     delta: i32,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     handle.term.scroll_display(Scroll::Delta(delta));
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
 }
 
 /// Scroll terminal to an absolute position (0 = bottom/live).
@@ -219,14 +228,27 @@ pub fn scroll_terminal_to(
     pty_id: String,
     offset: usize,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     // First scroll to bottom, then scroll up by the desired offset
     handle.term.scroll_display(Scroll::Bottom);
     if offset > 0 {
         handle.term.scroll_display(Scroll::Delta(offset as i32));
     }
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
+}
+
+/// Full frame of the current viewport, for an xterm.js instance that has
+/// nothing yet (a pane reattaching to a running PTY) — output frames only
+/// carry changed rows.
+#[tauri::command]
+pub fn refresh_terminal_frame(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+) -> Result<TerminalFrame, String> {
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
+    Ok(handle.render_full())
 }
 
 /// Scrollback metadata.
@@ -244,8 +266,8 @@ pub fn get_terminal_scrollback_info(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<ScrollInfo, String> {
-    let registry = state.terminal_registry.read();
-    let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
     Ok(ScrollInfo {
         display_offset: handle.term.grid().display_offset(),
         total_lines: handle.term.grid().total_lines(),
@@ -262,25 +284,85 @@ pub fn terminal_bracketed_paste(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<bool, String> {
-    let registry = state.terminal_registry.read();
-    let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
     Ok(handle
         .term
         .mode()
         .contains(alacritty_terminal::term::TermMode::BRACKETED_PASTE))
 }
 
-/// Search the terminal buffer.
+/// Search the terminal buffer. `options` selects regex/whole-word mode,
+/// the scope, and paging (see `search::SearchOptions`).
 #[tauri::command]
 pub fn search_terminal(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
     query: String,
     case_sensitive: bool,
+    options: Option<search::SearchOptions>,
 ) -> Result<search::SearchResult, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
-    search::search_buffer(&mut handle.term, &query, case_sensitive)
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
+    search::search_buffer(&mut handle.term, &query, case_sensitive, &options.unwrap_or_default())
+}
+
+/// A saved-scrollback search hit, placed in the workspace tree.
+#[derive(serde::Serialize)]
+pub struct ScrollbackSearchResult {
+    pub window_id: String,
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub tab_id: String,
+    pub tab_name: String,
+    pub archived: bool,
+    /// 0-based line within the tab's saved scrollback.
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// Search the saved scrollback of every tab, archived ones included. Live
+/// tabs are searched as of their last scrollback save.
+#[tauri::command]
+pub fn search_all_scrollback(
+    state: State<'_, Arc<AppState>>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ScrollbackSearchResult>, String> {
+    let app_data = state.app_data.read();
+    let mut tabs = HashMap::new();
+    for win in &app_data.windows {
+        for ws in &win.workspaces {
+            for tab in ws.panes.iter().flat_map(|p| &p.tabs) {
+                tabs.insert(tab.id.as_str(), (win, ws, tab.name.as_str(), false));
+            }
+            for tab in &ws.archived_tabs {
+                let name = tab.archived_name.as_deref().unwrap_or(&tab.name);
+                tabs.insert(tab.id.as_str(), (win, ws, name, true));
+            }
+        }
+    }
+
+    // Rows for tabs that no longer exist are left to prune_orphans
+    let matches = state
+        .scrollback_db
+        .search(&query, limit.unwrap_or(200).min(1000), |tab_id| tabs.contains_key(tab_id))?;
+    Ok(matches
+        .into_iter()
+        .filter_map(|m| {
+            let (win, ws, tab_name, archived) = tabs.get(m.tab_id.as_str())?;
+            Some(ScrollbackSearchResult {
+                window_id: win.id.clone(),
+                workspace_id: ws.id.clone(),
+                workspace_name: ws.name.clone(),
+                tab_name: tab_name.to_string(),
+                archived: *archived,
+                tab_id: m.tab_id,
+                line: m.line,
+                snippet: m.snippet,
+            })
+        })
+        .collect())
 }
 
 /// Serialize the terminal buffer for persistence.
@@ -291,8 +373,8 @@ pub fn serialize_terminal(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<Vec<u8>, String> {
-    let registry = state.terminal_registry.read();
-    let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
     // Skip serialization when alternate screen is active
     if handle.term.mode().contains(alacritty_terminal::term::TermMode::ALT_SCREEN) {
         return Err("Alternate screen active".to_string());
@@ -307,10 +389,10 @@ pub fn restore_terminal_scrollback(
     pty_id: String,
     scrollback: Vec<u8>,
 ) -> Result<(), String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     let scrollback_str = String::from_utf8_lossy(&scrollback);
-    serialize::restore_scrollback(&mut handle.term, &scrollback_str);
+    handle.restore_scrollback(&scrollback_str);
     Ok(())
 }
 
@@ -323,8 +405,8 @@ pub fn save_terminal_scrollback(
     tab_id: String,
 ) -> Result<(), String> {
     let (scrollback, size) = {
-        let registry = state.terminal_registry.read();
-        let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+        let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+        let handle = handle.lock();
         if handle.term.mode().contains(alacritty_terminal::term::TermMode::ALT_SCREEN) {
             return Err("Alternate screen active".to_string());
         }
@@ -357,9 +439,9 @@ pub fn restore_terminal_from_saved(
     let scrollback = state.scrollback_db.load(&tab_id)?;
 
     if let Some(scrollback) = scrollback {
-        let mut registry = state.terminal_registry.write();
-        let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
-        serialize::restore_scrollback(&mut handle.term, &scrollback);
+        let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+        let mut handle = handle.lock();
+        handle.restore_scrollback(&scrollback);
     }
     Ok(())
 }
@@ -384,44 +466,7 @@ pub fn get_saved_scrollback_text(
     let scrollback = state.scrollback_db.load(&tab_id)?;
     match scrollback {
         Some(data) => {
-            // Strip ANSI escape sequences
-            let mut plain = String::with_capacity(data.len());
-            let mut chars = data.chars().peekable();
-            while let Some(c) = chars.next() {
-                if c == '\x1b' {
-                    // Skip ESC sequences
-                    if let Some(&next) = chars.peek() {
-                        if next == '[' {
-                            // CSI sequence — skip until letter
-                            chars.next();
-                            while let Some(&ch) = chars.peek() {
-                                chars.next();
-                                if ch.is_ascii_alphabetic() || ch == '@' || ch == '`' {
-                                    break;
-                                }
-                            }
-                        } else if next == ']' {
-                            // OSC sequence — skip until BEL or ST
-                            chars.next();
-                            while let Some(ch) = chars.next() {
-                                if ch == '\x07' {
-                                    break;
-                                }
-                                if ch == '\x1b' {
-                                    if chars.peek() == Some(&'\\') {
-                                        chars.next();
-                                        break;
-                                    }
-                                }
-                            }
-                        } else {
-                            chars.next(); // Skip single char after ESC
-                        }
-                    }
-                } else {
-                    plain.push(c);
-                }
-            }
+            let plain = strip_ansi(&data);
             let lines: Vec<&str> = plain.lines().collect();
             let start = lines.len().saturating_sub(line_count);
             let result = lines[start..].join("\n");
@@ -436,6 +481,62 @@ pub fn get_saved_scrollback_text(
     }
 }
 
+/// Export a live terminal's buffer as HTML, plain text or a Markdown
+/// transcript.
+#[tauri::command]
+pub fn export_terminal(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+    tab_id: Option<String>,
+    format: export::ExportFormat,
+) -> Result<String, String> {
+    let title = export_title(&state, tab_id.as_deref());
+    let palette = *state.terminal_palette.read();
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
+    Ok(export::export(&handle.term, format, &palette, Some(&handle.blocks), &title))
+}
+
+/// Export a tab's saved scrollback (SQLite), for tabs that aren't mounted or
+/// have been archived. The saved format doesn't record soft wraps, so lines
+/// stay split at the width they were saved at, and there are no command
+/// blocks to fence.
+#[tauri::command]
+pub fn export_saved_scrollback(
+    state: State<'_, Arc<AppState>>,
+    tab_id: String,
+    format: export::ExportFormat,
+) -> Result<String, String> {
+    let scrollback = state.scrollback_db.load(&tab_id)?.ok_or("No saved scrollback for this tab")?;
+    let term = restore_headless_terminal(&scrollback, state.scrollback_db.saved_size(&tab_id)?);
+
+    let title = export_title(&state, Some(&tab_id));
+    let palette = *state.terminal_palette.read();
+    Ok(export::export(&term, format, &palette, None, &title))
+}
+
+/// Tab name (live or archived) used as the export's title.
+fn export_title(state: &AppState, tab_id: Option<&str>) -> String {
+    let app_data = state.app_data.read();
+    tab_id
+        .and_then(|id| {
+            app_data.windows.iter().flat_map(|win| &win.workspaces).find_map(|ws| {
+                ws.panes
+                    .iter()
+                    .flat_map(|p| &p.tabs)
+                    .find(|t| t.id == id)
+                    .map(|t| t.name.clone())
+                    .or_else(|| {
+                        ws.archived_tabs
+                            .iter()
+                            .find(|t| t.id == id)
+                            .map(|t| t.archived_name.clone().unwrap_or_else(|| t.name.clone()))
+                    })
+            })
+        })
+        .unwrap_or_else(|| "Terminal".to_string())
+}
+
 /// Clear the terminal's scrollback history and visible screen.
 /// Mimics iTerm2/Terminal.app Cmd+K: clears everything, shell redraws prompt.
 #[tauri::command]
@@ -444,9 +545,11 @@ pub fn clear_terminal_scrollback(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<(), String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut guard = handle.lock();
+    let handle = &mut *guard;
     handle.term.scroll_display(Scroll::Bottom);
+    handle.blocks.clear(&handle.term);
     // Clear visible screen first (this pushes content into scrollback),
     // then clear scrollback history (removes everything including what was just pushed).
     handle.term.clear_screen(alacritty_terminal::vte::ansi::ClearMode::All);
@@ -454,7 +557,7 @@ pub fn clear_terminal_scrollback(
     // Move cursor to home so shell prompt redraws at top
     handle.term.goto(0, 0);
     // Emit a frame immediately so the frontend sees the cleared state
-    let frame = render::render_viewport(&handle.term, handle.selection.as_ref());
+    let frame = handle.render_full();
     let _ = app_handle.emit(&format!("term-frame-{}", pty_id), &frame);
     Ok(())
 }
@@ -470,8 +573,8 @@ pub fn get_terminal_selection_text(
     end_x: usize,
     end_y: i32,
 ) -> Result<String, String> {
-    let registry = state.terminal_registry.read();
-    let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
     let grid = handle.term.grid();
     let num_cols = handle.term.columns();
 
@@ -521,8 +624,8 @@ pub fn get_terminal_recent_text(
     pty_id: String,
     line_count: usize,
 ) -> Result<String, String> {
-    let registry = state.terminal_registry.read();
-    let handle = registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
     let grid = handle.term.grid();
     let num_cols = handle.term.columns();
 
@@ -579,12 +682,8 @@ pub fn resize_terminal_grid(
     cols: u16,
     rows: u16,
 ) -> Result<(), String> {
-    let mut registry = state.terminal_registry.write();
-    if let Some(handle) = registry.get_mut(&pty_id) {
-        handle.term.resize(TermDimensions {
-            cols: cols as usize,
-            rows: rows as usize,
-        });
+    if let Some(handle) = state.terminal_registry.get(&pty_id) {
+        handle.lock().resize(cols, rows);
     }
     Ok(())
 }
@@ -620,8 +719,8 @@ pub fn start_selection(
     side: String,
     selection_type: String,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     let display_offset = handle.term.grid().display_offset();
     let point = viewport_to_point(col, row, display_offset);
     handle.selection = Some(Selection::new(
@@ -629,7 +728,7 @@ pub fn start_selection(
         point,
         parse_side(&side),
     ));
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
 }
 
 /// Update the end of the current selection.
@@ -641,14 +740,14 @@ pub fn update_selection(
     row: usize,
     side: String,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     let display_offset = handle.term.grid().display_offset();
     let point = viewport_to_point(col, row, display_offset);
     if let Some(ref mut sel) = handle.selection {
         sel.update(point, parse_side(&side));
     }
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
 }
 
 /// Clear the current selection.
@@ -657,10 +756,10 @@ pub fn clear_selection(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     handle.selection = None;
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
 }
 
 /// Copy the current selection text.
@@ -669,8 +768,8 @@ pub fn copy_selection(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<Option<String>, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     // Temporarily set term.selection so selection_to_string() can extract text
     handle.term.selection = handle.selection.clone();
     let text = handle.term.selection_to_string();
@@ -684,8 +783,8 @@ pub fn select_all(
     state: State<'_, Arc<AppState>>,
     pty_id: String,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     let top = handle.term.topmost_line();
     let bottom = handle.term.bottommost_line();
     let last_col = handle.term.last_column();
@@ -696,7 +795,7 @@ pub fn select_all(
     );
     sel.update(Point::new(bottom, last_col), Side::Right);
     handle.selection = Some(sel);
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
 }
 
 /// Scroll the viewport while maintaining an active selection, updating the
@@ -708,8 +807,8 @@ pub fn scroll_selection(
     delta: i32,
     col: usize,
 ) -> Result<TerminalFrame, String> {
-    let mut registry = state.terminal_registry.write();
-    let handle = registry.get_mut(&pty_id).ok_or("Terminal not found")?;
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let mut handle = handle.lock();
     handle.term.scroll_display(Scroll::Delta(delta));
     // Update selection endpoint to the edge row in the scroll direction
     let display_offset = handle.term.grid().display_offset();
@@ -718,5 +817,230 @@ pub fn scroll_selection(
     if let Some(ref mut sel) = handle.selection {
         sel.update(point, if delta > 0 { Side::Left } else { Side::Right });
     }
-    Ok(render::render_viewport(&handle.term, handle.selection.as_ref()))
+    Ok(handle.render_full())
+}
+
+/// Answer an OSC 52 prompt. `remember` = "tab" or "host" stores the answer as
+/// that scope's policy so the same request no longer asks.
+#[tauri::command]
+pub fn respond_clipboard_request(
+    app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    request_id: String,
+    allow: bool,
+    remember: Option<String>,
+) -> Result<(), String> {
+    let pending = pty::clipboard::take_pending(&state, &request_id)
+        .ok_or("Clipboard request expired")?;
+
+    if let Some(scope) = remember.as_deref() {
+        let policy = if allow { ClipboardPolicy::Always } else { ClipboardPolicy::Never };
+        let data_clone = {
+            let mut app_data = state.app_data.write();
+            match scope {
+                "tab" => {
+                    let tab = app_data.find_tab_mut(&pending.tab_id).ok_or("Tab not found")?;
+                    tab.osc52_policy = Some(policy);
+                }
+                "host" => {
+                    let host = pending.host.clone().ok_or("Request has no SSH host")?;
+                    app_data.preferences.osc52_host_policies.insert(host, policy);
+                }
+                other => return Err(format!("Unknown remember scope: {}", other)),
+            }
+            app_data.clone()
+        };
+        save_state(&data_clone)?;
+        if scope == "host" {
+            let _ = app_handle.emit("preferences-changed", &data_clone.preferences);
+        }
+    }
+
+    if allow {
+        let max_bytes = state.app_data.read().preferences.osc52_max_bytes as usize;
+        pty::clipboard::perform(&app_handle, &state, &pending.pty_id, pending.op, max_bytes);
+    }
+    Ok(())
+}
+
+/// Report the active theme's terminal colors (builtin or custom) so OSC
+/// 4/10/11/12 queries are answered with what the user actually sees.
+#[tauri::command]
+pub fn set_terminal_theme(state: State<'_, Arc<AppState>>, colors: TerminalColors) -> Result<(), String> {
+    *state.terminal_palette.write() = ThemePalette::from_colors(&colors)?;
+    Ok(())
+}
+
+/// A command block plus where its prompt currently sits.
+#[derive(serde::Serialize)]
+pub struct CommandBlockInfo {
+    #[serde(flatten)]
+    pub block: CommandBlock,
+    /// `scroll_terminal_to` offset that brings the prompt to the top of the
+    /// viewport (0 when it's on the live screen).
+    pub prompt_offset: Option<usize>,
+}
+
+/// Command history of a terminal, oldest first.
+#[tauri::command]
+pub fn list_command_blocks(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+) -> Result<Vec<CommandBlockInfo>, String> {
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
+    Ok(handle
+        .blocks
+        .blocks()
+        .map(|block| CommandBlockInfo {
+            prompt_offset: handle.blocks.scroll_offset(&handle.term, block.prompt_line),
+            block: block.clone(),
+        })
+        .collect())
+}
+
+/// Output of one command as plain text.
+#[tauri::command]
+pub fn get_command_block_output(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+    block_id: u64,
+) -> Result<String, String> {
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
+    let block = handle.blocks.get(block_id).ok_or("Command block not found")?;
+    handle
+        .blocks
+        .output_text(&handle.term, block)
+        .ok_or_else(|| "Command output is no longer in scrollback".to_string())
+}
+
+/// `scroll_terminal_to` offsets of every prompt still in the buffer, oldest
+/// first — for jumping between prompts.
+#[tauri::command]
+pub fn get_prompt_offsets(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+) -> Result<Vec<usize>, String> {
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
+    Ok(handle
+        .blocks
+        .blocks()
+        .filter_map(|block| handle.blocks.scroll_offset(&handle.term, block.prompt_line))
+        .collect())
+}
+
+// --- Recording and playback ---
+
+/// Start recording a terminal's output to an asciicast v2 file (a new file
+/// under the app data folder when `path` is omitted). Returns the path.
+#[tauri::command]
+pub fn start_terminal_recording(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+    tab_id: String,
+    path: Option<String>,
+) -> Result<String, String> {
+    let path = pty::start_recording(&state, &pty_id, &tab_id, path.map(PathBuf::from))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Stop recording, returning the finished file's path.
+#[tauri::command]
+pub fn stop_terminal_recording(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+) -> Result<Option<String>, String> {
+    let path = pty::stop_recording(&state, &pty_id)?;
+    Ok(path.map(|p| p.to_string_lossy().into_owned()))
+}
+
+/// Path of the terminal's active recording, if any.
+#[tauri::command]
+pub fn get_terminal_recording(
+    state: State<'_, Arc<AppState>>,
+    pty_id: String,
+) -> Result<Option<String>, String> {
+    let handle = state.terminal_registry.get(&pty_id).ok_or("Terminal not found")?;
+    let handle = handle.lock();
+    Ok(handle.recorder.as_ref().map(|r| r.path().to_string_lossy().into_owned()))
+}
+
+#[derive(serde::Serialize)]
+pub struct PlaybackInfo {
+    pub width: u16,
+    pub height: u16,
+    /// Seconds, with the recording's idle limit applied.
+    pub duration: f64,
+    pub title: Option<String>,
+}
+
+/// Replay a recording, emitting `playback-frame-{playback_id}` until it ends
+/// or is stopped. The caller picks the id (the playback tab's id) so it can
+/// listen before the first frame. Starting an id again restarts it.
+#[tauri::command]
+pub fn start_playback(
+    app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    playback_id: String,
+    path: String,
+    speed: Option<f64>,
+) -> Result<PlaybackInfo, String> {
+    let recording = Recording::load(Path::new(&path))?;
+    let info = PlaybackInfo {
+        width: recording.header.width,
+        height: recording.header.height,
+        duration: recording.duration(),
+        title: recording.header.title.clone(),
+    };
+
+    let control = Arc::new(PlaybackControl::new(speed.unwrap_or(1.0)));
+    if let Some(previous) = state.playbacks.lock().insert(playback_id.clone(), Arc::clone(&control)) {
+        previous.stopped.store(true, Ordering::Relaxed);
+    }
+    let state = state.inner().clone();
+    std::thread::spawn(move || {
+        let event = format!("playback-frame-{}", playback_id);
+        asciicast::play(&recording, &control, |frame| {
+            let _ = app_handle.emit(&event, &frame);
+        });
+        let mut playbacks = state.playbacks.lock();
+        if playbacks.get(&playback_id).is_some_and(|c| Arc::ptr_eq(c, &control)) {
+            playbacks.remove(&playback_id);
+        }
+    });
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn set_playback_paused(
+    state: State<'_, Arc<AppState>>,
+    playback_id: String,
+    paused: bool,
+) -> Result<(), String> {
+    let playbacks = state.playbacks.lock();
+    let control = playbacks.get(&playback_id).ok_or("Playback not found")?;
+    control.paused.store(paused, Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_playback_speed(
+    state: State<'_, Arc<AppState>>,
+    playback_id: String,
+    speed: f64,
+) -> Result<(), String> {
+    let playbacks = state.playbacks.lock();
+    let control = playbacks.get(&playback_id).ok_or("Playback not found")?;
+    control.set_speed(speed);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_playback(state: State<'_, Arc<AppState>>, playback_id: String) -> Result<(), String> {
+    if let Some(control) = state.playbacks.lock().remove(&playback_id) {
+        control.stopped.store(true, Ordering::Relaxed);
+    }
+    Ok(())
 }
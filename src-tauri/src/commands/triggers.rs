@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+use crate::pty::{auto_resume, triggers};
+use crate::state::AppState;
+use crate::terminal::triggers::TriggerStats;
+
+/// Set (or clear, with a null value) one trigger variable for a tab, then
+/// re-evaluate variable-mode triggers (may fire auto-resume etc.).
+#[tauri::command]
+pub fn set_tab_trigger_variable(
+    app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    tab_id: String,
+    name: String,
+    value: Option<String>,
+) -> Result<(), String> {
+    let variables = state.trigger_engine.set_variable(&tab_id, &name, value);
+    triggers::publish_variables(&app_handle, &state, &tab_id, variables);
+    triggers::evaluate_variable_triggers(&app_handle, &state, &tab_id);
+    Ok(())
+}
+
+/// Suppress trigger actions for a tab during the restore/auto-resume window.
+/// Variables are still extracted; output written meanwhile is never matched.
+#[tauri::command]
+pub fn set_tab_triggers_suppressed(
+    state: State<'_, Arc<AppState>>,
+    tab_id: String,
+    suppressed: bool,
+) -> Result<(), String> {
+    state.trigger_engine.set_suppressed(&tab_id, suppressed);
+    Ok(())
+}
+
+/// Record the tab's auto-resume context (the enable_auto_resume action).
+/// `remote_cwd_hint` is the frontend's prompt-derived remote cwd, used when
+/// the remote shell doesn't report one.
+#[tauri::command]
+pub fn enable_tab_auto_resume(
+    app_handle: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    tab_id: String,
+    command_template: String,
+    remote_cwd_hint: Option<String>,
+) -> Result<(), String> {
+    auto_resume::enable(&app_handle, &state, &tab_id, &command_template, remote_cwd_hint)
+}
+
+/// Replay the tab's auto-resume context into its PTY (the replay_auto_resume action).
+#[tauri::command]
+pub fn replay_tab_auto_resume(state: State<'_, Arc<AppState>>, tab_id: String) -> Result<(), String> {
+    auto_resume::replay(&state, &tab_id)
+}
+
+/// Drop trigger state for a destroyed terminal.
+#[tauri::command]
+pub fn clear_tab_triggers(state: State<'_, Arc<AppState>>, tab_id: String) -> Result<(), String> {
+    state.trigger_engine.remove_tab(&tab_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_trigger_stats(state: State<'_, Arc<AppState>>) -> Result<TriggerStats, String> {
+    Ok(state.trigger_engine.stats())
+}
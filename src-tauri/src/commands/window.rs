@@ -469,9 +469,14 @@ pub(crate) fn clone_workspace_with_id_mapping(
                 last_cwd: tab.last_cwd.clone(),
                 diff_context: tab.diff_context.clone(),
                 import_highlight: false,
-                // Tab ids are remapped for the new window, so an Agent Bridge (which
-                // references the partner by tab id) can't carry over — drop it.
+                // Agent Bridge groups are routed within one window, so the
+                // membership can't carry over to the new one — drop it.
                 agent_bridge: None,
+                osc52_policy: tab.osc52_policy,
+                auto_record: tab.auto_record,
+                playback_file: tab.playback_file.clone(),
+                transcript: tab.transcript.clone(),
+                port_forwards: tab.port_forwards.clone(),
             }
         }).collect();
 
@@ -503,6 +508,8 @@ pub(crate) fn clone_workspace_with_id_mapping(
         archived_tabs: Vec::new(),
         import_highlight: false,
         suspended: false,
+        port_forwards: ws.port_forwards.clone(),
+        osc52_policy: ws.osc52_policy,
         pane_sizes: None,
     };
 
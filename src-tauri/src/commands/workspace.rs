@@ -7,8 +7,9 @@ use tauri::{Emitter, State};
 use crate::state::{save_state, AppState, Pane, Preferences, Tab, Workspace};
 use crate::state::workspace::WorkspaceNote;
 use crate::state::persistence::{app_data_slug, parse_state};
-use crate::state::workspace::{EditorFileInfo, SplitDirection, TabType};
+use crate::state::workspace::{EditorFileInfo, SplitDirection, TabType, TranscriptSource};
 use crate::state::ScrollbackDb;
+use crate::claude_code::resources::{self, ResourceUri};
 use crate::commands::window::{TabContext, clone_workspace_with_id_mapping};
 
 /// Extract any scrollback from imported AppData tabs into SQLite and clear from structs.
@@ -52,6 +53,8 @@ pub fn exit_app(app: tauri::AppHandle, state: State<'_, Arc<AppState>>) {
 
     // Kill all SSH MCP tunnels
     crate::commands::ssh_tunnel::kill_all_tunnels(&state);
+    // and user-defined port forwards
+    crate::commands::port_forward::kill_all_port_forwards(&state);
 
     // Kill all remaining PTYs so their threads can exit cleanly
     let pty_ids: Vec<String> = state.pty_registry.read().keys().cloned().collect();
@@ -798,6 +801,30 @@ pub fn set_tab_notes(
         }
     }
     save_state(&app_data)?;
+    resources::mark_changed(&state, ResourceUri::TabNotes(tab_id));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_tab_auto_record(
+    window: tauri::Window,
+    state: State<'_, Arc<AppState>>,
+    workspace_id: String,
+    pane_id: String,
+    tab_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let label = window.label().to_string();
+    let mut app_data = state.app_data.write();
+    let win = app_data.window_mut(&label).ok_or("Window not found")?;
+    if let Some(workspace) = win.workspaces.iter_mut().find(|w| w.id == workspace_id) {
+        if let Some(pane) = workspace.panes.iter_mut().find(|p| p.id == pane_id) {
+            if let Some(tab) = pane.tabs.iter_mut().find(|t| t.id == tab_id) {
+                tab.auto_record = enabled;
+            }
+        }
+    }
+    save_state(&app_data)?;
     Ok(())
 }
 
@@ -1162,10 +1189,12 @@ pub fn set_tab_trigger_variables(
     if let Some(workspace) = win.workspaces.iter_mut().find(|w| w.id == workspace_id) {
         if let Some(pane) = workspace.panes.iter_mut().find(|p| p.id == pane_id) {
             if let Some(tab) = pane.tabs.iter_mut().find(|t| t.id == tab_id) {
-                tab.trigger_variables = vars;
+                tab.trigger_variables = vars.clone();
             }
         }
     }
+    // Keep the trigger engine in step without firing (restore, clone, clear)
+    state.trigger_engine.load_variables(&tab_id, vars, &app_data.preferences.triggers);
     Ok(())
 }
 
@@ -1476,7 +1505,9 @@ pub fn update_workspace_note(
             return Err("Workspace not found".to_string());
         }
     };
-    save_state(&data_clone)
+    save_state(&data_clone)?;
+    resources::mark_changed(&state, ResourceUri::WorkspaceNote { workspace_id, note_id });
+    Ok(())
 }
 
 #[tauri::command]
@@ -1497,7 +1528,9 @@ pub fn delete_workspace_note(
             return Err("Workspace not found".to_string());
         }
     };
-    save_state(&data_clone)
+    save_state(&data_clone)?;
+    resources::mark_changed(&state, ResourceUri::WorkspaceNote { workspace_id, note_id });
+    Ok(())
 }
 
 #[tauri::command]
@@ -1537,6 +1570,98 @@ pub fn create_diff_tab(
     Ok(tab)
 }
 
+/// Open the Claude session last run in `source_tab_id` (a live or archived tab
+/// of the workspace) in a read-only Transcript tab.
+#[tauri::command]
+pub fn create_transcript_tab(
+    window: tauri::Window,
+    state: State<'_, Arc<AppState>>,
+    workspace_id: String,
+    pane_id: String,
+    source_tab_id: String,
+    after_tab_id: Option<String>,
+) -> Result<Tab, String> {
+    let label = window.label().to_string();
+    let mut app_data = state.app_data.write();
+    let win = app_data.window_mut(&label).ok_or("Window not found")?;
+    let ws = win
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    let source_tab = ws
+        .panes
+        .iter()
+        .flat_map(|p| &p.tabs)
+        .chain(&ws.archived_tabs)
+        .find(|t| t.id == source_tab_id)
+        .ok_or("Tab not found")?;
+    let source = TranscriptSource::from_tab(source_tab).ok_or("Tab has no Claude session to show")?;
+    let name = format!("{} (transcript)", source_tab.archived_name.as_deref().unwrap_or(&source_tab.name));
+
+    let pane = ws
+        .panes
+        .iter_mut()
+        .find(|p| p.id == pane_id)
+        .ok_or("Pane not found")?;
+
+    let tab = Tab::new_transcript(name, source);
+    let tab_id = tab.id.clone();
+
+    let insert_idx = after_tab_id
+        .and_then(|id| pane.tabs.iter().position(|t| t.id == id))
+        .map(|idx| idx + 1)
+        .unwrap_or(pane.tabs.len());
+    pane.tabs.insert(insert_idx, tab.clone());
+    pane.active_tab_id = Some(tab_id);
+    save_state(&app_data)?;
+
+    Ok(tab)
+}
+
+/// Open a recording in a read-only Playback tab.
+#[tauri::command]
+pub fn create_playback_tab(
+    window: tauri::Window,
+    state: State<'_, Arc<AppState>>,
+    workspace_id: String,
+    pane_id: String,
+    path: String,
+    after_tab_id: Option<String>,
+) -> Result<Tab, String> {
+    let name = std::path::Path::new(&path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Recording".to_string());
+    let label = window.label().to_string();
+    let mut app_data = state.app_data.write();
+    let win = app_data.window_mut(&label).ok_or("Window not found")?;
+    let ws = win
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+    let pane = ws
+        .panes
+        .iter_mut()
+        .find(|p| p.id == pane_id)
+        .ok_or("Pane not found")?;
+
+    let tab = Tab::new_playback(name, path);
+    let tab_id = tab.id.clone();
+
+    let insert_idx = after_tab_id
+        .and_then(|id| pane.tabs.iter().position(|t| t.id == id))
+        .map(|idx| idx + 1)
+        .unwrap_or(pane.tabs.len());
+    pane.tabs.insert(insert_idx, tab.clone());
+    pane.active_tab_id = Some(tab_id);
+    save_state(&app_data)?;
+
+    Ok(tab)
+}
+
 #[tauri::command]
 pub fn archive_tab(
     window: tauri::Window,
@@ -2094,6 +2219,8 @@ pub fn get_app_diagnostics(state: State<'_, Arc<AppState>>) -> serde_json::Value
     let mut terminal_tabs = 0usize;
     let mut editor_tabs = 0usize;
     let mut diff_tabs = 0usize;
+    let mut playback_tabs = 0usize;
+    let mut transcript_tabs = 0usize;
     let mut total_panes = 0usize;
     let mut total_workspaces = 0usize;
     // All terminal tab PTY IDs (for orphaned PTY detection)
@@ -2109,6 +2236,8 @@ pub fn get_app_diagnostics(state: State<'_, Arc<AppState>>) -> serde_json::Value
                     match tab.tab_type {
                         crate::state::workspace::TabType::Editor => editor_tabs += 1,
                         crate::state::workspace::TabType::Diff => diff_tabs += 1,
+                        crate::state::workspace::TabType::Playback => playback_tabs += 1,
+                        crate::state::workspace::TabType::Transcript => transcript_tabs += 1,
                         crate::state::workspace::TabType::Terminal => {
                             terminal_tabs += 1;
                             all_tab_pty_ids.push(tab.pty_id.clone());
@@ -2242,6 +2371,11 @@ pub fn get_app_diagnostics(state: State<'_, Arc<AppState>>) -> serde_json::Value
                 "pty_id": pty_id,
                 "bytes_read": s.bytes_read.load(Ordering::Relaxed),
                 "bytes_written": s.bytes_written.load(Ordering::Relaxed),
+                "frames": s.frames.load(Ordering::Relaxed),
+                "full_frames": s.full_frames.load(Ordering::Relaxed),
+                "frames_coalesced": s.frames_coalesced.load(Ordering::Relaxed),
+                "frame_bytes": s.frame_bytes.load(Ordering::Relaxed),
+                "frame_bytes_saved": s.frame_bytes_saved.load(Ordering::Relaxed),
             })
         }).collect()
     };
@@ -2264,19 +2398,7 @@ pub fn get_app_diagnostics(state: State<'_, Arc<AppState>>) -> serde_json::Value
     let prev_run = crate::state::persistence::previous_run_info();
     let crash_reports = crate::commands::system::scan_crash_reports(20, 30);
 
-    let ssh_mcp_tunnel_info: Vec<serde_json::Value> = {
-        let tunnels = state.ssh_tunnels.read();
-        tunnels.values().map(|t| {
-            let tab_ids: Vec<&str> = t.tab_ids.iter().map(|s| s.as_str()).collect();
-            serde_json::json!({
-                "host_key": t.host_key,
-                "remote_port": t.remote_port,
-                "pid": t.pid,
-                "alive": crate::commands::ssh_tunnel::is_tunnel_alive(t.pid),
-                "tab_ids": tab_ids,
-            })
-        }).collect()
-    };
+    let ssh_mcp_tunnel_info = crate::commands::ssh_tunnel::tunnel_diagnostics(&state);
 
     serde_json::json!({
         "version": crate::APP_VERSION,
@@ -2289,6 +2411,8 @@ pub fn get_app_diagnostics(state: State<'_, Arc<AppState>>) -> serde_json::Value
             "terminal": terminal_tabs,
             "editor": editor_tabs,
             "diff": diff_tabs,
+            "playback": playback_tabs,
+            "transcript": transcript_tabs,
         },
         "pty_registry_count": pty_count,
         "active_terminal_tabs": active_terminal_tabs,
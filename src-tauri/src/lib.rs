@@ -397,8 +397,17 @@ pub fn run() {
             commands::terminal::detect_windows_shells,
             commands::terminal::scroll_terminal,
             commands::terminal::scroll_terminal_to,
+            commands::terminal::refresh_terminal_frame,
             commands::terminal::get_terminal_scrollback_info,
             commands::terminal::search_terminal,
+            commands::terminal::search_all_scrollback,
+            commands::terminal::start_terminal_recording,
+            commands::terminal::stop_terminal_recording,
+            commands::terminal::get_terminal_recording,
+            commands::terminal::start_playback,
+            commands::terminal::set_playback_paused,
+            commands::terminal::set_playback_speed,
+            commands::terminal::stop_playback,
             commands::terminal::terminal_bracketed_paste,
             commands::terminal::serialize_terminal,
             commands::terminal::restore_terminal_scrollback,
@@ -416,7 +425,20 @@ pub fn run() {
             commands::terminal::restore_terminal_from_saved,
             commands::terminal::has_saved_scrollback,
             commands::terminal::get_saved_scrollback_text,
+            commands::terminal::export_terminal,
+            commands::terminal::export_saved_scrollback,
             commands::terminal::get_saved_terminal_size,
+            commands::terminal::respond_clipboard_request,
+            commands::terminal::set_terminal_theme,
+            commands::terminal::list_command_blocks,
+            commands::terminal::get_command_block_output,
+            commands::terminal::get_prompt_offsets,
+            commands::triggers::set_tab_trigger_variable,
+            commands::triggers::set_tab_triggers_suppressed,
+            commands::triggers::enable_tab_auto_resume,
+            commands::triggers::replay_tab_auto_resume,
+            commands::triggers::clear_tab_triggers,
+            commands::triggers::get_trigger_stats,
             commands::workspace::get_app_data,
             commands::workspace::create_workspace,
             commands::workspace::delete_workspace,
@@ -443,6 +465,7 @@ pub fn run() {
             commands::workspace::set_split_ratio,
             commands::workspace::set_tab_scrollback,
             commands::workspace::set_tab_notes,
+            commands::workspace::set_tab_auto_record,
             commands::workspace::set_tab_notes_open,
             commands::workspace::set_tab_notes_mode,
             commands::workspace::set_tab_composer_open,
@@ -503,14 +526,28 @@ pub fn run() {
             commands::editor::ssh_list_files,
             commands::claude_code::claude_code_respond,
             commands::claude_code::claude_code_notify_selection,
+            commands::claude_code::claude_code_editor_changed,
+            commands::claude_code::get_hook_timeline,
+            commands::claude_code::get_mcp_audit_log,
+            commands::claude_code::list_mcp_tokens,
+            commands::claude_code::rotate_mcp_token,
+            commands::claude_code::get_claude_usage,
+            commands::claude_code::read_claude_transcript,
             commands::ssh_tunnel::start_ssh_tunnel,
             commands::ssh_tunnel::detach_ssh_tunnel,
             commands::ssh_tunnel::get_ssh_tunnel,
             commands::ssh_tunnel::get_mcp_port,
             commands::ssh_tunnel::get_mcp_auth,
+            commands::ssh_tunnel::revoke_mcp_host_token,
             commands::ssh_tunnel::get_maiterm_skill_scripts,
             commands::ssh_tunnel::ssh_run_setup,
+            commands::port_forward::sync_port_forwards,
+            commands::port_forward::add_port_forward,
+            commands::port_forward::remove_port_forward,
+            commands::port_forward::list_port_forwards,
             commands::workspace::create_diff_tab,
+            commands::workspace::create_playback_tab,
+            commands::workspace::create_transcript_tab,
             commands::workspace::archive_tab,
             commands::workspace::restore_archived_tab,
             commands::workspace::delete_archived_tab,
@@ -0,0 +1,199 @@
+use regex::Regex;
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+use super::manager::{get_pty_info, write_pty};
+use super::triggers::tab_window;
+use crate::state::{save_state, AppState};
+use crate::terminal::triggers::interpolate_variables;
+
+/// Stock resume commands of the agent CLIs (`AGENT_RESUME_COMMANDS` in the
+/// frontend's trigger defaults).
+const AGENT_RESUME_COMMANDS: &[&str] = &["claude --resume %claudeSessionId", "gemini --resume %agentSessionId"];
+
+/// The `cd … && exec $SHELL -l` remote command `build_ssh_command` appends,
+/// as ps shows it (unquoted) or as typed (single-quoted).
+fn injected_cd_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\s+(?:cd\s+.*?&&\s+exec\s+\$?SHELL\s+-l|'cd\s+.*?&&\s+exec\s+\$?SHELL\s+-l')\s*$")
+            .expect("valid injected cd regex")
+    })
+}
+
+/// Reduce an ssh command line to the `user@host [flags]` form stored for
+/// auto-resume: drops `ssh`, the remote cd we inject, the flags
+/// `build_ssh_command` re-adds and repeated single-letter flags.
+/// Anything that isn't an ssh command is returned as is.
+pub fn clean_ssh_command(cmd: &str) -> String {
+    let Some(args) = cmd.strip_prefix("ssh").filter(|rest| rest.starts_with(char::is_whitespace)) else {
+        return cmd.to_string();
+    };
+    let args = injected_cd_re().replace(args, "");
+    let mut parts: Vec<&str> = Vec::new();
+    let mut tokens = args.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        if token == "-t" || token.starts_with("ControlMaster=") {
+            continue;
+        }
+        if token == "-o" && tokens.peek().is_some_and(|next| next.starts_with("ControlMaster=")) {
+            tokens.next();
+            continue;
+        }
+        let single_flag = token.len() == 2 && token.starts_with('-') && token.as_bytes()[1].is_ascii_alphabetic();
+        if single_flag && parts.contains(&token) {
+            continue;
+        }
+        parts.push(token);
+    }
+    parts.join(" ")
+}
+
+/// Single-quote a path for the shell, leaving a leading `~/` unquoted so it
+/// still expands.
+pub fn shell_escape_path(path: &str) -> String {
+    if path == "~" {
+        return "~".to_string();
+    }
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("~/'{}'", rest.replace('\'', r"'\''")),
+        None => format!("'{}'", path.replace('\'', r"'\''")),
+    }
+}
+
+/// Rebuild the ssh command for a stored `user@host [flags]`, landing in
+/// `remote_cwd` when given.
+pub fn build_ssh_command(ssh_command: &str, remote_cwd: Option<&str>) -> String {
+    let rest = ssh_command
+        .strip_prefix("ssh")
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+        .map_or(ssh_command, str::trim_start);
+    match remote_cwd {
+        Some(cwd) => format!(
+            "ssh -t -o ControlMaster=no {} 'cd {} && exec $SHELL -l'",
+            rest,
+            shell_escape_path(cwd)
+        ),
+        None => format!("ssh -o ControlMaster=no {}", rest),
+    }
+}
+
+/// Record what the tab is running so it can be resumed after a restart: the
+/// local cwd, the ssh session and its remote cwd, and the command to replay.
+/// A command already on the tab wins over `template`, except a fork command
+/// or another agent's stock resume command. `remote_cwd_hint` is used when
+/// the remote shell hasn't reported its cwd (OSC 7).
+pub fn enable(
+    app_handle: &AppHandle,
+    state: &Arc<AppState>,
+    tab_id: &str,
+    template: &str,
+    remote_cwd_hint: Option<String>,
+) -> Result<(), String> {
+    let pty_id = state.tab_pty_map.read().get(tab_id).cloned().ok_or("Tab has no terminal")?;
+    let info = get_pty_info(state, &pty_id)?;
+    let ssh_command = info.foreground_command.as_deref().map(clean_ssh_command);
+    let local_cwd = info.cwd;
+    // Before a remote shell reports its own, OSC 7 still holds the local cwd
+    let osc7_cwd = state
+        .terminal_registry
+        .get(&pty_id)
+        .and_then(|terminal| terminal.lock().blocks.cwd().map(str::to_string))
+        .filter(|cwd| Some(cwd) != local_cwd.as_ref());
+
+    let mut app_data = state.app_data.write();
+    let tab = app_data.find_tab_mut(tab_id).ok_or("Tab not found")?;
+
+    // A `--fork-session` command re-forks the original session on every
+    // resume, so once the fork has its own session id the template takes over.
+    let existing = tab.auto_resume_command.clone().or_else(|| tab.auto_resume_remembered_command.clone());
+    let stale_default = existing
+        .as_deref()
+        .is_some_and(|e| e != template && AGENT_RESUME_COMMANDS.contains(&e));
+    let command = existing
+        .filter(|e| !e.contains("--fork-session") && !stale_default && !e.is_empty())
+        .or_else(|| Some(template.to_string()).filter(|t| !t.is_empty()));
+
+    // Don't downgrade an SSH context when this PTY shows no ssh (e.g. the
+    // ssh replay failed on restore): only the command is updated.
+    if ssh_command.is_some() || tab.auto_resume_ssh_command.is_none() {
+        tab.auto_resume_remote_cwd = match ssh_command {
+            Some(_) => osc7_cwd.or(remote_cwd_hint).or_else(|| tab.auto_resume_remote_cwd.clone()),
+            None => None,
+        };
+        tab.auto_resume_cwd = local_cwd;
+        tab.auto_resume_ssh_command = ssh_command;
+    }
+    if command.is_some() {
+        tab.auto_resume_remembered_command = command.clone();
+    }
+    tab.auto_resume_command = command;
+    tab.auto_resume_enabled = true;
+
+    let payload = serde_json::json!({
+        "tab_id": tab_id,
+        "cwd": tab.auto_resume_cwd,
+        "ssh_command": tab.auto_resume_ssh_command,
+        "remote_cwd": tab.auto_resume_remote_cwd,
+        "command": tab.auto_resume_command,
+    });
+    save_state(&app_data)?;
+    if let Some(label) = tab_window(&app_data, tab_id) {
+        let _ = app_handle.emit_to(&label, "tab-auto-resume-changed", payload);
+    }
+    Ok(())
+}
+
+/// Replay the tab's stored auto-resume context into its PTY: the ssh session
+/// followed by the command, or a `cd` to the local cwd and the command.
+pub fn replay(state: &Arc<AppState>, tab_id: &str) -> Result<(), String> {
+    let pty_id = state.tab_pty_map.read().get(tab_id).cloned().ok_or("Tab has no terminal")?;
+    let (ssh_command, remote_cwd, local_cwd, command) = {
+        let app_data = state.app_data.read();
+        let (_, tab) = app_data.find_tab(tab_id).ok_or("Tab not found")?;
+        (
+            tab.auto_resume_ssh_command.clone(),
+            tab.auto_resume_remote_cwd.clone(),
+            tab.auto_resume_cwd.clone(),
+            tab.auto_resume_command.clone(),
+        )
+    };
+    let variables = state.trigger_engine.variables(tab_id);
+    let command = command.map(|cmd| format!("{}\n", interpolate_variables(&cmd, &variables, true)));
+
+    let mut payload = String::new();
+    if let Some(ssh_command) = ssh_command {
+        payload.push_str(&build_ssh_command(&ssh_command, remote_cwd.as_deref()));
+        payload.push('\n');
+    } else if command.is_none() {
+        return Ok(());
+    } else if let Some(cwd) = local_cwd {
+        payload.push_str(&format!("cd {}\n", shell_escape_path(&cwd)));
+    }
+    payload.push_str(command.as_deref().unwrap_or_default());
+    write_pty(state, &pty_id, payload.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssh_commands_round_trip() {
+        assert_eq!(
+            clean_ssh_command("ssh -t -o ControlMaster=no -C -C me@box 'cd ~/src && exec $SHELL -l'"),
+            "-C me@box"
+        );
+        assert_eq!(clean_ssh_command("ssh -t -o ControlMaster=no me@box cd /srv && exec $SHELL -l"), "me@box");
+        assert_eq!(clean_ssh_command("ssh -p 2222 -o ServerAliveInterval=30 me@box"), "-p 2222 -o ServerAliveInterval=30 me@box");
+        assert_eq!(clean_ssh_command("mosh me@box"), "mosh me@box");
+
+        assert_eq!(build_ssh_command("me@box", None), "ssh -o ControlMaster=no me@box");
+        assert_eq!(
+            build_ssh_command("ssh me@box", Some("~/it's here")),
+            r"ssh -t -o ControlMaster=no me@box 'cd ~/'it'\''s here' && exec $SHELL -l'"
+        );
+        assert_eq!(shell_escape_path("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(shell_escape_path("~"), "~");
+    }
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use super::manager::{get_pty_info, write_pty};
+use crate::state::workspace::ClipboardPolicy;
+use crate::state::AppState;
+
+/// "Ask" requests nobody answered are dropped after this long.
+const REQUEST_TTL: Duration = Duration::from_secs(60);
+
+/// Formats clipboard text as the OSC 52 reply the program asked for
+/// (alacritty bakes in the selection char and terminator).
+pub type ReplyFormatter = Arc<dyn Fn(&str) -> String + Sync + Send + 'static>;
+
+/// One OSC 52 operation from a program running in a terminal.
+pub enum ClipboardOp {
+    /// Program → local clipboard (already base64-decoded).
+    Write(String),
+    /// Program wants the local clipboard's contents sent back.
+    Read(ReplyFormatter),
+}
+
+impl ClipboardOp {
+    fn kind(&self) -> &'static str {
+        match self {
+            ClipboardOp::Write(_) => "write",
+            ClipboardOp::Read(_) => "read",
+        }
+    }
+}
+
+/// An OSC 52 request waiting on the user's Allow/Deny (policy "ask").
+pub struct PendingClipboard {
+    pub pty_id: String,
+    pub tab_id: String,
+    pub host: Option<String>,
+    pub op: ClipboardOp,
+    pub requested_at: Instant,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ClipboardRequestEvent {
+    request_id: String,
+    kind: &'static str,
+    host: Option<String>,
+    bytes: usize,
+}
+
+/// Entry point from the event proxy. Runs on its own thread — the proxy is
+/// called with the terminal locked, and resolving the policy needs
+/// a process lookup.
+pub fn handle_request(app_handle: AppHandle, pty_id: String, tab_id: String, op: ClipboardOp) {
+    std::thread::spawn(move || {
+        let Some(state) = app_handle.try_state::<Arc<AppState>>() else {
+            return;
+        };
+        let state = Arc::clone(&state);
+        process_request(&app_handle, &state, pty_id, tab_id, op);
+    });
+}
+
+fn process_request(app_handle: &AppHandle, state: &Arc<AppState>, pty_id: String, tab_id: String, op: ClipboardOp) {
+    // Restored scrollback and auto-resumed programs redrawing during the
+    // restore window aren't the user yanking or pasting: drop writes and
+    // answer reads with an empty clipboard.
+    if state.trigger_engine.is_suppressed(&tab_id) {
+        log::info!("OSC 52 {} from pty {} ignored during session restore", op.kind(), pty_id);
+        if let ClipboardOp::Read(formatter) = op {
+            let _ = write_pty(state, &pty_id, formatter("").as_bytes());
+        }
+        return;
+    }
+
+    let host = get_pty_info(state, &pty_id)
+        .ok()
+        .and_then(|info| info.foreground_command)
+        .and_then(|cmd| ssh_host(&cmd));
+
+    let (policy, max_bytes) = {
+        let app_data = state.app_data.read();
+        let prefs = &app_data.preferences;
+        let (workspace_policy, tab_policy) = app_data
+            .find_tab(&tab_id)
+            .map_or((None, None), |(ws, tab)| (ws.osc52_policy, tab.osc52_policy));
+        let host_policy = host.as_ref().and_then(|h| prefs.osc52_host_policies.get(h).copied());
+        let default = match op {
+            ClipboardOp::Write(_) => prefs.osc52_write_policy,
+            ClipboardOp::Read(_) => prefs.osc52_read_policy,
+        };
+        (
+            resolve_policy(host_policy, tab_policy, workspace_policy, default),
+            prefs.osc52_max_bytes as usize,
+        )
+    };
+
+    if let ClipboardOp::Write(text) = &op {
+        if text.len() > max_bytes {
+            log::warn!("OSC 52 write from pty {} dropped: {} bytes exceeds limit of {}", pty_id, text.len(), max_bytes);
+            return;
+        }
+    }
+
+    match policy {
+        ClipboardPolicy::Never => {
+            log::info!("OSC 52 {} from pty {} denied by policy", op.kind(), pty_id);
+        }
+        ClipboardPolicy::Always => perform(app_handle, state, &pty_id, op, max_bytes),
+        ClipboardPolicy::Ask => {
+            let request_id = uuid::Uuid::new_v4().to_string();
+            let event = ClipboardRequestEvent {
+                request_id: request_id.clone(),
+                kind: op.kind(),
+                host: host.clone(),
+                bytes: match &op {
+                    ClipboardOp::Write(text) => text.len(),
+                    ClipboardOp::Read(_) => 0,
+                },
+            };
+            {
+                let mut pending = state.clipboard_requests.lock();
+                // One open prompt per terminal and direction: a newer yank
+                // replaces an unanswered one, repeated reads don't stack.
+                pending.retain(|_, p| {
+                    p.requested_at.elapsed() < REQUEST_TTL
+                        && !(p.pty_id == pty_id && p.op.kind() == op.kind())
+                });
+                pending.insert(
+                    request_id,
+                    PendingClipboard { pty_id: pty_id.clone(), tab_id, host, op, requested_at: Instant::now() },
+                );
+            }
+            let _ = app_handle.emit(&format!("term-clipboard-request-{}", pty_id), &event);
+        }
+    }
+}
+
+/// The policy that applies to a request: the ssh host's, then the tab's, then
+/// the workspace's, then the global preference. The host comes first so a
+/// host set to `never` stays blocked whichever tab connects to it.
+fn resolve_policy(
+    host: Option<ClipboardPolicy>,
+    tab: Option<ClipboardPolicy>,
+    workspace: Option<ClipboardPolicy>,
+    global: ClipboardPolicy,
+) -> ClipboardPolicy {
+    host.or(tab).or(workspace).unwrap_or(global)
+}
+
+/// Carry out an allowed request: write the local clipboard, or read it and
+/// reply to the program through the PTY.
+pub fn perform(app_handle: &AppHandle, state: &Arc<AppState>, pty_id: &str, op: ClipboardOp, max_bytes: usize) {
+    match op {
+        ClipboardOp::Write(text) => {
+            if let Err(e) = app_handle.clipboard().write_text(text) {
+                log::error!("OSC 52 clipboard write failed: {}", e);
+            }
+        }
+        ClipboardOp::Read(formatter) => {
+            let text = match app_handle.clipboard().read_text() {
+                Ok(text) => text,
+                Err(e) => {
+                    log::warn!("OSC 52 clipboard read failed: {}", e);
+                    String::new()
+                }
+            };
+            // Answer oversize contents with an empty reply rather than a
+            // truncated one — programs wait for the response either way.
+            let text = if text.len() > max_bytes { "" } else { text.as_str() };
+            if let Err(e) = write_pty(state, pty_id, formatter(text).as_bytes()) {
+                log::error!("OSC 52 reply to pty {} failed: {}", pty_id, e);
+            }
+        }
+    }
+}
+
+/// Remove a pending request, returning it unless it has expired.
+pub fn take_pending(state: &AppState, request_id: &str) -> Option<PendingClipboard> {
+    let mut pending = state.clipboard_requests.lock();
+    pending.retain(|_, p| p.requested_at.elapsed() < REQUEST_TTL);
+    pending.remove(request_id)
+}
+
+/// Drop unanswered requests for a terminal that went away.
+pub fn clear_pty(state: &AppState, pty_id: &str) {
+    state.clipboard_requests.lock().retain(|_, p| p.pty_id != pty_id);
+}
+
+/// Destination host of an ssh command line (`ssh -p 2222 me@box` → `box`),
+/// or None when the command isn't ssh.
+pub fn ssh_host(command: &str) -> Option<String> {
+    // ssh options that consume the following argument
+    const WITH_ARG: &str = "BbcDEeFIiJLlmOoPpQRSWw";
+
+    let mut tokens = command.split_whitespace();
+    let program = tokens.next()?;
+    if program.rsplit('/').next() != Some("ssh") {
+        return None;
+    }
+    while let Some(token) = tokens.next() {
+        if let Some(flags) = token.strip_prefix('-') {
+            // `-p 22` takes the next token; `-p22` and `-vA` don't
+            if let Some((i, c)) = flags.char_indices().find(|(_, c)| WITH_ARG.contains(*c)) {
+                if i + c.len_utf8() == flags.len() {
+                    tokens.next();
+                }
+            }
+            continue;
+        }
+        let dest = token.strip_prefix("ssh://").unwrap_or(token);
+        let host = dest.rsplit('@').next().unwrap_or(dest);
+        let host = host.split(':').next().unwrap_or(host);
+        return (!host.is_empty()).then(|| host.to_lowercase());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ClipboardPolicy::{Always, Ask, Never};
+
+    #[test]
+    fn ssh_host_finds_the_destination() {
+        assert_eq!(ssh_host("ssh me@Box.example"), Some("box.example".into()));
+        assert_eq!(ssh_host("/usr/bin/ssh -p 2222 -i ~/.ssh/id box"), Some("box".into()));
+        assert_eq!(ssh_host("ssh -p2222 -vA -o ControlMaster=no me@box uptime"), Some("box".into()));
+        assert_eq!(ssh_host("ssh -J jump ssh://me@box:2222"), Some("box".into()));
+        assert_eq!(ssh_host("ssh -t"), None);
+        assert_eq!(ssh_host("sshd -D"), None);
+        assert_eq!(ssh_host("mosh me@box"), None);
+    }
+
+    #[test]
+    fn host_policy_wins_over_tab_workspace_and_global() {
+        assert_eq!(resolve_policy(Some(Never), Some(Always), Some(Always), Always), Never);
+        assert_eq!(resolve_policy(None, Some(Ask), Some(Never), Always), Ask);
+        assert_eq!(resolve_policy(None, None, Some(Never), Always), Never);
+        assert_eq!(resolve_policy(None, None, None, Ask), Ask);
+    }
+}
@@ -1,17 +1,52 @@
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use regex::Regex;
 use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::thread;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+use super::{clipboard, triggers};
+use crate::claude_code::resources;
 use crate::state::{AppState, PtyCommand, PtyHandle, PtyStats};
 use crate::state::persistence::app_data_slug;
+use crate::terminal::asciicast::Recorder;
 use crate::terminal::event_proxy::AitermEventProxy;
 use crate::terminal::handle::create_terminal;
 use crate::terminal::osc::OscEvent;
-use crate::terminal::render;
+use crate::terminal::triggers::is_redraw;
+
+/// Minimum spacing of output frames per terminal (~60 fps). Output arriving
+/// faster is coalesced into the next frame.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Per-read summary emitted as `pty-activity-{pty_id}`.
+#[derive(serde::Serialize, Clone)]
+struct PtyActivity {
+    bytes: usize,
+    /// Chunk repositions the cursor (TUI repaint) rather than emitting new output
+    redraw: bool,
+    /// ssh transport-failure message seen in the output
+    ssh_drop: bool,
+}
+
+/// ssh transport-failure stderr (a drop), deliberately NOT matching the bare
+/// "Connection to HOST closed." lines a clean logout (incl. ControlMaster mux)
+/// prints — those are ambiguous. Fallback drop detection for sessions without
+/// shell integration (no OSC 133 exit code to read).
+fn ssh_drop_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)client_loop: send disconnect|closed by remote host|server \S+ not responding|Timeout, server",
+            r"|Write failed: Broken pipe|packet_write_wait|Connection (?:reset|timed out)|Operation timed out",
+            r"|ssh_dispatch_run_fatal|kex_exchange_identification: (?:read|Connection)",
+        ))
+        .expect("valid ssh drop regex")
+    })
+}
 
 pub fn spawn_pty(
     app_handle: &AppHandle,
@@ -265,11 +300,16 @@ pub fn spawn_pty(
             bytes_written: AtomicU64::new(0),
             bytes_read: AtomicU64::new(0),
             last_read_ms: AtomicU64::new(0),
+            frames: AtomicU64::new(0),
+            full_frames: AtomicU64::new(0),
+            frames_coalesced: AtomicU64::new(0),
+            frame_bytes: AtomicU64::new(0),
+            frame_bytes_saved: AtomicU64::new(0),
         });
     }
 
     // Create alacritty_terminal instance
-    {
+    let terminal = {
         let scrollback_limit = {
             let app_data = state.app_data.read();
             let limit = app_data.preferences.scrollback_limit;
@@ -278,12 +318,36 @@ pub fn spawn_pty(
 
         let event_proxy = AitermEventProxy {
             pty_id: pty_id.to_string(),
+            tab_id: tab_id.to_string(),
             app_handle: app_handle.clone(),
             pty_sender: tx_for_proxy,
+            replies: Default::default(),
+            title: Default::default(),
         };
 
         let terminal_handle = create_terminal(cols, rows, scrollback_limit, event_proxy);
-        state.terminal_registry.write().insert(pty_id.to_string(), terminal_handle);
+        state.terminal_registry.insert(pty_id, terminal_handle)
+    };
+
+    // Seed the trigger engine with the tab's persisted variables. Transitions
+    // are primed from them so a restart doesn't fire variable triggers.
+    {
+        let app_data = state.app_data.read();
+        if let Some((_, tab)) = app_data.find_tab(tab_id) {
+            state.trigger_engine.load_variables(
+                tab_id,
+                tab.trigger_variables.clone(),
+                &app_data.preferences.triggers,
+            );
+        }
+    }
+
+    // Tabs flagged for auto-record capture from the first byte
+    let auto_record = state.app_data.read().find_tab(tab_id).map(|(_, tab)| tab.auto_record).unwrap_or(false);
+    if auto_record {
+        if let Err(e) = start_recording(state, pty_id, tab_id, None) {
+            log::warn!("Auto-record for tab {} failed: {}", tab_id, e);
+        }
     }
 
     // Spawn writer thread (with PTY registry cleanup on exit)
@@ -333,7 +397,8 @@ pub fn spawn_pty(
         drop(master);
         // Cleanup: remove PTY handle, terminal handle, and tab mapping on exit
         state_clone.pty_registry.write().remove(&pty_id_owned);
-        state_clone.terminal_registry.write().remove(&pty_id_owned);
+        state_clone.terminal_registry.remove(&pty_id_owned);
+        clipboard::clear_pty(&state_clone, &pty_id_owned);
         // Only remove tab mapping if it still points to this PTY (a new spawn may have already replaced it)
         {
             let mut tab_map = state_clone.tab_pty_map.write();
@@ -343,8 +408,27 @@ pub fn spawn_pty(
         }
     });
 
+    // Spawn frame thread — the reader signals new output, this renders the
+    // damaged rows at a bounded rate. A signal that arrives while it waits
+    // out the interval stays queued, so the last output always gets a frame.
+    let (frame_tx, frame_rx) = mpsc::channel::<()>();
+    let frame_thread = {
+        let pty_id = pty_id.to_string();
+        let app_handle = app_handle.clone();
+        let state = Arc::clone(state);
+        thread::spawn(move || {
+            while frame_rx.recv().is_ok() {
+                let coalesced = frame_rx.try_iter().count() as u64;
+                emit_frame(&app_handle, &state, &pty_id, coalesced);
+                thread::sleep(FRAME_INTERVAL);
+            }
+            // Reader finished — emit final frame before closing
+            emit_frame(&app_handle, &state, &pty_id, 0);
+        })
+    };
+
     // Spawn reader thread — feeds PTY output through OSC interceptor + alacritty_terminal,
-    // then emits rendered frames to the frontend
+    // then signals the frame thread
     let pty_id_clone = pty_id.to_string();
     let tab_id_reader = tab_id.to_string();
     let app_handle_clone = app_handle.clone();
@@ -352,6 +436,8 @@ pub fn spawn_pty(
 
     thread::spawn(move || {
         let mut buf = [0u8; 4096];
+        // Trailing output kept so an ssh error split across reads still matches
+        let mut ssh_tail = String::new();
 
         loop {
             match reader.read(&mut buf) {
@@ -360,26 +446,30 @@ pub fn spawn_pty(
                 }
                 Ok(n) => {
                     // Track bytes read for diagnostics + resize coalescing
-                    {
+                    let now_ms = epoch_millis();
+                    let bytes_written = {
                         use std::sync::atomic::Ordering;
                         let stats = state_reader.pty_stats.read();
                         if let Some(s) = stats.get(&pty_id_clone) {
                             s.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
-                            s.last_read_ms.store(epoch_millis(), Ordering::Relaxed);
-                        }
-                    }
-                    let data = &buf[..n];
-
-                    // Process through OscInterceptor → emit OSC events
-                    let osc_events = {
-                        let mut registry = state_reader.terminal_registry.write();
-                        if let Some(handle) = registry.get_mut(&pty_id_clone) {
-                            handle.osc_interceptor.process(data)
+                            s.last_read_ms.store(now_ms, Ordering::Relaxed);
+                            s.bytes_written.load(Ordering::Relaxed)
                         } else {
-                            vec![]
+                            0
                         }
                     };
-                    for osc_event in osc_events {
+                    let data = &buf[..n];
+
+                    // Run the OscInterceptor, then feed bytes to the alacritty_terminal
+                    // VTE parser. Only this terminal is locked, so other tabs keep
+                    // parsing and rendering meanwhile.
+                    let (osc_events, replies) =
+                        terminal.lock().feed(data, now_ms, bytes_written, &state_reader.terminal_palette.read());
+                    if !replies.is_empty() {
+                        let _ = write_pty(&state_reader, &pty_id_clone, &replies);
+                    }
+
+                    for (_, osc_event) in &osc_events {
                         match osc_event {
                             OscEvent::Cwd { cwd, host } => {
                                 let _ = app_handle_clone.emit(
@@ -408,38 +498,30 @@ pub fn spawn_pty(
                         }
                     }
 
-                    // Feed bytes to alacritty_terminal VTE parser.
-                    // Temporarily move our external selection onto term.selection so
-                    // alacritty's scroll handlers rotate it correctly when new output
-                    // pushes content up. Read it back after processing.
-                    {
-                        let mut registry = state_reader.terminal_registry.write();
-                        if let Some(handle) = registry.get_mut(&pty_id_clone) {
-                            handle.term.selection = handle.selection.take();
-                            handle.processor.advance(&mut handle.term, data);
-                            handle.selection = handle.term.selection.take();
-                        }
-                    }
-
-                    // Render viewport frame and emit to frontend after every read.
-                    // No throttling — render_viewport() is fast (grid iteration) and
-                    // the read() blocking naturally rate-limits during burst output.
-                    // Throttling caused missed final frames when no more data arrives.
-                    {
-                        let registry = state_reader.terminal_registry.read();
-                        if let Some(handle) = registry.get(&pty_id_clone) {
-                            let frame = render::render_viewport(&handle.term, handle.selection.as_ref());
-                            let _ = app_handle_clone.emit(
-                                &format!("term-frame-{}", pty_id_clone),
-                                &frame,
-                            );
+                    let _ = frame_tx.send(());
+                    resources::scrollback_changed(&state_reader, &tab_id_reader);
+
+                    // Match triggers natively so they keep firing with no webview attached
+                    let redraw = is_redraw(data);
+                    triggers::process_output(&app_handle_clone, &state_reader, &tab_id_reader, &terminal, redraw);
+
+                    // Activity summary for the frontend (dirty marking, background
+                    // activity, SSH-drop fallback) — raw bytes don't cross IPC.
+                    let ssh_drop = if state_reader.app_data.read().preferences.shell_integration {
+                        false
+                    } else {
+                        ssh_tail.push_str(&String::from_utf8_lossy(data));
+                        let dropped = ssh_drop_re().is_match(&ssh_tail);
+                        let mut cut = ssh_tail.len().saturating_sub(200);
+                        while !ssh_tail.is_char_boundary(cut) {
+                            cut += 1;
                         }
-                    }
-
-                    // Emit raw bytes for trigger engine (frontend, temporary bridge)
+                        ssh_tail.drain(..cut);
+                        dropped
+                    };
                     let _ = app_handle_clone.emit(
-                        &format!("pty-raw-{}", pty_id_clone),
-                        data.to_vec(),
+                        &format!("pty-activity-{}", pty_id_clone),
+                        PtyActivity { bytes: n, redraw, ssh_drop },
                     );
                 }
                 Err(_) => {
@@ -448,17 +530,9 @@ pub fn spawn_pty(
             }
         }
 
-        // Emit final frame before closing
-        {
-            let registry = state_reader.terminal_registry.read();
-            if let Some(handle) = registry.get(&pty_id_clone) {
-                let frame = render::render_viewport(&handle.term, handle.selection.as_ref());
-                let _ = app_handle_clone.emit(
-                    &format!("term-frame-{}", pty_id_clone),
-                    &frame,
-                );
-            }
-        }
+        // Let the frame thread flush the final frame before pty-close
+        drop(frame_tx);
+        let _ = frame_thread.join();
 
         // Only emit close event if this PTY wasn't replaced by a new spawn for the same tab.
         let was_replaced = {
@@ -476,6 +550,32 @@ pub fn spawn_pty(
     Ok(())
 }
 
+/// Render and emit the output frame for a terminal, if anything changed.
+fn emit_frame(app_handle: &AppHandle, state: &Arc<AppState>, pty_id: &str, coalesced: u64) {
+    let rendered = state
+        .terminal_registry
+        .get(pty_id)
+        .and_then(|handle| handle.lock().render_damage());
+    let Some((frame, full_bytes)) = rendered else {
+        return;
+    };
+    {
+        use std::sync::atomic::Ordering;
+        let stats = state.pty_stats.read();
+        if let Some(s) = stats.get(pty_id) {
+            s.frames.fetch_add(1, Ordering::Relaxed);
+            if frame.full {
+                s.full_frames.fetch_add(1, Ordering::Relaxed);
+            }
+            s.frames_coalesced.fetch_add(coalesced, Ordering::Relaxed);
+            s.frame_bytes.fetch_add(frame.ansi.len() as u64, Ordering::Relaxed);
+            s.frame_bytes_saved
+                .fetch_add(full_bytes.saturating_sub(frame.ansi.len()) as u64, Ordering::Relaxed);
+        }
+    }
+    let _ = app_handle.emit(&format!("term-frame-{}", pty_id), &frame);
+}
+
 pub fn write_pty(state: &Arc<AppState>, pty_id: &str, data: &[u8]) -> Result<(), String> {
     // Track bytes written for diagnostics
     {
@@ -493,6 +593,53 @@ pub fn write_pty(state: &Arc<AppState>, pty_id: &str, data: &[u8]) -> Result<(),
         .map_err(|e| e.to_string())
 }
 
+/// Start recording a terminal's output as asciicast v2. Without a `path`,
+/// the file goes to the app data `recordings` folder, named after the tab.
+pub fn start_recording(
+    state: &Arc<AppState>,
+    pty_id: &str,
+    tab_id: &str,
+    path: Option<PathBuf>,
+) -> Result<PathBuf, String> {
+    use alacritty_terminal::grid::Dimensions;
+    let tab_name = state.app_data.read().find_tab(tab_id).map(|(_, tab)| tab.name.clone());
+    let path = match path {
+        Some(path) => path,
+        None => default_recording_path(tab_name.as_deref())?,
+    };
+    let terminal = state.terminal_registry.get(pty_id).ok_or("Terminal not found")?;
+    let mut handle = terminal.lock();
+    if let Some(recorder) = &handle.recorder {
+        return Err(format!("Already recording to {}", recorder.path().display()));
+    }
+    let (cols, rows) = (handle.term.columns() as u16, handle.term.screen_lines() as u16);
+    handle.recorder = Some(Recorder::create(&path, cols, rows, tab_name)?);
+    Ok(path)
+}
+
+/// Finish a terminal's recording, returning the file written (None if it
+/// wasn't recording).
+pub fn stop_recording(state: &Arc<AppState>, pty_id: &str) -> Result<Option<PathBuf>, String> {
+    let terminal = state.terminal_registry.get(pty_id).ok_or("Terminal not found")?;
+    let recorder = terminal.lock().recorder.take();
+    recorder.map(|r| r.finish()).transpose()
+}
+
+fn default_recording_path(tab_name: Option<&str>) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("No data directory found")?
+        .join(app_data_slug())
+        .join("recordings");
+    let slug: String = tab_name
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "terminal" } else { slug };
+    Ok(dir.join(format!("{}-{}.cast", slug, epoch_millis())))
+}
+
 /// Output within this window means a TUI is actively drawing — resizes are
 /// then coalesced instead of applied per-event. Streaming output arrives in
 /// bursts with gaps of several hundred ms, so this must comfortably exceed a
@@ -624,13 +771,8 @@ fn apply_resize(state: &Arc<AppState>, pty_id: &str, cols: u16, rows: u16) -> Re
 
     // Also resize the alacritty_terminal instance
     {
-        use crate::terminal::handle::TermDimensions;
-        let mut term_registry = state.terminal_registry.write();
-        if let Some(term_handle) = term_registry.get_mut(pty_id) {
-            term_handle.term.resize(TermDimensions {
-                cols: cols as usize,
-                rows: rows as usize,
-            });
+        if let Some(term_handle) = state.terminal_registry.get(pty_id) {
+            term_handle.lock().resize(cols, rows);
         }
     }
 
@@ -645,11 +787,14 @@ pub fn kill_pty(state: &Arc<AppState>, pty_id: &str) -> Result<(), String> {
     }
 
     // Clean up terminal registry
-    state.terminal_registry.write().remove(pty_id);
+    state.terminal_registry.remove(pty_id);
 
     // Drop any resize still waiting on the debounce
     state.pending_resizes.write().remove(pty_id);
 
+    // Drop unanswered OSC 52 prompts
+    clipboard::clear_pty(state, pty_id);
+
     // Clean up tab → pty mapping (reverse lookup)
     {
         let mut tab_map = state.tab_pty_map.write();
@@ -1,3 +1,6 @@
+pub mod auto_resume;
+pub mod clipboard;
 pub mod manager;
+pub mod triggers;
 
-pub use manager::{get_pty_info, kill_pty, resize_pty, spawn_pty, write_pty, PtyInfo};
+pub use manager::{get_pty_info, kill_pty, resize_pty, spawn_pty, start_recording, stop_recording, write_pty, PtyInfo};
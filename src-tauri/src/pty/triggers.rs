@@ -0,0 +1,180 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+use super::auto_resume;
+use super::manager::write_pty;
+use crate::state::workspace::{AppData, TriggerActionEntry, TriggerActionType};
+use crate::state::AppState;
+use crate::terminal::handle::TerminalHandle;
+use crate::terminal::triggers::{dir_from_title, interpolate_variables, TriggerFire};
+
+/// Run trigger matching over the grid text a chunk of PTY output produced.
+/// Called from the reader thread after the chunk has been fed to alacritty.
+pub fn process_output(
+    app_handle: &AppHandle,
+    state: &Arc<AppState>,
+    tab_id: &str,
+    terminal: &Mutex<TerminalHandle>,
+    redraw: bool,
+) {
+    let outcome = {
+        let app_data = state.app_data.read();
+        let triggers = &app_data.preferences.triggers;
+        if triggers.is_empty() {
+            return;
+        }
+        let workspace_id = app_data.find_tab(tab_id).map(|(ws, _)| ws.id.as_str());
+        state.trigger_engine.scan_terminal(terminal, tab_id, workspace_id, triggers, redraw)
+    };
+
+    if let Some(variables) = outcome.variables {
+        publish_variables(app_handle, state, tab_id, variables);
+    }
+    run_fires(app_handle, state, tab_id, outcome.fires);
+}
+
+/// Re-evaluate variable-mode triggers after a tab's variables were changed
+/// outside the output path (MCP tools, Claude hooks, the UI).
+pub fn evaluate_variable_triggers(app_handle: &AppHandle, state: &Arc<AppState>, tab_id: &str) {
+    let fires = {
+        let app_data = state.app_data.read();
+        let workspace_id = app_data.find_tab(tab_id).map(|(ws, _)| ws.id.as_str());
+        state
+            .trigger_engine
+            .evaluate_variable_triggers(tab_id, workspace_id, &app_data.preferences.triggers)
+    };
+    run_fires(app_handle, state, tab_id, fires);
+}
+
+/// Store a tab's variables on the tab (persisted with the next state save)
+/// and push the full map to the frontend.
+pub fn publish_variables(app_handle: &AppHandle, state: &Arc<AppState>, tab_id: &str, variables: HashMap<String, String>) {
+    if let Some(tab) = state.app_data.write().find_tab_mut(tab_id) {
+        tab.trigger_variables = variables.clone();
+    }
+    let _ = app_handle.emit(&format!("trigger-vars-{}", tab_id), &variables);
+}
+
+/// Label of the window that owns a tab.
+pub fn tab_window(app_data: &AppData, tab_id: &str) -> Option<String> {
+    app_data
+        .windows
+        .iter()
+        .find(|win| win.workspaces.iter().flat_map(|ws| &ws.panes).flat_map(|p| &p.tabs).any(|t| t.id == tab_id))
+        .map(|win| win.label.clone())
+}
+
+/// Execute the actions of fired triggers. Everything runs here so triggers
+/// act on tabs whose terminal isn't mounted; notifications and tab state are
+/// sent to the owning window, which shows them whatever tab is on screen.
+fn run_fires(app_handle: &AppHandle, state: &Arc<AppState>, tab_id: &str, fires: Vec<TriggerFire>) {
+    if fires.is_empty() {
+        return;
+    }
+    let pty_id = state.tab_pty_map.read().get(tab_id).cloned();
+    let variables = state.trigger_engine.variables(tab_id);
+    let Some(label) = tab_window(&state.app_data.read(), tab_id) else {
+        return;
+    };
+
+    for fire in fires {
+        for action in &fire.actions {
+            match action.action_type {
+                TriggerActionType::SendCommand => {
+                    let (Some(command), Some(pty_id)) = (action.command.as_deref(), pty_id.as_deref()) else {
+                        continue;
+                    };
+                    if command.is_empty() {
+                        continue;
+                    }
+                    let payload = format!("{}\n", interpolate_variables(command, &variables, false));
+                    if let Err(e) = write_pty(state, pty_id, payload.as_bytes()) {
+                        log::error!("Trigger \"{}\" failed to send command: {}", fire.trigger_name, e);
+                    }
+                }
+                TriggerActionType::Notify => {
+                    let (title, message) = notification(state, tab_id, pty_id.as_deref(), &variables, action);
+                    let _ = app_handle.emit_to(
+                        &label,
+                        "trigger-notify",
+                        serde_json::json!({ "tab_id": tab_id, "title": title, "message": message }),
+                    );
+                }
+                TriggerActionType::SetTabState => {
+                    let tab_state = action.tab_state.as_deref().unwrap_or("alert");
+                    let _ = app_handle.emit_to(
+                        &label,
+                        "trigger-tab-state",
+                        serde_json::json!({ "tab_id": tab_id, "state": tab_state }),
+                    );
+                }
+                TriggerActionType::EnableAutoResume => {
+                    let template = action.command.as_deref().unwrap_or_default();
+                    if let Err(e) = auto_resume::enable(app_handle, state, tab_id, template, None) {
+                        log::error!("Trigger \"{}\": enable_auto_resume failed: {}", fire.trigger_name, e);
+                    }
+                }
+                TriggerActionType::ReplayAutoResume => {
+                    if let Err(e) = auto_resume::replay(state, tab_id) {
+                        log::error!("Trigger \"{}\": replay_auto_resume failed: {}", fire.trigger_name, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Title and body of a notify action. Besides the tab's variables, `%tab` is
+/// the tab's name, `%title` the program's title (OSC 0/2) and `%tabtitle`
+/// the name shown in the tab header.
+fn notification(
+    state: &AppState,
+    tab_id: &str,
+    pty_id: Option<&str>,
+    variables: &HashMap<String, String>,
+    action: &TriggerActionEntry,
+) -> (String, String) {
+    let (osc_title, cwd) = pty_id
+        .and_then(|id| state.terminal_registry.get(id))
+        .map(|terminal| {
+            let handle = terminal.lock();
+            (handle.title(), handle.blocks.cwd().map(str::to_string))
+        })
+        .unwrap_or_default();
+    let (name, custom_name, prompt_patterns) = {
+        let app_data = state.app_data.read();
+        let (name, custom_name) = app_data
+            .find_tab(tab_id)
+            .map(|(_, tab)| (tab.name.clone(), tab.custom_name))
+            .unwrap_or_else(|| ("Terminal".to_string(), false));
+        (name, custom_name, app_data.preferences.prompt_patterns.clone())
+    };
+
+    let tab_title = if custom_name {
+        let mut result = name.clone();
+        if let Some(title) = &osc_title {
+            result = result.replacen("%title", title, 1);
+            if result.contains("%dir") {
+                // Tabs without OSC 7 (SSH, mostly) only name the directory in their title
+                let dir = cwd.unwrap_or_else(|| dir_from_title(title, &prompt_patterns));
+                result = result.replacen("%dir", &dir, 1);
+            }
+        }
+        interpolate_variables(&result, variables, true)
+    } else {
+        osc_title.clone().unwrap_or_else(|| name.clone())
+    };
+
+    let mut variables = variables.clone();
+    variables.insert("title".to_string(), osc_title.unwrap_or_default());
+    variables.insert("tab".to_string(), name);
+    variables.insert("tabtitle".to_string(), tab_title);
+    let title = action.title.as_deref().filter(|t| !t.is_empty()).unwrap_or("%tabtitle");
+    let message = action.message.as_deref().unwrap_or_default();
+    (
+        interpolate_variables(title, &variables, false),
+        interpolate_variables(message, &variables, false),
+    )
+}
@@ -5,9 +5,18 @@ use std::sync::mpsc::Sender;
 use std::time::Instant;
 
 use super::persistence::app_data_slug;
+use super::hook_event_db::HookEventDb;
+use super::mcp_audit_db::McpAuditDb;
 use super::scrollback_db::ScrollbackDb;
-use super::workspace::AppData;
-use crate::terminal::handle::TerminalHandle;
+use super::workspace::{AppData, PortForward};
+use crate::claude_code::resources::ResourceSubscriptions;
+use crate::claude_code::tokens::McpTokens;
+use crate::claude_code::usage::UsageTracker;
+use crate::pty::clipboard::PendingClipboard;
+use crate::terminal::asciicast::PlaybackControl;
+use crate::terminal::palette::ThemePalette;
+use crate::terminal::registry::TerminalRegistry;
+use crate::terminal::triggers::TriggerEngine;
 
 pub enum PtyCommand {
     Write(Vec<u8>),
@@ -31,6 +40,14 @@ pub struct PtyStats {
     /// Millis since UNIX_EPOCH of the last PTY read. Used to detect an
     /// actively-drawing TUI so resizes can be coalesced (see resize_pty).
     pub last_read_ms: AtomicU64,
+    /// Output frames emitted, and how many of those repainted the whole screen.
+    pub frames: AtomicU64,
+    pub full_frames: AtomicU64,
+    /// Reads folded into a later frame by the frame-rate cap.
+    pub frames_coalesced: AtomicU64,
+    /// ANSI bytes sent in frames, and bytes saved versus full redraws.
+    pub frame_bytes: AtomicU64,
+    pub frame_bytes_saved: AtomicU64,
 }
 
 /// A resize waiting for the trailing debounce while the PTY is streaming.
@@ -67,11 +84,64 @@ pub struct SshTunnel {
     pub remote_port: u16,
     pub host_key: String,
     pub tab_ids: std::collections::HashSet<String>,
+    /// Cleaned ssh args and local port the tunnel was started with, for restarts
+    pub ssh_args: String,
+    pub local_port: u16,
+    /// The ssh client handed the forward to a ControlMaster and exited, so
+    /// `pid` says nothing about the forward's health
+    pub muxed: bool,
+    pub health: TunnelHealth,
 }
 
-/// Tracked Claude Code session (registered via hooks).
+/// What the tunnel supervisor knows about one tunnel (times are unix ms).
+#[derive(Default)]
+pub struct TunnelHealth {
+    /// When the current forward came up
+    pub up_since_ms: i64,
+    /// Set while the forward is down and waiting to be restarted
+    pub down_since_ms: Option<i64>,
+    pub last_check_ms: i64,
+    /// The last check couldn't fetch `/health`: the host has no curl, wget
+    /// or bash, so the forward is assumed to work
+    pub unverified: bool,
+    pub restarts: u32,
+    /// Failed checks and restart attempts over the tunnel's lifetime
+    pub failures: u32,
+    /// Failures since the forward was last up, driving the restart backoff
+    pub consecutive_failures: u32,
+    pub retry_at_ms: i64,
+    pub last_error: Option<String>,
+}
+
+/// A running user-defined port forward over one SSH destination, shared by
+/// every tab in a session there that wants it.
+pub struct ActivePortForward {
+    pub pid: u32,
+    /// Cleaned ssh args of the session (`user@host`, `-p 2222 user@host`)
+    pub host_key: String,
+    pub forward: PortForward,
+    pub tab_ids: std::collections::HashSet<String>,
+    pub state: PortForwardState,
+    pub error: Option<String>,
+    /// When the forward last came up (unix ms)
+    pub up_since_ms: i64,
+    /// The ssh client handed the forward to a ControlMaster and exited
+    pub muxed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortForwardState {
+    Starting,
+    Up,
+    Failed,
+}
+
+/// Tracked agent session (registered via hooks). Despite the name this covers
+/// every CLI with an `AgentAdapter`; `agent` says which one.
 pub struct ClaudeSessionInfo {
     pub tab_id: String,
+    pub agent: crate::claude_code::agents::AgentKind,
     pub cwd: Option<String>,
     pub state: ClaudeSessionState,
     /// Current tool being executed (set by PreToolUse, cleared by PostToolUse/Stop)
@@ -82,6 +152,19 @@ pub struct ClaudeSessionInfo {
     /// Used to recover affinity after SSE reconnects: if a session's
     /// connection_id is no longer in connection_tabs, it's orphaned.
     pub connection_id: Option<String>,
+    /// Subagents started and not yet stopped (SubagentStart/SubagentStop);
+    /// reset by Stop and UserPromptSubmit
+    pub subagents: u32,
+    /// Set by PostToolUseFailure, cleared by the next successful tool call or prompt
+    pub last_tool_failure: Option<ToolFailure>,
+}
+
+/// The most recent failed tool call in a Claude session.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolFailure {
+    pub tool_name: String,
+    pub error: Option<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
@@ -95,9 +178,13 @@ pub enum ClaudeSessionState {
 
 pub struct AppState {
     pub scrollback_db: ScrollbackDb,
+    // Every Claude Code hook event, for per-session timelines
+    pub hook_event_db: HookEventDb,
+    // Every MCP tool call with its caller, scope and outcome
+    pub mcp_audit_db: McpAuditDb,
     pub pty_registry: RwLock<HashMap<String, PtyHandle>>,
-    /// alacritty_terminal instances keyed by pty_id
-    pub terminal_registry: RwLock<HashMap<String, TerminalHandle>>,
+    /// alacritty_terminal instances keyed by pty_id, each behind its own lock
+    pub terminal_registry: TerminalRegistry,
     /// Maps tab_id → pty_id so we can auto-kill a previous PTY when a new one
     /// is spawned for the same tab (e.g. HMR remount, frontend crash recovery).
     pub tab_pty_map: RwLock<HashMap<String, String>>,
@@ -109,12 +196,23 @@ pub struct AppState {
     // Claude Code IDE integration
     pub claude_code_port: RwLock<Option<u16>>,
     pub claude_code_auth: RwLock<Option<String>>,
+    // Accepted MCP tokens: the local one (mirrored in claude_code_auth) plus one per SSH tunnel
+    pub mcp_tokens: parking_lot::Mutex<McpTokens>,
     pub claude_code_pending: RwLock<HashMap<String, tokio::sync::oneshot::Sender<serde_json::Value>>>,
     pub claude_code_connected: RwLock<bool>,
     pub claude_code_notify_tx: parking_lot::Mutex<Option<tokio::sync::mpsc::UnboundedSender<String>>>,
     pub claude_code_shutdown: parking_lot::Mutex<Option<tokio::sync::watch::Sender<bool>>>,
+    // MCP resources/subscribe state, flushed as notifications/resources/updated
+    pub resource_subscriptions: parking_lot::Mutex<ResourceSubscriptions>,
+    // Set while resource_subscriptions has subscribers; checked lock-free per PTY chunk
+    pub resources_watched: std::sync::Arc<std::sync::atomic::AtomicBool>,
     // SSH MCP tunnels: keyed by host_key (user@host)
     pub ssh_tunnels: RwLock<HashMap<String, SshTunnel>>,
+    pub tunnel_supervisor_running: std::sync::atomic::AtomicBool,
+    // User-defined SSH port forwards, keyed by host_key plus the ssh flag
+    pub port_forwards: RwLock<HashMap<String, ActivePortForward>>,
+    // Tabs currently in an SSH session: tab_id → cleaned ssh args
+    pub port_forward_sessions: RwLock<HashMap<String, String>>,
     // Remote file watchers (SSH stat polling): keyed by tab_id
     pub remote_file_watchers: RwLock<HashMap<String, RemoteFileWatch>>,
     pub remote_watcher_running: std::sync::atomic::AtomicBool,
@@ -127,32 +225,56 @@ pub struct AppState {
     pub claude_sessions: RwLock<HashMap<String, ClaudeSessionInfo>>,
     // Pending session IDs from SessionStart HTTP hooks awaiting initSession to assign a tab
     pub pending_hook_sessions: RwLock<Vec<(String, Option<String>, Instant)>>, // (session_id, cwd, timestamp)
+    // Token usage read from Claude session transcripts
+    pub claude_usage: parking_lot::Mutex<UsageTracker>,
+    // Trigger matching state, fed from PTY reader threads (keyed by tab_id inside)
+    pub trigger_engine: TriggerEngine,
+    // OSC 52 requests awaiting the user's answer (policy "ask"), keyed by request id
+    pub clipboard_requests: parking_lot::Mutex<HashMap<String, PendingClipboard>>,
+    // Active theme's terminal colors (pushed by the frontend) for OSC color queries
+    pub terminal_palette: RwLock<ThemePalette>,
+    // Running recording playbacks, keyed by playback id (the playback tab's id)
+    pub playbacks: parking_lot::Mutex<HashMap<String, std::sync::Arc<PlaybackControl>>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let db_path = dirs::data_dir()
+        let data_dir = dirs::data_dir()
             .expect("No data directory found")
-            .join(app_data_slug())
-            .join("aiterm-scrollback.db");
-        let scrollback_db = ScrollbackDb::open(db_path)
+            .join(app_data_slug());
+        let scrollback_db = ScrollbackDb::open(data_dir.join("aiterm-scrollback.db"))
             .expect("Failed to open scrollback database");
+        let hook_event_db = HookEventDb::open(data_dir.join("aiterm-hook-events.db"))
+            .expect("Failed to open hook event database");
+        let mcp_audit_db = McpAuditDb::open(data_dir.join("aiterm-mcp-audit.db"))
+            .expect("Failed to open MCP audit database");
+
+        let resource_subscriptions = ResourceSubscriptions::default();
+        let resources_watched = resource_subscriptions.watched();
 
         Self {
             scrollback_db,
+            hook_event_db,
+            mcp_audit_db,
             pty_registry: RwLock::new(HashMap::new()),
-            terminal_registry: RwLock::new(HashMap::new()),
+            terminal_registry: TerminalRegistry::new(),
             tab_pty_map: RwLock::new(HashMap::new()),
             app_data: RwLock::new(AppData::default()),
             file_watchers: RwLock::new(HashMap::new()),
             scp_uploads: RwLock::new(HashMap::new()),
             claude_code_port: RwLock::new(None),
             claude_code_auth: RwLock::new(None),
+            mcp_tokens: parking_lot::Mutex::new(McpTokens::default()),
             claude_code_pending: RwLock::new(HashMap::new()),
             claude_code_connected: RwLock::new(false),
             claude_code_notify_tx: parking_lot::Mutex::new(None),
             claude_code_shutdown: parking_lot::Mutex::new(None),
+            resource_subscriptions: parking_lot::Mutex::new(resource_subscriptions),
+            resources_watched,
             ssh_tunnels: RwLock::new(HashMap::new()),
+            tunnel_supervisor_running: std::sync::atomic::AtomicBool::new(false),
+            port_forwards: RwLock::new(HashMap::new()),
+            port_forward_sessions: RwLock::new(HashMap::new()),
             remote_file_watchers: RwLock::new(HashMap::new()),
             remote_watcher_running: std::sync::atomic::AtomicBool::new(false),
             pending_resizes: RwLock::new(HashMap::new()),
@@ -160,14 +282,19 @@ impl AppState {
             memory_samples: RwLock::new(Vec::new()),
             claude_sessions: RwLock::new(HashMap::new()),
             pending_hook_sessions: RwLock::new(Vec::new()),
+            claude_usage: parking_lot::Mutex::new(UsageTracker::default()),
+            trigger_engine: TriggerEngine::new(),
+            clipboard_requests: parking_lot::Mutex::new(HashMap::new()),
+            terminal_palette: RwLock::new(ThemePalette::default()),
+            playbacks: parking_lot::Mutex::new(HashMap::new()),
         }
     }
 
     /// Current alacritty grid size for a live PTY, if one exists.
     pub fn live_grid_size(&self, pty_id: &str) -> Option<(u16, u16)> {
         use alacritty_terminal::grid::Dimensions;
-        let registry = self.terminal_registry.read();
-        let handle = registry.get(pty_id)?;
+        let handle = self.terminal_registry.get(pty_id)?;
+        let handle = handle.lock();
         Some((handle.term.columns() as u16, handle.term.screen_lines() as u16))
     }
 }
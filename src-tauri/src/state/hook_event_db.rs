@@ -0,0 +1,395 @@
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::now_ms;
+
+/// Events older than this are dropped when the database is opened.
+const RETENTION_DAYS: i64 = 30;
+
+/// String values in `detail` longer than this are cut (e.g. a `Write` call's
+/// file contents); the timeline is for auditing, not replay.
+const MAX_DETAIL_STRING: usize = 2000;
+
+/// One Claude Code hook event as recorded by `hooks_handler`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HookEvent {
+    pub id: i64,
+    pub session_id: String,
+    pub tab_id: Option<String>,
+    /// Hook name: SessionStart, PreToolUse, Notification, ...
+    pub event: String,
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    /// Event-specific fields (tool_input, prompt, notification text, ...)
+    pub detail: Option<Value>,
+    /// Hook policy answer given for PreToolUse / PermissionRequest
+    pub decision: Option<String>,
+    /// Unix time in milliseconds
+    pub ts: i64,
+    /// ISO 8601 (UTC) rendering of `ts`
+    pub time: String,
+    /// Set on both halves of a PreToolUse → PostToolUse pair
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+}
+
+/// A hook event to record.
+pub struct NewHookEvent<'a> {
+    pub session_id: &'a str,
+    pub tab_id: Option<&'a str>,
+    pub event: &'a str,
+    pub tool_name: Option<&'a str>,
+    pub tool_use_id: Option<&'a str>,
+    pub detail: Option<Value>,
+    pub decision: Option<&'a str>,
+    /// When the hook arrived (unix ms) — a PreToolUse is recorded only after
+    /// its policy decision, which may wait minutes on the user.
+    pub ts: i64,
+}
+
+/// Which events `timeline` returns. A tab's timeline includes every event of
+/// the sessions that ran in it, even those recorded before the tab was known.
+#[derive(Debug, Default)]
+pub struct TimelineQuery {
+    pub tab_id: Option<String>,
+    pub session_id: Option<String>,
+    /// Only events at or after this unix time in milliseconds
+    pub since: Option<i64>,
+    /// Most recent events kept when more match
+    pub limit: usize,
+}
+
+pub struct HookEventDb {
+    conn: Mutex<Connection>,
+}
+
+impl HookEventDb {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create DB directory: {}", e))?;
+        }
+        let conn = Connection::open(&path).map_err(|e| format!("Failed to open hook event DB: {}", e))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;
+             CREATE TABLE IF NOT EXISTS hook_events (
+                 id INTEGER PRIMARY KEY,
+                 session_id TEXT NOT NULL,
+                 tab_id TEXT,
+                 event TEXT NOT NULL,
+                 tool_name TEXT,
+                 tool_use_id TEXT,
+                 detail TEXT,
+                 decision TEXT,
+                 ts INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS hook_events_session ON hook_events(session_id, ts);
+             CREATE INDEX IF NOT EXISTS hook_events_tab ON hook_events(tab_id, ts);"
+        ).map_err(|e| format!("Failed to initialize hook event DB: {}", e))?;
+
+        let cutoff = now_ms() - RETENTION_DAYS * 24 * 60 * 60 * 1000;
+        let _ = conn.execute("DELETE FROM hook_events WHERE ts < ?1", rusqlite::params![cutoff]);
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn record(&self, event: NewHookEvent) -> Result<(), String> {
+        let detail = event.detail.map(|mut d| {
+            clip_strings(&mut d, MAX_DETAIL_STRING);
+            d.to_string()
+        });
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO hook_events (session_id, tab_id, event, tool_name, tool_use_id, detail, decision, ts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                event.session_id,
+                event.tab_id,
+                event.event,
+                event.tool_name,
+                event.tool_use_id,
+                detail,
+                event.decision,
+                event.ts,
+            ],
+        ).map_err(|e| format!("Failed to record hook event: {}", e))?;
+        Ok(())
+    }
+
+    /// Matching events in chronological order, with tool call durations.
+    pub fn timeline(&self, query: &TimelineQuery) -> Result<Vec<HookEvent>, String> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, session_id, tab_id, event, tool_name, tool_use_id, detail, decision, ts,
+                        strftime('%Y-%m-%dT%H:%M:%fZ', ts / 1000.0, 'unixepoch')
+                 FROM hook_events
+                 WHERE (?1 IS NULL OR session_id = ?1)
+                   AND (?2 IS NULL OR tab_id = ?2
+                        OR session_id IN (SELECT session_id FROM hook_events WHERE tab_id = ?2))
+                   AND ts >= ?3
+                 ORDER BY ts DESC, id DESC
+                 LIMIT ?4",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![query.session_id, query.tab_id, query.since.unwrap_or(0), query.limit as i64],
+                |row| {
+                    let detail: Option<String> = row.get(6)?;
+                    Ok(HookEvent {
+                        id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        tab_id: row.get(2)?,
+                        event: row.get(3)?,
+                        tool_name: row.get(4)?,
+                        tool_use_id: row.get(5)?,
+                        detail: detail.and_then(|d| serde_json::from_str(&d).ok()),
+                        decision: row.get(7)?,
+                        ts: row.get(8)?,
+                        time: row.get(9)?,
+                        duration_ms: None,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to query hook events: {}", e))?;
+        let mut events: Vec<HookEvent> = rows
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to query hook events: {}", e))?;
+        events.reverse();
+        pair_tool_calls(&mut events);
+        pair_before_window(&conn, &mut events).map_err(|e| format!("Failed to query hook events: {}", e))?;
+        Ok(events)
+    }
+
+    /// Sessions with any event at or after `since` (unix ms), each with the
+    /// last tab it ran in.
+    pub fn sessions_since(&self, since: i64) -> Result<Vec<(String, Option<String>)>, String> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(
+                "SELECT session_id,
+                        (SELECT tab_id FROM hook_events t
+                         WHERE t.session_id = h.session_id AND t.tab_id IS NOT NULL
+                         ORDER BY ts DESC, id DESC LIMIT 1)
+                 FROM hook_events h
+                 WHERE ts >= ?1 AND session_id != ''
+                 GROUP BY session_id",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![since], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query hook sessions: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to query hook sessions: {}", e))
+    }
+}
+
+/// Set `duration_ms` on each PreToolUse → PostToolUse(Failure) pair, matched by
+/// `tool_use_id` when Claude sends one, else the session's oldest open call
+/// to the same tool. Denied calls never run, so they stay unpaired.
+fn pair_tool_calls(events: &mut [HookEvent]) {
+    let mut open: Vec<usize> = Vec::new();
+    for i in 0..events.len() {
+        match events[i].event.as_str() {
+            "PreToolUse" if events[i].decision.as_deref() != Some("deny") => open.push(i),
+            "PostToolUse" | "PostToolUseFailure" => {
+                let post = &events[i];
+                let found = open.iter().position(|&j| {
+                    let pre = &events[j];
+                    pre.session_id == post.session_id
+                        && match (&pre.tool_use_id, &post.tool_use_id) {
+                            (Some(a), Some(b)) => a == b,
+                            _ => pre.tool_name == post.tool_name,
+                        }
+                });
+                if let Some(pos) = found {
+                    let j = open.remove(pos);
+                    let duration = events[i].ts - events[j].ts;
+                    events[j].duration_ms = Some(duration);
+                    events[i].duration_ms = Some(duration);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Pair tool calls that started before the oldest event in `events` (cut off
+/// by the query's limit or `since`) with their PostToolUse(Failure) in it.
+/// Only the later half is in the window, so only it gets a duration.
+fn pair_before_window(conn: &Connection, events: &mut [HookEvent]) -> rusqlite::Result<()> {
+    let Some(first) = events.first() else {
+        return Ok(());
+    };
+    let (start_ts, start_id) = (first.ts, first.id);
+    let mut stmt = conn.prepare(
+        "SELECT id, ts FROM hook_events
+         WHERE session_id = ?1 AND event = 'PreToolUse'
+           AND (decision IS NULL OR decision != 'deny')
+           AND (tool_use_id = ?2 OR ((?2 IS NULL OR tool_use_id IS NULL) AND tool_name IS ?3))
+           AND (ts < ?4 OR (ts = ?4 AND id < ?5))
+         ORDER BY ts DESC, id DESC
+         LIMIT 16",
+    )?;
+    let mut used = HashSet::new();
+    // Latest first, so each takes the latest call it can belong to
+    for post in events.iter_mut().rev() {
+        if post.duration_ms.is_some() || !matches!(post.event.as_str(), "PostToolUse" | "PostToolUseFailure") {
+            continue;
+        }
+        let params = rusqlite::params![post.session_id, post.tool_use_id, post.tool_name, start_ts, start_id];
+        let candidates = stmt.query_map(params, |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        for candidate in candidates {
+            let (id, ts) = candidate?;
+            if used.insert(id) {
+                post.duration_ms = Some(post.ts - ts);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Cut long string values anywhere in `value` to `max` chars.
+pub(crate) fn clip_strings(value: &mut Value, max: usize) {
+    match value {
+        Value::String(s) if s.chars().count() > max => {
+            let cut: String = s.chars().take(max).collect();
+            *s = format!("{}… [{} chars]", cut, s.chars().count());
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| clip_strings(v, max)),
+        Value::Object(map) => map.values_mut().for_each(|v| clip_strings(v, max)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64, session: &str, name: &str, tool: &str, tool_use_id: Option<&str>, ts: i64) -> HookEvent {
+        HookEvent {
+            id,
+            session_id: session.into(),
+            tab_id: None,
+            event: name.into(),
+            tool_name: Some(tool.into()),
+            tool_use_id: tool_use_id.map(Into::into),
+            detail: None,
+            decision: None,
+            ts,
+            time: String::new(),
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn tool_calls_are_paired_by_id_then_tool_name() {
+        let mut events = vec![
+            event(1, "s1", "PreToolUse", "Bash", Some("a"), 100),
+            event(2, "s1", "PreToolUse", "Bash", Some("b"), 150),
+            event(3, "s2", "PreToolUse", "Read", None, 160),
+            event(4, "s1", "PostToolUse", "Bash", Some("b"), 400),
+            event(5, "s1", "PostToolUse", "Bash", Some("a"), 900),
+            event(6, "s2", "PostToolUseFailure", "Read", None, 170),
+        ];
+        events[2].decision = Some("allow".into());
+        pair_tool_calls(&mut events);
+        let durations: Vec<Option<i64>> = events.iter().map(|e| e.duration_ms).collect();
+        assert_eq!(durations, vec![Some(800), Some(250), Some(10), Some(250), Some(800), Some(10)]);
+
+        let mut denied = vec![
+            event(1, "s1", "PreToolUse", "Bash", None, 100),
+            event(2, "s1", "PreToolUse", "Bash", None, 200),
+            event(3, "s1", "PostToolUse", "Bash", None, 260),
+        ];
+        denied[0].decision = Some("deny".into());
+        pair_tool_calls(&mut denied);
+        assert_eq!(denied[0].duration_ms, None);
+        assert_eq!(denied[1].duration_ms, Some(60));
+    }
+
+    #[test]
+    fn records_and_queries_by_tab_or_session() {
+        let db = HookEventDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        let record = |session: &str, tab: Option<&str>, name: &str, detail: Option<Value>| {
+            db.record(NewHookEvent {
+                session_id: session,
+                tab_id: tab,
+                event: name,
+                tool_name: None,
+                tool_use_id: None,
+                detail,
+                decision: None,
+                ts: now_ms(),
+            })
+            .unwrap();
+        };
+        // SessionStart arrives before the tab is known
+        record("s1", None, "SessionStart", None);
+        record("s1", Some("t1"), "UserPromptSubmit", Some(serde_json::json!({ "prompt": "x".repeat(3000) })));
+        record("s2", Some("t2"), "Stop", None);
+
+        let tab = db.timeline(&TimelineQuery { tab_id: Some("t1".into()), limit: 100, ..Default::default() }).unwrap();
+        let names: Vec<&str> = tab.iter().map(|e| e.event.as_str()).collect();
+        assert_eq!(names, vec!["SessionStart", "UserPromptSubmit"]);
+        let prompt = tab[1].detail.as_ref().unwrap()["prompt"].as_str().unwrap();
+        assert!(prompt.ends_with("… [3000 chars]"));
+        assert!(tab[0].time.ends_with('Z'));
+
+        let session = db.timeline(&TimelineQuery { session_id: Some("s2".into()), limit: 100, ..Default::default() }).unwrap();
+        assert_eq!(session.len(), 1);
+        let latest = db.timeline(&TimelineQuery { limit: 1, ..Default::default() }).unwrap();
+        assert_eq!(latest[0].event, "Stop");
+
+        let mut sessions = db.sessions_since(0).unwrap();
+        sessions.sort();
+        assert_eq!(sessions, vec![("s1".into(), Some("t1".into())), ("s2".into(), Some("t2".into()))]);
+    }
+
+    #[test]
+    fn tool_calls_pair_across_the_query_window() {
+        let db = HookEventDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        let record = |name: &str, tool_use_id: &str, ts: i64| {
+            db.record(NewHookEvent {
+                session_id: "s1",
+                tab_id: Some("t1"),
+                event: name,
+                tool_name: Some("Bash"),
+                tool_use_id: Some(tool_use_id),
+                detail: None,
+                decision: None,
+                ts,
+            })
+            .unwrap();
+        };
+        record("PreToolUse", "a", 1_000);
+        // Recorded after the user answered the permission prompt, stamped on arrival
+        record("PostToolUse", "b", 9_000);
+        record("PreToolUse", "b", 2_000);
+        record("PostToolUse", "a", 10_000);
+
+        let all = db.timeline(&TimelineQuery { limit: 100, ..Default::default() }).unwrap();
+        let order: Vec<(&str, i64)> = all.iter().map(|e| (e.event.as_str(), e.ts)).collect();
+        assert_eq!(order, vec![("PreToolUse", 1_000), ("PreToolUse", 2_000), ("PostToolUse", 9_000), ("PostToolUse", 10_000)]);
+        assert_eq!(all[1].duration_ms, Some(7_000));
+
+        // Both calls started before the last two events
+        let recent = db.timeline(&TimelineQuery { limit: 2, ..Default::default() }).unwrap();
+        let durations: Vec<Option<i64>> = recent.iter().map(|e| e.duration_ms).collect();
+        assert_eq!(durations, vec![Some(7_000), Some(9_000)]);
+        let since = db.timeline(&TimelineQuery { since: Some(9_500), limit: 100, ..Default::default() }).unwrap();
+        assert_eq!(since[0].duration_ms, Some(9_000));
+    }
+}
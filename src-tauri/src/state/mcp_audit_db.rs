@@ -0,0 +1,198 @@
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::path::PathBuf;
+
+use super::now_ms;
+
+/// Calls older than this are dropped when the database is opened.
+const RETENTION_DAYS: i64 = 30;
+
+/// One MCP `tools/call` as recorded by the server.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct McpAuditEntry {
+    pub id: i64,
+    /// Unix time in milliseconds
+    pub ts: i64,
+    /// ISO 8601 (UTC) rendering of `ts`
+    pub time: String,
+    pub connection_id: String,
+    /// Tab the caller is bound to or claimed
+    pub tab_id: Option<String>,
+    /// SSH host the call came through, if remote
+    pub host: Option<String>,
+    pub tool: String,
+    /// Tab, pane or workspace the call acted on
+    pub target: Option<String>,
+    /// Fingerprint of the arguments, to spot repeated calls without storing them
+    pub args_digest: String,
+    /// MCP scope the call ran under
+    pub scope: Option<String>,
+    /// "ok", "error" or "denied"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+/// A call to record; `ts` is taken at insert time.
+pub struct NewMcpAuditEntry<'a> {
+    pub connection_id: &'a str,
+    pub tab_id: Option<&'a str>,
+    pub host: Option<&'a str>,
+    pub tool: &'a str,
+    pub arguments: &'a Value,
+    pub scope: Option<&'a str>,
+    pub status: &'a str,
+    pub error: Option<&'a str>,
+}
+
+pub struct McpAuditDb {
+    conn: Mutex<Connection>,
+}
+
+impl McpAuditDb {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create DB directory: {}", e))?;
+        }
+        let conn = Connection::open(&path).map_err(|e| format!("Failed to open MCP audit DB: {}", e))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;
+             CREATE TABLE IF NOT EXISTS tool_calls (
+                 id INTEGER PRIMARY KEY,
+                 ts INTEGER NOT NULL,
+                 connection_id TEXT NOT NULL,
+                 tab_id TEXT,
+                 host TEXT,
+                 tool TEXT NOT NULL,
+                 target TEXT,
+                 args_digest TEXT NOT NULL,
+                 scope TEXT,
+                 status TEXT NOT NULL,
+                 error TEXT
+             );
+             CREATE INDEX IF NOT EXISTS tool_calls_ts ON tool_calls(ts);
+             CREATE INDEX IF NOT EXISTS tool_calls_tab ON tool_calls(tab_id, ts);"
+        ).map_err(|e| format!("Failed to initialize MCP audit DB: {}", e))?;
+
+        let cutoff = now_ms() - RETENTION_DAYS * 24 * 60 * 60 * 1000;
+        let _ = conn.execute("DELETE FROM tool_calls WHERE ts < ?1", rusqlite::params![cutoff]);
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn record(&self, entry: NewMcpAuditEntry) -> Result<(), String> {
+        let target = ["targetTabId", "tabId", "paneId", "workspaceId"]
+            .iter()
+            .find_map(|key| entry.arguments.get(*key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()));
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO tool_calls (ts, connection_id, tab_id, host, tool, target, args_digest, scope, status, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                now_ms(),
+                entry.connection_id,
+                entry.tab_id,
+                entry.host,
+                entry.tool,
+                target,
+                args_digest(entry.arguments),
+                entry.scope,
+                entry.status,
+                entry.error,
+            ],
+        ).map_err(|e| format!("Failed to record MCP tool call: {}", e))?;
+        Ok(())
+    }
+
+    /// Most recent calls first, optionally only those at or after `since`
+    /// (unix ms) or made by `tab_id`.
+    pub fn recent(&self, tab_id: Option<&str>, since: Option<i64>, limit: usize) -> Result<Vec<McpAuditEntry>, String> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, ts, strftime('%Y-%m-%dT%H:%M:%fZ', ts / 1000.0, 'unixepoch'),
+                        connection_id, tab_id, host, tool, target, args_digest, scope, status, error
+                 FROM tool_calls
+                 WHERE (?1 IS NULL OR tab_id = ?1) AND ts >= ?2
+                 ORDER BY ts DESC, id DESC
+                 LIMIT ?3",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map(rusqlite::params![tab_id, since.unwrap_or(0), limit as i64], |row| {
+                Ok(McpAuditEntry {
+                    id: row.get(0)?,
+                    ts: row.get(1)?,
+                    time: row.get(2)?,
+                    connection_id: row.get(3)?,
+                    tab_id: row.get(4)?,
+                    host: row.get(5)?,
+                    tool: row.get(6)?,
+                    target: row.get(7)?,
+                    args_digest: row.get(8)?,
+                    scope: row.get(9)?,
+                    status: row.get(10)?,
+                    error: row.get(11)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query MCP tool calls: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to query MCP tool calls: {}", e))
+    }
+}
+
+/// 64-bit FNV-1a of the arguments' JSON, as hex. Not cryptographic: it only
+/// needs to tell calls apart in the log without keeping their contents.
+fn args_digest(arguments: &Value) -> String {
+    let hash = arguments.to_string().bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_calls_with_digest_and_target() {
+        let db = McpAuditDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        let args = serde_json::json!({ "tabId": "t1", "targetTabId": "t2", "command": "make" });
+        let record = |tab: Option<&str>, tool: &str, status: &str, error: Option<&str>| {
+            db.record(NewMcpAuditEntry {
+                connection_id: "c1",
+                tab_id: tab,
+                host: Some("build-box"),
+                tool,
+                arguments: &args,
+                scope: Some("workspace"),
+                status,
+                error,
+            })
+            .unwrap();
+        };
+        record(Some("t1"), "runInTab", "ok", None);
+        record(Some("t3"), "setPreference", "denied", Some("not allowed"));
+
+        let all = db.recent(None, None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].tool, "setPreference");
+        assert_eq!(all[0].error.as_deref(), Some("not allowed"));
+        assert_eq!(all[1].target.as_deref(), Some("t2"));
+        assert_eq!(all[1].args_digest, args_digest(&args));
+        assert_eq!(all[1].args_digest.len(), 16);
+        assert!(all[1].time.ends_with('Z'));
+
+        let tab = db.recent(Some("t1"), None, 10).unwrap();
+        assert_eq!(tab.len(), 1);
+        assert_eq!(tab[0].status, "ok");
+        assert_ne!(args_digest(&serde_json::json!({})), tab[0].args_digest);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod app_state;
+pub mod hook_event_db;
+pub mod mcp_audit_db;
 pub mod persistence;
 pub mod scrollback_db;
 pub mod workspace;
@@ -7,3 +9,11 @@ pub use app_state::{AppState, FileWatcherHandle, PendingResize, PtyCommand, PtyH
 pub use persistence::{load_state, save_state};
 pub use scrollback_db::ScrollbackDb;
 pub use workspace::{AgentBridge, AppData, DiffContext, EditorFileInfo, Pane, Preferences, Tab, WindowData, WindowGeometry, Workspace};
+
+/// Current unix time in milliseconds.
+pub fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
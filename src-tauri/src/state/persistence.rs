@@ -1,8 +1,9 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
-use super::workspace::{AppData, Layout, SplitDirection, SplitNode, WindowData};
+use super::workspace::{AppData, Layout, SplitDirection, SplitNode, Tab, WindowData};
 
 /// Tracks whether the last load_state() successfully parsed a real state file.
 /// When false, save_state() will NOT overwrite the backup — preserving the last
@@ -380,6 +381,70 @@ pub fn migrate_app_data(data: &mut AppData) {
             }
         }
     }
+
+    migrate_agent_bridges(data);
+}
+
+fn all_tabs_mut(data: &mut AppData) -> impl Iterator<Item = &mut Tab> {
+    data.windows
+        .iter_mut()
+        .flat_map(|w| w.workspaces.iter_mut())
+        .flat_map(|ws| ws.panes.iter_mut().flat_map(|p| p.tabs.iter_mut()).chain(ws.archived_tabs.iter_mut()))
+}
+
+/// Turn pairwise Agent Bridges (from before groups) into two-member groups. Both
+/// sides of a pair get the same group id, and each side takes its own session id
+/// from its partner's record of it. A one-sided pairing becomes a group of one,
+/// which the frontend clears as an orphan.
+fn migrate_agent_bridges(data: &mut AppData) {
+    let mut partners: HashMap<String, (String, Option<String>)> = HashMap::new();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for tab in all_tabs_mut(data) {
+        names.insert(tab.id.clone(), tab.name.clone());
+        if let Some(bridge) = tab.agent_bridge.as_ref().filter(|b| b.group_id.is_empty()) {
+            if let Some(partner) = &bridge.partner_tab_id {
+                partners.insert(tab.id.clone(), (partner.clone(), bridge.partner_session_id.clone()));
+            }
+        }
+    }
+    if partners.is_empty() {
+        return;
+    }
+
+    let mut group_ids: HashMap<(String, String), String> = HashMap::new();
+    for tab in all_tabs_mut(data) {
+        let Some((partner, _)) = partners.get(&tab.id) else {
+            continue;
+        };
+        let key = if tab.id < *partner {
+            (tab.id.clone(), partner.clone())
+        } else {
+            (partner.clone(), tab.id.clone())
+        };
+        let group_id = group_ids.entry(key.clone()).or_insert_with(|| uuid::Uuid::new_v4().to_string()).clone();
+        let group_name = match (names.get(&key.0), names.get(&key.1)) {
+            (Some(a), Some(b)) => format!("{} + {}", a, b),
+            _ => "Agent Bridge".to_string(),
+        };
+        // Only the partner's record is trusted, and only if it points back here
+        let session_id = partners
+            .get(partner)
+            .filter(|(back, _)| *back == tab.id)
+            .and_then(|(_, sid)| sid.clone());
+        let Some(bridge) = tab.agent_bridge.as_mut() else {
+            continue;
+        };
+        bridge.group_id = group_id;
+        bridge.group_name = group_name;
+        // The old role said how the tab joined (caller/fork/peer), not what it does
+        if matches!(bridge.role.as_str(), "caller" | "fork" | "peer") {
+            bridge.role.clear();
+        }
+        bridge.session_id = session_id;
+        bridge.partner_tab_id = None;
+        bridge.partner_session_id = None;
+        log::info!("Migration: moved Agent Bridge of tab {} into group '{}'", tab.id, bridge.group_name);
+    }
 }
 
 pub fn save_state(data: &AppData) -> Result<(), String> {
@@ -529,3 +594,48 @@ pub fn migrate_scrollback_to_db(data: &mut AppData, db: &super::scrollback_db::S
         log::info!("Migration: moved {} tab scrollbacks from JSON to SQLite", migrated);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::workspace::Workspace;
+
+    #[test]
+    fn pairwise_agent_bridges_become_groups() {
+        let legacy = |partner: &str, partner_session: &str| {
+            serde_json::from_value(serde_json::json!({
+                "partner_tab_id": partner,
+                "partner_label": "x",
+                "partner_session_id": partner_session,
+                "role": "caller",
+                "turn": 3,
+            }))
+            .unwrap()
+        };
+        let mut ws = Workspace::new("W".into());
+        let mut tabs = vec![Tab::new("api".into()), Tab::new("web".into()), Tab::new("lonely".into())];
+        let (a, b) = (tabs[0].id.clone(), tabs[1].id.clone());
+        tabs[0].agent_bridge = Some(legacy(&b, "sess-b"));
+        tabs[1].agent_bridge = Some(legacy(&a, "sess-a"));
+        tabs[2].agent_bridge = Some(legacy("gone", "sess-x"));
+        ws.panes[0].tabs = tabs;
+        let mut win = WindowData::new("main".into());
+        win.workspaces.push(ws);
+        let mut data = AppData::default();
+        data.windows.push(win);
+
+        migrate_agent_bridges(&mut data);
+        let tabs = &data.windows[0].workspaces[0].panes[0].tabs;
+        let bridges: Vec<_> = tabs.iter().map(|t| t.agent_bridge.clone().unwrap()).collect();
+        assert_eq!(bridges[0].group_id, bridges[1].group_id);
+        assert_ne!(bridges[0].group_id, bridges[2].group_id);
+        assert!(!bridges[2].group_id.is_empty());
+        assert_eq!(bridges[0].session_id.as_deref(), Some("sess-a"));
+        assert_eq!(bridges[1].session_id.as_deref(), Some("sess-b"));
+        assert_eq!(bridges[2].session_id, None);
+        assert_eq!((bridges[0].role.as_str(), bridges[0].turn), ("", 3));
+        assert!(bridges.iter().all(|b| b.partner_tab_id.is_none()));
+        let saved = serde_json::to_value(&bridges[0]).unwrap();
+        assert!(saved.get("partner_tab_id").is_none());
+    }
+}
@@ -1,8 +1,19 @@
 use parking_lot::Mutex;
-use rusqlite::Connection;
+use rusqlite::{Connection, Transaction};
 use std::collections::HashSet;
 use std::path::PathBuf;
 
+use crate::terminal::ansi::strip_ansi;
+
+/// One line of saved scrollback matching a full-text search.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScrollbackMatch {
+    pub tab_id: String,
+    /// 0-based line within the tab's saved scrollback.
+    pub line: usize,
+    pub snippet: String,
+}
+
 pub struct ScrollbackDb {
     conn: Mutex<Connection>,
 }
@@ -14,7 +25,10 @@ impl ScrollbackDb {
         }
 
         let conn = Connection::open(&path).map_err(|e| format!("Failed to open scrollback DB: {}", e))?;
+        Self::init(conn)
+    }
 
+    fn init(mut conn: Connection) -> Result<Self, String> {
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;
@@ -30,6 +44,52 @@ impl ScrollbackDb {
         let _ = conn.execute("ALTER TABLE scrollback ADD COLUMN cols INTEGER", []);
         let _ = conn.execute("ALTER TABLE scrollback ADD COLUMN rows INTEGER", []);
 
+        // Plain-text index of every saved buffer, one row per non-blank line.
+        // Trigram tokens make MATCH a case-insensitive substring search, so
+        // paths and identifiers are found from any fragment. Created and
+        // back-filled in one transaction, keyed on the FTS table: a failed
+        // attempt leaves nothing behind and is retried on the next open.
+        let tx = conn.transaction().map_err(|e| format!("Failed to begin tx: {}", e))?;
+        let indexed = tx
+            .query_row("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'scrollback_fts'", [], |_| Ok(()))
+            .is_ok();
+        if !indexed {
+            tx.execute_batch(
+                "DROP TABLE IF EXISTS scrollback_lines;
+                 CREATE TABLE scrollback_lines (
+                     id INTEGER PRIMARY KEY,
+                     tab_id TEXT NOT NULL,
+                     line INTEGER NOT NULL,
+                     text TEXT NOT NULL
+                 );
+                 CREATE INDEX scrollback_lines_tab ON scrollback_lines(tab_id);
+                 CREATE VIRTUAL TABLE scrollback_fts USING fts5(
+                     text, content='scrollback_lines', content_rowid='id', tokenize='trigram'
+                 );
+                 CREATE TRIGGER scrollback_lines_ai AFTER INSERT ON scrollback_lines BEGIN
+                     INSERT INTO scrollback_fts(rowid, text) VALUES (new.id, new.text);
+                 END;
+                 CREATE TRIGGER scrollback_lines_ad AFTER DELETE ON scrollback_lines BEGIN
+                     INSERT INTO scrollback_fts(scrollback_fts, rowid, text) VALUES ('delete', old.id, old.text);
+                 END;"
+            ).map_err(|e| format!("Failed to initialize scrollback search index: {}", e))?;
+
+            // Index buffers saved before the index existed
+            let saved: Vec<(String, String)> = {
+                let mut stmt = tx
+                    .prepare("SELECT tab_id, data FROM scrollback")
+                    .map_err(|e| format!("Failed to prepare query: {}", e))?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(|e| format!("Failed to query: {}", e))?;
+                rows.filter_map(|r| r.ok()).collect()
+            };
+            for (tab_id, data) in &saved {
+                index_lines(&tx, tab_id, data)?;
+            }
+        }
+        tx.commit().map_err(|e| format!("Failed to commit: {}", e))?;
+
         Ok(Self {
             conn: Mutex::new(conn),
         })
@@ -39,12 +99,21 @@ impl ScrollbackDb {
     /// pass None when no live terminal exists — the previously saved size is
     /// then preserved rather than nulled.
     pub fn save(&self, tab_id: &str, data: &str, size: Option<(u16, u16)>) -> Result<(), String> {
-        let conn = self.conn.lock();
+        let mut conn = self.conn.lock();
         let (cols, rows) = match size {
             Some((c, r)) => (Some(c), Some(r)),
             None => (None, None),
         };
-        conn.execute(
+        let tx = conn.transaction().map_err(|e| format!("Failed to begin tx: {}", e))?;
+        // Auto-save rewrites unchanged buffers; skip re-indexing those
+        let unchanged = tx
+            .query_row(
+                "SELECT data = ?2 FROM scrollback WHERE tab_id = ?1",
+                rusqlite::params![tab_id, data],
+                |row| row.get::<_, bool>(0),
+            )
+            .unwrap_or(false);
+        tx.execute(
             "INSERT INTO scrollback (tab_id, data, updated_at, cols, rows)
              VALUES (?1, ?2, datetime('now'), ?3, ?4)
              ON CONFLICT(tab_id) DO UPDATE SET
@@ -54,9 +123,53 @@ impl ScrollbackDb {
                  rows = COALESCE(excluded.rows, scrollback.rows)",
             rusqlite::params![tab_id, data, cols, rows],
         ).map_err(|e| format!("Failed to save scrollback: {}", e))?;
+        if !unchanged {
+            tx.execute("DELETE FROM scrollback_lines WHERE tab_id = ?1", rusqlite::params![tab_id])
+                .map_err(|e| format!("Failed to clear search index: {}", e))?;
+            index_lines(&tx, tab_id, data)?;
+        }
+        tx.commit().map_err(|e| format!("Failed to commit: {}", e))?;
         Ok(())
     }
 
+    /// Search every saved buffer for `query` (a literal, case-insensitive
+    /// substring of at least 3 characters). Most recently saved tabs first,
+    /// up to `limit` hits in tabs `keep` accepts.
+    pub fn search(&self, query: &str, limit: usize, keep: impl Fn(&str) -> bool) -> Result<Vec<ScrollbackMatch>, String> {
+        let query = query.trim();
+        if query.chars().count() < 3 {
+            return Err("Search needs at least 3 characters".to_string());
+        }
+        // Quote as a single FTS5 string so operators and punctuation are literal
+        let phrase = format!("\"{}\"", query.replace('"', "\"\""));
+
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(
+                "SELECT l.tab_id, l.line, snippet(scrollback_fts, 0, '', '', '…', 48)
+                 FROM scrollback_fts
+                 JOIN scrollback_lines l ON l.id = scrollback_fts.rowid
+                 JOIN scrollback s ON s.tab_id = l.tab_id
+                 WHERE scrollback_fts MATCH ?1
+                 ORDER BY s.updated_at DESC, l.tab_id, l.line",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        // Rows are filtered as they're read, so the limit counts kept hits only
+        let rows = stmt
+            .query_map(rusqlite::params![phrase], |row| {
+                Ok(ScrollbackMatch {
+                    tab_id: row.get(0)?,
+                    line: row.get::<_, i64>(1)? as usize,
+                    snippet: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Failed to search scrollback: {}", e))?;
+        rows.filter(|row| row.as_ref().map_or(true, |m| keep(&m.tab_id)))
+            .take(limit)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to search scrollback: {}", e))
+    }
+
     /// Terminal size (cols, rows) recorded with the last scrollback save.
     pub fn saved_size(&self, tab_id: &str) -> Result<Option<(u16, u16)>, String> {
         let conn = self.conn.lock();
@@ -96,11 +209,10 @@ impl ScrollbackDb {
     }
 
     pub fn delete(&self, tab_id: &str) -> Result<(), String> {
-        let conn = self.conn.lock();
-        conn.execute(
-            "DELETE FROM scrollback WHERE tab_id = ?1",
-            rusqlite::params![tab_id],
-        ).map_err(|e| format!("Failed to delete scrollback: {}", e))?;
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction().map_err(|e| format!("Failed to begin tx: {}", e))?;
+        delete_tab(&tx, tab_id)?;
+        tx.commit().map_err(|e| format!("Failed to commit: {}", e))?;
         Ok(())
     }
 
@@ -111,10 +223,7 @@ impl ScrollbackDb {
         let mut conn = self.conn.lock();
         let tx = conn.transaction().map_err(|e| format!("Failed to begin tx: {}", e))?;
         for id in tab_ids {
-            tx.execute(
-                "DELETE FROM scrollback WHERE tab_id = ?1",
-                rusqlite::params![id],
-            ).map_err(|e| format!("Failed to delete scrollback: {}", e))?;
+            delete_tab(&tx, id)?;
         }
         tx.commit().map_err(|e| format!("Failed to commit: {}", e))?;
         Ok(())
@@ -147,3 +256,89 @@ impl ScrollbackDb {
         Ok(orphans.len())
     }
 }
+
+fn delete_tab(tx: &Transaction, tab_id: &str) -> Result<(), String> {
+    tx.execute(
+        "DELETE FROM scrollback WHERE tab_id = ?1",
+        rusqlite::params![tab_id],
+    ).map_err(|e| format!("Failed to delete scrollback: {}", e))?;
+    tx.execute(
+        "DELETE FROM scrollback_lines WHERE tab_id = ?1",
+        rusqlite::params![tab_id],
+    ).map_err(|e| format!("Failed to delete scrollback: {}", e))?;
+    Ok(())
+}
+
+/// Add the ANSI-stripped lines of a saved buffer to the search index.
+fn index_lines(tx: &Transaction, tab_id: &str, data: &str) -> Result<(), String> {
+    let plain = strip_ansi(data);
+    let mut stmt = tx
+        .prepare_cached("INSERT INTO scrollback_lines (tab_id, line, text) VALUES (?1, ?2, ?3)")
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    for (i, line) in plain.lines().enumerate() {
+        let text = line.trim_end_matches('\r').trim_end();
+        if text.trim().is_empty() {
+            continue;
+        }
+        stmt.execute(rusqlite::params![tab_id, i as i64, text])
+            .map_err(|e| format!("Failed to index scrollback: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(matches: &[ScrollbackMatch]) -> Vec<(&str, usize)> {
+        matches.iter().map(|m| (m.tab_id.as_str(), m.line)).collect()
+    }
+
+    #[test]
+    fn indexes_saved_buffers_for_search() {
+        let db = ScrollbackDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        db.save("t1", "$ cargo build\r\n\x1b[31merror\x1b[0m: src/Main.rs\r\n\r\n$ ls", Some((80, 24))).unwrap();
+        db.save("t2", "nothing to see", None).unwrap();
+
+        // Case-insensitive substring, ANSI stripped, blank lines keep numbering
+        let hits = db.search("main.RS", 10, |_| true).unwrap();
+        assert_eq!(tabs(&hits), vec![("t1", 1)]);
+        assert_eq!(hits[0].snippet, "error: src/Main.rs");
+        // FTS operators and quotes are literal
+        assert!(db.search("\"build OR see\"", 10, |_| true).unwrap().is_empty());
+        assert!(db.search("ab", 10, |_| true).is_err());
+
+        // Re-saving replaces the tab's lines; deleting drops them
+        db.save("t1", "fresh output", None).unwrap();
+        assert!(db.search("Main.rs", 10, |_| true).unwrap().is_empty());
+        assert_eq!(tabs(&db.search("output", 10, |_| true).unwrap()), vec![("t1", 0)]);
+        assert_eq!(db.saved_size("t1").unwrap(), Some((80, 24)));
+        db.delete("t1").unwrap();
+        assert!(db.search("output", 10, |_| true).unwrap().is_empty());
+        assert_eq!(tabs(&db.search("see", 10, |_| true).unwrap()), vec![("t2", 0)]);
+
+        // Hits in tabs that are gone don't use up the limit
+        db.save("gone", "see one\nsee two", None).unwrap();
+        assert_eq!(tabs(&db.search("see", 1, |tab| tab != "gone").unwrap()), vec![("t2", 0)]);
+    }
+
+    #[test]
+    fn migration_indexes_buffers_saved_before_the_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE scrollback (tab_id TEXT PRIMARY KEY, data TEXT NOT NULL, updated_at TEXT NOT NULL);
+             INSERT INTO scrollback VALUES ('old', 'line one\nline two', datetime('now'));
+             -- left behind by an interrupted migration: lines without the FTS table
+             CREATE TABLE scrollback_lines (id INTEGER PRIMARY KEY, tab_id TEXT NOT NULL, line INTEGER NOT NULL, text TEXT NOT NULL);
+             INSERT INTO scrollback_lines (tab_id, line, text) VALUES ('old', 0, 'line one');"
+        ).unwrap();
+        let db = ScrollbackDb::init(conn).unwrap();
+        assert_eq!(tabs(&db.search("line", 10, |_| true).unwrap()), vec![("old", 0), ("old", 1)]);
+
+        // Opening an indexed database again doesn't index twice
+        let conn = db.conn.into_inner();
+        let db = ScrollbackDb::init(conn).unwrap();
+        assert_eq!(db.search("two", 10, |_| true).unwrap().len(), 1);
+        assert_eq!(db.search("line", 10, |_| true).unwrap().len(), 2);
+    }
+}
@@ -165,6 +165,44 @@ pub enum TabType {
     Terminal,
     Editor,
     Diff,
+    /// Read-only replay of an asciicast recording.
+    Playback,
+    /// Read-only view of a Claude Code session transcript.
+    Transcript,
+}
+
+/// The Claude session a Transcript tab shows and where its JSONL lives.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptSource {
+    pub session_id: String,
+    /// Directory the session ran in; picks its `~/.claude/projects` folder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Set for sessions that ran over SSH; the transcript is fetched by SCP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_command: Option<String>,
+}
+
+impl TranscriptSource {
+    /// The session a tab last ran, from its resume state (`%claudeSessionId`).
+    pub fn from_tab(tab: &Tab) -> Option<Self> {
+        let session_id = tab.trigger_variables.get("claudeSessionId").filter(|id| !id.is_empty())?;
+        let ssh_command = tab
+            .auto_resume_ssh_command
+            .clone()
+            .or_else(|| tab.restore_ssh_command.clone())
+            .filter(|c| !c.is_empty());
+        let cwd = if ssh_command.is_some() {
+            tab.auto_resume_remote_cwd.clone().or_else(|| tab.restore_remote_cwd.clone())
+        } else {
+            tab.auto_resume_cwd.clone().or_else(|| tab.last_cwd.clone()).or_else(|| tab.restore_cwd.clone())
+        };
+        Some(Self {
+            session_id: session_id.clone(),
+            cwd,
+            ssh_command,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,26 +227,34 @@ pub struct EditorFileInfo {
     pub language: Option<String>,
 }
 
-/// Agent Bridge: a durable pairing between two Claude tabs that can message each
-/// other. Persisted on both tabs (symmetric) so the bridge survives app restart and
-/// is rebuilt by the frontend once both tabs + their resumed sessions are back.
+/// Agent Bridge membership: the tab belongs to a named group of Claude tabs that
+/// can message each other (broadcast or direct). Persisted on every member so the
+/// group survives app restart; the frontend rebuilds it from the tabs sharing a
+/// `group_id` once they and their resumed sessions are back.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentBridge {
-    /// The tab this one is bridged to.
-    pub partner_tab_id: String,
-    /// Human-readable label of the partner (for the agent's own awareness).
-    pub partner_label: String,
-    /// Partner's last-known Claude session id — refreshed when the partner
-    /// re-initializes after a resume; used to detect/re-bind a drifted session.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub partner_session_id: Option<String>,
-    /// "caller" (initiated the bridge), "fork" (the forked peer), or "peer"
-    /// (an existing tab connected without forking).
+    /// Shared by every member of the group.
+    #[serde(default)]
+    pub group_id: String,
+    #[serde(default)]
+    pub group_name: String,
+    /// This member's role in the group ("frontend", "qa", …), shown to the others
+    /// and usable as a direct-message address.
     #[serde(default)]
     pub role: String,
+    /// This tab's last-known Claude session id — refreshed when it re-initializes
+    /// after a resume; lets the others detect a drifted session and re-bind.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
     /// Conversation turn counter (messages this tab has sent).
     #[serde(default)]
     pub turn: u32,
+    /// Pairwise bridge written before groups existed; consumed by
+    /// `migrate_agent_bridges` and never written back.
+    #[serde(default, skip_serializing)]
+    pub partner_tab_id: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub partner_session_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -294,10 +340,26 @@ pub struct Tab {
     pub editor_file: Option<EditorFileInfo>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub diff_context: Option<DiffContext>,
-    /// Agent Bridge pairing (persisted both sides) — see AgentBridge. The `agent_link`
-    /// alias migrates state written before the link→bridge rename.
+    /// Agent Bridge group membership — see AgentBridge. The `agent_link` alias
+    /// migrates state written before the link→bridge rename.
     #[serde(default, alias = "agent_link", skip_serializing_if = "Option::is_none")]
     pub agent_bridge: Option<AgentBridge>,
+    /// OSC 52 clipboard permission for this tab (both directions). Overrides
+    /// the workspace and global preferences, not a host's; None = inherit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc52_policy: Option<ClipboardPolicy>,
+    /// Record the terminal to an asciicast file whenever its PTY spawns.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_record: bool,
+    /// Recording shown by a Playback tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playback_file: Option<String>,
+    /// Session shown by a Transcript tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<TranscriptSource>,
+    /// SSH port forwards started whenever this tab is in an SSH session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForward>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -338,6 +400,13 @@ pub struct Workspace {
     /// Whether this workspace is suspended (PTYs killed, resources freed).
     #[serde(default)]
     pub suspended: bool,
+    /// SSH port forwards started for every SSH session in this workspace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port_forwards: Vec<PortForward>,
+    /// OSC 52 clipboard permission for this workspace's tabs (both
+    /// directions). None = inherit the global preferences.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc52_policy: Option<ClipboardPolicy>,
     // Old field kept for migration deserialization only
     #[serde(default, alias = "window_sizes", skip_serializing)]
     #[allow(dead_code)]
@@ -460,6 +529,29 @@ impl AppData {
         }
         ids
     }
+
+    /// Find a live (non-archived) tab by ID along with its owning workspace.
+    pub fn find_tab(&self, tab_id: &str) -> Option<(&Workspace, &Tab)> {
+        self.windows
+            .iter()
+            .flat_map(|win| &win.workspaces)
+            .find_map(|ws| {
+                ws.panes
+                    .iter()
+                    .flat_map(|p| &p.tabs)
+                    .find(|t| t.id == tab_id)
+                    .map(|t| (ws, t))
+            })
+    }
+
+    pub fn find_tab_mut(&mut self, tab_id: &str) -> Option<&mut Tab> {
+        self.windows
+            .iter_mut()
+            .flat_map(|win| &mut win.workspaces)
+            .flat_map(|ws| &mut ws.panes)
+            .flat_map(|p| &mut p.tabs)
+            .find(|t| t.id == tab_id)
+    }
 }
 
 fn default_sidebar_width() -> u32 {
@@ -572,6 +664,49 @@ fn default_notification_volume() -> u32 {
     50
 }
 
+fn default_osc52_write_policy() -> ClipboardPolicy {
+    ClipboardPolicy::Always
+}
+
+fn default_osc52_read_policy() -> ClipboardPolicy {
+    ClipboardPolicy::Ask
+}
+
+fn default_osc52_max_bytes() -> u32 {
+    1024 * 1024
+}
+
+fn default_hook_prompt_timeout() -> u32 {
+    60
+}
+
+fn default_mcp_remote_scope() -> McpScope {
+    McpScope::Workspace
+}
+
+fn default_mcp_token_rotation_hours() -> u32 {
+    24
+}
+
+/// List prices (USD per million tokens) for current Claude models. More
+/// specific ids come first since the first contained id wins.
+fn default_claude_model_prices() -> Vec<ModelPrice> {
+    let price = |model: &str, input: f64, output: f64, cache_write: f64, cache_read: f64| ModelPrice {
+        model: model.to_string(),
+        input,
+        output,
+        cache_write,
+        cache_read,
+    };
+    vec![
+        price("opus-4-5", 5.0, 25.0, 6.25, 0.5),
+        price("opus", 15.0, 75.0, 18.75, 1.5),
+        price("sonnet", 3.0, 15.0, 3.75, 0.3),
+        price("haiku-4-5", 1.0, 5.0, 1.25, 0.1),
+        price("haiku", 0.8, 4.0, 1.0, 0.08),
+    ]
+}
+
 /// Deserialize notification_sound: accepts string or bool (migration from old format).
 fn deserialize_notification_sound<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
@@ -667,6 +802,17 @@ pub struct Trigger {
     pub user_modified: bool,
 }
 
+/// A reusable prompt served to Claude over MCP (`prompts/list`), shown as a
+/// slash command. `%name` references are interpolated on `prompts/get`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub template: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum CursorStyle {
@@ -676,6 +822,139 @@ pub enum CursorStyle {
     Bar,
 }
 
+/// How maiTerm answers a Claude `PreToolUse` / `PermissionRequest` hook.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookDecision {
+    Allow,
+    Deny,
+    /// Defer to Claude's own permission prompt in the terminal
+    Ask,
+    /// Ask in the maiTerm UI; falls back to `Ask` on timeout
+    Prompt,
+}
+
+/// A policy rule for Claude tool calls, matched by tool name and an optional
+/// regex over the call's subject (Bash command, file path, or JSON input).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookPolicyRule {
+    pub id: String,
+    /// Tool name, `*` wildcards allowed (e.g. `mcp__*`); empty matches any tool
+    #[serde(default)]
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    pub decision: HookDecision,
+    /// Workspace IDs the rule is limited to; empty applies everywhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+    /// Reason reported back to Claude
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// How much of maiTerm an MCP client may drive. Where several scopes apply
+/// (SSH host and workspace) the caller gets only what all of them allow.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpScope {
+    /// Read-only tools, on tabs in the caller's own workspace
+    ReadOnly,
+    /// Any tool, but only on the caller's own tab
+    OwnTab,
+    /// Any tool on tabs in the caller's own workspace; no app-wide changes
+    Workspace,
+    /// Every tool on every tab, including preferences and backups
+    Full,
+}
+
+/// Token prices for Claude models whose id contains `model`, in USD per
+/// million tokens. Used to cost session usage read from transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelPrice {
+    pub model: String,
+    pub input: f64,
+    pub output: f64,
+    /// Cache writes (`cache_creation_input_tokens`)
+    #[serde(default)]
+    pub cache_write: f64,
+    /// Cache hits (`cache_read_input_tokens`)
+    #[serde(default)]
+    pub cache_read: f64,
+}
+
+/// Which side of an SSH session a port forward listens on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PortForwardKind {
+    /// `-L`: a local port reaching `target` from the remote host
+    Local,
+    /// `-R`: a remote port reaching `target` from this machine
+    Remote,
+    /// `-D`: a local SOCKS proxy out of the remote host
+    Dynamic,
+}
+
+/// A user-defined SSH port forward, attached to a tab or workspace and
+/// brought up over its SSH sessions (see commands/port_forward.rs).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PortForward {
+    pub id: String,
+    pub kind: PortForwardKind,
+    /// Local port for -L/-D, remote port for -R
+    pub listen_port: u16,
+    /// `host:port` connections are sent to; empty for -D
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub target: String,
+    /// Only start on SSH sessions to this host; None = any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl PortForward {
+    /// The ssh option and its argument, e.g. `("-L", "5432:db:5432")`.
+    pub fn ssh_flag(&self) -> (&'static str, String) {
+        match self.kind {
+            PortForwardKind::Local => ("-L", format!("{}:{}", self.listen_port, self.target)),
+            PortForwardKind::Remote => ("-R", format!("{}:{}", self.listen_port, self.target)),
+            PortForwardKind::Dynamic => ("-D", self.listen_port.to_string()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.listen_port == 0 {
+            return Err("Listen port must be between 1 and 65535".to_string());
+        }
+        if self.kind == PortForwardKind::Dynamic {
+            return Ok(());
+        }
+        let (host, port) = self
+            .target
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Target must be host:port, got \"{}\"", self.target))?;
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            return Err(format!("Invalid target host \"{}\"", host));
+        }
+        match port.parse::<u16>() {
+            Ok(p) if p > 0 => Ok(()),
+            _ => Err(format!("Invalid target port \"{}\"", port)),
+        }
+    }
+}
+
+/// Permission for programs in a terminal to use the clipboard via OSC 52.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardPolicy {
+    Always,
+    Ask,
+    Never,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preferences {
     /// UI font size (non-terminal elements)
@@ -838,6 +1117,50 @@ pub struct Preferences {
     /// Quick Open: show gitignored files by default
     #[serde(default)]
     pub quick_open_show_ignored: bool,
+
+    /// OSC 52 writes (program → local clipboard), e.g. vim/tmux yanks over SSH
+    #[serde(default = "default_osc52_write_policy")]
+    pub osc52_write_policy: ClipboardPolicy,
+    /// OSC 52 reads (program queries the local clipboard). Asks by default —
+    /// otherwise any remote host could silently read whatever you copied.
+    #[serde(default = "default_osc52_read_policy")]
+    pub osc52_read_policy: ClipboardPolicy,
+    /// Per-host overrides for both directions, keyed by SSH destination host.
+    /// Take precedence over tab and workspace policies.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub osc52_host_policies: HashMap<String, ClipboardPolicy>,
+    /// Largest OSC 52 payload (decoded bytes) accepted in either direction
+    #[serde(default = "default_osc52_max_bytes")]
+    pub osc52_max_bytes: u32,
+    /// MCP prompt templates offered to every connected Claude session
+    #[serde(default)]
+    pub prompt_templates: Vec<PromptTemplate>,
+    /// Allow/deny/ask rules applied to Claude tool calls via hooks
+    #[serde(default)]
+    pub hook_policy_rules: Vec<HookPolicyRule>,
+    /// Seconds to wait for an answer to a "prompt" rule before deferring to Claude
+    #[serde(default = "default_hook_prompt_timeout")]
+    pub hook_prompt_timeout: u32,
+    /// Price table for Claude token usage; the first entry whose `model` is
+    /// contained in a session's model id applies
+    #[serde(default = "default_claude_model_prices")]
+    pub claude_model_prices: Vec<ModelPrice>,
+    /// Scope of MCP clients reached through an SSH tunnel (local clients get `Full`)
+    #[serde(default = "default_mcp_remote_scope")]
+    pub mcp_remote_scope: McpScope,
+    /// Per-host overrides of `mcp_remote_scope`, keyed by SSH destination host
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub mcp_host_scopes: HashMap<String, McpScope>,
+    /// Scope of MCP clients running in a workspace's tabs, keyed by workspace ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub mcp_workspace_scopes: HashMap<String, McpScope>,
+    /// Replace SSH hosts' MCP auth tokens older than this many hours (0 = never)
+    #[serde(default = "default_mcp_token_rotation_hours")]
+    pub mcp_token_rotation_hours: u32,
+    /// Same for the local token. Off by default: a local session keeps the
+    /// token it started with, and may run for days.
+    #[serde(default)]
+    pub mcp_local_token_rotation_hours: u32,
 }
 
 impl Default for Preferences {
@@ -906,6 +1229,19 @@ impl Default for Preferences {
             auto_check_updates: true,
             quick_open_show_hidden: false,
             quick_open_show_ignored: false,
+            osc52_write_policy: default_osc52_write_policy(),
+            osc52_read_policy: default_osc52_read_policy(),
+            osc52_host_policies: HashMap::new(),
+            osc52_max_bytes: default_osc52_max_bytes(),
+            prompt_templates: Vec::new(),
+            hook_policy_rules: Vec::new(),
+            hook_prompt_timeout: default_hook_prompt_timeout(),
+            claude_model_prices: default_claude_model_prices(),
+            mcp_remote_scope: default_mcp_remote_scope(),
+            mcp_host_scopes: HashMap::new(),
+            mcp_workspace_scopes: HashMap::new(),
+            mcp_token_rotation_hours: default_mcp_token_rotation_hours(),
+            mcp_local_token_rotation_hours: 0,
         }
     }
 }
@@ -943,6 +1279,11 @@ impl Tab {
             diff_context: None,
             import_highlight: false,
             agent_bridge: None,
+            osc52_policy: None,
+            auto_record: false,
+            playback_file: None,
+            transcript: None,
+            port_forwards: Vec::new(),
         }
     }
 
@@ -978,6 +1319,11 @@ impl Tab {
             diff_context: None,
             import_highlight: false,
             agent_bridge: None,
+            osc52_policy: None,
+            auto_record: false,
+            playback_file: None,
+            transcript: None,
+            port_forwards: Vec::new(),
         }
     }
 
@@ -1013,6 +1359,91 @@ impl Tab {
             diff_context: Some(diff_context),
             import_highlight: false,
             agent_bridge: None,
+            osc52_policy: None,
+            auto_record: false,
+            playback_file: None,
+            transcript: None,
+            port_forwards: Vec::new(),
+        }
+    }
+
+    pub fn new_playback(name: String, path: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            pty_id: None,
+            scrollback: None,
+            custom_name: true,
+            restore_cwd: None,
+            restore_ssh_command: None,
+            restore_remote_cwd: None,
+            auto_resume_cwd: None,
+            auto_resume_ssh_command: None,
+            auto_resume_remote_cwd: None,
+            auto_resume_command: None,
+            auto_resume_remembered_command: None,
+            auto_resume_pinned: false,
+            auto_resume_enabled: true,
+            notes: None,
+            notes_mode: None,
+            notes_open: false,
+            composer_open: None,
+            composer_draft: None,
+            trigger_variables: HashMap::new(),
+            archived_name: None,
+            archived_at: None,
+            suspended_at: None,
+            tab_type: TabType::Playback,
+            editor_file: None,
+            last_cwd: None,
+            diff_context: None,
+            import_highlight: false,
+            agent_bridge: None,
+            osc52_policy: None,
+            auto_record: false,
+            playback_file: Some(path),
+            transcript: None,
+            port_forwards: Vec::new(),
+        }
+    }
+
+    pub fn new_transcript(name: String, source: TranscriptSource) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            pty_id: None,
+            scrollback: None,
+            custom_name: true,
+            restore_cwd: None,
+            restore_ssh_command: None,
+            restore_remote_cwd: None,
+            auto_resume_cwd: None,
+            auto_resume_ssh_command: None,
+            auto_resume_remote_cwd: None,
+            auto_resume_command: None,
+            auto_resume_remembered_command: None,
+            auto_resume_pinned: false,
+            auto_resume_enabled: true,
+            notes: None,
+            notes_mode: None,
+            notes_open: false,
+            composer_open: None,
+            composer_draft: None,
+            trigger_variables: HashMap::new(),
+            archived_name: None,
+            archived_at: None,
+            suspended_at: None,
+            tab_type: TabType::Transcript,
+            editor_file: None,
+            last_cwd: None,
+            diff_context: None,
+            import_highlight: false,
+            agent_bridge: None,
+            osc52_policy: None,
+            auto_record: false,
+            playback_file: None,
+            transcript: Some(source),
+            port_forwards: Vec::new(),
         }
     }
 }
@@ -1044,6 +1475,8 @@ impl Workspace {
             archived_tabs: Vec::new(),
             import_highlight: false,
             suspended: false,
+            port_forwards: Vec::new(),
+            osc52_policy: None,
             pane_sizes: None,
         }
     }
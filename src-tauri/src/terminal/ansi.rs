@@ -0,0 +1,41 @@
+/// Strip ANSI escape sequences (CSI, OSC, charset designations) and C0
+/// control codes from terminal text. Tab, newline, and carriage return are
+/// kept so callers can decide how to treat line structure.
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            match chars.next() {
+                Some('[') => {
+                    // CSI — parameter/intermediate bytes, then one final byte (@ through ~)
+                    for ch in chars.by_ref() {
+                        if ('@'..='~').contains(&ch) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    // OSC — skip until BEL or ST
+                    while let Some(ch) = chars.next() {
+                        if ch == '\x07' {
+                            break;
+                        }
+                        if ch == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                Some('(') | Some(')') => {
+                    // Charset designation (ESC ( B) — one more byte
+                    chars.next();
+                }
+                _ => {}
+            }
+        } else if c >= ' ' || matches!(c, '\t' | '\n' | '\x0b' | '\x0c' | '\r') {
+            plain.push(c);
+        }
+    }
+    plain
+}
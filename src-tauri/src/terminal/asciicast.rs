@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::event::VoidListener;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vte::ansi::Processor;
+
+use super::handle::{create_headless_terminal, TermDimensions};
+use super::render::{self, TerminalFrame};
+
+/// Buffered events are flushed at least this often, so a crash loses at
+/// most this much of a recording.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Playback emits frames no faster than the live terminal does.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// First line of an asciicast v2 file.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Header {
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Pauses longer than this are shortened to it on playback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_time_limit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// Writes a terminal's output to an asciicast v2 file as it arrives.
+pub struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    started: Instant,
+    last_flush: Instant,
+    /// Start of a UTF-8 sequence split across reads, held until the rest
+    /// arrives — event data must be valid UTF-8.
+    partial: Vec<u8>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, cols: u16, rows: u16, title: Option<String>) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create recording directory: {}", e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("Failed to create recording: {}", e))?;
+        let mut env = HashMap::new();
+        env.insert("TERM".to_string(), "xterm-256color".to_string());
+        if let Ok(shell) = std::env::var("SHELL") {
+            env.insert("SHELL".to_string(), shell);
+        }
+        let header = Header {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs()),
+            idle_time_limit: None,
+            title,
+            env,
+        };
+        let mut recorder = Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+            started: Instant::now(),
+            last_flush: Instant::now(),
+            partial: Vec::new(),
+        };
+        let line = serde_json::to_string(&header).map_err(|e| e.to_string())?;
+        recorder.write_line(&line)?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record raw PTY output.
+    pub fn output(&mut self, data: &[u8]) -> Result<(), String> {
+        self.partial.extend_from_slice(data);
+        let complete = self.partial.len() - incomplete_tail(&self.partial);
+        if complete == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.partial[..complete]).into_owned();
+        self.partial.drain(..complete);
+        self.event("o", &text)
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), String> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+
+    /// Flush everything recorded and close the file.
+    pub fn finish(mut self) -> Result<PathBuf, String> {
+        if !self.partial.is_empty() {
+            let text = String::from_utf8_lossy(&std::mem::take(&mut self.partial)).into_owned();
+            self.event("o", &text)?;
+        }
+        self.out.flush().map_err(|e| format!("Failed to write recording: {}", e))?;
+        Ok(self.path)
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> Result<(), String> {
+        let time = (self.started.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        let line = serde_json::to_string(&(time, code, data)).map_err(|e| e.to_string())?;
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.out, "{}", line).map_err(|e| format!("Failed to write recording: {}", e))?;
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.out.flush().map_err(|e| format!("Failed to write recording: {}", e))?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+/// Length of an unfinished UTF-8 sequence at the end of `bytes`.
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    for i in 1..=bytes.len().min(3) {
+        let b = bytes[bytes.len() - i];
+        if b & 0xC0 == 0x80 {
+            continue; // continuation byte — look further back
+        }
+        let needed = match b {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if needed > i { i } else { 0 };
+    }
+    0
+}
+
+pub struct Event {
+    /// Seconds since the start of the recording.
+    pub time: f64,
+    /// "o" output, "r" resize, "i"/"m" input and markers (ignored on playback).
+    pub code: String,
+    pub data: String,
+}
+
+pub struct Recording {
+    pub header: Header,
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read recording: {}", e))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let (_, first) = lines.next().ok_or("Recording is empty")?;
+        let header: Header =
+            serde_json::from_str(first).map_err(|e| format!("Invalid asciicast header: {}", e))?;
+        if header.version != 2 {
+            return Err(format!("Unsupported asciicast version {}", header.version));
+        }
+        let events = lines
+            .map(|(i, line)| {
+                let (time, code, data): (f64, String, String) = serde_json::from_str(line)
+                    .map_err(|e| format!("Invalid asciicast event on line {}: {}", i + 1, e))?;
+                Ok(Event { time, code, data })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { header, events })
+    }
+
+    /// Playback length in seconds, with the idle limit applied.
+    pub fn duration(&self) -> f64 {
+        let mut prev = 0.0;
+        self.events.iter().fold(0.0, |total, event| {
+            let gap = self.idle_gap(event.time - prev);
+            prev = event.time;
+            total + gap
+        })
+    }
+
+    fn idle_gap(&self, gap: f64) -> f64 {
+        let gap = gap.max(0.0);
+        match self.header.idle_time_limit {
+            Some(limit) if limit > 0.0 => gap.min(limit),
+            _ => gap,
+        }
+    }
+}
+
+/// Shared between a playback thread and the commands controlling it.
+pub struct PlaybackControl {
+    pub paused: AtomicBool,
+    pub stopped: AtomicBool,
+    /// Playback rate as f64 bits.
+    speed: AtomicU64,
+}
+
+impl PlaybackControl {
+    pub fn new(speed: f64) -> Self {
+        let control = Self {
+            paused: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            speed: AtomicU64::new(0),
+        };
+        control.set_speed(speed);
+        control
+    }
+
+    pub fn speed(&self) -> f64 {
+        f64::from_bits(self.speed.load(Ordering::Relaxed))
+    }
+
+    /// Non-positive or non-finite rates fall back to real time.
+    pub fn set_speed(&self, speed: f64) {
+        let speed = if speed.is_finite() && speed > 0.0 { speed } else { 1.0 };
+        self.speed.store(speed.to_bits(), Ordering::Relaxed);
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct PlaybackFrame {
+    #[serde(flatten)]
+    pub frame: TerminalFrame,
+    pub cols: u16,
+    pub rows: u16,
+    /// Playback position in seconds.
+    pub time: f64,
+    pub finished: bool,
+}
+
+/// Replay a recording through a headless terminal in real time (scaled by
+/// the control's speed), emitting full viewport frames. Returns early when
+/// stopped.
+pub fn play(recording: &Recording, control: &PlaybackControl, mut emit: impl FnMut(PlaybackFrame)) {
+    let (mut cols, mut rows) = (recording.header.width, recording.header.height);
+    let mut term = create_headless_terminal(cols, rows, 0);
+    let mut processor: Processor = Processor::default();
+
+    let mut due = 0.0;
+    let mut position = 0.0;
+    let mut prev = 0.0;
+    let mut dirty = true;
+    let mut last_emit: Option<Instant> = None;
+    let mut tick = Instant::now();
+
+    for event in &recording.events {
+        due += recording.idle_gap(event.time - prev);
+        prev = event.time;
+
+        while position < due {
+            if control.stopped.load(Ordering::Relaxed) {
+                return;
+            }
+            if dirty && last_emit.is_none_or(|t| t.elapsed() >= FRAME_INTERVAL) {
+                emit(playback_frame(&term, cols, rows, position, false));
+                dirty = false;
+                last_emit = Some(Instant::now());
+            }
+            let speed = control.speed();
+            let wait = Duration::from_secs_f64((due - position) / speed).min(FRAME_INTERVAL);
+            std::thread::sleep(wait);
+            let now = Instant::now();
+            if !control.paused.load(Ordering::Relaxed) {
+                position += (now - tick).as_secs_f64() * speed;
+            }
+            tick = now;
+        }
+
+        match event.code.as_str() {
+            "o" => {
+                processor.advance(&mut term, event.data.as_bytes());
+                dirty = true;
+            }
+            "r" => {
+                let size = event.data.split_once('x').and_then(|(c, r)| Some((c.parse().ok()?, r.parse().ok()?)));
+                if let Some((c, r)) = size {
+                    (cols, rows) = (c, r);
+                    term.resize(TermDimensions { cols: c as usize, rows: r as usize });
+                    dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !control.stopped.load(Ordering::Relaxed) {
+        emit(playback_frame(&term, cols, rows, due, true));
+    }
+}
+
+fn playback_frame(term: &Term<VoidListener>, cols: u16, rows: u16, time: f64, finished: bool) -> PlaybackFrame {
+    PlaybackFrame { frame: render::render_viewport(term, None), cols, rows, time, finished }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_split_utf8_and_resizes() {
+        let path = std::env::temp_dir().join(format!("aiterm-cast-{}.cast", uuid::Uuid::new_v4()));
+        let mut recorder = Recorder::create(&path, 80, 24, Some("demo".to_string())).unwrap();
+        let bytes = "héllo → ok\r\n".as_bytes();
+        recorder.output(&bytes[..2]).unwrap();
+        recorder.output(&bytes[2..8]).unwrap();
+        recorder.resize(100, 30).unwrap();
+        recorder.output(&bytes[8..]).unwrap();
+        recorder.finish().unwrap();
+
+        let recording = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((recording.header.width, recording.header.height), (80, 24));
+        assert_eq!(recording.header.title.as_deref(), Some("demo"));
+        let events: Vec<(&str, &str)> =
+            recording.events.iter().map(|e| (e.code.as_str(), e.data.as_str())).collect();
+        assert_eq!(events, vec![("o", "h"), ("o", "éllo "), ("r", "100x30"), ("o", "→ ok\r\n")]);
+        assert!(recording.events.windows(2).all(|w| w[0].time <= w[1].time));
+    }
+
+    #[test]
+    fn plays_back_to_the_final_screen() {
+        let cast = concat!(
+            "{\"version\": 2, \"width\": 20, \"height\": 3, \"idle_time_limit\": 0.01}\n",
+            "[0.5, \"o\", \"one\\r\\n\"]\n",
+            "[30.0, \"o\", \"\\u001b[31mtwo\\u001b[0m\"]\n",
+            "[30.1, \"r\", \"30x4\"]\n",
+            "[30.1, \"i\", \"ignored\"]\n",
+        );
+        let recording = Recording::parse(cast).unwrap();
+        assert!((recording.duration() - 0.03).abs() < 1e-9);
+
+        let mut frames = Vec::new();
+        play(&recording, &PlaybackControl::new(1.0), |f| frames.push(f));
+        let last = frames.last().unwrap();
+        assert!(last.finished);
+        assert_eq!((last.cols, last.rows), (30, 4));
+        let screen = String::from_utf8_lossy(&last.frame.ansi).into_owned();
+        assert!(screen.contains("one") && screen.contains("two"));
+
+        let stopped = PlaybackControl::new(0.0);
+        assert_eq!(stopped.speed(), 1.0);
+        stopped.stopped.store(true, Ordering::Relaxed);
+        let mut count = 0;
+        play(&recording, &stopped, |_| count += 1);
+        assert_eq!(count, 0);
+        assert!(Recording::parse("{\"version\": 1, \"width\": 1, \"height\": 1}").is_err());
+    }
+}
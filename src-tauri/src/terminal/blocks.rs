@@ -0,0 +1,592 @@
+use std::collections::VecDeque;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vte::ansi::Processor;
+
+/// Oldest blocks are dropped past this many, even if still in scrollback.
+const MAX_BLOCKS: usize = 5000;
+
+/// Sequences that empty the primary grid's history (ED 3, RIS) or switch
+/// between the primary and alternate screens. `advance` feeds output in
+/// pieces that start at these, so their effect on the history is seen alone.
+const HISTORY_CLEARS: &[&[u8]] = &[b"\x1b[3J", b"\x1bc"];
+const SCREEN_SWITCHES: &[&[u8]] = &[b"\x1b[?1049", b"\x1b[?1047", b"\x1b[?47"];
+
+/// History limit while output is processed: more than one piece can push,
+/// with room for alacritty's `limit + screen lines` arithmetic.
+const LIFTED_HISTORY: usize = u32::MAX as usize;
+
+/// One command's span in the terminal, assembled from OSC 133 marks.
+///
+/// Lines are absolute: counted from the first line the terminal ever had,
+/// so they stay valid as scrollback rotates (see `BlockTracker::evicted`).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CommandBlock {
+    pub id: u64,
+    /// Line where the prompt started (133;A).
+    pub prompt_line: u64,
+    /// Command text as it appeared on screen.
+    pub command: Option<String>,
+    /// First output line (133;C, or our shells' preexec 133;B).
+    pub output_start: Option<u64>,
+    /// Line after the last output line (133;D). None while running.
+    pub output_end: Option<u64>,
+    /// Millis since UNIX_EPOCH when the command started / finished.
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub exit_code: Option<i32>,
+    /// Last OSC 7 directory reported before the prompt.
+    pub cwd: Option<String>,
+    /// Where the user's input begins: an explicit 133;B, else the cursor
+    /// after the prompt was drawn.
+    #[serde(skip)]
+    input_start: Option<(u64, usize)>,
+    #[serde(skip)]
+    explicit_input: bool,
+}
+
+impl CommandBlock {
+    /// Last line belonging to the block (for pruning).
+    fn last_line(&self) -> u64 {
+        match (self.output_start, self.output_end) {
+            (Some(start), Some(end)) => end.saturating_sub(1).max(start),
+            (Some(start), None) => start,
+            _ => self.prompt_line,
+        }
+    }
+}
+
+/// Per-terminal command history built from OSC 133 prompt/command/output marks.
+#[derive(Default)]
+pub struct BlockTracker {
+    blocks: VecDeque<CommandBlock>,
+    next_id: u64,
+    /// Lines that have fallen off the top of scrollback. Absolute line =
+    /// evicted + history_size + viewport line.
+    evicted: u64,
+    scrollback_limit: usize,
+    /// The primary grid's history size when it was last active, for a reset
+    /// that arrives while the alternate screen is up.
+    primary_history: usize,
+    cwd: Option<String>,
+    /// PTY bytes written when the current prompt started — the prompt end
+    /// is tracked until the user types.
+    prompt_input_mark: Option<u64>,
+}
+
+impl BlockTracker {
+    pub fn new(scrollback_limit: usize) -> Self {
+        Self { scrollback_limit, ..Default::default() }
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = &CommandBlock> {
+        self.blocks.iter()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&CommandBlock> {
+        self.blocks.iter().find(|b| b.id == id)
+    }
+
+    pub fn set_cwd(&mut self, cwd: String) {
+        self.cwd = Some(cwd);
+    }
+
+    /// Last directory reported by OSC 7.
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Feed output to the terminal, counting lines pushed out of full
+    /// scrollback so absolute line numbers survive the rotation. It's counted
+    /// even with no blocks, since trigger scanning also relies on absolute
+    /// lines.
+    pub fn advance<T: EventListener>(&mut self, term: &mut Term<T>, processor: &mut Processor, data: &[u8]) {
+        let mut start = 0;
+        for cut in (1..data.len()).filter(|&i| starts_history_change(&data[i..])) {
+            self.advance_piece(term, processor, &data[start..cut]);
+            start = cut;
+        }
+        self.advance_piece(term, processor, &data[start..]);
+    }
+
+    /// Process one piece of output, with a history clear or screen switch
+    /// only at its start. The primary grid's history limit is lifted while
+    /// the piece is processed, so no line falls off the top unseen, then
+    /// trimmed back by `settle`, which counts what it drops.
+    fn advance_piece<T: EventListener>(&mut self, term: &mut Term<T>, processor: &mut Processor, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let was_alt = term.mode().contains(TermMode::ALT_SCREEN);
+        let clears = HISTORY_CLEARS.iter().any(|seq| data.starts_with(seq));
+        // A clear resets display_offset: hold the view off the bottom so
+        // it shows whether the sequence took effect.
+        let probe = !was_alt && clears && self.primary_history > 0;
+        let pinned = probe && term.grid().display_offset() == 0;
+        if !was_alt {
+            // Left lifted while the alternate screen is up, for output that
+            // returns to the primary screen mid-piece
+            term.grid_mut().update_history(LIFTED_HISTORY);
+        }
+        if pinned {
+            term.grid_mut().scroll_display(Scroll::Delta(1));
+        }
+
+        processor.advance(term, data);
+
+        let is_alt = term.mode().contains(TermMode::ALT_SCREEN);
+        let cleared = if was_alt {
+            // RIS on the alternate screen resets the primary one too
+            !is_alt && data.starts_with(b"\x1bc")
+        } else {
+            probe && !is_alt && term.grid().display_offset() == 0
+        };
+        if cleared {
+            self.evict(self.primary_history as u64);
+        }
+        if pinned && !cleared && !is_alt {
+            term.grid_mut().scroll_display(Scroll::Bottom);
+        }
+        if !is_alt {
+            self.settle(term);
+        }
+    }
+
+    /// Trim the primary grid's history back to the scrollback limit,
+    /// counting the lines that fall off.
+    fn settle<T: EventListener>(&mut self, term: &mut Term<T>) {
+        let history = term.grid().history_size();
+        let overflow = history.saturating_sub(self.scrollback_limit);
+        term.grid_mut().update_history(self.scrollback_limit);
+        self.primary_history = history - overflow;
+        self.evict(overflow as u64);
+    }
+
+    fn evict(&mut self, lines: u64) {
+        if lines > 0 {
+            self.evicted += lines;
+            let first = self.evicted;
+            self.blocks.retain(|b| b.last_line() >= first);
+        }
+    }
+
+    /// Resize the terminal. Lines keep their numbers when only the height
+    /// changes; a width change reflows wrapped lines, so blocks are moved to
+    /// where their lines ended up. The primary grid can't be read while the
+    /// alternate screen is up, so blocks are dropped then instead.
+    pub fn resize<T: EventListener, S: Dimensions>(&mut self, term: &mut Term<T>, size: S) {
+        let reflow = size.columns() != term.columns();
+        let alt = term.mode().contains(TermMode::ALT_SCREEN);
+        let old_lines = term.screen_lines();
+        let before = (reflow && !alt && !self.blocks.is_empty()).then(|| LogicalLines::read(term, self));
+        if !alt {
+            term.grid_mut().update_history(LIFTED_HISTORY);
+        }
+
+        term.resize(size);
+
+        if reflow && alt {
+            self.forget(self.primary_history + old_lines);
+        }
+        if !alt {
+            self.settle(term);
+        }
+        let Some(before) = before else { return };
+        let after = LogicalLines::read(term, self);
+        let map = |line: u64| after.place(before.locate(line)).0;
+        for block in &mut self.blocks {
+            block.prompt_line = map(block.prompt_line);
+            block.output_start = block.output_start.map(map);
+            block.output_end = block.output_end.map(map);
+            block.input_start = block.input_start.map(|(line, column)| after.place(before.locate_column(line, column)));
+        }
+        let first = self.evicted;
+        self.blocks.retain(|b| b.last_line() >= first);
+    }
+
+    /// Forget every block before the buffer is wiped (Cmd+K). Lines already
+    /// handed out stay unique: numbering continues past the old screen.
+    pub fn clear<T: EventListener>(&mut self, term: &Term<T>) {
+        self.forget(term.grid().history_size() + term.screen_lines());
+    }
+
+    fn forget(&mut self, lines: usize) {
+        self.evicted += lines as u64;
+        self.blocks.clear();
+        self.prompt_input_mark = None;
+    }
+
+    /// Apply an OSC 133 mark at the terminal's current cursor position.
+    pub fn mark<T: EventListener>(&mut self, term: &Term<T>, cmd: char, exit_code: Option<i32>, now_ms: u64, bytes_written: u64) {
+        // Marks inside full-screen apps don't describe the shell's buffer
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let cursor = term.grid().cursor.point;
+        let line = self.absolute(term, cursor.line);
+
+        match cmd {
+            'A' => {
+                self.finish(line, cursor.column.0, None, now_ms);
+                self.next_id += 1;
+                self.blocks.push_back(CommandBlock {
+                    id: self.next_id,
+                    prompt_line: line,
+                    command: None,
+                    output_start: None,
+                    output_end: None,
+                    started_at: None,
+                    finished_at: None,
+                    exit_code: None,
+                    cwd: self.cwd.clone(),
+                    input_start: None,
+                    explicit_input: false,
+                });
+                if self.blocks.len() > MAX_BLOCKS {
+                    self.blocks.pop_front();
+                }
+                self.prompt_input_mark = Some(bytes_written);
+            }
+            'B' => {
+                // Our own shell hooks send B from preexec, after Enter — the
+                // cursor is already at the start of a fresh line. A
+                // FinalTerm-style B sits right after the prompt, mid-line.
+                let after_enter = cursor.column.0 == 0
+                    && match self.pending() {
+                        Some(block) => line > block.prompt_line,
+                        None => true,
+                    };
+                if after_enter {
+                    self.start_output(term, line, cursor.column.0, now_ms);
+                } else if let Some(block) = self.pending_mut() {
+                    block.input_start = Some((line, cursor.column.0));
+                    block.explicit_input = true;
+                    self.prompt_input_mark = None;
+                }
+            }
+            'C' => self.start_output(term, line, cursor.column.0, now_ms),
+            'D' => self.finish(line, cursor.column.0, exit_code, now_ms),
+            _ => {}
+        }
+    }
+
+    /// Called after each chunk: until the user types, the cursor marks the
+    /// end of the prompt (prompts are often drawn in a separate write from
+    /// the A mark).
+    pub fn end_of_chunk<T: EventListener>(&mut self, term: &Term<T>, bytes_written: u64) {
+        if self.prompt_input_mark != Some(bytes_written) || term.mode().contains(TermMode::ALT_SCREEN) {
+            self.prompt_input_mark = None;
+            return;
+        }
+        let cursor = term.grid().cursor.point;
+        let line = self.absolute(term, cursor.line);
+        if let Some(block) = self.pending_mut() {
+            if !block.explicit_input {
+                block.input_start = Some((line, cursor.column.0));
+            }
+        }
+    }
+
+    /// Viewport line (negative = scrollback) of an absolute line, if still
+    /// in the buffer.
+    pub fn to_line<T: EventListener>(&self, term: &Term<T>, absolute: u64) -> Option<Line> {
+        let history = term.grid().history_size() as u64;
+        let top = self.evicted;
+        if absolute < top {
+            return None;
+        }
+        let line = absolute as i64 - (top + history) as i64;
+        (line < term.screen_lines() as i64).then_some(Line(line as i32))
+    }
+
+    /// Output text of a block (whatever of it is still in the buffer).
+    pub fn output_text<T: EventListener>(&self, term: &Term<T>, block: &CommandBlock) -> Option<String> {
+        let start = block.output_start?;
+        let end = match block.output_end {
+            Some(end) => end,
+            // Still running — everything up to the cursor line
+            None => self.cursor_line(term) + 1,
+        };
+        self.text_between(term, start, end)
+    }
+
+    /// Text of absolute lines `start..end` (whatever of it is still in the
+    /// buffer).
+    pub fn text_between<T: EventListener>(&self, term: &Term<T>, start: u64, end: u64) -> Option<String> {
+        if end <= start {
+            return Some(String::new());
+        }
+        let first = self.to_line(term, start.max(self.evicted))?;
+        let last = self.to_line(term, end - 1)?;
+        Some(term.bounds_to_string(Point::new(first, Column(0)), Point::new(last, term.last_column())))
+    }
+
+    /// Absolute line the cursor is on.
+    pub fn cursor_line<T: EventListener>(&self, term: &Term<T>) -> u64 {
+        self.absolute(term, term.grid().cursor.point.line)
+    }
+
+    /// `display_offset` that scrolls `absolute` to the top of the viewport
+    /// (0 when it's on the live screen).
+    pub fn scroll_offset<T: EventListener>(&self, term: &Term<T>, absolute: u64) -> Option<usize> {
+        let line = self.to_line(term, absolute)?;
+        Some(if line.0 < 0 { (-line.0) as usize } else { 0 })
+    }
+
+    fn absolute<T: EventListener>(&self, term: &Term<T>, line: Line) -> u64 {
+        (self.evicted as i64 + term.grid().history_size() as i64 + line.0 as i64).max(0) as u64
+    }
+
+    /// The newest block, if it's still at its prompt.
+    fn pending(&self) -> Option<&CommandBlock> {
+        self.blocks.back().filter(|b| b.output_start.is_none() && b.finished_at.is_none())
+    }
+
+    fn pending_mut(&mut self) -> Option<&mut CommandBlock> {
+        self.blocks.back_mut().filter(|b| b.output_start.is_none() && b.finished_at.is_none())
+    }
+
+    fn start_output<T: EventListener>(&mut self, term: &Term<T>, line: u64, column: usize, now_ms: u64) {
+        self.prompt_input_mark = None;
+        if self.pending().is_none() {
+            // Output mark without a prompt mark — start a block here
+            self.next_id += 1;
+            self.blocks.push_back(CommandBlock {
+                id: self.next_id,
+                prompt_line: line,
+                command: None,
+                output_start: None,
+                output_end: None,
+                started_at: None,
+                finished_at: None,
+                exit_code: None,
+                cwd: self.cwd.clone(),
+                input_start: None,
+                explicit_input: false,
+            });
+        }
+        let evicted = self.evicted;
+        let history = term.grid().history_size() as u64;
+        let Some(block) = self.blocks.back_mut() else { return };
+
+        // Command text runs from the input start to the end of the line
+        // before the output (C usually arrives at column 0 after Enter).
+        let (input_line, input_col) = block.input_start.unwrap_or((block.prompt_line, 0));
+        let last_input_line = if column == 0 { line.saturating_sub(1) } else { line };
+        if last_input_line >= input_line && input_line >= evicted {
+            let to_line = |abs: u64| Line((abs as i64 - (evicted + history) as i64) as i32);
+            let start = Point::new(to_line(input_line), Column(input_col.min(term.last_column().0)));
+            let end = Point::new(to_line(last_input_line), term.last_column());
+            let text = term.bounds_to_string(start, end);
+            let text = text.trim();
+            if !text.is_empty() {
+                block.command = Some(text.to_string());
+            }
+        }
+        block.output_start = Some(line);
+        block.started_at = Some(now_ms);
+    }
+
+    fn finish(&mut self, line: u64, column: usize, exit_code: Option<i32>, now_ms: u64) {
+        let Some(block) = self.blocks.back_mut() else { return };
+        if block.finished_at.is_some() {
+            return;
+        }
+        if block.output_start.is_none() {
+            // Prompt abandoned (empty Enter, Ctrl-C) — nothing ran
+            self.blocks.pop_back();
+            return;
+        }
+        // Output that didn't end in a newline still owns the cursor's line
+        block.output_end = Some(if column > 0 { line + 1 } else { line });
+        block.exit_code = exit_code;
+        block.finished_at = Some(now_ms);
+    }
+}
+
+/// Whether `data` starts with a sequence `advance` processes on its own.
+fn starts_history_change(data: &[u8]) -> bool {
+    data[0] == 0x1b && HISTORY_CLEARS.iter().chain(SCREEN_SWITCHES).any(|seq| data.starts_with(seq))
+}
+
+/// A line's place in the unwrapped text: which logical line, counted from
+/// the cursor's (reflow keeps the cursor with its text), and how many cells
+/// into it.
+type LogicalPos = (i64, usize);
+
+/// The logical (unwrapped) lines of the grid, for carrying absolute lines
+/// across a reflow.
+struct LogicalLines {
+    /// Absolute line each logical line starts on, top to bottom.
+    starts: Vec<u64>,
+    /// Index in `starts` of the cursor's logical line.
+    cursor: usize,
+    /// Absolute line below the bottom row.
+    end: u64,
+    columns: usize,
+}
+
+impl LogicalLines {
+    fn read<T: EventListener>(term: &Term<T>, tracker: &BlockTracker) -> Self {
+        let grid = term.grid();
+        let last = term.last_column();
+        let mut starts = Vec::new();
+        let mut wrapped = false;
+        for row in grid.topmost_line().0..=grid.bottommost_line().0 {
+            if !wrapped {
+                starts.push(tracker.absolute(term, Line(row)));
+            }
+            wrapped = grid[Line(row)][last].flags.contains(Flags::WRAPLINE);
+        }
+        let cursor = starts.partition_point(|&start| start <= tracker.cursor_line(term)).saturating_sub(1);
+        let end = tracker.absolute(term, grid.bottommost_line()) + 1;
+        Self { starts, cursor, end, columns: term.columns() }
+    }
+
+    fn locate(&self, line: u64) -> LogicalPos {
+        self.locate_column(line, 0)
+    }
+
+    fn locate_column(&self, line: u64, column: usize) -> LogicalPos {
+        if line >= self.end {
+            // Below the grid (the end of output on the bottom row)
+            return ((self.starts.len() as u64 + line - self.end) as i64 - self.cursor as i64, 0);
+        }
+        let index = self.starts.partition_point(|&start| start <= line);
+        let Some(start) = index.checked_sub(1).map(|i| self.starts[i]) else {
+            return (-(self.cursor as i64) - 1, 0);
+        };
+        (index as i64 - 1 - self.cursor as i64, (line - start) as usize * self.columns + column)
+    }
+
+    /// Absolute line and column of a logical position.
+    fn place(&self, (logical, cells): LogicalPos) -> (u64, usize) {
+        let index = self.cursor as i64 + logical;
+        if index < 0 {
+            return (self.starts.first().map_or(0, |s| s.saturating_sub(1)), 0);
+        }
+        let index = index as usize;
+        let Some(&start) = self.starts.get(index) else {
+            return (self.end + (index - self.starts.len()) as u64, 0);
+        };
+        let next = self.starts.get(index + 1).copied().unwrap_or(self.end);
+        let row = ((cells / self.columns) as u64).min(next - start - 1);
+        (start + row, cells % self.columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+
+    /// One prompt → command → output round, marked the way our zsh/bash
+    /// hooks do it (B from preexec, D before the next A).
+    fn session(term: &mut Term<VoidListener>, tracker: &mut BlockTracker, command: &str, output: &str, exit: i32) {
+        let mut processor = Processor::default();
+        let mut feed = |tracker: &mut BlockTracker, term: &mut Term<VoidListener>, data: &str, mark: Option<(char, Option<i32>)>| {
+            tracker.advance(term, &mut processor, data.as_bytes());
+            if let Some((cmd, exit_code)) = mark {
+                tracker.mark(term, cmd, exit_code, 0, 0);
+            }
+            tracker.end_of_chunk(term, 0);
+        };
+        feed(tracker, term, "", Some(('A', None)));
+        feed(tracker, term, "$ ", None);
+        feed(tracker, term, &format!("{}\r\n", command), Some(('B', None)));
+        feed(tracker, term, &format!("{}\r\n", output), Some(('D', Some(exit))));
+    }
+
+    #[test]
+    fn records_command_output_and_exit() {
+        let mut term = Term::new(Config::default(), &TermSize::new(40, 10), VoidListener);
+        let mut tracker = BlockTracker::new(100);
+        session(&mut term, &mut tracker, "ls", "a.txt\r\nb.txt", 0);
+        session(&mut term, &mut tracker, "false", "", 1);
+
+        let blocks: Vec<_> = tracker.blocks().cloned().collect();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].command.as_deref(), Some("ls"));
+        assert_eq!(blocks[0].exit_code, Some(0));
+        assert_eq!(tracker.output_text(&term, &blocks[0]).unwrap().trim_end(), "a.txt\nb.txt");
+        assert_eq!(blocks[1].command.as_deref(), Some("false"));
+        assert_eq!(blocks[1].exit_code, Some(1));
+        assert_eq!(blocks[1].prompt_line, blocks[0].output_end.unwrap());
+    }
+
+    #[test]
+    fn lines_stay_stable_when_scrollback_rotates() {
+        let mut term = Term::new(Config { scrolling_history: 20, ..Config::default() }, &TermSize::new(40, 5), VoidListener);
+        let mut tracker = BlockTracker::new(20);
+        session(&mut term, &mut tracker, "first", "one", 0);
+        for i in 0..30 {
+            session(&mut term, &mut tracker, &format!("cmd{}", i), "out", 0);
+        }
+
+        // Older blocks fell out of scrollback; the survivors still point at their text
+        assert!(tracker.blocks().next().unwrap().command.as_deref() != Some("first"));
+        let last = tracker.blocks().last().unwrap().clone();
+        assert_eq!(last.command.as_deref(), Some("cmd29"));
+        assert_eq!(tracker.output_text(&term, &last).unwrap().trim_end(), "out");
+        for block in tracker.blocks() {
+            assert_eq!(tracker.output_text(&term, block).unwrap().trim_end(), "out");
+        }
+    }
+
+    #[test]
+    fn evictions_are_counted_exactly() {
+        let mut term = Term::new(Config { scrolling_history: 20, ..Config::default() }, &TermSize::new(40, 5), VoidListener);
+        let mut tracker = BlockTracker::new(20);
+        let mut processor = Processor::default();
+
+        // 100 scroll-ups of the whole screen in one chunk push 500 lines
+        tracker.advance(&mut term, &mut processor, "\x1b[5S".repeat(100).as_bytes());
+        assert_eq!(tracker.cursor_line(&term), 500);
+
+        // Viewed from the top of full history, where display_offset can't grow
+        term.scroll_display(Scroll::Top);
+        tracker.advance(&mut term, &mut processor, "x\r\n".repeat(50).as_bytes());
+        assert_eq!(tracker.cursor_line(&term), 550);
+        assert_eq!(term.grid().display_offset(), 20);
+
+        // A full-screen program doesn't take the primary screen's history with it
+        term.scroll_display(Scroll::Bottom);
+        session(&mut term, &mut tracker, "ls", "out", 0);
+        tracker.advance(&mut term, &mut processor, b"\x1b[?1049hvim\r\n\x1b[?1049l");
+        let block = tracker.blocks().last().unwrap().clone();
+        assert_eq!(tracker.output_text(&term, &block).unwrap().trim_end(), "out");
+
+        // Clearing history counts as evicting it
+        let cursor = tracker.cursor_line(&term);
+        tracker.advance(&mut term, &mut processor, b"\x1b[3J");
+        assert_eq!(tracker.cursor_line(&term), cursor);
+        assert_eq!(term.grid().history_size(), 0);
+    }
+
+    #[test]
+    fn blocks_follow_their_lines_through_a_reflow() {
+        let mut term = Term::new(Config::default(), &TermSize::new(40, 10), VoidListener);
+        let mut tracker = BlockTracker::new(100);
+        let long = "0123456789".repeat(6);
+        session(&mut term, &mut tracker, "cat long", &long, 0);
+        session(&mut term, &mut tracker, "echo hi", "hi", 0);
+        session(&mut term, &mut tracker, "cat long", &long, 0);
+
+        for columns in [20, 80, 13] {
+            tracker.resize(&mut term, TermSize::new(columns, 10));
+            let blocks: Vec<_> = tracker.blocks().cloned().collect();
+            assert_eq!(blocks.len(), 3, "{} columns", columns);
+            assert_eq!(tracker.output_text(&term, &blocks[0]).unwrap().trim_end(), long);
+            assert_eq!(tracker.output_text(&term, &blocks[1]).unwrap().trim_end(), "hi");
+            assert_eq!(tracker.output_text(&term, &blocks[2]).unwrap().trim_end(), long);
+            assert_eq!(blocks[1].prompt_line, blocks[0].output_end.unwrap());
+            assert_eq!(blocks[2].prompt_line, blocks[1].output_end.unwrap());
+        }
+    }
+}
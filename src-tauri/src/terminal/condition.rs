@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+/// Variable condition used by `match_mode: "variable"` triggers.
+///
+/// Syntax:
+///   expr     := and_expr ('||' and_expr)*
+///   and_expr := atom ('&&' atom)*
+///   atom     := '!' IDENT | IDENT ('==' | '!=') STRING | IDENT
+///   STRING   := "..." | '...'
+///   IDENT    := [a-zA-Z0-9_]+
+///
+/// A bare identifier is truthy when the variable is set and non-empty.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Truthy(String),
+    Falsy(String),
+    Eq(String, String),
+    Neq(String, String),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn evaluate(&self, vars: &HashMap<String, String>) -> bool {
+        let is_set = |name: &str| vars.get(name).is_some_and(|v| !v.is_empty());
+        match self {
+            Condition::Truthy(name) => is_set(name),
+            Condition::Falsy(name) => !is_set(name),
+            Condition::Eq(name, value) => vars.get(name) == Some(value),
+            Condition::Neq(name, value) => vars.get(name) != Some(value),
+            Condition::And(children) => children.iter().all(|c| c.evaluate(vars)),
+            Condition::Or(children) => children.iter().any(|c| c.evaluate(vars)),
+        }
+    }
+}
+
+/// Parse a condition expression into a [`Condition`] tree.
+pub fn parse_condition(expr: &str) -> Result<Condition, String> {
+    let mut parser = Parser { input: expr.chars().collect(), pos: 0 };
+    let node = parser.parse_or()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.input.len() {
+        return Err(format!(
+            "Unexpected character at position {}: '{}'",
+            parser.pos, parser.input[parser.pos]
+        ));
+    }
+    Ok(node)
+}
+
+struct Parser {
+    input: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut children = vec![self.parse_and()?];
+        while self.eat("||") {
+            children.push(self.parse_and()?);
+        }
+        Ok(if children.len() == 1 { children.remove(0) } else { Condition::Or(children) })
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut children = vec![self.parse_atom()?];
+        while self.eat("&&") {
+            children.push(self.parse_atom()?);
+        }
+        Ok(if children.len() == 1 { children.remove(0) } else { Condition::And(children) })
+    }
+
+    fn parse_atom(&mut self) -> Result<Condition, String> {
+        self.skip_whitespace();
+        match self.input.get(self.pos) {
+            None => return Err("Unexpected end of expression".to_string()),
+            Some('!') => {
+                self.pos += 1;
+                return Ok(Condition::Falsy(self.read_ident()?));
+            }
+            _ => {}
+        }
+
+        let name = self.read_ident()?;
+        if self.eat("==") {
+            return Ok(Condition::Eq(name, self.read_string()?));
+        }
+        if self.eat("!=") {
+            return Ok(Condition::Neq(name, self.read_string()?));
+        }
+        Ok(Condition::Truthy(name))
+    }
+
+    fn read_ident(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.input.len()
+            && (self.input[self.pos].is_ascii_alphanumeric() || self.input[self.pos] == '_')
+        {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(format!("Expected identifier at position {}", self.pos));
+        }
+        Ok(self.input[start..self.pos].iter().collect())
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let quote = match self.input.get(self.pos) {
+            Some(&q) if q == '"' || q == '\'' => q,
+            Some(c) => return Err(format!("Expected string literal at position {}, got '{}'", self.pos, c)),
+            None => return Err("Expected string literal".to_string()),
+        };
+        self.pos += 1;
+        let start = self.pos;
+        while self.pos < self.input.len() && self.input[self.pos] != quote {
+            self.pos += 1;
+        }
+        if self.pos >= self.input.len() {
+            return Err(format!("Unterminated string literal starting at position {}", start - 1));
+        }
+        let value = self.input[start..self.pos].iter().collect();
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let len = token.chars().count();
+        if self.input.len() >= self.pos + len
+            && self.input[self.pos..self.pos + len].iter().copied().eq(token.chars())
+        {
+            self.pos += len;
+            return true;
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+}
@@ -1,22 +1,59 @@
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
 
 use alacritty_terminal::event::{Event, EventListener};
+use alacritty_terminal::vte::ansi::Rgb;
 use tauri::{AppHandle, Emitter};
 
+use crate::pty::clipboard::{self, ClipboardOp};
 use crate::state::PtyCommand;
 
+/// A reply to the program that has to wait until the current chunk of output
+/// has been processed.
+pub enum PendingReply {
+    Bytes(Vec<u8>),
+    /// OSC 4/10/11/12 query: alacritty color index + response formatter.
+    Color(usize, Arc<dyn Fn(Rgb) -> String + Sync + Send + 'static>),
+}
+
+/// Replies queued by the proxy, drained by `TerminalHandle::take_replies`.
+pub type ReplyQueue = Arc<parking_lot::Mutex<Vec<PendingReply>>>;
+
+/// The program's last OSC 0/2 title, read back through `TerminalHandle::title`.
+pub type TitleCell = Arc<parking_lot::Mutex<Option<String>>>;
+
 /// Bridge between alacritty_terminal's internal events and our Tauri event system.
 /// alacritty_terminal calls `send_event()` when the terminal state changes.
 pub struct AitermEventProxy {
     pub pty_id: String,
+    pub tab_id: String,
     pub app_handle: AppHandle,
     pub pty_sender: Sender<PtyCommand>,
+    /// Color queries are answered after the chunk is processed (the proxy
+    /// can't see the Term's color overrides). Later replies queue behind
+    /// them so a program that sends OSC 11 followed by DA1 gets the answers
+    /// in order.
+    pub replies: ReplyQueue,
+    /// Kept for trigger notifications (`%title`), which run without a webview.
+    pub title: TitleCell,
+}
+
+impl AitermEventProxy {
+    fn reply(&self, bytes: Vec<u8>) {
+        let mut replies = self.replies.lock();
+        if replies.is_empty() {
+            let _ = self.pty_sender.send(PtyCommand::Write(bytes));
+        } else {
+            replies.push(PendingReply::Bytes(bytes));
+        }
+    }
 }
 
 impl EventListener for AitermEventProxy {
     fn send_event(&self, event: Event) {
         match event {
             Event::Title(title) => {
+                *self.title.lock() = Some(title.clone());
                 let _ = self.app_handle.emit(
                     &format!("term-title-{}", self.pty_id),
                     title,
@@ -28,28 +65,27 @@ impl EventListener for AitermEventProxy {
                     (),
                 );
             }
+            // OSC 52 — policy checks and the clipboard itself live in pty::clipboard
             Event::ClipboardStore(_clipboard_type, text) => {
-                let _ = self.app_handle.emit(
-                    &format!("term-clipboard-{}", self.pty_id),
-                    text,
+                clipboard::handle_request(
+                    self.app_handle.clone(),
+                    self.pty_id.clone(),
+                    self.tab_id.clone(),
+                    ClipboardOp::Write(text),
                 );
             }
-            Event::PtyWrite(text) => {
-                let bytes = text.into_bytes();
-                let _ = self.pty_sender.send(PtyCommand::Write(bytes));
-            }
+            Event::PtyWrite(text) => self.reply(text.into_bytes()),
             Event::ResetTitle => {
+                *self.title.lock() = None;
                 let _ = self.app_handle.emit(
                     &format!("term-title-{}", self.pty_id),
                     String::new(),
                 );
             }
-            // ColorRequest: terminal querying current color values — respond via PtyWrite
-            Event::ColorRequest(_index, formatter) => {
-                // Look up the color from our configured colors and respond.
-                // For now, respond with a default black to avoid leaving the query unanswered.
-                let response = formatter(alacritty_terminal::vte::ansi::Rgb { r: 0, g: 0, b: 0 });
-                let _ = self.pty_sender.send(PtyCommand::Write(response.into_bytes()));
+            // ColorRequest: OSC 4/10/11/12 query — resolved against the Term's
+            // overrides and the theme palette once the chunk is done
+            Event::ColorRequest(index, formatter) => {
+                self.replies.lock().push(PendingReply::Color(index, formatter));
             }
             // TextAreaSizeRequest: terminal querying window size in pixels
             Event::TextAreaSizeRequest(formatter) => {
@@ -60,10 +96,16 @@ impl EventListener for AitermEventProxy {
                     cell_width: 0,
                     cell_height: 0,
                 });
-                let _ = self.pty_sender.send(PtyCommand::Write(response.into_bytes()));
+                self.reply(response.into_bytes());
+            }
+            Event::ClipboardLoad(_clipboard_type, formatter) => {
+                clipboard::handle_request(
+                    self.app_handle.clone(),
+                    self.pty_id.clone(),
+                    self.tab_id.clone(),
+                    ClipboardOp::Read(formatter),
+                );
             }
-            // ClipboardLoad: terminal wants to read clipboard — not supported from backend
-            Event::ClipboardLoad(_clipboard_type, _formatter) => {}
             // Wakeup, CursorBlinkingChange, MouseCursorDirty, Exit, ChildExit — not needed
             _ => {}
         }
@@ -0,0 +1,368 @@
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::Column;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vte::ansi::{Color, NamedColor, Rgb};
+
+use super::blocks::BlockTracker;
+use super::palette::ThemePalette;
+
+/// Output formats for `export_terminal` / `export_saved_scrollback`.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Html,
+    Text,
+    Markdown,
+}
+
+/// Export the whole buffer (scrollback + screen). `blocks` is only used for
+/// Markdown; without command blocks the transcript is one fenced block.
+pub fn export<T: EventListener>(
+    term: &Term<T>,
+    format: ExportFormat,
+    palette: &ThemePalette,
+    blocks: Option<&BlockTracker>,
+    title: &str,
+) -> String {
+    match format {
+        ExportFormat::Html => export_html(term, palette, title),
+        ExportFormat::Text => export_text(term),
+        ExportFormat::Markdown => export_markdown(term, blocks, title),
+    }
+}
+
+/// Plain text with soft-wrapped rows joined back into their logical lines
+/// and trailing whitespace removed.
+pub fn export_text<T: EventListener>(term: &Term<T>) -> String {
+    let mut out = String::new();
+    for line in logical_lines(term) {
+        let mut text = String::new();
+        for cell in line {
+            push_cell_text(&mut text, cell);
+        }
+        out.push_str(text.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// A standalone HTML page: colors resolved against the theme palette (and any
+/// OSC 4/10/11 overrides), bold/italic/underline/strikethrough as inline
+/// styles, OSC 8 hyperlinks as anchors (web, mail and file links only).
+pub fn export_html<T: EventListener>(term: &Term<T>, palette: &ThemePalette, title: &str) -> String {
+    let default_fg = resolve(term, palette, Color::Named(NamedColor::Foreground));
+    let default_bg = resolve(term, palette, Color::Named(NamedColor::Background));
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    escape_html(&mut out, title);
+    out.push_str("</title>\n<style>\n");
+    out.push_str(&format!(
+        "body {{ margin: 0; background: {}; color: {}; }}\n",
+        hex(default_bg),
+        hex(default_fg)
+    ));
+    out.push_str(
+        "pre { margin: 0; padding: 16px; font: 13px/1.3 ui-monospace, Menlo, Monaco, \"Courier New\", monospace; white-space: pre-wrap; word-break: break-all; }\n",
+    );
+    out.push_str("a { color: inherit; }\n</style>\n</head>\n<body>\n<pre>");
+
+    for line in logical_lines(term) {
+        let mut run: Option<(Style, String)> = None;
+        for cell in line {
+            let style = Style::of(term, palette, cell, default_fg, default_bg);
+            match &mut run {
+                Some((current, text)) if *current == style => push_cell_text(text, cell),
+                _ => {
+                    if let Some((style, text)) = run.take() {
+                        write_run(&mut out, &style, &text);
+                    }
+                    let mut text = String::new();
+                    push_cell_text(&mut text, cell);
+                    run = Some((style, text));
+                }
+            }
+        }
+        if let Some((style, text)) = run {
+            write_run(&mut out, &style, &text);
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+/// A Markdown transcript: one fenced `console` block per tracked command
+/// (`$ command` then its output), with non-zero exit codes noted below it.
+pub fn export_markdown<T: EventListener>(term: &Term<T>, blocks: Option<&BlockTracker>, title: &str) -> String {
+    let mut out = format!("# {}\n\n", title.trim());
+
+    let mut wrote_block = false;
+    if let Some(tracker) = blocks {
+        for block in tracker.blocks() {
+            // Unfinished (still running) or already scrolled out of the buffer
+            let (Some(command), Some(output)) = (&block.command, tracker.output_text(term, block)) else {
+                continue;
+            };
+            let mut body = format!("$ {}\n", command);
+            for line in output.lines() {
+                body.push_str(line.trim_end());
+                body.push('\n');
+            }
+            push_fenced(&mut out, "console", body.trim_end());
+            match block.exit_code {
+                Some(0) | None => out.push('\n'),
+                Some(code) => out.push_str(&format!("Exit code: {}\n\n", code)),
+            }
+            wrote_block = true;
+        }
+    }
+
+    if !wrote_block {
+        push_fenced(&mut out, "text", export_text(term).trim_end_matches('\n'));
+    }
+    out
+}
+
+/// Grid rows top to bottom, with rows that soft-wrapped joined onto the
+/// next one. Wide-char spacers and trailing blank cells are dropped, as are
+/// blank lines below the last output.
+fn logical_lines<T: EventListener>(term: &Term<T>) -> Vec<Vec<&Cell>> {
+    let grid = term.grid();
+    let last_column = term.last_column();
+    let mut lines = Vec::new();
+    let mut current: Vec<&Cell> = Vec::new();
+
+    let mut line = grid.topmost_line();
+    while line <= grid.bottommost_line() {
+        let row = &grid[line];
+        for col in 0..term.columns() {
+            let cell = &row[Column(col)];
+            if !cell.flags.intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER) {
+                current.push(cell);
+            }
+        }
+        if !row[last_column].flags.contains(Flags::WRAPLINE) {
+            while current.last().is_some_and(|cell| is_blank(cell)) {
+                current.pop();
+            }
+            lines.push(std::mem::take(&mut current));
+        }
+        line += 1;
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    (cell.c == ' ' || cell.c == '\0')
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell.flags.intersects(Flags::INVERSE | Flags::ALL_UNDERLINES | Flags::STRIKEOUT)
+}
+
+fn push_cell_text(out: &mut String, cell: &Cell) {
+    if cell.c == '\0' || cell.flags.contains(Flags::HIDDEN) {
+        out.push(' ');
+        return;
+    }
+    out.push(cell.c);
+    if let Some(zerowidth) = cell.zerowidth() {
+        out.extend(zerowidth);
+    }
+}
+
+#[derive(PartialEq)]
+struct Style {
+    fg: Option<Rgb>,
+    bg: Option<Rgb>,
+    flags: Flags,
+    link: Option<String>,
+}
+
+impl Style {
+    const SHOWN: Flags = Flags::BOLD
+        .union(Flags::ITALIC)
+        .union(Flags::DIM)
+        .union(Flags::ALL_UNDERLINES)
+        .union(Flags::STRIKEOUT);
+
+    /// Colors are only kept where they differ from the page defaults.
+    fn of<T: EventListener>(term: &Term<T>, palette: &ThemePalette, cell: &Cell, default_fg: Rgb, default_bg: Rgb) -> Self {
+        let mut fg = resolve(term, palette, cell.fg);
+        let mut bg = resolve(term, palette, cell.bg);
+        if cell.flags.contains(Flags::INVERSE) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+        Style {
+            fg: (fg != default_fg).then_some(fg),
+            bg: (bg != default_bg).then_some(bg),
+            flags: cell.flags & Self::SHOWN,
+            link: cell.hyperlink().map(|h| h.uri().to_string()),
+        }
+    }
+
+    fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some(fg) = self.fg {
+            css.push(format!("color:{}", hex(fg)));
+        }
+        if let Some(bg) = self.bg {
+            css.push(format!("background:{}", hex(bg)));
+        }
+        if self.flags.contains(Flags::BOLD) {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.flags.contains(Flags::ITALIC) {
+            css.push("font-style:italic".to_string());
+        }
+        if self.flags.contains(Flags::DIM) {
+            css.push("opacity:0.66".to_string());
+        }
+        let underline = self.flags.intersects(Flags::ALL_UNDERLINES);
+        let strike = self.flags.contains(Flags::STRIKEOUT);
+        match (underline, strike) {
+            (true, true) => css.push("text-decoration:underline line-through".to_string()),
+            (true, false) => css.push("text-decoration:underline".to_string()),
+            (false, true) => css.push("text-decoration:line-through".to_string()),
+            (false, false) => {}
+        }
+        css.join(";")
+    }
+}
+
+/// OSC 8 link schemes exported as links. Anything else (`javascript:`,
+/// `data:`, …) is written as plain text, since the program chose the href.
+const LINK_SCHEMES: &[&str] = &["http", "https", "mailto", "file"];
+
+fn safe_link(uri: &str) -> Option<&str> {
+    let (scheme, _) = uri.split_once(':')?;
+    LINK_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme)).then_some(uri)
+}
+
+fn write_run(out: &mut String, style: &Style, text: &str) {
+    let link = style.link.as_deref().and_then(safe_link);
+    if let Some(uri) = link {
+        out.push_str("<a href=\"");
+        escape_html(out, uri);
+        out.push_str("\">");
+    }
+    let css = style.css();
+    if css.is_empty() {
+        escape_html(out, text);
+    } else {
+        out.push_str(&format!("<span style=\"{}\">", css));
+        escape_html(out, text);
+        out.push_str("</span>");
+    }
+    if link.is_some() {
+        out.push_str("</a>");
+    }
+}
+
+/// A cell color as the user saw it: program overrides (OSC 4/10/11) first,
+/// then the theme palette.
+fn resolve<T: EventListener>(term: &Term<T>, palette: &ThemePalette, color: Color) -> Rgb {
+    let index = match color {
+        Color::Spec(rgb) => return rgb,
+        Color::Named(named) => named as usize,
+        Color::Indexed(index) => index as usize,
+    };
+    term.colors()[index].unwrap_or_else(|| palette.color(index))
+}
+
+fn hex(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b)
+}
+
+fn escape_html(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Fence `body`, using a fence longer than any backtick run inside it.
+fn push_fenced(out: &mut String, info: &str, body: &str) {
+    let longest = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest + 1).max(3));
+    out.push_str(&format!("{}{}\n{}\n{}\n", fence, info, body, fence));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    fn term(cols: usize, text: &str) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(cols, 5), VoidListener);
+        let mut processor: Processor = Processor::default();
+        processor.advance(&mut term, text.as_bytes());
+        term
+    }
+
+    #[test]
+    fn text_and_html_keep_wraps_styles_and_links() {
+        let t = term(
+            10,
+            "0123456789abcde   \r\n\x1b[1;31mred\x1b[0m \x1b]8;;https://example.com/?a=1&b=2\x1b\\<link>\x1b]8;;\x1b\\\r\n",
+        );
+
+        assert_eq!(export_text(&t), "0123456789abcde\nred <link>\n");
+
+        let html = export_html(&t, &ThemePalette::default(), "a & b");
+        assert!(html.contains("<title>a &amp; b</title>"));
+        assert!(html.contains("0123456789abcde\n"));
+        let red = hex(ThemePalette::default().color(1));
+        assert!(html.contains(&format!("<span style=\"color:{};font-weight:bold\">red</span>", red)));
+        assert!(html.contains("<a href=\"https://example.com/?a=1&amp;b=2\">&lt;link&gt;</a>"));
+    }
+
+    #[test]
+    fn html_links_only_for_safe_schemes() {
+        let link = |href: &str, text: &str| format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\ ", href, text);
+        let t = term(
+            80,
+            &[
+                link("JavaScript:alert(1)", "js"),
+                link("data:text/html,x", "data"),
+                link("mailto:me@example.com", "mail"),
+                link("file:///tmp/a.txt", "file"),
+            ]
+            .concat(),
+        );
+
+        let html = export_html(&t, &ThemePalette::default(), "links");
+        assert!(!html.contains("alert") && !html.contains("data:"));
+        assert!(html.contains("js data "));
+        assert!(html.contains("<a href=\"mailto:me@example.com\">mail</a>"));
+        assert!(html.contains("<a href=\"file:///tmp/a.txt\">file</a>"));
+    }
+
+    #[test]
+    fn markdown_without_blocks_fences_whole_buffer() {
+        let t = term(40, "echo ```\r\n```\r\n");
+        let md = export_markdown(&t, None, "Session");
+        assert_eq!(md, "# Session\n\n````text\necho ```\n```\n````\n");
+    }
+}
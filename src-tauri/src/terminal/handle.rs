@@ -1,10 +1,16 @@
+use alacritty_terminal::event::{EventListener, VoidListener};
 use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::selection::Selection;
-use alacritty_terminal::term::{Config, Term};
+use alacritty_terminal::term::{Config, Osc52, Term};
 use alacritty_terminal::vte;
 
-use super::event_proxy::AitermEventProxy;
-use super::osc::OscInterceptor;
+use super::asciicast::Recorder;
+use super::blocks::BlockTracker;
+use super::event_proxy::{AitermEventProxy, PendingReply, ReplyQueue, TitleCell};
+use super::osc::{OscEvent, OscInterceptor};
+use super::palette::ThemePalette;
+use super::render::{self, FrameCache, TerminalFrame};
+use super::triggers::{self, GridPos, OutputWindow};
 
 /// Dimensions implementation for creating/resizing Term instances.
 pub struct TermDimensions {
@@ -26,15 +32,191 @@ impl Dimensions for TermDimensions {
     }
 }
 
-/// Wraps one alacritty_terminal instance with its associated state.
-pub struct TerminalHandle {
-    pub term: Term<AitermEventProxy>,
+/// Wraps one alacritty_terminal instance with its associated state. The
+/// listener is the Tauri event proxy outside of tests.
+pub struct TerminalHandle<L = AitermEventProxy> {
+    pub term: Term<L>,
     pub osc_interceptor: OscInterceptor,
     /// VTE processor for feeding bytes to the terminal.
     pub processor: vte::ansi::Processor,
     /// User selection managed externally (not on term.selection which gets
     /// cleared by VTE processing). Stored here so it survives PTY output.
     pub selection: Option<Selection>,
+    /// Command history from OSC 133 marks.
+    pub blocks: BlockTracker,
+    /// Active asciicast recording of this terminal's output.
+    pub recorder: Option<Recorder>,
+    /// Where trigger matching resumes; output before it was already matched.
+    pub trigger_scan: Option<GridPos>,
+    /// What the frontend was last sent, for incremental frames.
+    frame_cache: FrameCache,
+    /// Shared with the event proxy — see `AitermEventProxy::replies`.
+    replies: ReplyQueue,
+    title: TitleCell,
+}
+
+impl<L: EventListener> TerminalHandle<L> {
+    /// A handle for a new terminal whose listener shares `replies` and `title`.
+    fn with_listener(
+        cols: u16,
+        rows: u16,
+        scrollback_limit: usize,
+        listener: L,
+        replies: ReplyQueue,
+        title: TitleCell,
+    ) -> Self {
+        let config = Config {
+            scrolling_history: scrollback_limit,
+            // Both directions reach the event proxy; the per-tab/host policy
+            // is applied in pty::clipboard.
+            osc52: Osc52::CopyPaste,
+            ..Config::default()
+        };
+
+        let dims = TermDimensions {
+            cols: cols as usize,
+            rows: rows as usize,
+        };
+
+        TerminalHandle {
+            term: Term::new(config, &dims, listener),
+            osc_interceptor: OscInterceptor::new(),
+            processor: vte::ansi::Processor::default(),
+            selection: None,
+            blocks: BlockTracker::new(scrollback_limit),
+            recorder: None,
+            trigger_scan: None,
+            frame_cache: FrameCache::default(),
+            replies,
+            title,
+        }
+    }
+
+    /// Handle a chunk of PTY output as the reader thread does: record it,
+    /// pull out the OSC events we act on and parse it into the grid. Returns
+    /// those events and the replies owed to the program.
+    pub fn feed(
+        &mut self,
+        data: &[u8],
+        now_ms: u64,
+        bytes_written: u64,
+        palette: &ThemePalette,
+    ) -> (Vec<(usize, OscEvent)>, Vec<u8>) {
+        self.record_output(data);
+        let osc_events = self.osc_interceptor.process(data);
+        // Temporarily move our external selection onto term.selection so
+        // alacritty's scroll handlers rotate it correctly when new output
+        // pushes content up. Read it back after processing.
+        self.term.selection = self.selection.take();
+        self.advance(data, &osc_events, now_ms, bytes_written);
+        self.selection = self.term.selection.take();
+        (osc_events, self.take_replies(palette))
+    }
+
+    /// Feed PTY output to the terminal. Shell integration marks are applied
+    /// to the block tracker at the point in the stream where they occurred,
+    /// so each sees the cursor where the shell left it.
+    pub fn advance(&mut self, data: &[u8], osc_events: &[(usize, OscEvent)], now_ms: u64, bytes_written: u64) {
+        let mut pos = 0;
+        for (end, event) in osc_events {
+            match event {
+                OscEvent::ShellIntegration { cmd, exit_code } => {
+                    let end = (*end).clamp(pos, data.len());
+                    self.blocks.advance(&mut self.term, &mut self.processor, &data[pos..end]);
+                    pos = end;
+                    self.blocks.mark(&self.term, *cmd, *exit_code, now_ms, bytes_written);
+                }
+                OscEvent::Cwd { cwd, .. } | OscEvent::CurrentDir { cwd } => self.blocks.set_cwd(cwd.clone()),
+                OscEvent::Notification { .. } => {}
+            }
+        }
+        self.blocks.advance(&mut self.term, &mut self.processor, &data[pos..]);
+        self.blocks.end_of_chunk(&self.term, bytes_written);
+    }
+
+    /// Append raw PTY output to the active recording. A write error ends
+    /// the recording rather than failing the terminal.
+    pub fn record_output(&mut self, data: &[u8]) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.output(data) {
+                log::warn!("Recording to {} stopped: {}", recorder.path().display(), e);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Resize the grid, noting the new size in the active recording.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        if (self.term.columns(), self.term.screen_lines()) == (cols as usize, rows as usize) {
+            return;
+        }
+        let reflow = self.term.columns() != cols as usize;
+        self.blocks.resize(
+            &mut self.term,
+            TermDimensions {
+                cols: cols as usize,
+                rows: rows as usize,
+            },
+        );
+        if reflow {
+            // Reflowed text was already matched, at the lines it used to be on
+            self.trigger_scan = Some(triggers::grid_end(&self.term, &self.blocks));
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.resize(cols, rows) {
+                log::warn!("Recording to {} stopped: {}", recorder.path().display(), e);
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Full frame for a command response (scroll, selection, …). The next
+    /// output frame is full as well, so rows from an output frame that
+    /// reaches the frontend late can't linger.
+    pub fn render_full(&mut self) -> TerminalFrame {
+        self.frame_cache.invalidate();
+        render::render_viewport(&self.term, self.selection.as_ref())
+    }
+
+    /// Frame with only the rows changed since the last one — see
+    /// `render::render_damage`.
+    pub fn render_damage(&mut self) -> Option<(TerminalFrame, usize)> {
+        render::render_damage(&mut self.term, self.selection.as_ref(), &mut self.frame_cache)
+    }
+
+    /// Drain replies queued while processing output, resolving color queries
+    /// against this terminal's OSC 4/10/11/12 overrides, then the theme.
+    pub fn take_replies(&self, palette: &ThemePalette) -> Vec<u8> {
+        let mut out = Vec::new();
+        for reply in self.replies.lock().drain(..) {
+            match reply {
+                PendingReply::Bytes(bytes) => out.extend(bytes),
+                PendingReply::Color(index, formatter) => {
+                    let rgb = self.term.colors()[index].unwrap_or_else(|| palette.color(index));
+                    out.extend(formatter(rgb).into_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// Grid text written since the last trigger pass — the whole screen
+    /// after a redraw, which rewrites rows in place.
+    pub fn trigger_window(&self, redraw: bool) -> OutputWindow {
+        OutputWindow::new(&self.term, &self.blocks, self.trigger_scan, redraw)
+    }
+
+    /// Replay saved scrollback into the grid. Triggers skip the replayed
+    /// output — it already fired in the session that produced it.
+    pub fn restore_scrollback(&mut self, scrollback: &str) {
+        super::serialize::restore_scrollback(&mut self.term, scrollback);
+        self.trigger_scan = Some(triggers::grid_end(&self.term, &self.blocks));
+    }
+
+    /// Title last set by the program (OSC 0/2), if any.
+    pub fn title(&self) -> Option<String> {
+        self.title.lock().clone()
+    }
 }
 
 /// Create a new alacritty_terminal instance.
@@ -44,24 +226,36 @@ pub fn create_terminal(
     scrollback_limit: usize,
     event_proxy: AitermEventProxy,
 ) -> TerminalHandle {
+    let replies = event_proxy.replies.clone();
+    let title = event_proxy.title.clone();
+    TerminalHandle::with_listener(cols, rows, scrollback_limit, event_proxy, replies, title)
+}
+
+/// A terminal handle with no PTY or frontend behind it, for tests.
+#[cfg(test)]
+pub fn create_test_terminal(cols: u16, rows: u16, scrollback_limit: usize) -> TerminalHandle<VoidListener> {
+    TerminalHandle::with_listener(cols, rows, scrollback_limit, VoidListener, Default::default(), Default::default())
+}
+
+/// A terminal with no PTY or frontend behind it, for replaying recordings
+/// and exporting saved scrollback.
+pub fn create_headless_terminal(cols: u16, rows: u16, scrollback_limit: usize) -> Term<VoidListener> {
     let config = Config {
         scrolling_history: scrollback_limit,
         ..Config::default()
     };
-
     let dims = TermDimensions {
         cols: cols as usize,
         rows: rows as usize,
     };
+    Term::new(config, &dims, VoidListener)
+}
 
-    let term = Term::new(config, &dims, event_proxy);
-    let processor = vte::ansi::Processor::default();
-    let osc_interceptor = OscInterceptor::new();
-
-    TerminalHandle {
-        term,
-        osc_interceptor,
-        processor,
-        selection: None,
-    }
+/// A headless terminal holding a tab's saved scrollback (as stored in
+/// `ScrollbackDb`), sized to the grid it was saved at when known.
+pub fn restore_headless_terminal(scrollback: &str, saved_size: Option<(u16, u16)>) -> Term<VoidListener> {
+    let (cols, rows) = saved_size.unwrap_or((200, 24));
+    let mut term = create_headless_terminal(cols.max(1), rows.max(1), scrollback.matches('\n').count() + 1);
+    super::serialize::restore_scrollback(&mut term, scrollback);
+    term
 }
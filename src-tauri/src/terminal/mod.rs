@@ -1,6 +1,14 @@
+pub mod ansi;
+pub mod asciicast;
+pub mod blocks;
+pub mod condition;
 pub mod event_proxy;
+pub mod export;
 pub mod handle;
 pub mod osc;
+pub mod palette;
+pub mod registry;
 pub mod render;
 pub mod search;
 pub mod serialize;
+pub mod triggers;
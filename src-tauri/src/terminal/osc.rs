@@ -32,11 +32,12 @@ impl OscInterceptor {
         }
     }
 
-    /// Scan raw bytes, extract OSC events. Returns structured events.
-    pub fn process(&mut self, data: &[u8]) -> Vec<OscEvent> {
+    /// Scan raw bytes, extract OSC events. Returns structured events, each
+    /// with the offset in `data` just past its terminator.
+    pub fn process(&mut self, data: &[u8]) -> Vec<(usize, OscEvent)> {
         let mut events = Vec::new();
 
-        for &byte in data {
+        for (i, &byte) in data.iter().enumerate() {
             if self.saw_esc {
                 self.saw_esc = false;
                 if byte == b']' {
@@ -48,7 +49,7 @@ impl OscInterceptor {
                     // ESC \ — String Terminator (ST), end of OSC
                     self.in_osc = false;
                     if let Some(event) = self.parse_osc() {
-                        events.push(event);
+                        events.push((i + 1, event));
                     }
                     continue;
                 }
@@ -67,7 +68,7 @@ impl OscInterceptor {
                     // BEL — also terminates OSC
                     self.in_osc = false;
                     if let Some(event) = self.parse_osc() {
-                        events.push(event);
+                        events.push((i + 1, event));
                     }
                 } else {
                     self.osc_buffer.push(byte);
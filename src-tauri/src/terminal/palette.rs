@@ -0,0 +1,158 @@
+use alacritty_terminal::vte::ansi::{NamedColor, Rgb};
+
+/// Terminal colors of a theme as the frontend defines them (`TerminalColors`
+/// in themes/index.ts) — the resolved builtin or custom theme is pushed here.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalColors {
+    pub background: String,
+    pub foreground: String,
+    pub cursor: String,
+    pub black: String,
+    pub red: String,
+    pub green: String,
+    pub yellow: String,
+    pub blue: String,
+    pub magenta: String,
+    pub cyan: String,
+    pub white: String,
+    pub bright_black: String,
+    pub bright_red: String,
+    pub bright_green: String,
+    pub bright_yellow: String,
+    pub bright_blue: String,
+    pub bright_magenta: String,
+    pub bright_cyan: String,
+    pub bright_white: String,
+}
+
+/// The active theme's palette, used to answer OSC 4/10/11/12 color queries
+/// for colors a program hasn't overridden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemePalette {
+    pub ansi: [Rgb; 16],
+    pub foreground: Rgb,
+    pub background: Rgb,
+    pub cursor: Rgb,
+}
+
+impl ThemePalette {
+    pub fn from_colors(colors: &TerminalColors) -> Result<Self, String> {
+        let ansi = [
+            &colors.black,
+            &colors.red,
+            &colors.green,
+            &colors.yellow,
+            &colors.blue,
+            &colors.magenta,
+            &colors.cyan,
+            &colors.white,
+            &colors.bright_black,
+            &colors.bright_red,
+            &colors.bright_green,
+            &colors.bright_yellow,
+            &colors.bright_blue,
+            &colors.bright_magenta,
+            &colors.bright_cyan,
+            &colors.bright_white,
+        ];
+        let mut palette = Self {
+            ansi: [Rgb::default(); 16],
+            foreground: parse_hex(&colors.foreground)?,
+            background: parse_hex(&colors.background)?,
+            cursor: parse_hex(&colors.cursor)?,
+        };
+        for (slot, hex) in palette.ansi.iter_mut().zip(ansi) {
+            *slot = parse_hex(hex)?;
+        }
+        Ok(palette)
+    }
+
+    /// Color for an alacritty color index: 0–15 theme ANSI colors, 16–255 the
+    /// standard xterm cube and gray ramp, 256+ the named special colors.
+    pub fn color(&self, index: usize) -> Rgb {
+        const FOREGROUND: usize = NamedColor::Foreground as usize;
+        const BACKGROUND: usize = NamedColor::Background as usize;
+        const CURSOR: usize = NamedColor::Cursor as usize;
+        const DIM_BLACK: usize = NamedColor::DimBlack as usize;
+        const DIM_WHITE: usize = NamedColor::DimWhite as usize;
+        const BRIGHT_FOREGROUND: usize = NamedColor::BrightForeground as usize;
+
+        match index {
+            0..=15 => self.ansi[index],
+            16..=231 => {
+                let i = index - 16;
+                let level = |v: usize| if v == 0 { 0 } else { (v * 40 + 55) as u8 };
+                Rgb { r: level(i / 36), g: level((i / 6) % 6), b: level(i % 6) }
+            }
+            232..=255 => {
+                let v = ((index - 232) * 10 + 8) as u8;
+                Rgb { r: v, g: v, b: v }
+            }
+            FOREGROUND | BRIGHT_FOREGROUND => self.foreground,
+            BACKGROUND => self.background,
+            CURSOR => self.cursor,
+            DIM_BLACK..=DIM_WHITE => dim(self.ansi[index - DIM_BLACK]),
+            _ => dim(self.foreground),
+        }
+    }
+}
+
+impl Default for ThemePalette {
+    /// Tokyo Night — the default theme, used until the frontend reports one.
+    fn default() -> Self {
+        let rgb = |hex: u32| Rgb { r: (hex >> 16) as u8, g: (hex >> 8) as u8, b: hex as u8 };
+        Self {
+            ansi: [
+                rgb(0x15161e), rgb(0xf7768e), rgb(0x9ece6a), rgb(0xe0af68),
+                rgb(0x7aa2f7), rgb(0xbb9af7), rgb(0x7dcfff), rgb(0xa9b1d6),
+                rgb(0x414868), rgb(0xf7768e), rgb(0x9ece6a), rgb(0xe0af68),
+                rgb(0x7aa2f7), rgb(0xbb9af7), rgb(0x7dcfff), rgb(0xc0caf5),
+            ],
+            foreground: rgb(0xc0caf5),
+            background: rgb(0x1a1b26),
+            cursor: rgb(0xc0caf5),
+        }
+    }
+}
+
+/// Same factor alacritty uses for its dim colors.
+fn dim(color: Rgb) -> Rgb {
+    color * 0.66
+}
+
+/// Parse `#rrggbb` (or `#rgb`, or with a trailing alpha byte, which is ignored).
+fn parse_hex(hex: &str) -> Result<Rgb, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| format!("Invalid color: {}", hex));
+    match digits.len() {
+        3 => {
+            let expand = |i: usize| channel(&digits[i..=i].repeat(2));
+            Ok(Rgb { r: expand(0)?, g: expand(1)?, b: expand(2)? })
+        }
+        6 | 8 => Ok(Rgb { r: channel(&digits[0..2])?, g: channel(&digits[2..4])?, b: channel(&digits[4..6])? }),
+        _ => Err(format!("Invalid color: {}", hex)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_theme_cube_and_named_indices() {
+        let palette = ThemePalette::default();
+        assert_eq!(palette.color(1), Rgb { r: 0xf7, g: 0x76, b: 0x8e });
+        assert_eq!(palette.color(16), Rgb { r: 0, g: 0, b: 0 });
+        assert_eq!(palette.color(196), Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(palette.color(244), Rgb { r: 128, g: 128, b: 128 });
+        assert_eq!(palette.color(NamedColor::Background as usize), Rgb { r: 0x1a, g: 0x1b, b: 0x26 });
+    }
+
+    #[test]
+    fn parses_short_and_alpha_hex() {
+        assert_eq!(parse_hex("#fff").unwrap(), Rgb { r: 255, g: 255, b: 255 });
+        assert_eq!(parse_hex("#33467c80").unwrap(), Rgb { r: 0x33, g: 0x46, b: 0x7c });
+        assert!(parse_hex("transparent").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::handle::TerminalHandle;
+
+/// Live terminals keyed by pty_id. The map lock only guards membership —
+/// each terminal has its own lock, so a chatty PTY parsing output never
+/// stalls rendering, search or selection in another tab.
+///
+/// Look a terminal up, then lock it; the map lock is released before the
+/// terminal's lock is taken.
+pub struct TerminalRegistry<H = TerminalHandle> {
+    terminals: RwLock<HashMap<String, Arc<Mutex<H>>>>,
+}
+
+impl<H> TerminalRegistry<H> {
+    pub fn new() -> Self {
+        Self { terminals: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn insert(&self, pty_id: &str, handle: H) -> Arc<Mutex<H>> {
+        let handle = Arc::new(Mutex::new(handle));
+        self.terminals.write().insert(pty_id.to_string(), Arc::clone(&handle));
+        handle
+    }
+
+    pub fn get(&self, pty_id: &str) -> Option<Arc<Mutex<H>>> {
+        self.terminals.read().get(pty_id).cloned()
+    }
+
+    pub fn remove(&self, pty_id: &str) {
+        self.terminals.write().remove(pty_id);
+    }
+}
+
+impl<H> Default for TerminalRegistry<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::workspace::Trigger;
+    use crate::terminal::handle::create_test_terminal;
+    use crate::terminal::palette::ThemePalette;
+    use crate::terminal::triggers::TriggerEngine;
+    use std::sync::Barrier;
+    use std::time::{Duration, Instant};
+
+    /// N terminals stream output concurrently through the registry and the
+    /// PTY reader's steps (parse, trigger scan, render) while one of them is
+    /// held locked the whole time (a stuck render, a long search). The others
+    /// must finish regardless, with no chunk waiting anywhere near as long as
+    /// the held lock, and each tab's triggers must see all of its output.
+    #[test]
+    fn concurrent_streams_do_not_stall_each_other() {
+        const TERMINALS: usize = 8;
+        const CHUNKS: usize = 200;
+        const HELD: Duration = Duration::from_secs(2);
+
+        let registry = Arc::new(TerminalRegistry::new());
+        for i in 0..TERMINALS {
+            registry.insert(&format!("pty-{}", i), create_test_terminal(120, 40, 1000));
+        }
+        let engine = Arc::new(TriggerEngine::new());
+        let trigger: Trigger = serde_json::from_value(serde_json::json!({
+            "id": "done", "name": "done", "pattern": r"chunk \d+ finished", "enabled": true, "cooldown": 0.0,
+        }))
+        .unwrap();
+        let triggers = Arc::new(vec![trigger]);
+        let body: String = (0..40)
+            .map(|i| format!("\x1b[3{}mline {} of some streaming output\x1b[0m\r\n", i % 8, i))
+            .collect();
+
+        let start = Arc::new(Barrier::new(TERMINALS));
+        let held = registry.get("pty-0").unwrap();
+        let holder = {
+            let start = Arc::clone(&start);
+            std::thread::spawn(move || {
+                let _guard = held.lock();
+                start.wait();
+                std::thread::sleep(HELD);
+            })
+        };
+
+        let streams: Vec<_> = (1..TERMINALS)
+            .map(|i| {
+                let (registry, engine, triggers) = (Arc::clone(&registry), Arc::clone(&engine), Arc::clone(&triggers));
+                let start = Arc::clone(&start);
+                let body = body.clone();
+                std::thread::spawn(move || {
+                    let pty_id = format!("pty-{}", i);
+                    let palette = ThemePalette::default();
+                    start.wait();
+                    let mut worst = Duration::ZERO;
+                    let mut fires = 0;
+                    for n in 0..CHUNKS {
+                        let chunk = format!("{}chunk {} finished\r\n", body, n);
+                        let requested = Instant::now();
+                        let terminal = registry.get(&pty_id).unwrap();
+                        let mut handle = terminal.lock();
+                        worst = worst.max(requested.elapsed());
+                        handle.feed(chunk.as_bytes(), 0, 0, &palette);
+                        drop(handle);
+                        fires += engine.scan_terminal(&terminal, &pty_id, None, &triggers, false).fires.len();
+                        terminal.lock().render_damage();
+                    }
+                    (worst, fires)
+                })
+            })
+            .collect();
+
+        for (i, stream) in streams.into_iter().enumerate() {
+            let (worst, fires) = stream.join().unwrap();
+            assert!(worst < HELD / 2, "pty-{} stalled {:?} behind another terminal", i + 1, worst);
+            assert_eq!(fires, CHUNKS, "pty-{} missed trigger matches", i + 1);
+        }
+        holder.join().unwrap();
+    }
+}
@@ -1,14 +1,17 @@
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::selection::{Selection, SelectionRange};
 use alacritty_terminal::term::cell::Flags;
-use alacritty_terminal::term::{Term, TermMode};
-use alacritty_terminal::vte::ansi::{Color, NamedColor};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::term::{Term, TermDamage, TermMode};
+use alacritty_terminal::vte::ansi::{Color, NamedColor, Rgb};
 
 /// A rendered viewport frame sent to the frontend.
-#[derive(serde::Serialize, Clone)]
+#[derive(serde::Serialize, Clone, PartialEq)]
 pub struct TerminalFrame {
-    /// Full viewport as ANSI escape sequences (raw UTF-8 bytes).
+    /// Viewport as ANSI escape sequences (raw UTF-8 bytes) — the whole screen,
+    /// or only changed rows when `full` is false.
     /// Sent as bytes to avoid WebView string encoding issues with non-ASCII characters.
     pub ansi: Vec<u8>,
     pub cursor_x: usize,
@@ -22,6 +25,26 @@ pub struct TerminalFrame {
     pub alternate_screen: bool,
     /// Whether there is an active selection
     pub has_selection: bool,
+    /// Whether `ansi` clears and repaints the whole viewport
+    pub full: bool,
+}
+
+/// Rows as last sent to the frontend, so incremental frames can skip rows
+/// that re-render identically.
+#[derive(Default)]
+pub struct FrameCache {
+    rows: Vec<String>,
+    /// Last frame's metadata (with empty `ansi`).
+    last: Option<TerminalFrame>,
+}
+
+impl FrameCache {
+    /// Forget what the frontend has — the next incremental frame is full.
+    /// Call whenever a full frame reaches it by another path.
+    pub fn invalidate(&mut self) {
+        self.rows.clear();
+        self.last = None;
+    }
 }
 
 /// Extract the visible viewport from a Term and produce an ANSI string.
@@ -32,159 +55,256 @@ pub fn render_viewport<T: EventListener>(
     term: &Term<T>,
     ext_selection: Option<&Selection>,
 ) -> TerminalFrame {
-    let content = term.renderable_content();
-    let num_cols = term.columns();
-    let num_lines = term.screen_lines();
-
-    let cursor = content.cursor;
-    let cursor_visible = content.mode.contains(TermMode::SHOW_CURSOR);
-    let display_offset = content.display_offset;
-    let alternate_screen = content.mode.contains(TermMode::ALT_SCREEN);
-    let total_lines = term.grid().total_lines();
-    // Prefer the externally-managed selection over term.selection
-    let selection_range: Option<SelectionRange> = ext_selection
-        .and_then(|s| s.to_range(term))
-        .or(content.selection);
+    let view = Viewport::new(term, ext_selection);
+    let rows: Vec<String> = (0..view.num_lines).map(|row| view.render_row(row)).collect();
 
     // Pre-allocate output — rough estimate: 10 bytes per cell for ANSI + content
-    let mut out = String::with_capacity(num_cols * num_lines * 10);
-
-    // Clear screen and home cursor
-    out.push_str("\x1b[H\x1b[2J");
-
-    let mut prev_fg = Color::Named(NamedColor::Foreground);
-    let mut prev_bg = Color::Named(NamedColor::Background);
-    let mut prev_flags = Flags::empty();
-    let mut current_line: i32 = i32::MIN;
-    // Track active OSC 8 hyperlink URI so we emit open/close at boundaries
-    let mut active_hyperlink_uri: Option<String> = None;
-
-    for indexed in content.display_iter {
-        let point = indexed.point;
-        let cell = indexed.cell;
-
-        // Track line changes for newlines
-        if point.line.0 != current_line {
-            if current_line != i32::MIN {
-                // Close hyperlink before line break
-                if active_hyperlink_uri.is_some() {
-                    out.push_str("\x1b]8;;\x1b\\");
-                    active_hyperlink_uri = None;
-                }
-                // Reset attributes at end of line and emit newline
-                out.push_str("\x1b[0m\r\n");
-                prev_fg = Color::Named(NamedColor::Foreground);
-                prev_bg = Color::Named(NamedColor::Background);
-                prev_flags = Flags::empty();
+    let mut out = String::with_capacity(view.num_cols * view.num_lines * 10);
+    push_full(&mut out, &rows);
+    view.push_cursor(&mut out);
+    view.frame(out.into_bytes(), true)
+}
+
+/// Incremental frame for PTY output: re-renders only the rows alacritty
+/// reports damaged and sends those whose output changed since the last
+/// frame. Returns None when nothing visible changed, else the frame and the
+/// size a full redraw would have had (for diagnostics).
+pub fn render_damage<T: EventListener>(
+    term: &mut Term<T>,
+    ext_selection: Option<&Selection>,
+    cache: &mut FrameCache,
+) -> Option<(TerminalFrame, usize)> {
+    let damaged: Option<Vec<usize>> = match term.damage() {
+        TermDamage::Full => None,
+        TermDamage::Partial(lines) => Some(lines.map(|bounds| bounds.line).collect()),
+    };
+    term.reset_damage();
+
+    let view = Viewport::new(term, ext_selection);
+    let mut full = cache.rows.len() != view.num_lines;
+    let mut out = String::new();
+    if full {
+        cache.rows = (0..view.num_lines).map(|row| view.render_row(row)).collect();
+    } else {
+        let rows = damaged.unwrap_or_else(|| (0..view.num_lines).collect());
+        for row in rows.into_iter().filter(|&row| row < view.num_lines) {
+            let rendered = view.render_row(row);
+            if rendered != cache.rows[row] {
+                out.push_str(&format!("\x1b[{};1H", row + 1));
+                out.push_str(&rendered);
+                out.push_str("\x1b[0m");
+                cache.rows[row] = rendered;
             }
-            current_line = point.line.0;
         }
+    }
 
-        // Skip wide char spacers (the trailing cell of a double-width char)
-        if cell.flags.contains(Flags::WIDE_CHAR_SPACER)
-            || cell.flags.contains(Flags::LEADING_WIDE_CHAR_SPACER)
-        {
-            continue;
+    let full_len = FULL_PREFIX.len()
+        + cache.rows.iter().map(String::len).sum::<usize>()
+        + ROW_SEPARATOR.len() * view.num_lines.saturating_sub(1)
+        + "\x1b[0m".len();
+    // Most rows changed (scrolling) — a plain redraw is smaller
+    if full || out.len() >= full_len {
+        full = true;
+        out.clear();
+        push_full(&mut out, &cache.rows);
+    }
+
+    let meta = view.frame(Vec::new(), false);
+    if out.is_empty() && cache.last.as_ref() == Some(&meta) {
+        return None;
+    }
+    cache.last = Some(meta);
+
+    let mut cursor = String::new();
+    view.push_cursor(&mut cursor);
+    out.push_str(&cursor);
+    Some((view.frame(out.into_bytes(), full), full_len + cursor.len()))
+}
+
+/// Clear screen and home cursor
+const FULL_PREFIX: &str = "\x1b[H\x1b[2J";
+/// Reset attributes at end of line and emit newline
+const ROW_SEPARATOR: &str = "\x1b[0m\r\n";
+
+fn push_full(out: &mut String, rows: &[String]) {
+    out.push_str(FULL_PREFIX);
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push_str(ROW_SEPARATOR);
         }
+        out.push_str(row);
+    }
+    // Reset at end
+    out.push_str("\x1b[0m");
+}
 
-        // Handle OSC 8 hyperlink transitions
-        let cell_uri = cell.hyperlink().map(|h| h.uri().to_string());
-        match (&active_hyperlink_uri, &cell_uri) {
-            (None, Some(uri)) => {
-                // Open new hyperlink
-                out.push_str(&format!("\x1b]8;;{}\x1b\\", uri));
-                active_hyperlink_uri = Some(uri.clone());
-            }
-            (Some(prev), Some(uri)) if prev != uri => {
-                // Close old, open new
-                out.push_str("\x1b]8;;\x1b\\");
-                out.push_str(&format!("\x1b]8;;{}\x1b\\", uri));
-                active_hyperlink_uri = Some(uri.clone());
+/// Everything row rendering needs from one snapshot of the terminal.
+struct Viewport<'a, T> {
+    term: &'a Term<T>,
+    colors: &'a Colors,
+    num_cols: usize,
+    num_lines: usize,
+    cursor: Point,
+    cursor_visible: bool,
+    display_offset: usize,
+    alternate_screen: bool,
+    selection_range: Option<SelectionRange>,
+    /// With OSC 10/11 overrides, default-colored cells need explicit SGR after
+    /// every reset — xterm.js would otherwise paint them in the theme colors.
+    defaults_overridden: bool,
+}
+
+impl<'a, T: EventListener> Viewport<'a, T> {
+    fn new(term: &'a Term<T>, ext_selection: Option<&Selection>) -> Self {
+        let content = term.renderable_content();
+        let colors = term.colors();
+        Self {
+            term,
+            colors,
+            num_cols: term.columns(),
+            num_lines: term.screen_lines(),
+            cursor: content.cursor.point,
+            cursor_visible: content.mode.contains(TermMode::SHOW_CURSOR),
+            display_offset: content.display_offset,
+            alternate_screen: content.mode.contains(TermMode::ALT_SCREEN),
+            // Prefer the externally-managed selection over term.selection
+            selection_range: ext_selection
+                .and_then(|s| s.to_range(term))
+                .or(content.selection),
+            defaults_overridden: colors[NamedColor::Foreground].is_some()
+                || colors[NamedColor::Background].is_some(),
+        }
+    }
+
+    /// One viewport row as ANSI, starting from reset attributes. Leaves
+    /// attributes set (the caller resets) but closes any hyperlink.
+    fn render_row(&self, row: usize) -> String {
+        let mut out = String::with_capacity(self.num_cols * 10);
+        let line = Line(row as i32 - self.display_offset as i32);
+        let grid_row = &self.term.grid()[line];
+
+        let mut prev_fg = Color::Named(NamedColor::Foreground);
+        let mut prev_bg = Color::Named(NamedColor::Background);
+        let mut prev_flags = Flags::empty();
+        let mut force_sgr = self.defaults_overridden;
+        // Track active OSC 8 hyperlink URI so we emit open/close at boundaries
+        let mut active_hyperlink_uri: Option<String> = None;
+
+        for col in 0..self.num_cols {
+            let point = Point::new(line, Column(col));
+            let cell = &grid_row[Column(col)];
+
+            // Skip wide char spacers (the trailing cell of a double-width char)
+            if cell.flags.contains(Flags::WIDE_CHAR_SPACER)
+                || cell.flags.contains(Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
             }
-            (Some(_), None) => {
-                // Close hyperlink
-                out.push_str("\x1b]8;;\x1b\\");
-                active_hyperlink_uri = None;
+
+            // Handle OSC 8 hyperlink transitions
+            let cell_uri = cell.hyperlink().map(|h| h.uri().to_string());
+            match (&active_hyperlink_uri, &cell_uri) {
+                (None, Some(uri)) => {
+                    // Open new hyperlink
+                    out.push_str(&format!("\x1b]8;;{}\x1b\\", uri));
+                    active_hyperlink_uri = Some(uri.clone());
+                }
+                (Some(prev), Some(uri)) if prev != uri => {
+                    // Close old, open new
+                    out.push_str("\x1b]8;;\x1b\\");
+                    out.push_str(&format!("\x1b]8;;{}\x1b\\", uri));
+                    active_hyperlink_uri = Some(uri.clone());
+                }
+                (Some(_), None) => {
+                    // Close hyperlink
+                    out.push_str("\x1b]8;;\x1b\\");
+                    active_hyperlink_uri = None;
+                }
+                _ => {} // Same link or both None — no change
             }
-            _ => {} // Same link or both None — no change
-        }
 
-        // Toggle INVERSE for selected cells so they appear highlighted
-        let mut flags = cell.flags;
-        if let Some(ref sel) = selection_range {
-            if sel.contains(point) {
-                flags.toggle(Flags::INVERSE);
+            // Toggle INVERSE for selected cells so they appear highlighted
+            let mut flags = cell.flags;
+            if let Some(ref sel) = self.selection_range {
+                if sel.contains(point) {
+                    flags.toggle(Flags::INVERSE);
+                }
             }
-        }
 
-        // Emit SGR changes if attributes differ
-        let needs_sgr = cell.fg != prev_fg || cell.bg != prev_bg || flags != prev_flags;
-        if needs_sgr {
-            emit_sgr(&mut out, cell.fg, cell.bg, flags);
-            prev_fg = cell.fg;
-            prev_bg = cell.bg;
-            prev_flags = flags;
-        }
+            // Emit SGR changes if attributes differ
+            let needs_sgr = force_sgr || cell.fg != prev_fg || cell.bg != prev_bg || flags != prev_flags;
+            if needs_sgr {
+                emit_sgr(&mut out, self.colors, cell.fg, cell.bg, flags);
+                prev_fg = cell.fg;
+                prev_bg = cell.bg;
+                prev_flags = flags;
+                force_sgr = false;
+            }
 
-        // Output the character
-        // Control characters (tab, etc.) must be emitted as spaces — the grid
-        // already reflects their visual effect (cursor movement / tab stops).
-        // Emitting them raw would cause xterm.js to re-interpret them, e.g. a
-        // tab in an 86-col grid produces 8+85 = 93 visible columns → line wrap.
-        let c = cell.c;
-        if c == '\0' || c == ' ' || c.is_ascii_control() {
-            out.push(' ');
-        } else {
-            out.push(c);
-        }
+            // Output the character
+            // Control characters (tab, etc.) must be emitted as spaces — the grid
+            // already reflects their visual effect (cursor movement / tab stops).
+            // Emitting them raw would cause xterm.js to re-interpret them, e.g. a
+            // tab in an 86-col grid produces 8+85 = 93 visible columns → line wrap.
+            let c = cell.c;
+            if c == '\0' || c == ' ' || c.is_ascii_control() {
+                out.push(' ');
+            } else {
+                out.push(c);
+            }
 
-        // Append zero-width characters
-        if let Some(zerowidth) = cell.zerowidth() {
-            for &zw in zerowidth {
-                out.push(zw);
+            // Append zero-width characters
+            if let Some(zerowidth) = cell.zerowidth() {
+                for &zw in zerowidth {
+                    out.push(zw);
+                }
             }
         }
-    }
 
-    // Close any open hyperlink
-    if active_hyperlink_uri.is_some() {
-        out.push_str("\x1b]8;;\x1b\\");
+        // Close hyperlink before line break
+        if active_hyperlink_uri.is_some() {
+            out.push_str("\x1b]8;;\x1b\\");
+        }
+        out
     }
 
-    // Reset at end
-    out.push_str("\x1b[0m");
-
-    // Position cursor (hidden when scrolled into history)
-    if cursor_visible && display_offset == 0 {
-        out.push_str("\x1b[?25h"); // Re-show cursor (may have been hidden by scrollback)
-        let cursor_viewport_line = cursor.point.line.0;
-        if cursor_viewport_line >= 0 {
-            let cy = cursor_viewport_line as usize + 1; // 1-based
-            let cx = cursor.point.column.0 + 1; // 1-based
-            out.push_str(&format!("\x1b[{};{}H", cy, cx));
+    /// Position cursor (hidden when scrolled into history)
+    fn push_cursor(&self, out: &mut String) {
+        if self.cursor_visible && self.display_offset == 0 {
+            out.push_str("\x1b[?25h"); // Re-show cursor (may have been hidden by scrollback)
+            let cursor_viewport_line = self.cursor.line.0;
+            if cursor_viewport_line >= 0 {
+                let cy = cursor_viewport_line as usize + 1; // 1-based
+                let cx = self.cursor.column.0 + 1; // 1-based
+                out.push_str(&format!("\x1b[{};{}H", cy, cx));
+            }
+        } else if self.display_offset > 0 {
+            out.push_str("\x1b[?25l"); // Hide cursor when browsing scrollback
         }
-    } else if display_offset > 0 {
-        out.push_str("\x1b[?25l"); // Hide cursor when browsing scrollback
     }
 
-    TerminalFrame {
-        ansi: out.into_bytes(),
-        cursor_x: cursor.point.column.0,
-        cursor_y: {
-            let line = cursor.point.line.0 + display_offset as i32;
-            if line >= 0 { line as usize } else { 0 }
-        },
-        cursor_visible,
-        display_offset,
-        total_lines,
-        alternate_screen,
-        has_selection: selection_range.is_some(),
+    fn frame(&self, ansi: Vec<u8>, full: bool) -> TerminalFrame {
+        TerminalFrame {
+            ansi,
+            cursor_x: self.cursor.column.0,
+            cursor_y: {
+                let line = self.cursor.line.0 + self.display_offset as i32;
+                if line >= 0 { line as usize } else { 0 }
+            },
+            cursor_visible: self.cursor_visible,
+            display_offset: self.display_offset,
+            total_lines: self.term.grid().total_lines(),
+            alternate_screen: self.alternate_screen,
+            has_selection: self.selection_range.is_some(),
+            full,
+        }
     }
 }
 
 /// Emit SGR escape sequence for the given attributes.
 fn emit_sgr(
     out: &mut String,
+    colors: &Colors,
     fg: Color,
     bg: Color,
     flags: Flags,
@@ -227,10 +347,10 @@ fn emit_sgr(
     }
 
     // Foreground color
-    emit_color_sgr(out, fg, true);
+    emit_color_sgr(out, colors, fg, true);
 
     // Background color
-    emit_color_sgr(out, bg, false);
+    emit_color_sgr(out, colors, bg, false);
 
     out.push('m');
 }
@@ -241,11 +361,18 @@ fn emit_sgr(
 /// (not resolved RGB values) so that xterm.js resolves them through its theme.
 /// If we looked up alacritty_terminal's default palette and emitted RGB, the
 /// colors would not match the user's xterm.js theme (e.g. Tokyo Night).
+/// The exception is a palette entry a program changed (OSC 4/10/11): that
+/// one is emitted as RGB, since xterm.js only knows the theme's value.
 fn emit_color_sgr(
     out: &mut String,
+    colors: &Colors,
     color: Color,
     is_fg: bool,
 ) {
+    let color = match palette_override(colors, color) {
+        Some(rgb) => Color::Spec(rgb),
+        None => color,
+    };
     match color {
         Color::Named(name) => {
             let code = match name {
@@ -312,3 +439,73 @@ fn emit_color_sgr(
         }
     }
 }
+
+/// The program-set value for a cell color's palette entry, if any.
+fn palette_override(colors: &Colors, color: Color) -> Option<Rgb> {
+    let index = match color {
+        Color::Spec(_) => return None,
+        Color::Indexed(idx) => idx as usize,
+        Color::Named(name) => match name {
+            // Dim variants share the base entry (dimming is the DIM flag)
+            NamedColor::DimBlack
+            | NamedColor::DimRed
+            | NamedColor::DimGreen
+            | NamedColor::DimYellow
+            | NamedColor::DimBlue
+            | NamedColor::DimMagenta
+            | NamedColor::DimCyan
+            | NamedColor::DimWhite => name as usize - NamedColor::DimBlack as usize,
+            NamedColor::BrightForeground | NamedColor::DimForeground => NamedColor::Foreground as usize,
+            _ => name as usize,
+        },
+    };
+    colors[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    fn screen_text(term: &Term<VoidListener>) -> String {
+        (0..term.screen_lines() as i32)
+            .map(|l| term.bounds_to_string(Point::new(Line(l), Column(0)), Point::new(Line(l), term.last_column())))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Incremental frames applied in order reproduce the screen, sending
+    /// only the rows that changed.
+    #[test]
+    fn incremental_frames_mirror_the_terminal() {
+        let size = TermSize::new(30, 5);
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut mirror = Term::new(Config::default(), &size, VoidListener);
+        let mut processor: Processor = Processor::default();
+        let mut mirror_processor: Processor = Processor::default();
+        let mut cache = FrameCache::default();
+
+        let steps: [&[u8]; 5] = [
+            b"$ ls\r\n\x1b[34mdir\x1b[0m  file\r\n$ ",
+            b"\x1b[1;3H\x1b[31mpatched\x1b[0m",
+            b"\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\",
+            b"a\r\nb\r\nc\r\nd\r\ne\r\n",
+            b"\x1b[?1049hfull screen app",
+        ];
+        for (i, step) in steps.iter().enumerate() {
+            processor.advance(&mut term, step);
+            let (frame, full_bytes) = render_damage(&mut term, None, &mut cache).unwrap();
+            assert!(frame.ansi.len() <= full_bytes);
+            if i == 1 || i == 2 {
+                assert!(!frame.full, "step {} should be incremental", i);
+            }
+            mirror_processor.advance(&mut mirror, &frame.ansi);
+            assert_eq!(screen_text(&mirror), screen_text(&term), "step {}", i);
+        }
+        assert!(render_damage(&mut term, None, &mut cache).is_none());
+    }
+}
+
@@ -1,9 +1,12 @@
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::index::{Column, Point};
+use alacritty_terminal::index::{Boundary, Column, Line, Point};
 use alacritty_terminal::term::Term;
 use alacritty_terminal::term::search::RegexSearch;
 
+/// Matches returned per call when the caller doesn't set `max_results`.
+const DEFAULT_MAX_RESULTS: usize = 1000;
+
 #[derive(serde::Serialize)]
 pub struct SearchMatch {
     pub line: i32,
@@ -15,7 +18,44 @@ pub struct SearchMatch {
 #[derive(serde::Serialize)]
 pub struct SearchResult {
     pub matches: Vec<SearchMatch>,
+    /// Matches in the whole scope, including those outside this page.
     pub total_count: usize,
+    /// Set when the cap was hit — pass back as `SearchOptions::cursor` for
+    /// the next page. Grid coordinates, so only valid until more output
+    /// arrives.
+    pub next_cursor: Option<SearchCursor>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct SearchCursor {
+    pub line: i32,
+    pub col: usize,
+}
+
+/// Which part of the buffer to search. Lines are grid lines: 0 is the top
+/// of the live screen, negative lines are scrollback.
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchScope {
+    #[default]
+    All,
+    /// The rows currently displayed (follows the scroll position).
+    Screen,
+    Scrollback,
+    Lines { start: i32, end: i32 },
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of literal text.
+    pub regex: bool,
+    /// Only matches with no word character directly before or after.
+    pub whole_word: bool,
+    pub scope: SearchScope,
+    pub max_results: Option<usize>,
+    /// Resume after a previous page's `next_cursor`.
+    pub cursor: Option<SearchCursor>,
 }
 
 /// Search the terminal buffer for a pattern.
@@ -23,82 +63,121 @@ pub fn search_buffer<T: EventListener>(
     term: &mut Term<T>,
     query: &str,
     case_sensitive: bool,
+    options: &SearchOptions,
 ) -> Result<SearchResult, String> {
+    let mut result = SearchResult {
+        matches: Vec::new(),
+        total_count: 0,
+        next_cursor: None,
+    };
     if query.is_empty() {
-        return Ok(SearchResult {
-            matches: Vec::new(),
-            total_count: 0,
-        });
+        return Ok(result);
     }
 
-    // Build regex pattern — escape special chars for literal search
-    let escaped = escape_regex(query);
+    // Literal search escapes the query. Case is always explicit —
+    // RegexSearch otherwise goes case-insensitive for lowercase patterns.
+    let body = if options.regex { query.to_string() } else { escape_regex(query) };
     let pattern = if case_sensitive {
-        escaped
+        format!("(?-i){}", body)
     } else {
-        format!("(?i){}", escaped)
+        format!("(?i){}", body)
     };
 
     let mut regex = RegexSearch::new(&pattern).map_err(|e| format!("Invalid search pattern: {}", e))?;
 
-    let mut matches = Vec::new();
+    let Some((first, last)) = scope_lines(term, options.scope) else {
+        return Ok(result);
+    };
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1);
+    let end = Point::new(last, term.last_column());
 
-    // Search from top of scrollback to bottom of visible area
-    let topmost = term.grid().topmost_line();
-    let bottommost = term.grid().bottommost_line();
+    // Matches before the cursor and past the cap are counted, not returned
+    let mut point = Point::new(first, Column(0));
+    let from = options
+        .cursor
+        .map(|cursor| Point::new(Line(cursor.line), Column(cursor.col.min(term.last_column().0))));
 
-    let mut point = Point::new(topmost, Column(0));
-
-    while point.line <= bottommost {
-        if let Some(m) = term.search_next(
-            &mut regex,
-            point,
-            alacritty_terminal::index::Direction::Right,
-            alacritty_terminal::index::Side::Left,
-            None,
-        ) {
-            let start = *m.start();
-            let end = *m.end();
-
-            // Extract matched text from grid
-            let mut text = String::new();
-            let mut p = start;
-            while p <= end {
-                let cell = &term.grid()[p];
-                if cell.c != '\0' {
-                    text.push(cell.c);
-                }
-                if p.column.0 + 1 < term.columns() {
-                    p.column = Column(p.column.0 + 1);
-                } else {
-                    p.column = Column(0);
-                    p.line += 1;
-                }
-            }
+    while point <= end {
+        let Some(m) = term.regex_search_right(&mut regex, point, end) else {
+            break;
+        };
+        let start = *m.start();
+        let match_end = *m.end();
+        // Continue past the match; stop if that wraps off the bottom
+        let next = match_end.add(term, Boundary::None, 1);
+        let wrapped = next <= match_end;
 
-            matches.push(SearchMatch {
-                line: start.line.0,
-                start_col: start.column.0,
-                end_col: end.column.0,
-                text,
-            });
-
-            // Move past this match
-            point = Point::new(end.line, Column(end.column.0 + 1));
-            if point.column.0 >= term.columns() {
-                point.column = Column(0);
-                point.line += 1;
+        if !options.whole_word || is_whole_word(term, start, match_end) {
+            result.total_count += 1;
+            if from.is_some_and(|from| start < from) || result.next_cursor.is_some() {
+                // Outside this page
+            } else if result.matches.len() == max_results {
+                result.next_cursor = Some(SearchCursor { line: start.line.0, col: start.column.0 });
+            } else {
+                result.matches.push(SearchMatch {
+                    line: start.line.0,
+                    start_col: start.column.0,
+                    end_col: match_end.column.0,
+                    text: match_text(term, start, match_end),
+                });
             }
-        } else {
+        }
+
+        if wrapped {
             break;
         }
+        point = next;
+    }
+
+    Ok(result)
+}
+
+/// First and last grid line of a scope, clamped to the buffer. None when
+/// the scope is empty (e.g. scrollback before any has accumulated).
+fn scope_lines<T: EventListener>(term: &Term<T>, scope: SearchScope) -> Option<(Line, Line)> {
+    let topmost = term.grid().topmost_line();
+    let bottommost = term.grid().bottommost_line();
+    let (first, last) = match scope {
+        SearchScope::All => (topmost, bottommost),
+        SearchScope::Screen => {
+            let top = Line(-(term.grid().display_offset() as i32));
+            (top, top + (term.screen_lines() as i32 - 1))
+        }
+        SearchScope::Scrollback => (topmost, Line(-1)),
+        SearchScope::Lines { start, end } => (Line(start).max(topmost), Line(end).min(bottommost)),
+    };
+    (first <= last).then_some((first, last))
+}
+
+/// Text of a match, read from the grid.
+fn match_text<T: EventListener>(term: &Term<T>, start: Point, end: Point) -> String {
+    let mut text = String::new();
+    let mut p = start;
+    while p <= end {
+        let cell = &term.grid()[p];
+        if cell.c != '\0' {
+            text.push(cell.c);
+        }
+        if p.column.0 + 1 < term.columns() {
+            p.column = Column(p.column.0 + 1);
+        } else {
+            p.column = Column(0);
+            p.line += 1;
+        }
     }
+    text
+}
 
-    let total_count = matches.len();
-    Ok(SearchResult {
-        matches,
-        total_count,
-    })
+/// True when neither neighbour of the match (on its own rows) is a word
+/// character.
+fn is_whole_word<T: EventListener>(term: &Term<T>, start: Point, end: Point) -> bool {
+    let is_word = |p: Point| {
+        let c = term.grid()[p].c;
+        c.is_alphanumeric() || c == '_'
+    };
+    let before = start.column.0 > 0 && is_word(Point::new(start.line, start.column - 1));
+    let after = end.column < term.last_column() && is_word(Point::new(end.line, end.column + 1));
+    !before && !after
 }
 
 /// Escape regex special characters for literal matching.
@@ -116,3 +195,65 @@ fn escape_regex(s: &str) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    fn term(text: &str) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 3), VoidListener);
+        let mut processor: Processor = Processor::default();
+        processor.advance(&mut term, text.as_bytes());
+        term
+    }
+
+    fn found(result: &SearchResult) -> Vec<(i32, usize)> {
+        result.matches.iter().map(|m| (m.line, m.start_col)).collect()
+    }
+
+    #[test]
+    fn regex_whole_word_and_case_modes() {
+        let mut t = term("foo food Foo\r\nbar f00 foo");
+        let literal = search_buffer(&mut t, "foo", false, &SearchOptions::default()).unwrap();
+        assert_eq!(found(&literal), vec![(0, 0), (0, 4), (0, 9), (1, 8)]);
+
+        let case = search_buffer(&mut t, "foo", true, &SearchOptions::default()).unwrap();
+        assert_eq!(case.total_count, 3);
+
+        let word = SearchOptions { whole_word: true, ..Default::default() };
+        assert_eq!(found(&search_buffer(&mut t, "foo", false, &word).unwrap()), vec![(0, 0), (0, 9), (1, 8)]);
+
+        let regex = SearchOptions { regex: true, ..Default::default() };
+        assert_eq!(found(&search_buffer(&mut t, "f[o0]{2}", true, &regex).unwrap()), vec![(0, 0), (0, 4), (1, 4), (1, 8)]);
+        assert!(search_buffer(&mut t, "(", false, &regex).is_err());
+    }
+
+    #[test]
+    fn scopes_and_paging() {
+        let mut t = term("a1\r\na2\r\na3\r\na4\r\na5");
+        let scrollback = SearchOptions { scope: SearchScope::Scrollback, ..Default::default() };
+        assert_eq!(found(&search_buffer(&mut t, "a", false, &scrollback).unwrap()), vec![(-2, 0), (-1, 0)]);
+        let screen = SearchOptions { scope: SearchScope::Screen, ..Default::default() };
+        assert_eq!(search_buffer(&mut t, "a", false, &screen).unwrap().total_count, 3);
+        let lines = SearchOptions { scope: SearchScope::Lines { start: -1, end: 0 }, ..Default::default() };
+        assert_eq!(found(&search_buffer(&mut t, "a", false, &lines).unwrap()), vec![(-1, 0), (0, 0)]);
+
+        let mut page = SearchOptions { max_results: Some(2), ..Default::default() };
+        let mut all = Vec::new();
+        loop {
+            let result = search_buffer(&mut t, "a", false, &page).unwrap();
+            assert!(result.matches.len() <= 2);
+            assert_eq!(result.total_count, 5);
+            all.extend(found(&result));
+            match result.next_cursor {
+                Some(cursor) => page.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(all, vec![(-2, 0), (-1, 0), (0, 0), (1, 0), (2, 0)]);
+    }
+}
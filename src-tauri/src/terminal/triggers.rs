@@ -0,0 +1,841 @@
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Point};
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::Term;
+use parking_lot::{Mutex, RwLock};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::blocks::BlockTracker;
+use super::handle::TerminalHandle;
+use super::condition::{parse_condition, Condition};
+use crate::state::workspace::{Trigger, TriggerActionEntry};
+
+/// Grid rows regex triggers match against per chunk at most. Unmatched rows
+/// stay in the window until this many newer rows push them out, so patterns
+/// can span chunks.
+const MAX_SCAN_ROWS: u64 = 64;
+
+/// Identical matches for the same trigger+tab inside this window are treated
+/// as TUI repaints (e.g. Claude Code redrawing "Enter to confirm" every frame)
+/// and don't fire again. Redraws refresh the window without firing.
+const DEDUP_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    Regex,
+    PlainText,
+    Variable,
+}
+
+impl MatchMode {
+    /// Effective match mode — `match_mode` wins, `plain_text` is the legacy flag.
+    pub fn of(trigger: &Trigger) -> Self {
+        match trigger.match_mode.as_deref() {
+            Some("regex") => MatchMode::Regex,
+            Some("plain_text") => MatchMode::PlainText,
+            Some("variable") => MatchMode::Variable,
+            _ if trigger.plain_text => MatchMode::PlainText,
+            _ => MatchMode::Regex,
+        }
+    }
+}
+
+/// A trigger that matched and should run its actions.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TriggerFire {
+    pub trigger_id: String,
+    pub trigger_name: String,
+    /// Matched text for regex/plain-text triggers; None for variable triggers.
+    pub matched_text: Option<String>,
+    pub actions: Vec<TriggerActionEntry>,
+}
+
+#[derive(Default)]
+pub struct TriggerOutcome {
+    /// Fired triggers whose actions should run (empty while the tab is suppressed).
+    pub fires: Vec<TriggerFire>,
+    /// The tab's full variable map, present only when a capture changed it.
+    pub variables: Option<HashMap<String, String>>,
+    /// Where matching picks up with the next chunk.
+    pub resume: GridPos,
+}
+
+/// A point in a terminal's text: an absolute line (see `BlockTracker`) and a
+/// byte offset into that row's text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GridPos {
+    pub line: u64,
+    pub offset: usize,
+}
+
+/// The grid text regex triggers match against for one chunk of output.
+/// Matching reads what alacritty rendered, so cursor moves, overwrites and
+/// line wrapping come out as the user sees them.
+pub struct OutputWindow {
+    text: String,
+    /// (absolute line, where the row starts in `text`, bytes of the row skipped)
+    rows: Vec<(u64, usize, usize)>,
+    /// Where to resume when nothing in the window matched.
+    resume: GridPos,
+    redraw: bool,
+}
+
+impl OutputWindow {
+    /// A redraw (a TUI repainting in place) matches the whole screen, relying
+    /// on dedup for text that didn't change. Other output matches from
+    /// `from`, where the previous match ended, through the cursor row.
+    pub fn new<T: EventListener>(term: &Term<T>, blocks: &BlockTracker, from: Option<GridPos>, redraw: bool) -> Self {
+        let cursor = blocks.cursor_line(term);
+        let floor = cursor.saturating_sub(MAX_SCAN_ROWS - 1);
+        let (start, end, resume) = if redraw {
+            let top = cursor.saturating_sub(term.grid().cursor.point.line.0.max(0) as u64);
+            let start = GridPos { line: top.max(floor), offset: 0 };
+            (start, top + term.screen_lines() as u64, GridPos { line: cursor, offset: 0 })
+        } else {
+            let start = from.filter(|pos| (floor..=cursor).contains(&pos.line)).unwrap_or(GridPos { line: floor, offset: 0 });
+            (start, cursor + 1, start)
+        };
+
+        let mut window = OutputWindow { text: String::new(), rows: Vec::new(), resume, redraw };
+        for line in start.line..end {
+            let Some(row) = blocks.to_line(term, line) else {
+                continue;
+            };
+            let text = term.bounds_to_string(Point::new(row, Column(0)), Point::new(row, term.last_column()));
+            let mut skip = if line == start.line { start.offset.min(text.len()) } else { 0 };
+            while !text.is_char_boundary(skip) {
+                skip -= 1;
+            }
+            window.rows.push((line, window.text.len(), skip));
+            window.text.push_str(&text[skip..]);
+            let wrapped = term.grid()[row][term.last_column()].flags.contains(Flags::WRAPLINE);
+            if !wrapped && line + 1 < end {
+                window.text.push('\n');
+            }
+        }
+        window
+    }
+
+    /// The grid position of byte `offset` of the window's text.
+    fn position(&self, offset: usize) -> Option<GridPos> {
+        let &(line, start, skip) = self.rows.iter().rev().find(|(_, start, _)| *start <= offset)?;
+        Some(GridPos { line, offset: skip + offset - start })
+    }
+}
+
+/// The end of the terminal's text so far (the cursor row's current text),
+/// for skipping output that shouldn't be matched, e.g. restored scrollback.
+pub fn grid_end<T: EventListener>(term: &Term<T>, blocks: &BlockTracker) -> GridPos {
+    let row = term.grid().cursor.point.line;
+    let text = term.bounds_to_string(Point::new(row, Column(0)), Point::new(row, term.last_column()));
+    GridPos { line: blocks.cursor_line(term), offset: text.len() }
+}
+
+/// Matching state of one tab.
+#[derive(Default)]
+struct TabTriggers {
+    variables: HashMap<String, String>,
+    /// Restore/auto-resume window: variables are still extracted but actions don't run.
+    suppressed: bool,
+    /// trigger_id → last fire
+    cooldowns: HashMap<String, Instant>,
+    /// trigger_id → (matched text, seen at)
+    last_matches: HashMap<String, (String, Instant)>,
+    /// trigger_id → (last result, variables snapshot)
+    transitions: HashMap<String, (bool, String)>,
+    calls: u64,
+    total_time_us: u64,
+    match_count: u64,
+}
+
+#[derive(serde::Serialize)]
+pub struct TriggerStats {
+    pub calls: u64,
+    pub total_time_us: u64,
+    pub avg_time_us: u64,
+    pub match_count: u64,
+    pub sizes: TriggerStatsSizes,
+}
+
+#[derive(serde::Serialize)]
+pub struct TriggerStatsSizes {
+    pub tabs: usize,
+    pub cooldowns_triggers: usize,
+    pub cooldowns_total_entries: usize,
+    pub last_matches_triggers: usize,
+    pub last_matches_total_entries: usize,
+    pub regex_cache: usize,
+    pub variables_tabs: usize,
+    pub variables_total_entries: usize,
+    pub variable_transitions_triggers: usize,
+    pub variable_transitions_total_entries: usize,
+    pub suppressed_tabs: usize,
+}
+
+/// Trigger matching state for every tab. Lives in AppState and is fed from
+/// the PTY reader threads, so triggers keep firing with no webview attached.
+/// Like `TerminalRegistry`, the map lock only guards membership: each tab's
+/// state has its own lock, so tabs match their output in parallel.
+#[derive(Default)]
+pub struct TriggerEngine {
+    tabs: RwLock<HashMap<String, Arc<Mutex<TabTriggers>>>>,
+    /// Compiled patterns (None = invalid), shared by every tab. Plain-text
+    /// patterns are keyed with a prefix.
+    regex_cache: RwLock<HashMap<String, Option<Regex>>>,
+    condition_cache: RwLock<HashMap<String, Option<Condition>>>,
+}
+
+impl TriggerEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn tab(&self, tab_id: &str) -> Arc<Mutex<TabTriggers>> {
+        if let Some(tab) = self.tabs.read().get(tab_id) {
+            return Arc::clone(tab);
+        }
+        Arc::clone(self.tabs.write().entry(tab_id.to_string()).or_default())
+    }
+
+    /// Match what a chunk of output left in a terminal's grid, from where the
+    /// previous pass stopped. The terminal is only locked to read the grid
+    /// and to store where the next pass resumes.
+    pub fn scan_terminal<L: EventListener>(
+        &self,
+        terminal: &Mutex<TerminalHandle<L>>,
+        tab_id: &str,
+        workspace_id: Option<&str>,
+        triggers: &[Trigger],
+        redraw: bool,
+    ) -> TriggerOutcome {
+        let window = terminal.lock().trigger_window(redraw);
+        let outcome = self.process_output(tab_id, workspace_id, triggers, &window);
+        terminal.lock().trigger_scan = Some(outcome.resume);
+        outcome
+    }
+
+    /// Match a chunk of output for a tab: regex/plain-text triggers against
+    /// the grid text in `window`, then variable triggers.
+    pub fn process_output(
+        &self,
+        tab_id: &str,
+        workspace_id: Option<&str>,
+        triggers: &[Trigger],
+        window: &OutputWindow,
+    ) -> TriggerOutcome {
+        let started = Instant::now();
+        let tab = self.tab(tab_id);
+        let mut tab = tab.lock();
+        tab.calls += 1;
+
+        let redraw = window.redraw;
+        // Matched text is consumed so it can't match again on later chunks
+        let mut consumed = 0;
+
+        let mut outcome = TriggerOutcome::default();
+        let mut variables_changed = false;
+        let now = Instant::now();
+
+        for trigger in triggers {
+            if !trigger.enabled || trigger.pattern.is_empty() || !in_scope(trigger, tab_id, workspace_id) {
+                continue;
+            }
+            let mode = MatchMode::of(trigger);
+            if mode == MatchMode::Variable {
+                continue;
+            }
+            let Some(re) = self.compiled_regex(&trigger.pattern, mode == MatchMode::PlainText) else {
+                continue;
+            };
+
+            let (matched_text, match_end, captured) = {
+                let Some(caps) = re.captures(&window.text[consumed..]) else {
+                    continue;
+                };
+                let whole = caps.get(0).expect("group 0 always participates");
+                let captured: Vec<Option<String>> = trigger
+                    .variables
+                    .iter()
+                    .map(|v| caps.get(v.group as usize).map(|m| m.as_str().to_string()))
+                    .collect();
+                (whole.as_str().to_string(), whole.end(), captured)
+            };
+
+            // Always consume the matched portion, even if we don't fire (cooldown
+            // or dedup), so the same text can't re-match on every chunk.
+            consumed += match_end;
+
+            if !cooldown_elapsed(&tab.cooldowns, trigger, now) {
+                continue;
+            }
+
+            if let Some((text, seen)) = tab.last_matches.get_mut(&trigger.id) {
+                if *text == matched_text && now.duration_since(*seen) < DEDUP_WINDOW {
+                    if redraw {
+                        *seen = now;
+                    }
+                    continue;
+                }
+            }
+            tab.last_matches.insert(trigger.id.clone(), (matched_text.clone(), now));
+
+            tab.match_count += 1;
+            tab.cooldowns.insert(trigger.id.clone(), now);
+
+            // Extract variables regardless of suppression so state stays correct.
+            for (mapping, raw) in trigger.variables.iter().zip(captured) {
+                let Some(raw) = raw else { continue };
+                let value = match mapping.template.as_deref() {
+                    Some(template) if !template.is_empty() => template.replace('%', &raw),
+                    _ => raw,
+                };
+                if tab.variables.get(&mapping.name) != Some(&value) {
+                    tab.variables.insert(mapping.name.clone(), value);
+                    variables_changed = true;
+                }
+            }
+
+            if !tab.suppressed {
+                outcome.fires.push(TriggerFire {
+                    trigger_id: trigger.id.clone(),
+                    trigger_name: trigger.name.clone(),
+                    matched_text: Some(matched_text),
+                    actions: trigger.actions.clone(),
+                });
+            }
+        }
+
+        if variables_changed {
+            outcome.variables = Some(tab.variables.clone());
+        }
+        // Output seen while suppressed (restores, auto-resume) is skipped for good
+        let resume_at = if tab.suppressed { window.text.len() } else { consumed };
+        outcome.resume = match window.position(resume_at) {
+            Some(pos) if resume_at > 0 && pos > window.resume => pos,
+            _ => window.resume,
+        };
+
+        // Variable triggers run last — captures above may have changed their inputs.
+        let variable_fires = self.evaluate_variables(&mut tab, tab_id, workspace_id, triggers);
+        outcome.fires.extend(variable_fires);
+
+        tab.total_time_us += started.elapsed().as_micros() as u64;
+        outcome
+    }
+
+    /// Evaluate variable-mode triggers for a tab. Fires on a false→true
+    /// transition, or when the condition stays true but variable values change.
+    pub fn evaluate_variable_triggers(
+        &self,
+        tab_id: &str,
+        workspace_id: Option<&str>,
+        triggers: &[Trigger],
+    ) -> Vec<TriggerFire> {
+        let tab = self.tab(tab_id);
+        let mut tab = tab.lock();
+        self.evaluate_variables(&mut tab, tab_id, workspace_id, triggers)
+    }
+
+    fn evaluate_variables(
+        &self,
+        tab: &mut TabTriggers,
+        tab_id: &str,
+        workspace_id: Option<&str>,
+        triggers: &[Trigger],
+    ) -> Vec<TriggerFire> {
+        let now = Instant::now();
+        let snapshot = variables_snapshot(&tab.variables);
+        let mut fires = Vec::new();
+
+        for trigger in triggers {
+            if !trigger.enabled || trigger.pattern.is_empty() || MatchMode::of(trigger) != MatchMode::Variable {
+                continue;
+            }
+            if !in_scope(trigger, tab_id, workspace_id) || !cooldown_elapsed(&tab.cooldowns, trigger, now) {
+                continue;
+            }
+            let Some(condition) = self.compiled_condition(&trigger.pattern) else {
+                continue;
+            };
+
+            let result = condition.evaluate(&tab.variables);
+            let previous = tab.transitions.insert(trigger.id.clone(), (result, snapshot.clone()));
+
+            let changed = match previous {
+                Some((prev_result, prev_snapshot)) => !prev_result || prev_snapshot != snapshot,
+                None => true,
+            };
+            if result && changed {
+                tab.cooldowns.insert(trigger.id.clone(), now);
+                if !tab.suppressed {
+                    fires.push(TriggerFire {
+                        trigger_id: trigger.id.clone(),
+                        trigger_name: trigger.name.clone(),
+                        matched_text: None,
+                        actions: trigger.actions.clone(),
+                    });
+                }
+            }
+        }
+        fires
+    }
+
+    /// Replace a tab's variables and seed variable-trigger transitions with the
+    /// current results without firing — persisted variables restored on
+    /// startup must not look like fresh false→true transitions.
+    pub fn load_variables(&self, tab_id: &str, variables: HashMap<String, String>, triggers: &[Trigger]) {
+        let snapshot = variables_snapshot(&variables);
+        let tab = self.tab(tab_id);
+        let mut tab = tab.lock();
+        for trigger in triggers {
+            if !trigger.enabled || trigger.pattern.is_empty() || MatchMode::of(trigger) != MatchMode::Variable {
+                continue;
+            }
+            if let Some(condition) = self.compiled_condition(&trigger.pattern) {
+                let result = condition.evaluate(&variables);
+                tab.transitions.insert(trigger.id.clone(), (result, snapshot.clone()));
+            }
+        }
+        tab.variables = variables;
+    }
+
+    /// Set (or clear, with `None`) a single variable. Returns the tab's full map.
+    pub fn set_variable(&self, tab_id: &str, name: &str, value: Option<String>) -> HashMap<String, String> {
+        let tab = self.tab(tab_id);
+        let mut tab = tab.lock();
+        match value {
+            Some(v) => {
+                tab.variables.insert(name.to_string(), v);
+            }
+            None => {
+                tab.variables.remove(name);
+            }
+        }
+        tab.variables.clone()
+    }
+
+    pub fn variables(&self, tab_id: &str) -> HashMap<String, String> {
+        let tab = self.tabs.read().get(tab_id).cloned();
+        tab.map(|t| t.lock().variables.clone()).unwrap_or_default()
+    }
+
+    /// Suppress or re-enable action execution for a tab. Output seen while
+    /// suppressed is skipped, so it can't combine with new output into a
+    /// false match.
+    pub fn set_suppressed(&self, tab_id: &str, suppressed: bool) {
+        self.tab(tab_id).lock().suppressed = suppressed;
+    }
+
+    /// Whether the tab is in its restore/auto-resume window.
+    pub fn is_suppressed(&self, tab_id: &str) -> bool {
+        let tab = self.tabs.read().get(tab_id).cloned();
+        tab.is_some_and(|t| t.lock().suppressed)
+    }
+
+    /// Drop all per-tab state (terminal destroyed).
+    pub fn remove_tab(&self, tab_id: &str) {
+        self.tabs.write().remove(tab_id);
+    }
+
+    pub fn stats(&self) -> TriggerStats {
+        let tabs: Vec<_> = self.tabs.read().values().cloned().collect();
+        let (mut calls, mut total_time_us, mut match_count) = (0, 0, 0);
+        let mut sizes = TriggerStatsSizes {
+            tabs: tabs.len(),
+            cooldowns_triggers: 0,
+            cooldowns_total_entries: 0,
+            last_matches_triggers: 0,
+            last_matches_total_entries: 0,
+            regex_cache: self.regex_cache.read().len(),
+            variables_tabs: 0,
+            variables_total_entries: 0,
+            variable_transitions_triggers: 0,
+            variable_transitions_total_entries: 0,
+            suppressed_tabs: 0,
+        };
+        let (mut cooldowns, mut last_matches, mut transitions) = (HashSet::new(), HashSet::new(), HashSet::new());
+        for tab in tabs {
+            let tab = tab.lock();
+            calls += tab.calls;
+            total_time_us += tab.total_time_us;
+            match_count += tab.match_count;
+            sizes.cooldowns_total_entries += tab.cooldowns.len();
+            sizes.last_matches_total_entries += tab.last_matches.len();
+            sizes.variable_transitions_total_entries += tab.transitions.len();
+            sizes.variables_total_entries += tab.variables.len();
+            sizes.variables_tabs += usize::from(!tab.variables.is_empty());
+            sizes.suppressed_tabs += usize::from(tab.suppressed);
+            cooldowns.extend(tab.cooldowns.keys().cloned());
+            last_matches.extend(tab.last_matches.keys().cloned());
+            transitions.extend(tab.transitions.keys().cloned());
+        }
+        sizes.cooldowns_triggers = cooldowns.len();
+        sizes.last_matches_triggers = last_matches.len();
+        sizes.variable_transitions_triggers = transitions.len();
+        TriggerStats {
+            calls,
+            total_time_us,
+            avg_time_us: total_time_us.checked_div(calls).unwrap_or(0),
+            match_count,
+            sizes,
+        }
+    }
+
+    fn compiled_regex(&self, pattern: &str, plain_text: bool) -> Option<Regex> {
+        let key = if plain_text { format!("__pt__{}", pattern) } else { pattern.to_string() };
+        if let Some(compiled) = self.regex_cache.read().get(&key) {
+            return compiled.clone();
+        }
+        let source = if plain_text { plain_text_source(pattern) } else { pattern.to_string() };
+        // (?s): `.` matches newlines so patterns can span rows of the window
+        let compiled = Regex::new(&format!("(?s){}", source)).ok();
+        self.regex_cache.write().insert(key, compiled.clone());
+        compiled
+    }
+
+    fn compiled_condition(&self, expr: &str) -> Option<Condition> {
+        if let Some(compiled) = self.condition_cache.read().get(expr) {
+            return compiled.clone();
+        }
+        let compiled = parse_condition(expr).ok();
+        self.condition_cache.write().insert(expr.to_string(), compiled.clone());
+        compiled
+    }
+}
+
+/// Replace `%name` references with the tab's variables. Unresolved references
+/// are left as-is, or removed when `clear_unresolved` is set.
+pub fn interpolate_variables(text: &str, variables: &HashMap<String, String>, clear_unresolved: bool) -> String {
+    if !text.contains('%') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find('%') {
+        out.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..name_len];
+        if name.is_empty() {
+            out.push('%');
+        } else if let Some(value) = variables.get(name) {
+            out.push_str(value);
+        } else if !clear_unresolved {
+            out.push('%');
+            out.push_str(name);
+        }
+        rest = &after[name_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The directory in a terminal title, via the first prompt pattern (`\u`,
+/// `\h`, `\d`, `\p` placeholders) that matches it. Falls back to the whole
+/// title, like the tab header does.
+pub fn dir_from_title(title: &str, prompt_patterns: &[String]) -> String {
+    prompt_patterns
+        .iter()
+        .filter_map(|pattern| title_pattern(pattern))
+        .find_map(|re| re.captures(title)?.get(1).map(|m| m.as_str().trim().to_string()))
+        .unwrap_or_else(|| title.to_string())
+}
+
+/// Compile a prompt pattern for matching titles, which omit the prompt
+/// character. Mirrors `compilePromptPattern` in `promptPattern.ts`.
+fn title_pattern(pattern: &str) -> Option<Regex> {
+    if pattern.matches("\\d").count() != 1 {
+        return None;
+    }
+    let mut out = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let placeholder = match chars.peek() {
+                Some('h' | 'u') => Some(r"\S+"),
+                Some('d') => Some("(.+?)"),
+                Some('p') => Some("[$#%>]?"),
+                _ => None,
+            };
+            match placeholder {
+                Some(source) => {
+                    out.push_str(source);
+                    chars.next();
+                }
+                None => out.push_str(r"\\"),
+            }
+        } else if c.is_whitespace() {
+            out.push_str(r"\s+");
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        } else {
+            out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+        }
+    }
+    out.push_str(r"\s*$");
+    Regex::new(&out).ok()
+}
+
+/// Detect TUI redraw sequences: cursor-up (`ESC[<n>A`), cursor position
+/// (`ESC[H`, `ESC[<row>;<col>H`/`f`), and erase-display (`ESC[<n>J`).
+pub fn is_redraw(data: &[u8]) -> bool {
+    let mut i = 0;
+    while i + 2 < data.len() {
+        if data[i] != 0x1b || data[i + 1] != b'[' {
+            i += 1;
+            continue;
+        }
+        let mut j = i + 2;
+        let row_start = j;
+        while j < data.len() && data[j].is_ascii_digit() {
+            j += 1;
+        }
+        match data.get(j) {
+            Some(b'A' | b'H' | b'f' | b'J') => return true,
+            Some(b';') if j > row_start => {
+                let col_start = j + 1;
+                let mut k = col_start;
+                while k < data.len() && data[k].is_ascii_digit() {
+                    k += 1;
+                }
+                if k > col_start && matches!(data.get(k), Some(b'H' | b'f')) {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        i += 2;
+    }
+    false
+}
+
+fn in_scope(trigger: &Trigger, tab_id: &str, workspace_id: Option<&str>) -> bool {
+    if let Some(ws) = workspace_id {
+        if !trigger.workspaces.is_empty() && !trigger.workspaces.iter().any(|w| w == ws) {
+            return false;
+        }
+    }
+    trigger.tabs.is_empty() || trigger.tabs.iter().any(|t| t == tab_id)
+}
+
+fn cooldown_elapsed(cooldowns: &HashMap<String, Instant>, trigger: &Trigger, now: Instant) -> bool {
+    match cooldowns.get(&trigger.id) {
+        Some(last) => now.duration_since(*last).as_secs_f64() >= trigger.cooldown,
+        None => true,
+    }
+}
+
+/// Deterministic snapshot of variable values for change detection.
+fn variables_snapshot(variables: &HashMap<String, String>) -> String {
+    let mut entries: Vec<_> = variables.iter().collect();
+    entries.sort();
+    entries.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("\0")
+}
+
+/// Build a regex from a plain-text pattern. Metacharacters are escaped and
+/// whitespace becomes `\s*` (TUIs position text with cursor moves, so gaps in
+/// the stripped output don't match the literal spaces). `(a|b)` groups are
+/// kept as non-capturing alternation; other parentheses are literal.
+fn plain_text_source(pattern: &str) -> String {
+    let mut out = String::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('(') else {
+            out.push_str(&escape_plain_segment(rest));
+            break;
+        };
+        out.push_str(&escape_plain_segment(&rest[..open]));
+        let Some(close) = rest[open + 1..].find(')').map(|i| open + 1 + i) else {
+            // Unmatched paren — the rest is literal
+            out.push_str(&escape_plain_segment(&rest[open..]));
+            break;
+        };
+        let inner = &rest[open + 1..close];
+        if inner.contains('|') {
+            let alternatives: Vec<String> = inner.split('|').map(escape_plain_segment).collect();
+            out.push_str(&format!("(?:{})", alternatives.join("|")));
+        } else {
+            out.push_str(&escape_plain_segment(&rest[open..=close]));
+        }
+        rest = &rest[close + 1..];
+    }
+    out
+}
+
+fn escape_plain_segment(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                out.push_str(r"\s*");
+            }
+            in_whitespace = true;
+        } else {
+            in_whitespace = false;
+            out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::workspace::VariableMapping;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::Processor;
+
+    /// A terminal fed the way the PTY reader feeds it.
+    struct Screen {
+        term: Term<VoidListener>,
+        blocks: BlockTracker,
+        processor: Processor,
+        scan: Option<GridPos>,
+    }
+
+    impl Screen {
+        fn new() -> Self {
+            Self::with_scrollback(10_000)
+        }
+
+        fn with_scrollback(scrollback: usize) -> Self {
+            let config = Config { scrolling_history: scrollback, ..Config::default() };
+            Screen {
+                term: Term::new(config, &TermSize::new(80, 10), VoidListener),
+                blocks: BlockTracker::new(scrollback),
+                processor: Processor::default(),
+                scan: None,
+            }
+        }
+
+        fn output(&mut self, engine: &TriggerEngine, triggers: &[Trigger], data: &[u8]) -> TriggerOutcome {
+            self.blocks.advance(&mut self.term, &mut self.processor, data);
+            let window = OutputWindow::new(&self.term, &self.blocks, self.scan, is_redraw(data));
+            let outcome = engine.process_output("tab", None, triggers, &window);
+            self.scan = Some(outcome.resume);
+            outcome
+        }
+    }
+
+    fn trigger(id: &str, pattern: &str, match_mode: &str) -> Trigger {
+        Trigger {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            pattern: pattern.to_string(),
+            actions: Vec::new(),
+            enabled: true,
+            workspaces: Vec::new(),
+            tabs: Vec::new(),
+            cooldown: 0.0,
+            variables: Vec::new(),
+            plain_text: false,
+            match_mode: Some(match_mode.to_string()),
+            default_id: None,
+            user_modified: false,
+        }
+    }
+
+    #[test]
+    fn plain_text_matches_across_cursor_gaps() {
+        let triggers = vec![trigger("t", "Do you want (to proceed|to continue)?", "plain_text")];
+        let engine = TriggerEngine::new();
+        let out = Screen::new().output(&engine, &triggers, b"Do you\x1b[1Cwant to\x1b[31m continue?");
+        assert_eq!(out.fires.len(), 1);
+    }
+
+    #[test]
+    fn captures_feed_variable_triggers() {
+        let mut capture = trigger("cap", r"session: (\w+)", "regex");
+        capture.variables.push(VariableMapping { name: "sid".to_string(), group: 1, template: None });
+        let on_sid = trigger("var", "sid == \"abc\"", "variable");
+        let triggers = vec![capture, on_sid];
+        let engine = TriggerEngine::new();
+
+        let out = Screen::new().output(&engine, &triggers, b"session: abc\r\n");
+        assert_eq!(out.variables.unwrap().get("sid").map(String::as_str), Some("abc"));
+        assert_eq!(out.fires.iter().map(|f| f.trigger_id.as_str()).collect::<Vec<_>>(), ["cap", "var"]);
+
+        // Condition still true with the same values — no second fire
+        assert!(engine.evaluate_variable_triggers("tab", None, &triggers).is_empty());
+    }
+
+    #[test]
+    fn redraws_of_the_same_text_are_deduplicated() {
+        let triggers = vec![trigger("t", "Enter to confirm", "regex")];
+        let engine = TriggerEngine::new();
+        let mut screen = Screen::new();
+        assert_eq!(screen.output(&engine, &triggers, b"\x1b[2;1HEnter to confirm").fires.len(), 1);
+        assert!(screen.output(&engine, &triggers, b"\x1b[2;1HEnter to confirm").fires.is_empty());
+    }
+
+    #[test]
+    fn suppressed_tabs_extract_variables_without_firing() {
+        let mut capture = trigger("cap", r"port (\d+)", "regex");
+        capture.variables.push(VariableMapping { name: "port".to_string(), group: 1, template: Some(":%".to_string()) });
+        let triggers = vec![capture];
+        let engine = TriggerEngine::new();
+        engine.set_suppressed("tab", true);
+
+        let mut screen = Screen::new();
+        let out = screen.output(&engine, &triggers, b"listening on port 8080\n");
+        assert!(out.fires.is_empty());
+        assert_eq!(engine.variables("tab").get("port").map(String::as_str), Some(":8080"));
+
+        // What was seen while suppressed doesn't fire once unsuppressed
+        engine.set_suppressed("tab", false);
+        assert!(screen.output(&engine, &triggers, b"\r\n").fires.is_empty());
+    }
+
+    #[test]
+    fn titles_name_their_directory_through_prompt_patterns() {
+        let patterns: Vec<String> = [r"\u@\h:\d\p", r"[\u@\h \d]\p", r"\d>", "no directory"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(dir_from_title("dev@box: ~/src/app", &patterns), "~/src/app");
+        assert_eq!(dir_from_title("[dev@box  proj]", &patterns), "proj");
+        assert_eq!(dir_from_title(r"C:\Users\dev>", &patterns), r"C:\Users\dev");
+        assert_eq!(dir_from_title("vim notes.md", &patterns), "vim notes.md");
+    }
+
+    #[test]
+    fn matches_read_the_grid_and_are_consumed() {
+        let triggers = vec![trigger("t", r"error: \w+", "regex")];
+        let engine = TriggerEngine::new();
+        let mut screen = Screen::new();
+
+        // Text erased before the chunk ends was never on screen
+        assert!(screen.output(&engine, &triggers, b"error: oops\r\x1b[2K$ ").fires.is_empty());
+        // A pattern split across chunks still matches
+        assert!(screen.output(&engine, &triggers, b"make\r\nerr").fires.is_empty());
+        let out = screen.output(&engine, &triggers, b"or: linker\r\n");
+        assert_eq!(out.fires[0].matched_text.as_deref(), Some("error: linker"));
+        // Already matched, so more output doesn't fire it again
+        assert!(screen.output(&engine, &triggers, b"done\r\n").fires.is_empty());
+
+        // Rows that scroll through in one chunk are all matched
+        let mut burst = String::new();
+        for i in 0..30 {
+            burst.push_str(&format!("line {}\r\n", i));
+        }
+        burst.push_str("error: late\r\n");
+        let out = screen.output(&engine, &triggers, burst.as_bytes());
+        assert_eq!(out.fires[0].matched_text.as_deref(), Some("error: late"));
+
+        // Still true once scrollback is full and rows rotate out
+        let mut screen = Screen::with_scrollback(20);
+        for i in 0..3 {
+            let out = screen.output(&engine, &triggers, burst.replace("late", &format!("again{}", i)).as_bytes());
+            assert_eq!(out.fires.len(), 1);
+        }
+    }
+}
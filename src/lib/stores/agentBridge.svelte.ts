@@ -7,14 +7,17 @@ import { claudeStateStore } from '$lib/stores/claudeState.svelte';
 import { error as logError, info as logInfo } from '@tauri-apps/plugin-log';
 
 /**
- * Agent Bridge — a bridge between two running Claude agents in different maiTerm panes.
+ * Agent Bridge — a named group of running Claude agents in different maiTerm panes.
  *
  * The human bridges the current tab to another running Claude session (picked from
- * any workspace). maiTerm FORKS that session (`claude --resume <id> --fork-session`)
- * into a fresh split pane beside the caller — an isolated peer with the target's
- * full context. The two agents then converse asynchronously via the
- * `sendToBridgedAgent` MCP tool; every message is injected as a real terminal turn
- * in the recipient's pane, so the human watches (and can interrupt with Esc).
+ * any workspace). maiTerm either connects the existing tab or FORKS that session
+ * (`claude --resume <id> --fork-session`) into a fresh split pane beside the caller —
+ * an isolated peer with the target's full context. Bridging again from a member adds
+ * the next agent to the same group (e.g. frontend + backend + QA). The agents then
+ * converse asynchronously via the `sendToBridgedAgent` MCP tool, broadcasting to the
+ * group or addressing one member by role, name or tab id; every message is injected
+ * as a real terminal turn in the recipient's pane, so the human watches (and can
+ * interrupt with Esc).
  *
  * Identity is stamped by maiTerm (not self-asserted), so the recipient always knows
  * a message is from a peer agent — never confused for the human operator.
@@ -33,9 +36,9 @@ import { error as logError, info as logInfo } from '@tauri-apps/plugin-log';
  * mid-turn double-injection), `hasCompletedTurn` (after a Stop, claudeState's
  * active/idle is trustworthy and we defer to it).
  *
- * Bridges are self-healing: at send time the recipient must still have a live Claude
- * session; if its session id drifted (it resumed) the bridge re-binds rather than
- * breaking, and a closed tab tears the bridge down cleanly.
+ * Groups are self-healing: at send time each recipient must still have a live Claude
+ * session; if its session id drifted (it resumed) the member re-binds rather than
+ * breaking, and a closed tab leaves the group cleanly.
  */
 
 const INJECT_GAP_MS = 120;           // gap between bracketed-paste and the submitting CR
@@ -46,24 +49,23 @@ const FORK_BOOT_TIMEOUT_MS = 15_000; // cap on waiting for the fork to boot befo
 const FORK_SETTLE_MS = 1500;         // extra settle after the fork registers, so its TUI accepts input
 const FORK_INIT_TIMEOUT_MS = 25_000; // if the fork never re-inits on this instance, tell the caller
 
-type BridgeRole = 'caller' | 'fork' | 'peer';
+interface BridgeGroup {
+  id: string;
+  name: string;
+  /** Member tab ids, in join order. */
+  members: string[];
+}
 
-interface BridgeEntry {
-  /** The tab this agent is bridged to. */
-  partnerTabId: string;
-  /** Human-readable label of the partner (for the agent's own awareness). */
-  partnerLabel: string;
+interface MemberEntry {
+  groupId: string;
+  /** What this agent does in the group ("frontend", "qa", …); may be empty. */
+  role: string;
   /** Conversation turn counter (incremented on each message this tab sends). */
   turn: number;
-  /** Partner's last-known Claude session id. Refreshed when the partner re-inits
-   *  after a resume; used to detect a drifted session and re-bind (NOT to break —
-   *  the persisted bridge is authoritative, so a new id means "it resumed"). */
-  partnerSessionId?: string;
-  /** Whether this tab initiated the bridge or is the forked peer. */
-  role: BridgeRole;
-  /** Human-written description of the PARTNER (what it's expert on / how to use it),
-   *  fed into this tab's opener. In-memory only (one-time, not persisted). */
-  purpose?: string;
+  /** This tab's last-known Claude session id. Refreshed when it re-inits after a
+   *  resume; a new id at send time means "it resumed", so the member re-binds
+   *  instead of breaking (the persisted membership is authoritative). */
+  sessionId?: string;
 }
 
 interface DeliveryState {
@@ -75,16 +77,30 @@ interface DeliveryState {
   busyTimer?: ReturnType<typeof setTimeout>;
 }
 
+/** Options for adding a peer to a group (from the picker). */
+export interface BridgeJoinOptions {
+  /** Human-written description of the new peer (what it's expert on / how to use
+   *  it), fed into the caller's opener. One-time, not persisted. */
+  purpose?: string;
+  /** Name for a new group; ignored when the caller is already in one. */
+  groupName?: string;
+  /** Role of the caller; left unchanged when empty. */
+  callerRole?: string;
+  /** Role of the peer being added. */
+  peerRole?: string;
+}
+
 const enc = (s: string) => Array.from(new TextEncoder().encode(s));
 const sleep = (ms: number) => new Promise<void>((r) => setTimeout(r, ms));
 
 function createAgentBridgeStore() {
-  // Both tabs of a bridge get an entry pointing at each other (symmetric).
-  const bridges = new Map<string, BridgeEntry>();
+  const groups = new Map<string, BridgeGroup>();
+  // Every member tab of a group has an entry pointing at its group.
+  const members = new Map<string, MemberEntry>();
   // Delivery state is keyed by the RECIPIENT tab.
   const delivery = new Map<string, DeliveryState>();
-  // Forked partners awaiting init → opener-into-caller (keyed by fork tab id).
-  const pendingOpeners = new Map<string, { callerTabId: string }>();
+  // Forked peers awaiting init → opener-into-caller (keyed by fork tab id).
+  const pendingOpeners = new Map<string, { callerTabId: string; purpose?: string }>();
   // Best-effort cwd label when live OSC cwd isn't available yet.
   const cwdHint = new Map<string, string>();
   // Reactive version bump so UI ($derived) can react to bridge changes.
@@ -116,25 +132,47 @@ function createAgentBridgeStore() {
     return loc.tab.name.replace(/^[⇄↔→]\s*/u, '').trim() || 'agent';
   }
 
+  /** Label plus role, e.g. `"api" (backend)`. */
+  function who(tabId: string): string {
+    const role = members.get(tabId)?.role;
+    return role ? `"${label(tabId)}" (${role})` : `"${label(tabId)}"`;
+  }
+
   function getCwd(tabId: string): string | null {
     const osc = terminalsStore.getOsc(tabId);
     return osc?.cwd ?? osc?.promptCwd ?? cwdHint.get(tabId) ?? null;
   }
 
-  /** Persist this tab's bridge to the backend (or clear it if the in-memory entry is
-   *  gone), so the pairing survives an app restart and can be rehydrated. The live
-   *  routing stays in memory; only the durable pairing is written here. */
+  function groupOf(tabId: string): BridgeGroup | null {
+    const entry = members.get(tabId);
+    return entry ? groups.get(entry.groupId) ?? null : null;
+  }
+
+  /** The other members of `tabId`'s group. */
+  function peersOf(tabId: string): string[] {
+    return groupOf(tabId)?.members.filter((id) => id !== tabId) ?? [];
+  }
+
+  /** Comma-separated members of a group, for notices and openers. */
+  function roster(group: BridgeGroup, exclude?: string): string {
+    return group.members.filter((id) => id !== exclude).map(who).join(', ');
+  }
+
+  /** Persist this tab's membership to the backend (or clear it if the in-memory entry
+   *  is gone), so the group survives an app restart and can be rehydrated. The live
+   *  routing stays in memory; only the durable membership is written here. */
   async function persistBridge(tabId: string) {
     const loc = resolveTab(tabId);
     if (!loc) return; // tab gone — nothing to persist to
-    const bridge = bridges.get(tabId);
-    const payload = bridge
+    const entry = members.get(tabId);
+    const group = entry ? groups.get(entry.groupId) : undefined;
+    const payload: AgentBridge | null = entry && group
       ? {
-          partner_tab_id: bridge.partnerTabId,
-          partner_label: bridge.partnerLabel,
-          partner_session_id: bridge.partnerSessionId ?? null,
-          role: bridge.role,
-          turn: bridge.turn,
+          group_id: group.id,
+          group_name: group.name,
+          role: entry.role,
+          session_id: entry.sessionId ?? null,
+          turn: entry.turn,
         }
       : null;
     try {
@@ -144,6 +182,31 @@ function createAgentBridgeStore() {
     }
   }
 
+  /** Put `tabId` in `group`; callers make sure it isn't in a different one. */
+  function addMember(group: BridgeGroup, tabId: string, role: string | undefined, sessionId: string | undefined) {
+    if (!group.members.includes(tabId)) group.members.push(tabId);
+    const prev = members.get(tabId);
+    members.set(tabId, {
+      groupId: group.id,
+      role: role?.trim() || prev?.role || '',
+      turn: prev?.groupId === group.id ? prev.turn : 0,
+      sessionId: sessionId ?? prev?.sessionId,
+    });
+  }
+
+  /** The caller's group, or a new one holding just the caller. */
+  function ensureGroup(callerTabId: string, opts: BridgeJoinOptions, peerTabId: string): BridgeGroup {
+    const existing = groupOf(callerTabId);
+    if (existing) return existing;
+    const group: BridgeGroup = {
+      id: crypto.randomUUID(),
+      name: opts.groupName?.trim() || `${label(callerTabId)} + ${label(peerTabId)}`,
+      members: [],
+    };
+    groups.set(group.id, group);
+    return group;
+  }
+
   // ─── Injection ──────────────────────────────────────────────────────────────
 
   /** Write a prompt into a tab's PTY as a bracketed paste, then submit with CR.
@@ -177,7 +240,7 @@ function createAgentBridgeStore() {
     // raced the queue into a deadlock (recipient idle, no further Stop, sender waiting).
     if (trustIdle) return true;
     // Post-boot: claudeState is trustworthy — require a LIVE, idle session. No live
-    // state means the partner is dormant/resuming, so queue (don't inject into a
+    // state means the member is dormant/resuming, so queue (don't inject into a
     // shell). Boot window (pre first Stop): trust `ready`.
     if (d.hasCompletedTurn) {
       const st = claudeStateStore.getState(tabId);
@@ -268,54 +331,64 @@ function createAgentBridgeStore() {
 
   // ─── Envelopes (identity stamped by maiTerm) ──────────────────────────────────
 
-  function buildEnvelope(senderTabId: string, message: string, turn: number): string {
-    const name = label(senderTabId);
+  function buildEnvelope(senderTabId: string, message: string, turn: number, broadcast: boolean): string {
+    const group = groupOf(senderTabId);
     const cwd = getCwd(senderTabId);
     const where = cwd ? `, working in ${cwd}` : '';
+    const scope = broadcast
+      ? `to everyone in Agent Bridge group "${group?.name ?? ''}"`
+      : `to you directly (Agent Bridge group "${group?.name ?? ''}")`;
+    const replyTo = members.get(senderTabId)?.role || label(senderTabId);
     return (
-      `⟦AGENT-BRIDGE⟧ Message from "${name}"${where} — a peer AI agent, NOT your human operator. [turn ${turn}]\n` +
-      `Reply with the sendToBridgedAgent tool. If this fully answers the request, you can stop — don't reply just to acknowledge.\n\n` +
+      `⟦AGENT-BRIDGE⟧ Message from ${who(senderTabId)}${where} ${scope} — a peer AI agent, NOT your human operator. [turn ${turn}]\n` +
+      `Reply with the sendToBridgedAgent tool (to: "${replyTo}", or omit "to" to answer the whole group). If this fully answers the request, you can stop — don't reply just to acknowledge.\n\n` +
       message
     );
   }
 
-  function buildOpener(callerTabId: string, partnerTabId: string, forked = true): string {
-    const bridge = bridges.get(callerTabId);
-    const partnerName = bridge?.partnerLabel ?? label(partnerTabId);
-    const cwd = getCwd(partnerTabId);
+  function buildOpener(callerTabId: string, peerTabId: string, forked: boolean, purpose?: string): string {
+    const group = groupOf(callerTabId);
+    const cwd = getCwd(peerTabId);
     const where = cwd ? ` (working in ${cwd})` : '';
     const what = forked
       ? `a peer AI agent forked with the FULL context of that session`
       : `a peer AI agent running in another tab`;
-    const purpose = bridge?.purpose?.trim();
-    const ctx = purpose ? ` Your human operator describes it as: "${purpose}".` : '';
+    const ctx = purpose?.trim() ? ` Your human operator describes it as: "${purpose.trim()}".` : '';
+    const others = group ? peersOf(callerTabId).filter((id) => id !== peerTabId) : [];
+    const rest = others.length ? ` The group also includes ${others.map(who).join(', ')}.` : '';
     return (
-      `⟦AGENT-BRIDGE⟧ You are now bridged to "${partnerName}"${where} — ${what}.${ctx}\n\n` +
+      `⟦AGENT-BRIDGE⟧ ${who(peerTabId)}${where} — ${what} — is now in your Agent Bridge group "${group?.name ?? ''}".${ctx}${rest}\n\n` +
       `Don't message it yet. First check in with your human operator: tell them the bridge is ready, summarize in a sentence what this peer can help with, and propose 2-3 specific things you could ask it that are relevant to your current work. Then wait for the human to say what to consult it about.\n\n` +
-      `When the human gives the go-ahead, use the sendToBridgedAgent tool — open by identifying yourself (who you are, what you're working on) and why you're reaching out, then ask. The peer's replies arrive here as new prompts; when you have what you need, just stop.`
+      `When the human gives the go-ahead, use the sendToBridgedAgent tool (to: "${members.get(peerTabId)?.role || label(peerTabId)}" for this peer, or omit "to" to address the whole group) — open by identifying yourself (who you are, what you're working on) and why you're reaching out, then ask. Replies arrive here as new prompts; when you have what you need, just stop.`
     );
   }
 
-  /** Heads-up delivered to an EXISTING tab that the human just bridged into (it didn't
-   *  initiate and isn't a fork, so prime it like primeFork primes a fork). */
-  function buildExistingBridgeNotice(peerLabel: string): string {
+  /** Heads-up delivered to an EXISTING tab that the human just added to a group (it
+   *  didn't initiate and isn't a fork, so prime it like primeFork primes a fork). */
+  function buildExistingBridgeNotice(tabId: string): string {
+    const group = groupOf(tabId);
+    const role = members.get(tabId)?.role;
+    const yourRole = role ? ` Your role in the group is "${role}".` : '';
     return (
-      `⟦AGENT-BRIDGE⟧ You have been bridged to a peer AI agent ("${peerLabel}") via maiTerm Agent Bridge — a peer agent in another tab, NOT your human operator. ` +
-      `It may reach out to consult you; its messages arrive here as new prompts. Reply with the sendToBridgedAgent tool. ` +
-      `There's nothing to do until its message arrives — carry on with your work.`
+      `⟦AGENT-BRIDGE⟧ You have been added to the maiTerm Agent Bridge group "${group?.name ?? ''}" with ${group ? roster(group, tabId) : 'a peer agent'} — peer agents in other tabs, NOT your human operator.${yourRole} ` +
+      `They may reach out to consult you; their messages arrive here as new prompts. Reply with the sendToBridgedAgent tool. ` +
+      `There's nothing to do until a message arrives — carry on with your work.`
     );
   }
 
   /** Directive injected into the fork to force it to re-initialize as its OWN tab
    *  (a resumed/forked session otherwise inherits the target's initSession and never
    *  re-binds its new MCP connection, so its maiTerm tools stay unusable). */
-  function buildForkInitDirective(forkTabId: string, peerLabel: string): string {
+  function buildForkInitDirective(forkTabId: string): string {
+    const group = groupOf(forkTabId);
+    const role = members.get(forkTabId)?.role;
+    const yourRole = role ? ` Your role in the group is "${role}".` : '';
     return (
       `⟦AGENT-BRIDGE⟧ You are now a FORKED peer agent in a NEW maiTerm tab (id ${forkTabId}). ` +
       `This is a fresh tab with a fresh MCP connection, so you must re-initialize: call your maiterm initSession tool with tabId "${forkTabId}" right now. ` +
       `Disregard any tab id mentioned earlier in this conversation — you are "${forkTabId}" now.\n\n` +
-      `You have been bridged to a peer AI agent ("${peerLabel}") via maiTerm Agent Bridge. ` +
-      `After initializing, reply with a one-line readiness note, then wait — the peer's message will arrive as a new prompt.`
+      `You have been added to the maiTerm Agent Bridge group "${group?.name ?? ''}" with ${group ? roster(group, forkTabId) : 'a peer agent'}.${yourRole} ` +
+      `After initializing, reply with a one-line readiness note, then wait — your peers' messages will arrive as new prompts.`
     );
   }
 
@@ -341,8 +414,7 @@ function createAgentBridgeStore() {
     await sleep(FORK_SETTLE_MS);
     if (!pendingOpeners.has(forkTabId)) return;
 
-    const peerLabel = bridges.get(forkTabId)?.partnerLabel ?? 'your bridged peer';
-    const ok = await injectPrompt(forkTabId, buildForkInitDirective(forkTabId, peerLabel));
+    const ok = await injectPrompt(forkTabId, buildForkInitDirective(forkTabId));
     if (!ok) {
       logError(`agentBridge: failed to prime fork ${forkTabId.slice(0, 8)}`);
       return;
@@ -356,67 +428,97 @@ function createAgentBridgeStore() {
     }, FORK_INIT_TIMEOUT_MS);
   }
 
-  // ─── Lifecycle: bridge / disconnect ────────────────────────────────────────────────
+  // ─── Lifecycle: join / leave ───────────────────────────────────────────────────
 
   function cleanup(tabId: string) {
     const d = delivery.get(tabId);
     if (d?.busyTimer) clearTimeout(d.busyTimer);
     delivery.delete(tabId);
-    bridges.delete(tabId);
+    members.delete(tabId);
     pendingOpeners.delete(tabId);
     cwdHint.delete(tabId);
   }
 
+  /** Resolve a `to` address against `senderTabId`'s peers: tab id, then role, then
+   *  tab name (both case-insensitive). */
+  function resolveRecipient(senderTabId: string, to: string): string | null {
+    const peers = peersOf(senderTabId);
+    const q = to.trim().toLowerCase();
+    return (
+      peers.find((id) => id === to.trim()) ??
+      peers.find((id) => members.get(id)?.role.toLowerCase() === q) ??
+      peers.find((id) => label(id).toLowerCase() === q) ??
+      null
+    );
+  }
+
   return {
     get version() { return version; },
 
     getInternalSizes() {
-      return { bridges: bridges.size, delivery: delivery.size, pending_openers: pendingOpeners.size };
+      return { groups: groups.size, members: members.size, delivery: delivery.size, pending_openers: pendingOpeners.size };
     },
 
     isBridged(tabId: string): boolean {
       void version;
-      return bridges.has(tabId);
+      return members.has(tabId);
+    },
+
+    getGroupId(tabId: string): string | null {
+      void version;
+      return members.get(tabId)?.groupId ?? null;
     },
 
-    getPartnerTabId(tabId: string): string | null {
-      return bridges.get(tabId)?.partnerTabId ?? null;
+    getGroupName(tabId: string): string | null {
+      void version;
+      return groupOf(tabId)?.name ?? null;
+    },
+
+    getRole(tabId: string): string {
+      void version;
+      return members.get(tabId)?.role ?? '';
     },
 
-    getPartnerLabel(tabId: string): string | null {
+    /** Tab-bar tooltip: the group name and the other members. */
+    describeBridge(tabId: string): string | null {
       void version;
-      return bridges.get(tabId)?.partnerLabel ?? null;
+      const group = groupOf(tabId);
+      if (!group) return null;
+      return `Bridged in "${group.name}" with ${roster(group, tabId)}`;
     },
 
     /** For the getBridgedAgent MCP tool. */
     getBridgeInfo(tabId: string) {
-      const bridge = bridges.get(tabId);
-      if (!bridge) return { bridged: false };
+      const group = groupOf(tabId);
+      if (!group) return { bridged: false };
       return {
         bridged: true,
-        partner: {
-          tabId: bridge.partnerTabId,
-          label: bridge.partnerLabel,
-          cwd: getCwd(bridge.partnerTabId),
-          available: tabExists(bridge.partnerTabId),
-        },
+        group: { id: group.id, name: group.name },
+        you: { tabId, label: label(tabId), role: members.get(tabId)?.role ?? '' },
+        members: peersOf(tabId).map((id) => ({
+          tabId: id,
+          label: label(id),
+          role: members.get(id)?.role ?? '',
+          cwd: getCwd(id),
+          available: tabExists(id),
+          online: !!claudeStateStore.getState(id),
+        })),
       };
     },
 
     /**
-     * Fork `target`'s session into a split beside `callerTabId` and bridge the two.
+     * Fork `target`'s session into a split beside `callerTabId` and add the fork to
+     * the caller's group (creating one if the caller isn't bridged yet).
      * `target` comes from the picker (getClaudeSessions / claudeState).
      */
     async establishBridge(
       callerTabId: string,
       target: { sessionId: string; tabName: string; workspaceName: string; cwd: string | null; sshCommand?: string | null; remoteCwd?: string | null },
-      purpose?: string,
-    ): Promise<{ ok: true; partnerTabId: string; partnerLabel: string } | { ok: false; error: string }> {
+      opts: BridgeJoinOptions = {},
+    ): Promise<{ ok: true; partnerTabId: string; groupName: string } | { ok: false; error: string }> {
       const loc = resolveTab(callerTabId);
       if (!loc) return { ok: false, error: 'Caller tab not found.' };
-      if (bridges.has(callerTabId)) return { ok: false, error: 'This tab is already bridged. Disconnect it first.' };
 
-      const partnerLabel = `${target.tabName} · ${target.workspaceName}`;
       const res = await workspacesStore.forkSessionIntoSplit(
         loc.ws.id,
         loc.pane.id,
@@ -430,44 +532,41 @@ function createAgentBridgeStore() {
       );
       if (!res) return { ok: false, error: 'Failed to spawn the forked partner pane.' };
 
-      const partnerTabId = res.newTabId;
-      const callerLabel = `${label(callerTabId)}`;
-      const callerSessionId = claudeStateStore.getState(callerTabId)?.sessionId;
-
-      bridges.set(callerTabId, { partnerTabId, partnerLabel, turn: 0, role: 'caller', purpose: purpose?.trim() || undefined });
-      // The fork's entry knows the caller's session id up front; the caller learns
-      // the fork's session id when the fork initializes (see init() handler).
-      bridges.set(partnerTabId, { partnerTabId: callerTabId, partnerLabel: callerLabel, turn: 0, partnerSessionId: callerSessionId, role: 'fork' });
+      const forkTabId = res.newTabId;
+      const group = ensureGroup(callerTabId, opts, forkTabId);
+      addMember(group, callerTabId, opts.callerRole, claudeStateStore.getState(callerTabId)?.sessionId);
+      // The fork's own session id is learned when it initializes (see init() handler).
+      addMember(group, forkTabId, opts.peerRole, undefined);
       // Caller is an established agent (past its boot window) → trust claudeState
       // immediately (hasCompletedTurn) so the opener can't inject mid-turn. The
-      // forked partner becomes ready when its initSession lands.
-      delivery.set(callerTabId, { ready: true, busy: false, hasCompletedTurn: true, queue: [] });
-      delivery.set(partnerTabId, { ready: false, busy: false, hasCompletedTurn: false, queue: [] });
-      if (target.cwd) cwdHint.set(partnerTabId, target.cwd);
+      // fork becomes ready when its initSession lands.
+      if (!delivery.has(callerTabId)) delivery.set(callerTabId, { ready: true, busy: false, hasCompletedTurn: true, queue: [] });
+      delivery.set(forkTabId, { ready: false, busy: false, hasCompletedTurn: false, queue: [] });
+      if (target.cwd) cwdHint.set(forkTabId, target.cwd);
       const callerCwd = getCwd(callerTabId);
       if (callerCwd) cwdHint.set(callerTabId, callerCwd);
       // The opener fires when the fork actually initializes; primeFork forces that init.
-      pendingOpeners.set(partnerTabId, { callerTabId });
+      pendingOpeners.set(forkTabId, { callerTabId, purpose: opts.purpose });
       bump();
-      // Persist both sides so the bridge survives a restart (rehydrate rebuilds it).
+      // Persist both so the group survives a restart (rehydrate rebuilds it).
       void persistBridge(callerTabId);
-      void persistBridge(partnerTabId);
-      void primeFork(partnerTabId);
+      void persistBridge(forkTabId);
+      void primeFork(forkTabId);
 
-      logInfo(`agentBridge: bridged ${callerTabId.slice(0, 8)} ⇄ ${partnerTabId.slice(0, 8)} (fork of ${target.sessionId.slice(0, 8)})`);
-      return { ok: true, partnerTabId, partnerLabel };
+      logInfo(`agentBridge: forked ${target.sessionId.slice(0, 8)} into ${forkTabId.slice(0, 8)}, group "${group.name}" now has ${group.members.length} members`);
+      return { ok: true, partnerTabId: forkTabId, groupName: group.name };
     },
 
     /**
-     * Bridge `callerTabId` to an ALREADY-RUNNING Claude tab — no fork, no new pane.
-     * For when the split is already set up (e.g. auto-reconnect failed but both agents
-     * are still live) and the human just wants to re-establish the bridge.
+     * Add an ALREADY-RUNNING Claude tab to the caller's group — no fork, no new pane.
+     * Re-selecting a tab that's already in the caller's group repairs it in place (e.g.
+     * a failed auto-reconnect); a caller without a group joins the target's.
      */
     async bridgeExistingTab(
       callerTabId: string,
       targetTabId: string,
-      purpose?: string,
-    ): Promise<{ ok: true; partnerTabId: string; partnerLabel: string } | { ok: false; error: string }> {
+      opts: BridgeJoinOptions = {},
+    ): Promise<{ ok: true; partnerTabId: string; groupName: string } | { ok: false; error: string }> {
       if (callerTabId === targetTabId) return { ok: false, error: 'Cannot bridge a tab to itself.' };
       const callerLoc = resolveTab(callerTabId);
       const targetLoc = resolveTab(targetTabId);
@@ -478,149 +577,185 @@ function createAgentBridgeStore() {
       if (!targetState) return { ok: false, error: 'The target tab has no running Claude session.' };
       const callerState = claudeStateStore.getState(callerTabId);
 
-      const callerLabel = label(callerTabId);
-      const targetLabel = `${targetLoc.tab.name} · ${targetLoc.ws.name}`;
+      const callerGroup = groupOf(callerTabId);
+      const targetGroup = groupOf(targetTabId);
+      // Don't pull a tab out of a DIFFERENT group.
+      if (callerGroup && targetGroup && callerGroup.id !== targetGroup.id) {
+        return { ok: false, error: `"${label(targetTabId)}" is already in the group "${targetGroup.name}". Disconnect it there first.` };
+      }
 
-      // Don't hijack a bridge the target already has with a DIFFERENT agent.
-      const targetPartner = bridges.get(targetTabId)?.partnerTabId;
-      if (targetPartner && targetPartner !== callerTabId) {
-        return { ok: false, error: `"${targetLabel}" is already bridged to another agent. Disconnect it there first.` };
+      // Repairing a member of the caller's own group → reconnect in place without
+      // re-introducing an ongoing conversation. Otherwise a target new to the group
+      // gets a notice, and the caller an opener about the target.
+      const repairing = !!callerGroup && callerGroup.id === targetGroup?.id;
+      const group = callerGroup ?? targetGroup ?? ensureGroup(callerTabId, opts, targetTabId);
+      const joining = callerGroup ? targetTabId : callerTabId;
+      addMember(group, callerTabId, opts.callerRole, callerState?.sessionId);
+      addMember(group, targetTabId, opts.peerRole, targetState.sessionId);
+      // Both are established agents — both ready, both trust claudeState immediately.
+      for (const id of [callerTabId, targetTabId]) {
+        const d = delivery.get(id);
+        if (d) { d.ready = true; d.busy = false; if (d.busyTimer) { clearTimeout(d.busyTimer); d.busyTimer = undefined; } }
+        else delivery.set(id, { ready: true, busy: false, hasCompletedTurn: true, queue: [] });
+        const cwd = getCwd(id); if (cwd) cwdHint.set(id, cwd);
       }
-      // Abandon any stale bridge the caller has with a DIFFERENT agent (notify it).
-      const callerPartner = bridges.get(callerTabId)?.partnerTabId;
-      if (callerPartner && callerPartner !== targetTabId) this.disconnect(callerTabId);
-
-      // Repairing an existing caller<->target pair (e.g. a failed auto-reconnect) →
-      // reconnect in place without re-introducing an ongoing conversation. Otherwise
-      // it's a fresh bridge → run the full intro flow.
-      const repairing = bridges.get(callerTabId)?.partnerTabId === targetTabId;
-      const callerTurn = bridges.get(callerTabId)?.turn ?? 0;
-      const targetTurn = bridges.get(targetTabId)?.turn ?? 0;
-
-      // Symmetric bridge between two established agents — both ready, both trust
-      // claudeState immediately, each records the other's live session id.
-      bridges.set(callerTabId, { partnerTabId: targetTabId, partnerLabel: targetLabel, turn: callerTurn, partnerSessionId: targetState.sessionId, role: 'caller', purpose: purpose?.trim() || undefined });
-      bridges.set(targetTabId, { partnerTabId: callerTabId, partnerLabel: callerLabel, turn: targetTurn, partnerSessionId: callerState?.sessionId, role: 'peer' });
-      delivery.set(callerTabId, { ready: true, busy: false, hasCompletedTurn: true, queue: [] });
-      delivery.set(targetTabId, { ready: true, busy: false, hasCompletedTurn: true, queue: [] });
-      const callerCwd = getCwd(callerTabId); if (callerCwd) cwdHint.set(callerTabId, callerCwd);
-      const targetCwd = getCwd(targetTabId); if (targetCwd) cwdHint.set(targetTabId, targetCwd);
 
       bump();
-      void persistBridge(callerTabId);
-      void persistBridge(targetTabId);
+      for (const id of group.members) void persistBridge(id);
 
       if (repairing) {
-        logInfo(`agentBridge: repaired existing bridge ${callerTabId.slice(0, 8)} ⇄ ${targetTabId.slice(0, 8)}`);
+        logInfo(`agentBridge: repaired ${targetTabId.slice(0, 8)} in group "${group.name}"`);
       } else {
         // Prime the target (it didn't initiate) and have the caller introduce itself.
-        void deliver(targetTabId, buildExistingBridgeNotice(callerLabel));
-        void deliver(callerTabId, buildOpener(callerTabId, targetTabId, false));
-        logInfo(`agentBridge: bridged existing ${callerTabId.slice(0, 8)} ⇄ ${targetTabId.slice(0, 8)} (no fork)`);
+        if (joining === targetTabId) void deliver(targetTabId, buildExistingBridgeNotice(targetTabId));
+        void deliver(callerTabId, buildOpener(callerTabId, targetTabId, false, opts.purpose));
+        logInfo(`agentBridge: ${joining.slice(0, 8)} joined group "${group.name}" (${group.members.length} members, no fork)`);
       }
-      return { ok: true, partnerTabId: targetTabId, partnerLabel: targetLabel };
+      return { ok: true, partnerTabId: targetTabId, groupName: group.name };
     },
 
-    /** Handle a sendToBridgedAgent tool call from `senderTabId`. */
-    async sendFromTab(senderTabId: string, message: string) {
-      const bridge = bridges.get(senderTabId);
-      if (!bridge) {
+    /** Handle a sendToBridgedAgent tool call from `senderTabId`: a direct message
+     *  when `to` is set, otherwise a broadcast to every other member. */
+    async sendFromTab(senderTabId: string, message: string, to?: string) {
+      const entry = members.get(senderTabId);
+      const group = groupOf(senderTabId);
+      if (!entry || !group) {
         return { ok: false, error: 'You are not bridged to any agent. Ask the human to bridge a session via the Agent Bridge picker.' };
       }
-      const recipient = bridge.partnerTabId;
-      if (recipient === senderTabId) {
-        // Corrupt/misrouted bridge (partner points at self) — never inject into the
-        // sender's own terminal. Surface it rather than acting on bad routing.
-        return { ok: false, error: 'Bridge routing error: this tab appears bridged to itself. Ask the human to reconnect the bridge.' };
-      }
-      if (!tabExists(recipient)) {
-        this.disconnect(senderTabId);
-        return { ok: false, error: 'The bridged agent is no longer available (its tab was closed). Bridge closed.' };
-      }
       if (!message || !message.trim()) {
         return { ok: false, error: 'Message is empty.' };
       }
-      // The persisted bridge is authoritative, so a session-id change means the partner
-      // RESUMED (not "a stranger") — re-bind to its new id rather than breaking. If
-      // the partner has no live session it's dormant/resuming: deliver() will queue.
-      const recipState = claudeStateStore.getState(recipient);
-      if (recipState && bridge.partnerSessionId && recipState.sessionId !== bridge.partnerSessionId) {
-        bridge.partnerSessionId = recipState.sessionId;
-        void persistBridge(senderTabId);
-        logInfo(`agentBridge: re-bound ${senderTabId.slice(0, 8)}'s partner to resumed session ${recipState.sessionId.slice(0, 8)}`);
+      // Members whose tab was closed leave the group before we route.
+      for (const id of peersOf(senderTabId)) {
+        if (!tabExists(id)) this.disconnect(id);
+      }
+      if (!members.has(senderTabId)) {
+        return { ok: false, error: 'The other agents in your group are no longer available (their tabs were closed). Bridge closed.' };
+      }
+
+      const broadcast = !to?.trim();
+      let recipients: string[];
+      if (broadcast) {
+        recipients = peersOf(senderTabId);
+      } else {
+        const id = resolveRecipient(senderTabId, to!);
+        if (!id) {
+          const known = peersOf(senderTabId).map(who).join(', ');
+          return { ok: false, error: `No member "${to}" in group "${group.name}". Members: ${known}.` };
+        }
+        recipients = [id];
       }
-      bridge.turn += 1;
+      // Never inject into the sender's own terminal, whatever the routing says.
+      recipients = recipients.filter((id) => id !== senderTabId);
+      if (recipients.length === 0) {
+        return { ok: false, error: 'Bridge routing error: no other member to deliver to. Ask the human to reconnect the bridge.' };
+      }
+
+      entry.turn += 1;
       void persistBridge(senderTabId); // keep the turn counter durable
-      const text = buildEnvelope(senderTabId, message, bridge.turn);
-      const status = await deliver(recipient, text);
-      const recipName = bridge.partnerLabel;
-      if (status === 'delivered') {
-        return { ok: true, delivered: true, recipient: recipName, note: `Delivered to ${recipName}. Their reply will arrive as a new prompt — finish your turn now.` };
+      const text = buildEnvelope(senderTabId, message, entry.turn, broadcast);
+      const delivered: string[] = [];
+      const queued: string[] = [];
+      const offline: string[] = [];
+      const failed: string[] = [];
+      for (const id of recipients) {
+        // The persisted membership is authoritative, so a session-id change means the
+        // member RESUMED (not "a stranger") — re-bind to its new id rather than breaking.
+        // No live session means it's dormant/resuming: deliver() will queue.
+        const st = claudeStateStore.getState(id);
+        const rec = members.get(id);
+        if (st && rec && rec.sessionId !== st.sessionId) {
+          rec.sessionId = st.sessionId;
+          void persistBridge(id);
+          logInfo(`agentBridge: re-bound ${id.slice(0, 8)} to resumed session ${st.sessionId.slice(0, 8)}`);
+        }
+        const status = await deliver(id, text);
+        const name = who(id);
+        if (status === 'delivered') delivered.push(name);
+        else if (status === 'queued') (st ? queued : offline).push(name);
+        else failed.push(name);
       }
-      if (status === 'queued') {
-        const offline = !claudeStateStore.getState(recipient);
-        const note = offline
-          ? `${recipName} is currently offline (its session isn't running). Your message is queued and will be delivered when it resumes.`
-          : `${recipName} is busy; your message is queued and will be delivered when they're free.`;
-        return { ok: true, delivered: false, queued: true, recipient: recipName, note };
+
+      if (failed.length === recipients.length) {
+        return { ok: false, error: 'Delivery failed (could not write to the bridged terminal).' };
       }
-      return { ok: false, error: 'Delivery failed (could not write to the bridged terminal).' };
+      const notes: string[] = [];
+      if (delivered.length) notes.push(`Delivered to ${delivered.join(', ')}.`);
+      if (queued.length) notes.push(`${queued.join(', ')} ${queued.length === 1 ? 'is' : 'are'} busy; queued until free.`);
+      if (offline.length) notes.push(`${offline.join(', ')} ${offline.length === 1 ? 'is' : 'are'} offline (session not running); queued until resumed.`);
+      if (failed.length) notes.push(`Could not write to ${failed.join(', ')}.`);
+      notes.push('Replies will arrive as new prompts — finish your turn now.');
+      return {
+        ok: true,
+        delivered: delivered.length === recipients.length,
+        queued: queued.length + offline.length > 0,
+        recipients: recipients.map(who),
+        note: notes.join(' '),
+      };
     },
 
-    /** Break the bridge from either side and notify the survivor. This is a permanent
-     *  teardown (user-initiated or tab closed) — it clears the persisted pairing too,
-     *  unlike a session-end which only suspends. */
+    /** Take a tab out of its group and notify the others. This is a permanent
+     *  teardown (user-initiated or tab closed) — it clears the persisted membership
+     *  too, unlike a session-end which only suspends. A group left with one member
+     *  is dissolved. */
     disconnect(tabId: string) {
-      const bridge = bridges.get(tabId);
-      if (!bridge) return;
-      const partner = bridge.partnerTabId;
+      const group = groupOf(tabId);
+      if (!group) return;
+      const leaving = label(tabId);
       cleanup(tabId);
-      cleanup(partner);
+      group.members = group.members.filter((id) => id !== tabId);
+      const remaining = [...group.members];
+      const dissolved = remaining.length < 2;
+      if (dissolved) {
+        for (const id of remaining) cleanup(id);
+        groups.delete(group.id);
+      }
       bump();
-      // Clear the durable pairing on both tabs (persistBridge writes null when the
-      // in-memory entry is gone). For a closed tab resolveTab fails and it's skipped.
+      // Clear the durable membership (persistBridge writes null when the in-memory
+      // entry is gone). For a closed tab resolveTab fails and it's skipped.
       void persistBridge(tabId);
-      void persistBridge(partner);
-      // Best-effort notice to the survivor (if it exists and isn't mid-turn).
-      if (tabExists(partner) && claudeStateStore.getState(partner)?.state !== 'active') {
-        void injectPrompt(partner, '⟦AGENT-BRIDGE⟧ The agent you were bridged with has disconnected. The bridge is closed.');
+      for (const id of remaining) void persistBridge(id);
+      // Best-effort notice to the others (if they exist and aren't mid-turn).
+      const notice = dissolved
+        ? `⟦AGENT-BRIDGE⟧ "${leaving}" has disconnected. The bridge is closed.`
+        : `⟦AGENT-BRIDGE⟧ "${leaving}" has left the Agent Bridge group "${group.name}". Remaining: ${roster(group)}.`;
+      for (const id of remaining) {
+        if (tabExists(id) && claudeStateStore.getState(id)?.state !== 'active') void injectPrompt(id, notice);
       }
-      logInfo(`agentBridge: disconnected ${tabId.slice(0, 8)} ⇄ ${partner.slice(0, 8)}`);
+      logInfo(`agentBridge: ${tabId.slice(0, 8)} left group "${group.name}"${dissolved ? ' (dissolved)' : ''}`);
     },
 
     async init() {
       // claude-init-session lands in two situations we care about:
       //   1. A fresh fork completing its handshake (primeFork forced the init).
-      //   2. An already-bridged tab re-initializing after a resume (or a rehydrated
-      //      bridge coming back online) — re-bind it.
+      //   2. A bridged tab re-initializing after a resume (or a rehydrated group
+      //      coming back online) — re-bind it.
       const u1 = await listen<{ tab_id: string | null; session_id: string }>('claude-init-session', (e) => {
         const { tab_id, session_id } = e.payload;
         if (!tab_id) return;
+        const entry = members.get(tab_id);
 
         // Case 1: fork handshake. Proves the fork is up, on THIS instance, and
-        // tool-capable. Record its session id on the caller, mark it ready, send the
-        // opener to the caller.
+        // tool-capable. Record its session id, mark it ready, send the opener to the
+        // caller.
         const po = pendingOpeners.get(tab_id);
         if (po) {
           pendingOpeners.delete(tab_id);
-          const callerBridge = bridges.get(po.callerTabId);
-          if (callerBridge) { callerBridge.partnerSessionId = session_id; void persistBridge(po.callerTabId); }
+          if (entry) { entry.sessionId = session_id; void persistBridge(tab_id); }
           const d = delivery.get(tab_id);
           if (d) { d.ready = true; void flush(tab_id); }
-          if (tabExists(po.callerTabId)) void deliver(po.callerTabId, buildOpener(po.callerTabId, tab_id));
+          if (tabExists(po.callerTabId)) void deliver(po.callerTabId, buildOpener(po.callerTabId, tab_id, true, po.purpose));
           logInfo(`agentBridge: fork ${tab_id.slice(0, 8)} initialized → opener to caller ${po.callerTabId.slice(0, 8)}`);
           return;
         }
 
-        // Case 2: a bridged tab resumed. Refresh the PARTNER's record of this tab's
-        // (possibly new) session id so the partner's self-healing send re-binds, and
-        // mark this tab deliverable again so any queued messages flush.
-        const bridge = bridges.get(tab_id);
-        if (bridge) {
-          const partner = bridges.get(bridge.partnerTabId);
-          if (partner && partner.partnerSessionId !== session_id) {
-            partner.partnerSessionId = session_id;
-            void persistBridge(bridge.partnerTabId);
+        // Case 2: a member resumed. Record its (possibly new) session id so the
+        // others' self-healing send re-binds, and mark it deliverable again so any
+        // queued messages flush.
+        if (entry) {
+          if (entry.sessionId !== session_id) {
+            entry.sessionId = session_id;
+            void persistBridge(tab_id);
           }
           const d = delivery.get(tab_id);
           if (d) {
@@ -654,13 +789,13 @@ function createAgentBridgeStore() {
       });
       unlisteners.push(u2);
 
-      // Session ended (process exit). DON'T tear the bridge down — the agent may resume
-      // (app-restart auto-resume or a manual resume) and re-bind via Case 2 above.
-      // Just suspend live delivery; the durable pairing is kept so it can come back.
-      // Only an explicit disconnect or a closed tab removes the bridge permanently.
+      // Session ended (process exit). DON'T take the tab out of its group — the agent
+      // may resume (app-restart auto-resume or a manual resume) and re-bind via Case 2
+      // above. Just suspend live delivery; the durable membership is kept so it can
+      // come back. Only an explicit disconnect or a closed tab removes it permanently.
       const u3 = await listen<{ session_id: string; tab_id: string | null }>('claude-hook-session-end', (e) => {
         const tabId = e.payload.tab_id;
-        if (!tabId || !bridges.has(tabId)) return;
+        if (!tabId || !members.has(tabId)) return;
         const d = delivery.get(tabId);
         if (d) {
           d.ready = false;
@@ -673,47 +808,47 @@ function createAgentBridgeStore() {
       unlisteners.push(u3);
     },
 
-    /** Rebuild in-memory bridges from persisted agent_bridge fields. Call once after
-     *  workspaces load. Only restores a pair when both tabs exist and reciprocally
-     *  reference each other; orphans are cleared. Last-known session ids are refreshed
+    /** Rebuild in-memory groups from persisted agent_bridge fields. Call once after
+     *  workspaces load. A group is restored when at least two of its member tabs
+     *  exist; a lone member is an orphan and is cleared. Session ids are refreshed
      *  as each agent re-inits (Case 2 above). */
     rehydrate() {
-      const persisted = new Map<string, AgentBridge>();
+      const persisted = new Map<string, Array<{ tabId: string; bridge: AgentBridge }>>();
       for (const ws of workspacesStore.workspaces)
         for (const pane of ws.panes)
-          for (const tab of pane.tabs)
-            if (tab.agent_bridge) persisted.set(tab.id, tab.agent_bridge);
+          for (const tab of pane.tabs) {
+            if (!tab.agent_bridge || members.has(tab.id)) continue; // none, or already live
+            const list = persisted.get(tab.agent_bridge.group_id) ?? [];
+            list.push({ tabId: tab.id, bridge: tab.agent_bridge });
+            persisted.set(tab.agent_bridge.group_id, list);
+          }
 
       let restored = 0;
-      for (const [tabId, al] of persisted) {
-        if (bridges.has(tabId)) continue; // already live this session
-        // A bridge must point at a DIFFERENT tab. A self-reference is corrupt data —
-        // never restore it (it would make the agent its own bridged peer).
-        if (al.partner_tab_id === tabId) {
-          void persistBridge(tabId); // clear the bad entry
+      for (const [groupId, list] of persisted) {
+        const live = groups.get(groupId);
+        if (!groupId || (!live && list.length < 2)) {
+          for (const { tabId } of list) void persistBridge(tabId); // orphan → clear (writes null)
           continue;
         }
-        const partnerAl = persisted.get(al.partner_tab_id);
-        // Require a reciprocal pairing (both tabs present, pointing at each other).
-        if (!partnerAl || partnerAl.partner_tab_id !== tabId) {
-          void persistBridge(tabId); // orphan → clear (no in-memory entry → writes null)
-          continue;
+        const group = live ?? { id: groupId, name: list.find((m) => m.bridge.group_name)?.bridge.group_name || 'Agent Bridge', members: [] };
+        groups.set(groupId, group);
+        for (const { tabId, bridge } of list) {
+          group.members.push(tabId);
+          members.set(tabId, {
+            groupId,
+            role: bridge.role ?? '',
+            turn: bridge.turn ?? 0,
+            sessionId: bridge.session_id ?? undefined,
+          });
+          // Deliverable only once this tab's Claude is live. On a cold restart it isn't
+          // up yet → ready=false; the init handler flips it on resume. If already live
+          // (e.g. webview reload), start ready.
+          const isLive = !!claudeStateStore.getState(tabId);
+          delivery.set(tabId, { ready: isLive, busy: false, hasCompletedTurn: true, queue: [] });
         }
-        bridges.set(tabId, {
-          partnerTabId: al.partner_tab_id,
-          partnerLabel: al.partner_label,
-          turn: al.turn ?? 0,
-          partnerSessionId: al.partner_session_id ?? undefined,
-          role: al.role === 'fork' || al.role === 'peer' ? al.role : 'caller',
-        });
-        // Deliverable only once this tab's Claude is live. On a cold restart it isn't
-        // up yet → ready=false; the init handler flips it on resume. If already live
-        // (e.g. webview reload), start ready.
-        const live = !!claudeStateStore.getState(tabId);
-        delivery.set(tabId, { ready: live, busy: false, hasCompletedTurn: true, queue: [] });
         restored++;
       }
-      if (restored) { bump(); logInfo(`agentBridge: rehydrated ${restored / 2} bridge(s) from persisted state`); }
+      if (restored) { bump(); logInfo(`agentBridge: rehydrated ${restored} group(s) from persisted state`); }
     },
 
     destroy() {
@@ -721,7 +856,8 @@ function createAgentBridgeStore() {
       unlisteners.length = 0;
       if (drainTimer) { clearInterval(drainTimer); drainTimer = undefined; }
       for (const d of delivery.values()) if (d.busyTimer) clearTimeout(d.busyTimer);
-      bridges.clear();
+      groups.clear();
+      members.clear();
       delivery.clear();
       pendingOpeners.clear();
       cwdHint.clear();
@@ -1,4 +1,4 @@
-import type { ClaudeCodeToolRequest, DiffContext, Workspace, Pane, Tab } from '$lib/tauri/types';
+import type { ClaudeCodeToolRequest, DiffContext, Workspace, Pane, SplitDirection, Tab } from '$lib/tauri/types';
 import * as commands from '$lib/tauri/commands';
 import { workspacesStore, navigateToTab } from '$lib/stores/workspaces.svelte';
 import { terminalsStore } from '$lib/stores/terminals.svelte';
@@ -33,6 +33,16 @@ export interface SelectionInfo {
   };
 }
 
+/** Shared arguments of the createTab / splitPane MCP tools. */
+interface CreateTerminalArgs {
+  tabId?: string;
+  name?: string;
+  cwd?: string;
+  command?: string;
+  startClaude?: boolean;
+  prompt?: string;
+}
+
 function createClaudeCodeStore() {
   let connected = $state(false);
   let latestSelection = $state<SelectionInfo | null>(null);
@@ -79,6 +89,10 @@ function createClaudeCodeStore() {
         case 'saveDocument':
           result = await handleSaveDocument(args as { filePath: string });
           break;
+        case 'readEditorBuffer':
+          // Internal: backs the maiterm://tab/<id>/editor MCP resource
+          result = handleReadEditorBuffer(args as { tabId: string });
+          break;
         case 'getCurrentSelection':
         case 'getLatestSelection':
           result = handleGetSelection();
@@ -110,6 +124,10 @@ function createClaudeCodeStore() {
         case 'closeAllDiffTabs':
           await handleCloseAllDiffTabs(request_id);
           return;
+        case 'hookPermissionRequest':
+          // Internal: a "prompt" hook policy rule -- responds when the toast is answered
+          handleHookPermissionRequest(request_id, args as { tabId?: string | null; toolName: string; subject: string; timeout: number });
+          return;
         case 'listWorkspaces':
           result = handleListWorkspaces();
           break;
@@ -177,11 +195,17 @@ function createClaudeCodeStore() {
           result = await handleRestoreArchivedTab(args as { workspaceId?: string; tabId: string });
           break;
         case 'sendToBridgedAgent':
-          result = await handleSendToBridgedAgent(args as { tabId?: string; message: string });
+          result = await handleSendToBridgedAgent(args as { tabId?: string; message: string; to?: string });
           break;
         case 'getBridgedAgent':
           result = handleGetBridgedAgent(args as { tabId?: string });
           break;
+        case 'createTab':
+          result = await handleCreateTab(args as CreateTerminalArgs & { workspaceId?: string; paneId?: string });
+          break;
+        case 'splitPane':
+          result = await handleSplitPane(args as CreateTerminalArgs & { paneId?: string; direction?: string });
+          break;
         // getPreferences, setPreference, createBackup, listWindows handled directly on backend
         default:
           result = { error: `Unknown tool: ${tool}` };
@@ -216,7 +240,7 @@ function createClaudeCodeStore() {
     }
 
     // Trigger engine stats
-    const triggerStats = getTriggerStats();
+    const triggerStats = await getTriggerStats().catch(() => null);
 
     // FPS probe: measure render performance over ~1 second
     const fps = await new Promise<number>((resolve) => {
@@ -343,6 +367,12 @@ function createClaudeCodeStore() {
     return { success: true, filePath: args.filePath, saved: true };
   }
 
+  function handleReadEditorBuffer(args: { tabId: string }) {
+    const entry = getEditorByTabId(args.tabId);
+    if (!entry) return { content: null };
+    return { content: entry.view.state.doc.toString(), filePath: entry.filePath, isDirty: entry.isDirty };
+  }
+
   function handleGetSelection() {
     if (latestSelection) return latestSelection;
     return {
@@ -670,6 +700,75 @@ function createClaudeCodeStore() {
     return { success: true, tabId: args.tabId, displayName: archived.archived_name ?? archived.name };
   }
 
+  // --- Layout tools ---
+
+  /** Initial command for an MCP-created terminal: `command`, or `claude` with an optional prompt. */
+  function initialCommand(args: CreateTerminalArgs): { command: string | null; args?: string[] } | { error: string } {
+    const startClaude = args.startClaude || !!args.prompt;
+    if (startClaude && args.command) return { error: 'Pass either command or startClaude/prompt, not both.' };
+    // The prompt is quoted by the terminal once it knows which shell it types into
+    if (startClaude) return { command: 'claude', args: args.prompt ? [args.prompt] : undefined };
+    return { command: args.command ?? null };
+  }
+
+  async function handleCreateTab(args: CreateTerminalArgs & { workspaceId?: string; paneId?: string }) {
+    const initial = initialCommand(args);
+    if ('error' in initial) return initial;
+
+    let ws: Workspace | undefined;
+    let pane: Pane | undefined;
+    if (args.paneId) {
+      ws = workspacesStore.workspaces.find(w => w.panes.some(p => p.id === args.paneId));
+      pane = ws?.panes.find(p => p.id === args.paneId);
+      if (!ws || !pane) return { error: `Pane not found: ${args.paneId}` };
+    } else if (args.workspaceId) {
+      ws = workspacesStore.workspaces.find(w => w.id === args.workspaceId);
+      if (!ws) return { error: `Workspace not found: ${args.workspaceId}` };
+      pane = ws.panes.find(p => p.id === ws!.active_pane_id) ?? ws.panes[0];
+      if (!pane) return { error: `Workspace has no panes: ${args.workspaceId}` };
+    } else {
+      const target = resolvePane(args.tabId);
+      if (!target) return { error: 'No active pane' };
+      ({ ws, pane } = target);
+    }
+
+    const tab = await workspacesStore.createTab(ws.id, pane.id, args.name ?? `Terminal ${pane.tabs.length + 1}`, {
+      cwd: args.cwd ?? null,
+      command: initial.command,
+      args: initial.args,
+    });
+    if (args.name) await workspacesStore.renameTab(ws.id, pane.id, tab.id, args.name, true);
+    return { success: true, tabId: tab.id, paneId: pane.id, workspaceId: ws.id, command: initial.command, args: initial.args };
+  }
+
+  async function handleSplitPane(args: CreateTerminalArgs & { paneId?: string; direction?: string }) {
+    const initial = initialCommand(args);
+    if ('error' in initial) return initial;
+    if (args.direction && args.direction !== 'horizontal' && args.direction !== 'vertical') {
+      return { error: `Invalid direction: ${args.direction}. Use 'horizontal' (side by side) or 'vertical' (stacked).` };
+    }
+
+    let target: { ws: Workspace; pane: Pane } | null;
+    if (args.paneId) {
+      const ws = workspacesStore.workspaces.find(w => w.panes.some(p => p.id === args.paneId));
+      const pane = ws?.panes.find(p => p.id === args.paneId);
+      target = ws && pane ? { ws, pane } : null;
+      if (!target) return { error: `Pane not found: ${args.paneId}` };
+    } else {
+      target = resolvePane(args.tabId);
+      if (!target) return { error: 'No active pane' };
+    }
+
+    const created = await workspacesStore.splitPaneWithCommand(
+      target.ws.id,
+      target.pane.id,
+      (args.direction ?? 'horizontal') as SplitDirection,
+      { name: args.name, cwd: args.cwd, command: initial.command, args: initial.args },
+    );
+    if (!created) return { error: 'Split failed' };
+    return { success: true, tabId: created.newTabId, paneId: created.newPaneId, workspaceId: target.ws.id, command: initial.command, args: initial.args };
+  }
+
   async function handleSwitchTab(args: { tabId: string }) {
     const loc = findTabLocation(args.tabId);
     if (!loc) return { error: `Tab not found: ${args.tabId}` };
@@ -1084,10 +1183,10 @@ function createClaudeCodeStore() {
 
   // --- Agent Bridge tools ---
 
-  async function handleSendToBridgedAgent(args: { tabId?: string; message: string }) {
+  async function handleSendToBridgedAgent(args: { tabId?: string; message: string; to?: string }) {
     const loc = resolveActiveTab(args.tabId);
     if ('error' in loc) return loc;
-    return agentBridgeStore.sendFromTab(loc.tab.id, args.message);
+    return agentBridgeStore.sendFromTab(loc.tab.id, args.message, args.to);
   }
 
   function handleGetBridgedAgent(args: { tabId?: string }) {
@@ -1202,6 +1301,27 @@ function createClaudeCodeStore() {
     return { sent: true };
   }
 
+  /** Allow/Deny toast for a tool call matched by a "prompt" hook policy rule.
+   *  Dismissing (or letting it expire) leaves the call to Claude's own prompt. */
+  function handleHookPermissionRequest(requestId: string, args: { tabId?: string | null; toolName: string; subject: string; timeout: number }) {
+    const loc = args.tabId ? findTabLocation(args.tabId) : null;
+    const respond = (decision: 'allow' | 'deny' | 'ask') => {
+      commands.claudeCodeRespond(requestId, { decision }).catch(() => {});
+    };
+    const subject = args.subject.length > 200 ? `${args.subject.slice(0, 200)}…` : args.subject;
+    toastStore.addPromptToast({
+      title: loc ? `${args.toolName} in ${loc.tab.name}` : `Claude wants to use ${args.toolName}`,
+      body: subject,
+      source: loc ? { tabId: loc.tab.id } : undefined,
+      duration: args.timeout * 1000,
+      actions: [
+        { label: 'Allow', variant: 'primary', onClick: () => respond('allow') },
+        { label: 'Deny', variant: 'danger', onClick: () => respond('deny') },
+      ],
+      onDismiss: () => respond('ask'),
+    });
+  }
+
   /** Resolve a tab by ID or fall back to the active tab. Returns { workspace, pane, tab } or { error }. */
   function resolveActiveTab(tabId?: string): { workspace: Workspace; pane: Pane; tab: Tab } | { error: string } {
     if (tabId) {
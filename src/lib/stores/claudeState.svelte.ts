@@ -5,16 +5,20 @@ import { terminalsStore } from './terminals.svelte';
 import { workspacesStore } from './workspaces.svelte';
 import { activityStore } from './activity.svelte';
 import { dispatch } from './notificationDispatch';
-import { CLAUDE_RESUME_COMMAND } from '$lib/triggers/defaults';
+import { AGENT_RESUME_COMMANDS } from '$lib/triggers/defaults';
+import type { AgentKind } from '$lib/tauri/types';
 
 /**
- * Claude Code session state per tab, driven by hook events.
+ * Claude Code session state per tab, driven by hook events. Other agent CLIs
+ * (Gemini CLI) have their hooks mapped onto the same events by the backend.
  *
  * State machine:
  *   SessionStart → active (thinking)
  *   UserPromptSubmit → active (thinking)
  *   PreToolUse → active (tool_name set)
  *   PostToolUse → active (tool_name cleared)
+ *   PostToolUseFailure → active (tool_name cleared, lastToolFailure set)
+ *   SubagentStart/SubagentStop → subagent count (state unchanged; Stop and UserPromptSubmit reset it)
  *   Stop → idle (waiting for user input)
  *   Notification(idle_prompt) → idle
  *   Notification(permission_prompt) → permission
@@ -36,8 +40,19 @@ export interface ClaudeTabSession {
   /** Wall-clock ms of the last state change for this tab — drives recency sorting
    *  (e.g. the Agent Bridge picker lists most-recently-active agents first). */
   updatedAt: number;
+  /** Subagents started and not yet stopped. */
+  subagents?: number;
+  /** The most recent tool call failed; cleared by the next successful call or prompt. */
+  lastToolFailure?: { toolName: string; error?: string };
+  /** Agent CLI running the session; absent means Claude Code. */
+  agent?: AgentKind;
 }
 
+const AGENT_LABELS: Record<AgentKind, string> = {
+  claude: 'Claude Code',
+  gemini: 'Gemini CLI',
+};
+
 /** Workspace-level rollup of Claude state across a set of tabs. `idle` is split
  *  into unread/read so the sidebar can show a filled vs hollow "done" dot. */
 export type WorkspaceClaudeState = 'permission' | 'active' | 'idle-unread' | 'idle-read';
@@ -53,14 +68,18 @@ function buildActionString(toolName: string, toolInput: Record<string, unknown>
 function summarizeToolDetail(toolName: string, toolInput: Record<string, unknown> | null): string | undefined {
   if (!toolInput) return undefined;
   switch (toolName) {
-    case 'Bash': {
+    case 'Bash':
+    case 'run_shell_command': {
       const cmd = toolInput.command as string | undefined;
       if (!cmd) return undefined;
       return cmd.length > 50 ? cmd.slice(0, 47) + '...' : cmd;
     }
     case 'Edit':
     case 'Write':
-    case 'Read': {
+    case 'Read':
+    case 'read_file':
+    case 'write_file':
+    case 'replace': {
       const fp = toolInput.file_path as string | undefined;
       if (!fp) return undefined;
       return fp.split('/').pop() || fp;
@@ -95,8 +114,12 @@ function createClaudeStateStore() {
     if (current?.sessionId === sessionId && current?.state === state && current?.toolName === toolName) return;
     // Entering idle fresh = unread; staying idle preserves whatever read flag we had.
     const read = state === 'idle' ? (current?.state === 'idle' ? current.read : false) : undefined;
+    // Subagent count and tool failure outlive individual state changes within a session
+    const carried = current?.sessionId === sessionId
+      ? { subagents: current.subagents, lastToolFailure: current.lastToolFailure, agent: current.agent }
+      : {};
     sessions = new Map(sessions);
-    sessions.set(tabId, { sessionId, state, toolName, toolDetail, read, updatedAt: Date.now() });
+    sessions.set(tabId, { sessionId, state, toolName, toolDetail, read, updatedAt: Date.now(), ...carried });
 
     // Propagate permission state to activityStore tab state so workspace sidebar shows alert.
     // Clear alert when leaving permission state (but only if we set it).
@@ -119,6 +142,18 @@ function createClaudeStateStore() {
     }
   }
 
+  /** Patch subagent/failure info without changing the session's state. */
+  function patchSession(tabId: string, sessionId: string, patch: Pick<ClaudeTabSession, 'subagents' | 'lastToolFailure' | 'agent'>) {
+    const current = sessions.get(tabId);
+    if (!current || current.sessionId !== sessionId) return;
+    sessions = new Map(sessions);
+    sessions.set(tabId, { ...current, ...patch, updatedAt: Date.now() });
+  }
+
+  function agentLabel(tabId: string): string {
+    return AGENT_LABELS[sessions.get(tabId)?.agent ?? 'claude'];
+  }
+
   function clearStaleTimer(tabId: string) {
     const timer = staleTimers.get(tabId);
     if (timer) {
@@ -241,12 +276,13 @@ function createClaudeStateStore() {
     },
 
     async init() {
-      const u1 = await listen<{ session_id: string; tab_id: string; source?: string }>('claude-hook-session-start', (e) => {
-        const { session_id, tab_id, source } = e.payload;
+      const u1 = await listen<{ session_id: string; tab_id: string; source?: string; agent?: AgentKind }>('claude-hook-session-start', (e) => {
+        const { session_id, tab_id, source, agent } = e.payload;
         if (!tab_id) return;
         setState(tab_id, session_id, 'active');
+        if (agent && agent !== 'claude') patchSession(tab_id, session_id, { agent });
         if (source === 'compact') {
-          dispatch('Claude Code', 'Compaction complete', 'info', { tabId: tab_id });
+          dispatch(agentLabel(tab_id), 'Compaction complete', 'info', { tabId: tab_id });
         }
         logInfo(`Claude state: session ${session_id.slice(0, 8)} started (${source ?? 'unknown'}) → tab ${tab_id.slice(0, 8)} = active`);
       });
@@ -265,6 +301,7 @@ function createClaudeStateStore() {
         const { session_id, tab_id } = e.payload;
         if (!tab_id) return;
         setState(tab_id, session_id, 'idle');
+        patchSession(tab_id, session_id, { subagents: 0 });
         setVariable(tab_id, 'claudeAction', '');
       });
       unlisteners.push(u3);
@@ -274,6 +311,7 @@ function createClaudeStateStore() {
         if (!tab_id) return;
         // Clear tool state — new prompt means previous operation ended (possibly interrupted)
         setState(tab_id, session_id, 'active');
+        patchSession(tab_id, session_id, { subagents: 0, lastToolFailure: undefined });
         setVariable(tab_id, 'claudeAction', '');
       });
       unlisteners.push(u4);
@@ -283,7 +321,7 @@ function createClaudeStateStore() {
         if (!tab_id) return;
         if (notification_type === 'permission_prompt') {
           setState(tab_id, session_id, 'permission');
-          dispatch('Claude Code', 'Needs permission approval', 'info', { tabId: tab_id });
+          dispatch(agentLabel(tab_id), 'Needs permission approval', 'info', { tabId: tab_id });
         } else if (notification_type === 'idle_prompt') {
           setState(tab_id, session_id, 'idle');
           // Notification disabled — the Stop hook already notifies when Claude finishes,
@@ -294,12 +332,15 @@ function createClaudeStateStore() {
       });
       unlisteners.push(u5);
 
-      // initSession sets claudeSessionId trigger variable and enables auto-resume directly
-      const u6 = await listen<{ tab_id: string; session_id: string }>('claude-init-session', (e) => {
+      // initSession (or, for other agents, SessionStart) sets the session id trigger
+      // variables and enables auto-resume directly
+      const u6 = await listen<{ tab_id: string; session_id: string; agent?: AgentKind }>('claude-init-session', (e) => {
         const { tab_id, session_id } = e.payload;
+        const agent = e.payload.agent ?? 'claude';
         if (!tab_id || !session_id) return;
-        // Always set the variable so pinned commands can reference %claudeSessionId
-        setVariable(tab_id, 'claudeSessionId', session_id);
+        // Always set the variables so pinned commands can reference %claudeSessionId / %agentSessionId
+        setVariable(tab_id, 'agentSessionId', session_id);
+        if (agent === 'claude') setVariable(tab_id, 'claudeSessionId', session_id);
 
         // Skip auto-resume setup if the tab has a pinned auto-resume — don't overwrite user's config
         const instance = terminalsStore.get(tab_id);
@@ -313,8 +354,8 @@ function createClaudeStateStore() {
           }
         }
 
-        handleEnableAutoResume(tab_id, CLAUDE_RESUME_COMMAND);
-        logInfo(`Claude init: set claudeSessionId for tab ${tab_id.slice(0, 8)} = ${session_id.slice(0, 8)}`);
+        handleEnableAutoResume(tab_id, AGENT_RESUME_COMMANDS[agent]);
+        logInfo(`Claude init: set ${agent} session id for tab ${tab_id.slice(0, 8)} = ${session_id.slice(0, 8)}`);
       });
       unlisteners.push(u6);
 
@@ -334,15 +375,37 @@ function createClaudeStateStore() {
         const { session_id, tab_id } = e.payload;
         if (!tab_id) return;
         setState(tab_id, session_id, 'active');
+        patchSession(tab_id, session_id, { lastToolFailure: undefined });
         setVariable(tab_id, 'claudeAction', '');
       });
       unlisteners.push(u8);
 
+      // PostToolUseFailure: tool finished with an error (or was interrupted)
+      const u10 = await listen<{ session_id: string; tab_id: string | null; tool_name: string; error: string | null; is_interrupt?: boolean }>('claude-hook-tool-failure', (e) => {
+        const { session_id, tab_id, tool_name, error, is_interrupt } = e.payload;
+        if (!tab_id) return;
+        setState(tab_id, session_id, 'active');
+        // An interrupt is the user's doing, not a failure worth flagging
+        patchSession(tab_id, session_id, { lastToolFailure: is_interrupt ? undefined : { toolName: tool_name, error: error ?? undefined } });
+        setVariable(tab_id, 'claudeAction', '');
+      });
+      unlisteners.push(u10);
+
+      // SubagentStart/SubagentStop: the backend keeps the authoritative count
+      for (const name of ['claude-hook-subagent-start', 'claude-hook-subagent-stop']) {
+        const u = await listen<{ session_id: string; tab_id: string | null; subagents: number }>(name, (e) => {
+          const { session_id, tab_id, subagents } = e.payload;
+          if (!tab_id) return;
+          patchSession(tab_id, session_id, { subagents });
+        });
+        unlisteners.push(u);
+      }
+
       // PreCompact: context compaction starting
       const u9 = await listen<{ session_id: string; tab_id: string | null; trigger: string }>('claude-hook-pre-compact', (e) => {
         const { tab_id, trigger } = e.payload;
         if (!tab_id) return;
-        dispatch('Claude Code', `Compacting conversation (${trigger})...`, 'info', { tabId: tab_id });
+        dispatch(agentLabel(tab_id), `Compacting conversation (${trigger})...`, 'info', { tabId: tab_id });
       });
       unlisteners.push(u9);
     },
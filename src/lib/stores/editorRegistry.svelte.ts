@@ -1,4 +1,5 @@
 import type { EditorView } from '@codemirror/view';
+import { claudeCodeEditorChanged } from '$lib/tauri/commands';
 
 export interface EditorRegistryEntry {
   view: EditorView;
@@ -18,6 +19,8 @@ export function registerEditor(tabId: string, view: EditorView, filePath: string
 
 export function unregisterEditor(tabId: string): void {
   registry.delete(tabId);
+  clearTimeout(changeTimers.get(tabId));
+  changeTimers.delete(tabId);
   if (dirtyTabs.has(tabId)) {
     dirtyTabs = new Set([...dirtyTabs].filter(id => id !== tabId));
   }
@@ -34,6 +37,18 @@ export function setEditorDirty(tabId: string, dirty: boolean): void {
   }
 }
 
+// Pending buffer-change notices for MCP resource subscribers, keyed by tab ID
+const changeTimers = new Map<string, ReturnType<typeof setTimeout>>();
+
+/** Tell the backend an editor buffer changed (debounced per tab). */
+export function notifyEditorChanged(tabId: string): void {
+  clearTimeout(changeTimers.get(tabId));
+  changeTimers.set(tabId, setTimeout(() => {
+    changeTimers.delete(tabId);
+    claudeCodeEditorChanged(tabId).catch(() => {});
+  }, 500));
+}
+
 export function isEditorDirty(tabId: string): boolean {
   return dirtyTabs.has(tabId);
 }
@@ -0,0 +1,108 @@
+/**
+ * SSH port forwards — user-defined -L / -R / -D forwards attached to a tab or
+ * its workspace, brought up by Rust over the tab's SSH session.
+ *
+ * Flow:
+ * 1. TerminalPane detects an interactive SSH session → startPortForwards()
+ * 2. Rust starts (or joins) one `ssh -N` per forward and destination,
+ *    ref-counted by tab like the MCP tunnel
+ * 3. Session ends, tab suspends or closes → stopPortForwards()
+ * 4. Status changes arrive as `port-forwards-changed`
+ */
+
+import * as commands from '$lib/tauri/commands';
+import type { PortForward, PortForwardStatus, Tab, Workspace } from '$lib/tauri/types';
+import { dispatch } from '$lib/stores/notificationDispatch';
+import { error as logError } from '@tauri-apps/plugin-log';
+import { isInteractiveSshSession } from '$lib/stores/sshMcpBridge.svelte';
+import { countedListen as listen } from '$lib/utils/listenCounter';
+
+/** Every running or failed forward, as last reported by Rust. */
+let statuses = $state<PortForwardStatus[]>([]);
+
+/** tabId → cleaned ssh args last reported to Rust. */
+const sessions = new Map<string, string>();
+
+let listening = false;
+
+async function listenForChanges(): Promise<void> {
+  if (listening) return;
+  listening = true;
+  statuses = await commands.listPortForwards().catch(() => []);
+  await listen<PortForwardStatus[]>('port-forwards-changed', (event) => {
+    const previous = statuses;
+    statuses = event.payload;
+    for (const s of event.payload) {
+      if (s.state !== 'failed') continue;
+      const before = previous.find(p => p.host_key === s.host_key && p.forward.id === s.forward.id);
+      if (before?.state === 'failed') continue;
+      dispatch('Port Forward Failed', `${describeForward(s.forward)} on ${s.host_key}: ${s.error}`, 'error', { tabId: s.tab_ids[0] });
+    }
+  });
+}
+
+/** e.g. `-L 5432 → db:5432`, `-D 1080 (SOCKS)` */
+export function describeForward(forward: PortForward): string {
+  switch (forward.kind) {
+    case 'local': return `-L ${forward.listen_port} → ${forward.target}`;
+    case 'remote': return `-R ${forward.listen_port} → ${forward.target}`;
+    case 'dynamic': return `-D ${forward.listen_port} (SOCKS)`;
+  }
+}
+
+/** Whether the tab or its workspace defines any forwards. */
+export function hasPortForwards(workspace: Workspace | undefined, tab: Tab | undefined): boolean {
+  return !!(tab?.port_forwards?.length || workspace?.port_forwards?.length);
+}
+
+/** Same filter TerminalPane uses for the MCP bridge: interactive ssh only. */
+function isForwardableSsh(cmd: string | null): cmd is string {
+  return !!cmd
+    && !cmd.includes('git@')
+    && !cmd.includes('BatchMode=yes')
+    && isInteractiveSshSession(cmd);
+}
+
+/** Report a tab's SSH session (the foreground ssh command) to start its forwards. */
+export async function startPortForwards(tabId: string, cmd: string): Promise<void> {
+  const sshArgs = commands.cleanSshCommand(cmd);
+  if (sessions.get(tabId) === sshArgs) return;
+  sessions.set(tabId, sshArgs);
+  listenForChanges().catch(() => {});
+  try {
+    await commands.syncPortForwards(tabId, sshArgs);
+  } catch (e) {
+    logError(`Port forwards: failed to start for tab ${tabId}: ${e}`);
+  }
+}
+
+/** The tab's SSH session ended (or the tab is going away); let go of its forwards. */
+export async function stopPortForwards(tabId: string): Promise<void> {
+  if (!sessions.delete(tabId)) return;
+  try {
+    await commands.syncPortForwards(tabId, null);
+  } catch (e) {
+    logError(`Port forwards: failed to stop for tab ${tabId}: ${e}`);
+  }
+}
+
+/**
+ * Report the SSH sessions of tabs that weren't being watched because they had
+ * no forwards, so forwards added while connected start right away.
+ */
+export async function detectSessions(tabs: Tab[]): Promise<void> {
+  listenForChanges().catch(() => {});
+  for (const tab of tabs) {
+    if (!tab.pty_id || sessions.has(tab.id)) continue;
+    const info = await commands.getPtyInfo(tab.pty_id).catch(() => null);
+    if (info && isForwardableSsh(info.foreground_command)) {
+      await startPortForwards(tab.id, info.foreground_command);
+    }
+  }
+}
+
+/** Status of `forward` for a tab: the entry for the tab's current session, if any. */
+export function getForwardStatus(tabId: string, forwardId: string): PortForwardStatus | undefined {
+  return statuses.find(s => s.forward.id === forwardId && s.tab_ids.includes(tabId));
+}
+
@@ -1,4 +1,4 @@
-import type { CursorStyle, Preferences, Trigger } from '$lib/tauri/types';
+import type { ClipboardPolicy, CursorStyle, HookPolicyRule, McpScope, ModelPrice, Preferences, PromptTemplate, Trigger } from '$lib/tauri/types';
 import type { Theme } from '$lib/themes';
 import { builtinThemes } from '$lib/themes';
 import * as commands from '$lib/tauri/commands';
@@ -66,6 +66,19 @@ function createPreferencesStore() {
   let autoCheckUpdates = $state(true);
   let quickOpenShowHidden = $state(false);
   let quickOpenShowIgnored = $state(false);
+  let osc52WritePolicy = $state<ClipboardPolicy>('always');
+  let osc52ReadPolicy = $state<ClipboardPolicy>('ask');
+  let osc52HostPolicies = $state<Record<string, ClipboardPolicy>>({});
+  let osc52MaxBytes = $state(1048576);
+  let promptTemplates = $state<PromptTemplate[]>([]);
+  let hookPolicyRules = $state<HookPolicyRule[]>([]);
+  let hookPromptTimeout = $state(60);
+  let claudeModelPrices = $state<ModelPrice[]>([]);
+  let mcpRemoteScope = $state<McpScope>('workspace');
+  let mcpHostScopes = $state<Record<string, McpScope>>({});
+  let mcpWorkspaceScopes = $state<Record<string, McpScope>>({});
+  let mcpTokenRotationHours = $state(24);
+  let mcpLocalTokenRotationHours = $state(0);
 
   return {
     /** Resolves once the initial load() has completed. */
@@ -129,6 +142,19 @@ function createPreferencesStore() {
     get autoCheckUpdates() { return autoCheckUpdates; },
     get quickOpenShowHidden() { return quickOpenShowHidden; },
     get quickOpenShowIgnored() { return quickOpenShowIgnored; },
+    get osc52WritePolicy() { return osc52WritePolicy; },
+    get osc52ReadPolicy() { return osc52ReadPolicy; },
+    get osc52HostPolicies() { return osc52HostPolicies; },
+    get osc52MaxBytes() { return osc52MaxBytes; },
+    get promptTemplates() { return promptTemplates; },
+    get hookPolicyRules() { return hookPolicyRules; },
+    get hookPromptTimeout() { return hookPromptTimeout; },
+    get claudeModelPrices() { return claudeModelPrices; },
+    get mcpRemoteScope() { return mcpRemoteScope; },
+    get mcpHostScopes() { return mcpHostScopes; },
+    get mcpWorkspaceScopes() { return mcpWorkspaceScopes; },
+    get mcpTokenRotationHours() { return mcpTokenRotationHours; },
+    get mcpLocalTokenRotationHours() { return mcpLocalTokenRotationHours; },
 
     async load() {
       const prefs = await commands.getPreferences();
@@ -202,6 +228,19 @@ function createPreferencesStore() {
       autoCheckUpdates = prefs.auto_check_updates ?? true;
       quickOpenShowHidden = prefs.quick_open_show_hidden ?? false;
       quickOpenShowIgnored = prefs.quick_open_show_ignored ?? false;
+      osc52WritePolicy = prefs.osc52_write_policy ?? 'always';
+      osc52ReadPolicy = prefs.osc52_read_policy ?? 'ask';
+      osc52HostPolicies = prefs.osc52_host_policies ?? {};
+      osc52MaxBytes = prefs.osc52_max_bytes ?? 1048576;
+      promptTemplates = prefs.prompt_templates ?? [];
+      hookPolicyRules = prefs.hook_policy_rules ?? [];
+      hookPromptTimeout = prefs.hook_prompt_timeout ?? 60;
+      claudeModelPrices = prefs.claude_model_prices ?? [];
+      mcpRemoteScope = prefs.mcp_remote_scope ?? 'workspace';
+      mcpHostScopes = prefs.mcp_host_scopes ?? {};
+      mcpWorkspaceScopes = prefs.mcp_workspace_scopes ?? {};
+      mcpTokenRotationHours = prefs.mcp_token_rotation_hours ?? 24;
+      mcpLocalTokenRotationHours = prefs.mcp_local_token_rotation_hours ?? 0;
       _resolveReady();
     },
 
@@ -492,6 +531,87 @@ function createPreferencesStore() {
       await this.save();
     },
 
+    async setOsc52WritePolicy(value: ClipboardPolicy) {
+      osc52WritePolicy = value;
+      await this.save();
+    },
+
+    async setOsc52ReadPolicy(value: ClipboardPolicy) {
+      osc52ReadPolicy = value;
+      await this.save();
+    },
+
+    async removeOsc52HostPolicy(host: string) {
+      const next = { ...osc52HostPolicies };
+      delete next[host];
+      osc52HostPolicies = next;
+      await this.save();
+    },
+
+    async setOsc52MaxBytes(value: number) {
+      osc52MaxBytes = Math.max(1024, Math.round(value));
+      await this.save();
+    },
+
+    async setPromptTemplates(value: PromptTemplate[]) {
+      promptTemplates = value;
+      await this.save();
+    },
+
+    async setHookPolicyRules(value: HookPolicyRule[]) {
+      hookPolicyRules = value;
+      await this.save();
+    },
+
+    async setHookPromptTimeout(value: number) {
+      hookPromptTimeout = Math.min(570, Math.max(5, Math.round(value)));
+      await this.save();
+    },
+
+    async setClaudeModelPrices(value: ModelPrice[]) {
+      claudeModelPrices = value;
+      await this.save();
+    },
+
+    async setMcpTokenRotationHours(value: number) {
+      mcpTokenRotationHours = value;
+      await this.save();
+    },
+
+    async setMcpLocalTokenRotationHours(value: number) {
+      mcpLocalTokenRotationHours = value;
+      await this.save();
+    },
+
+    async setMcpRemoteScope(value: McpScope) {
+      mcpRemoteScope = value;
+      await this.save();
+    },
+
+    async setMcpHostScope(host: string, scope: McpScope) {
+      mcpHostScopes = { ...mcpHostScopes, [host.trim().toLowerCase()]: scope };
+      await this.save();
+    },
+
+    async removeMcpHostScope(host: string) {
+      const next = { ...mcpHostScopes };
+      delete next[host];
+      mcpHostScopes = next;
+      await this.save();
+    },
+
+    async setMcpWorkspaceScope(workspaceId: string, scope: McpScope) {
+      mcpWorkspaceScopes = { ...mcpWorkspaceScopes, [workspaceId]: scope };
+      await this.save();
+    },
+
+    async removeMcpWorkspaceScope(workspaceId: string) {
+      const next = { ...mcpWorkspaceScopes };
+      delete next[workspaceId];
+      mcpWorkspaceScopes = next;
+      await this.save();
+    },
+
     async addCustomTheme(t: Theme) {
       customThemes = [...customThemes, t];
       await this.save();
@@ -574,6 +694,19 @@ function createPreferencesStore() {
       autoCheckUpdates = prefs.auto_check_updates ?? true;
       quickOpenShowHidden = prefs.quick_open_show_hidden ?? false;
       quickOpenShowIgnored = prefs.quick_open_show_ignored ?? false;
+      osc52WritePolicy = prefs.osc52_write_policy ?? 'always';
+      osc52ReadPolicy = prefs.osc52_read_policy ?? 'ask';
+      osc52HostPolicies = prefs.osc52_host_policies ?? {};
+      osc52MaxBytes = prefs.osc52_max_bytes ?? 1048576;
+      promptTemplates = prefs.prompt_templates ?? [];
+      hookPolicyRules = prefs.hook_policy_rules ?? [];
+      hookPromptTimeout = prefs.hook_prompt_timeout ?? 60;
+      claudeModelPrices = prefs.claude_model_prices ?? [];
+      mcpRemoteScope = prefs.mcp_remote_scope ?? 'workspace';
+      mcpHostScopes = prefs.mcp_host_scopes ?? {};
+      mcpWorkspaceScopes = prefs.mcp_workspace_scopes ?? {};
+      mcpTokenRotationHours = prefs.mcp_token_rotation_hours ?? 24;
+      mcpLocalTokenRotationHours = prefs.mcp_local_token_rotation_hours ?? 0;
     },
 
     async save() {
@@ -638,6 +771,19 @@ function createPreferencesStore() {
         auto_check_updates: autoCheckUpdates,
         quick_open_show_hidden: quickOpenShowHidden,
         quick_open_show_ignored: quickOpenShowIgnored,
+        osc52_write_policy: osc52WritePolicy,
+        osc52_read_policy: osc52ReadPolicy,
+        osc52_host_policies: osc52HostPolicies,
+        osc52_max_bytes: osc52MaxBytes,
+        prompt_templates: promptTemplates,
+        hook_policy_rules: hookPolicyRules,
+        hook_prompt_timeout: hookPromptTimeout,
+        claude_model_prices: claudeModelPrices,
+        mcp_remote_scope: mcpRemoteScope,
+        mcp_host_scopes: mcpHostScopes,
+        mcp_workspace_scopes: mcpWorkspaceScopes,
+        mcp_token_rotation_hours: mcpTokenRotationHours,
+        mcp_local_token_rotation_hours: mcpLocalTokenRotationHours,
       };
       await commands.setPreferences(prefs);
     }
@@ -7,6 +7,7 @@
  * 2. enableBridge() called → spawns reverse tunnel → writes lockfile via background SSH
  * 3. Claude Code on remote discovers lockfile → connects through tunnel → local MCP
  * 4. On tab close → disableBridge() → decrements ref count → kills tunnel if last
+ * 5. Meanwhile Rust's tunnel supervisor restarts dead forwards → ssh-tunnel-status
  */
 
 import * as commands from '$lib/tauri/commands';
@@ -15,7 +16,6 @@ import { dispatch } from '$lib/stores/notificationDispatch';
 import { error as logError, info as logInfo } from '@tauri-apps/plugin-log';
 import { setVariable } from '$lib/stores/triggers.svelte';
 import { countedListen as listen } from '$lib/utils/listenCounter';
-import type { UnlistenFn } from '@tauri-apps/api/event';
 
 export type BridgeStatus = 'connected' | 'pending' | 'failed';
 
@@ -29,40 +29,70 @@ interface BridgeState {
 /** Reactive map of tabId → bridge state. Svelte 5 $state for reactivity in TerminalTabs. */
 let bridgeStates = $state<Map<string, BridgeState>>(new Map());
 
-/** Per-tab event listeners for tunnel-down events from Rust. */
-const tunnelListeners = new Map<string, UnlistenFn>();
+/** Whether the app-wide tunnel and token listeners are registered. */
+let hostListenersStarted = false;
 
 /**
- * Remove bridge state for a tab (internal — no backend call).
- * Used when Rust notifies us the tunnel died.
+ * Re-run the remote setup for a host through one of its connected tabs
+ * (setup writes per-host config, so once is enough). Used when the host's
+ * token rotates or its tunnel comes back on another remote port.
  */
-function clearBridgeState(tabId: string): void {
-  if (!bridgeStates.has(tabId)) return;
-  bridgeStates.delete(tabId);
-  bridgeStates = new Map(bridgeStates);
-  logInfo(`SSH MCP bridge cleared for tab ${tabId} (tunnel down)`);
+async function reinstallSetup(hostKey: string, what: string): Promise<void> {
+  const entry = [...bridgeStates.entries()].find(([, b]) => b.hostKey === hostKey && b.status === 'connected');
+  if (!entry) return;
+  const [tabId, bridge] = entry;
+  try {
+    const tunnelInfo = await commands.getSshTunnel(hostKey);
+    if (!tunnelInfo?.auth_token) return;
+    const skillScripts = await commands.getMaitermSkillScripts();
+    // The host key is the cleaned ssh args the bridge was enabled with
+    await commands.sshRunSetup(hostKey, buildSetupScript(bridge.remotePort, tunnelInfo.auth_token, tabId, skillScripts));
+    logInfo(`SSH MCP bridge: installed ${what} on ${hostKey}`);
+  } catch (e) {
+    logError(`SSH MCP bridge: failed to install ${what} on ${hostKey}: ${e}`);
+  }
 }
 
 /**
- * Start listening for tunnel-down events from Rust for this tab.
+ * Follow host-wide changes from Rust:
+ * - `ssh-tunnel-status`: the tunnel supervisor lost or restored a host's
+ *   forward. Bridges show as pending while it reconnects; if the forward
+ *   came back on another remote port, the remote setup is re-run with it.
+ * - `mcp-host-token-rotated`: the new token is installed on the host.
+ * - `mcp-host-token-revoked`: the host's bridges are marked failed so
+ *   TerminalPane doesn't quietly re-enable them with a fresh token.
  */
-async function listenForTunnelDown(tabId: string): Promise<void> {
-  // Already listening?
-  if (tunnelListeners.has(tabId)) return;
-  const unlisten = await listen(`ssh-tunnel-down-${tabId}`, () => {
-    logInfo(`Received ssh-tunnel-down for tab ${tabId}`);
-    clearBridgeState(tabId);
-    cleanupListener(tabId);
+async function listenForHostEvents(): Promise<void> {
+  if (hostListenersStarted) return;
+  hostListenersStarted = true;
+  await listen<commands.SshTunnelStatus>('ssh-tunnel-status', async (event) => {
+    const { host_key: hostKey, up, remote_port: remotePort, port_changed: portChanged, error } = event.payload;
+    for (const [tabId, bridge] of bridgeStates) {
+      if (bridge.hostKey !== hostKey || bridge.status === 'failed') continue;
+      if (up) {
+        bridgeStates.set(tabId, { hostKey, remotePort, status: 'connected' });
+        setVariable(tabId, 'aitermPort', String(remotePort));
+        setVariable(tabId, 'aitermExport', `export AITERM_TAB_ID=${tabId} AITERM_PORT=${remotePort}`);
+      } else {
+        bridgeStates.set(tabId, { ...bridge, status: 'pending', error: error ?? undefined });
+      }
+    }
+    bridgeStates = new Map(bridgeStates);
+    logInfo(`SSH MCP bridge: tunnel to ${hostKey} ${up ? `up on remote port ${remotePort}` : `down (${error}), reconnecting`}`);
+    if (up && portChanged) {
+      await reinstallSetup(hostKey, `new remote port ${remotePort}`);
+    }
+  });
+  await listen<string>('mcp-host-token-rotated', (event) => reinstallSetup(event.payload, 'rotated token'));
+  await listen<string>('mcp-host-token-revoked', (event) => {
+    const hostKey = event.payload;
+    for (const [tabId, bridge] of bridgeStates) {
+      if (bridge.hostKey !== hostKey) continue;
+      bridgeStates.set(tabId, { hostKey, remotePort: 0, status: 'failed', error: 'MCP access revoked' });
+    }
+    bridgeStates = new Map(bridgeStates);
+    logInfo(`SSH MCP bridge: token for ${hostKey} revoked`);
   });
-  tunnelListeners.set(tabId, unlisten);
-}
-
-function cleanupListener(tabId: string): void {
-  const unlisten = tunnelListeners.get(tabId);
-  if (unlisten) {
-    unlisten();
-    tunnelListeners.delete(tabId);
-  }
 }
 
 /**
@@ -344,9 +374,10 @@ export async function enableBridge(tabId: string, sshArgs: string, ptyId?: strin
   const hostKey = extractHostKey(sshArgs);
   bridgeStates = new Map(bridgeStates.set(tabId, { hostKey, remotePort: 0, status: 'pending' }));
 
+  listenForHostEvents().catch(() => {});
+
   const localPort = await commands.getMcpPort();
-  const authToken = await commands.getMcpAuth();
-  if (!localPort || !authToken) {
+  if (!localPort) {
     logError('Cannot enable SSH MCP bridge: MCP server not running');
     bridgeStates.delete(tabId);
     bridgeStates = new Map(bridgeStates);
@@ -355,6 +386,7 @@ export async function enableBridge(tabId: string, sshArgs: string, ptyId?: strin
 
   try {
     // Start or join existing tunnel
+    // Each host gets its own MCP token, revoked when its tunnel goes away
     const tunnelInfo = await commands.startSshTunnel(sshArgs, hostKey, tabId, localPort);
     logInfo(`SSH MCP bridge: tunnel to ${hostKey} on remote port ${tunnelInfo.remote_port}`);
 
@@ -363,7 +395,7 @@ export async function enableBridge(tabId: string, sshArgs: string, ptyId?: strin
     // we already have — awaiting setup first delays the export landing in the
     // remote shell by ~0.5-2s, which collides with the user's first keystrokes.
     const skillScripts = await commands.getMaitermSkillScripts();
-    const setupScript = buildSetupScript(tunnelInfo.remote_port, authToken, tabId, skillScripts);
+    const setupScript = buildSetupScript(tunnelInfo.remote_port, tunnelInfo.auth_token, tabId, skillScripts);
     const setupPromise = commands.sshRunSetup(sshArgs, setupScript);
 
     // Set trigger variables so auto-resume commands can interpolate them.
@@ -398,15 +430,14 @@ export async function enableBridge(tabId: string, sshArgs: string, ptyId?: strin
     // If setup failed, this throws and the outer catch marks the bridge as failed.
     await setupPromise;
 
+    // A tunnel that's down is being restarted by the supervisor, and
+    // ssh-tunnel-status flips it to connected when it's back
     bridgeStates = new Map(bridgeStates.set(tabId, {
       hostKey,
       remotePort: tunnelInfo.remote_port,
-      status: 'connected',
+      status: tunnelInfo.up ? 'connected' : 'pending',
     }));
 
-    // Listen for tunnel process death from Rust — clears indicator in real-time
-    listenForTunnelDown(tabId).catch(() => {});
-
     logInfo(`SSH MCP bridge enabled for tab ${tabId} → ${hostKey}:${tunnelInfo.remote_port}`);
     return true;
   } catch (e) {
@@ -432,7 +463,6 @@ export async function disableBridge(tabId: string): Promise<void> {
   const bridge = bridgeStates.get(tabId);
   if (!bridge) return;
 
-  cleanupListener(tabId);
   bridgeStates.delete(tabId);
   bridgeStates = new Map(bridgeStates);
 
@@ -474,7 +504,7 @@ export async function buildUserSetupScript(tabId: string): Promise<string | null
   const bridge = bridgeStates.get(tabId);
   if (!bridge || bridge.status !== 'connected' || !bridge.remotePort) return null;
 
-  const authToken = await commands.getMcpAuth();
+  const authToken = (await commands.getSshTunnel(bridge.hostKey))?.auth_token;
   if (!authToken) return null;
 
   const skillScripts = await commands.getMaitermSkillScripts();
@@ -39,6 +39,10 @@ export interface SplitContext {
   remoteCwd: string | null;
   /** When true, fire auto-resume command even though this is a split context (used by reload tab). */
   fireAutoResume?: boolean;
+  /** Typed once after the shell starts (MCP createTab/splitPane). Not persisted — unlike auto-resume it won't rerun on restore. */
+  command?: string | null;
+  /** Arguments appended to `command`, quoted for the shell it's typed into (local or over SSH). */
+  args?: string[];
 }
 
 function createTerminalsStore() {
@@ -4,6 +4,12 @@ export interface ToastSource {
   tabId: string;
 }
 
+export interface ToastAction {
+  label: string;
+  variant?: 'primary' | 'danger';
+  onClick: () => void;
+}
+
 export interface Toast {
   id: string;
   title: string;
@@ -22,6 +28,10 @@ export interface Toast {
   indeterminate?: boolean;
   /** Optional callback for a Cancel button (replaces the close × on progress toasts). */
   onCancel?: () => void;
+  /** Buttons for a prompt toast; choosing one dismisses it. */
+  actions?: ToastAction[];
+  /** Called when a prompt toast goes away without an action (×, expiry, eviction). */
+  onDismiss?: () => void;
 }
 
 const MAX_VISIBLE = 3;
@@ -36,6 +46,8 @@ function createToastStore() {
   let toasts = $state<Toast[]>([]);
   let windowFocused = $state(true);
   const timers = new Map<string, TimerState>();
+  // Prompt toasts expire on a fixed deadline (the asker stops waiting), unaffected by hover/focus
+  const expiries = new Map<string, ReturnType<typeof setTimeout>>();
   // Track which toasts are hovered (to avoid resuming on focus if still hovered)
   const hoveredIds = new Set<string>();
   // Reactive signal bumped on every timer state change so isActive() triggers re-renders
@@ -98,11 +110,22 @@ function createToastStore() {
       clearTimeout(ts.timer);
       timers.delete(id);
     }
+    clearTimeout(expiries.get(id));
+    expiries.delete(id);
     hoveredIds.delete(id);
+    const removed = toasts.find(t => t.id === id);
     toasts = toasts.filter(t => t.id !== id);
+    removed?.onDismiss?.();
     if (wasActive) activateNext();
   }
 
+  /** Drop the oldest toasts over MAX_VISIBLE, sparing prompts while anything else can go. */
+  function evictOverflow() {
+    while (toasts.length > MAX_VISIBLE) {
+      removeToast((toasts.find(t => !t.actions) ?? toasts[0]).id);
+    }
+  }
+
   function pauseToast(id: string) {
     hoveredIds.add(id);
     // Only the active toast has a running timer to pause
@@ -144,9 +167,7 @@ function createToastStore() {
     }
 
     // Evict oldest if over max
-    while (toasts.length > MAX_VISIBLE) {
-      removeToast(toasts[0].id);
-    }
+    evictOverflow();
   }
 
   /** Add a sticky progress toast (no auto-dismiss). Returns its id for updateToast/removeToast. */
@@ -166,9 +187,44 @@ function createToastStore() {
     };
     toasts = [...toasts, toast];
     // Sticky toasts get no timer — they persist until updated/removed.
-    while (toasts.length > MAX_VISIBLE) {
-      removeToast(toasts[0].id);
-    }
+    evictOverflow();
+    return id;
+  }
+
+  /**
+   * Add a toast asking the user to choose one of `actions` within `duration` ms.
+   * Exactly one of the action callbacks or `onDismiss` runs. Returns its id.
+   */
+  function addPromptToast(opts: {
+    title: string;
+    body: string;
+    source?: ToastSource;
+    duration: number;
+    actions: ToastAction[];
+    onDismiss: () => void;
+  }): string {
+    const id = crypto.randomUUID();
+    let settled = false;
+    const settle = (fn: () => void) => () => {
+      if (settled) return;
+      settled = true;
+      fn();
+    };
+    const toast: Toast = {
+      id,
+      title: opts.title,
+      body: opts.body,
+      type: 'info',
+      createdAt: Date.now(),
+      duration: opts.duration,
+      source: opts.source,
+      sticky: true,
+      actions: opts.actions.map(a => ({ ...a, onClick: settle(a.onClick) })),
+      onDismiss: settle(opts.onDismiss),
+    };
+    toasts = [...toasts, toast];
+    expiries.set(id, setTimeout(() => removeToast(id), opts.duration));
+    evictOverflow();
     return id;
   }
 
@@ -225,6 +281,7 @@ function createToastStore() {
     get toasts() { return toasts; },
     addToast,
     addProgressToast,
+    addPromptToast,
     updateToast,
     removeToast,
     pauseToast,
@@ -238,6 +295,7 @@ function createToastStore() {
         toasts: toasts.length,
         timers: timers.size,
         hovered: hoveredIds.size,
+        expiries: expiries.size,
       };
     },
   };
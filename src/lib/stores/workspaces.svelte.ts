@@ -1,5 +1,5 @@
 import type { Terminal } from '@xterm/xterm';
-import type { SplitDirection, SplitNode, Tab, Pane, Workspace, WorkspaceNote, EditorFileInfo, DiffContext } from '$lib/tauri/types';
+import type { SplitDirection, SplitNode, Tab, Pane, Workspace, WorkspaceNote, EditorFileInfo, DiffContext, PortForward, AutoResumeContext } from '$lib/tauri/types';
 import * as commands from '$lib/tauri/commands';
 import { terminalsStore } from '$lib/stores/terminals.svelte';
 import { preferencesStore } from '$lib/stores/preferences.svelte';
@@ -10,6 +10,7 @@ import { pendingResumePanes } from '$lib/stores/resumeGate.svelte';
 import { getVariables } from '$lib/stores/triggers.svelte';
 import { CLAUDE_RESUME_COMMAND } from '$lib/triggers/defaults';
 import { disableBridge } from '$lib/stores/sshMcpBridge.svelte';
+import { stopPortForwards } from '$lib/stores/portForwards.svelte';
 
 /**
  * Extract the remote cwd from the terminal prompt using user-configured patterns.
@@ -589,6 +590,43 @@ function createWorkspacesStore() {
       return { newPaneId: newPane.id, newTabId };
     },
 
+    /**
+     * Split a pane into a fresh terminal that starts in `cwd` (default: the
+     * target pane's live terminal cwd) and types `command` once its shell is
+     * up. Context is set before the store refresh mounts the new pane.
+     */
+    async splitPaneWithCommand(
+      workspaceId: string,
+      targetPaneId: string,
+      direction: SplitDirection,
+      options: { name?: string | null; cwd?: string | null; command?: string | null; args?: string[] },
+    ): Promise<{ newPaneId: string; newTabId: string } | null> {
+      let cwd = options.cwd ?? null;
+      if (!cwd) {
+        const targetTabId = workspaces.find(w => w.id === workspaceId)?.panes.find(p => p.id === targetPaneId)?.active_tab_id;
+        const instance = targetTabId ? terminalsStore.get(targetTabId) : undefined;
+        if (instance) {
+          cwd = await commands.getPtyInfo(instance.ptyId).then(info => info.cwd).catch(() => null);
+        }
+      }
+
+      const newPane = await commands.splitPane(workspaceId, targetPaneId, direction);
+      const newTabId = newPane.tabs[0]?.id;
+      if (!newTabId) return null;
+      if (options.name) {
+        await commands.renameTab(workspaceId, newPane.id, newTabId, options.name, true);
+      }
+      terminalsStore.setSplitContext(newTabId, { cwd, sshCommand: null, remoteCwd: null, command: options.command, args: options.args });
+
+      const data = await commands.getWindowData();
+      const freshWs = data.workspaces.find(w => w.id === workspaceId);
+      if (freshWs) {
+        const idx = workspaces.findIndex(w => w.id === workspaceId);
+        if (idx >= 0) workspaces[idx] = freshWs;
+      }
+      return { newPaneId: newPane.id, newTabId };
+    },
+
     async splitPaneWithContext(workspaceId: string, sourcePaneId: string, sourceTabId: string, direction: SplitDirection) {
       // Look up source tab to determine its type
       const ws_current = workspaces.find(w => w.id === workspaceId);
@@ -771,7 +809,11 @@ function createWorkspacesStore() {
       }
     },
 
-    async createTab(workspaceId: string, paneId: string, name: string, options?: { append?: boolean }) {
+    /**
+     * `cwd` overrides the inferred sibling directory (and drops any SSH
+     * context); `command` is typed once the shell starts.
+     */
+    async createTab(workspaceId: string, paneId: string, name: string, options?: { append?: boolean; cwd?: string | null; command?: string | null; args?: string[] }) {
       const afterTabId = options?.append
         ? undefined
         : workspaces.flatMap(w => w.panes).find(p => p.id === paneId)?.active_tab_id ?? undefined;
@@ -876,8 +918,16 @@ function createWorkspacesStore() {
         if (!best && (liveCwd || liveSsh)) {
           best = { cwd: liveCwd, sshCommand: liveSsh, remoteCwd: null };
         }
-        if (best) {
-          terminalsStore.setSplitContext(tab.id, { cwd: best.cwd, sshCommand: best.sshCommand, remoteCwd: best.remoteCwd });
+        if (options?.cwd) {
+          terminalsStore.setSplitContext(tab.id, { cwd: options.cwd, sshCommand: null, remoteCwd: null, command: options.command, args: options.args });
+        } else if (best || options?.command) {
+          terminalsStore.setSplitContext(tab.id, {
+            cwd: best?.cwd ?? null,
+            sshCommand: best?.sshCommand ?? null,
+            remoteCwd: best?.remoteCwd ?? null,
+            command: options?.command,
+            args: options?.args,
+          });
         }
       }
 
@@ -929,6 +979,36 @@ function createWorkspacesStore() {
       return tab;
     },
 
+    async createPlaybackTab(workspaceId: string, paneId: string, path: string, afterTabId?: string | null) {
+      const tab = await commands.createPlaybackTab(workspaceId, paneId, path, afterTabId);
+      const ws = workspaces.find(w => w.id === workspaceId);
+      const pane = ws?.panes.find(p => p.id === paneId);
+      if (pane) {
+        const activeIdx = pane.tabs.findIndex(t => t.id === (afterTabId ?? pane.active_tab_id));
+        const insertIdx = activeIdx === -1 ? pane.tabs.length : activeIdx + 1;
+        pane.tabs.splice(insertIdx, 0, tab);
+        const { navHistoryStore: navHistory } = await import('$lib/stores/navHistory.svelte');
+        pane.active_tab_id = tab.id;
+        navHistory.push({ workspaceId, paneId, tabId: tab.id });
+      }
+      return tab;
+    },
+
+    async createTranscriptTab(workspaceId: string, paneId: string, sourceTabId: string, afterTabId?: string | null) {
+      const tab = await commands.createTranscriptTab(workspaceId, paneId, sourceTabId, afterTabId);
+      const ws = workspaces.find(w => w.id === workspaceId);
+      const pane = ws?.panes.find(p => p.id === paneId);
+      if (pane) {
+        const activeIdx = pane.tabs.findIndex(t => t.id === (afterTabId ?? pane.active_tab_id));
+        const insertIdx = activeIdx === -1 ? pane.tabs.length : activeIdx + 1;
+        pane.tabs.splice(insertIdx, 0, tab);
+        const { navHistoryStore: navHistory } = await import('$lib/stores/navHistory.svelte');
+        pane.active_tab_id = tab.id;
+        navHistory.push({ workspaceId, paneId, tabId: tab.id });
+      }
+      return tab;
+    },
+
     async deleteTab(workspaceId: string, paneId: string, tabId: string) {
       // If closing a diff tab with a pending Claude request, respond with rejection
       // so Claude Code doesn't hang waiting for accept/reject.
@@ -1029,6 +1109,7 @@ function createWorkspacesStore() {
       // Detach SSH MCP bridge — refcounted on the Rust side, so other tabs
       // sharing the tunnel keep it alive; suspended tab is removed from tab_ids.
       await disableBridge(tabId).catch(() => {});
+      await stopPortForwards(tabId).catch(() => {});
 
       // Guard the pty-close listener from deleting this tab
       suspendingTabIds.add(tabId);
@@ -1306,6 +1387,18 @@ function createWorkspacesStore() {
       }
     },
 
+    /** Mirror an auto-resume context Rust already stored (trigger actions). */
+    applyAutoResumeContextLocal(ctx: AutoResumeContext) {
+      const tab = workspaces.flatMap(w => w.panes).flatMap(p => p.tabs).find(t => t.id === ctx.tab_id);
+      if (!tab) return;
+      tab.auto_resume_cwd = ctx.cwd;
+      tab.auto_resume_ssh_command = ctx.ssh_command;
+      tab.auto_resume_remote_cwd = ctx.remote_cwd;
+      tab.auto_resume_command = ctx.command;
+      tab.auto_resume_enabled = true;
+      if (ctx.command != null) tab.auto_resume_remembered_command = ctx.command;
+    },
+
     async disableAutoResume(workspaceId: string, paneId: string, tabId: string) {
       await commands.setTabAutoResumeEnabled(workspaceId, paneId, tabId, false);
       const { tab } = findTab(workspaceId, paneId, tabId);
@@ -1958,6 +2051,28 @@ function createWorkspacesStore() {
       if (tab) tab.notes = notes;
     },
 
+    async setTabAutoRecord(workspaceId: string, paneId: string, tabId: string, enabled: boolean) {
+      await commands.setTabAutoRecord(workspaceId, paneId, tabId, enabled);
+      const { tab } = findTab(workspaceId, paneId, tabId);
+      if (tab) tab.auto_record = enabled;
+    },
+
+    /** Attach an SSH port forward to a tab, or to the workspace when tabId is null. */
+    async addPortForward(workspaceId: string, tabId: string | null, forward: PortForward): Promise<PortForward> {
+      const saved = await commands.addPortForward(workspaceId, tabId, forward);
+      const ws = workspaces.find(w => w.id === workspaceId);
+      const owner = tabId ? ws?.panes.flatMap(p => p.tabs).find(t => t.id === tabId) : ws;
+      if (owner) owner.port_forwards = [...(owner.port_forwards ?? []), saved];
+      return saved;
+    },
+
+    async removePortForward(workspaceId: string, tabId: string | null, forwardId: string) {
+      await commands.removePortForward(workspaceId, tabId, forwardId);
+      const ws = workspaces.find(w => w.id === workspaceId);
+      const owner = tabId ? ws?.panes.flatMap(p => p.tabs).find(t => t.id === tabId) : ws;
+      if (owner) owner.port_forwards = (owner.port_forwards ?? []).filter(f => f.id !== forwardId);
+    },
+
     async addWorkspaceNote(workspaceId: string, content: string, mode: string | null): Promise<WorkspaceNote | null> {
       try {
         const note = await commands.addWorkspaceNote(workspaceId, content, mode);
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AgentBridge, AppData, DiffContext, DuplicateWorkspaceResult, EditorFileInfo, Pane, Preferences, ScrollInfo, SearchResult, ShellInfo, SplitDirection, Tab, TerminalFrame, WindowData, Workspace, WorkspaceNote } from './types';
+import type { TerminalColors } from '$lib/themes';
+import type { AgentBridge, AppData, ClaudeTranscript, CommandBlock, DiffContext, DuplicateWorkspaceResult, EditorFileInfo, ExportFormat, HookEvent, McpAuditEntry, McpTokenInfo, Pane, PlaybackInfo, PortForward, PortForwardStatus, Preferences, ScrollInfo, ScrollbackSearchResult, SearchOptions, SearchResult, ShellInfo, SplitDirection, Tab, TerminalFrame, TranscriptSource, UsageReport, WindowData, Workspace, WorkspaceNote } from './types';
 
 // Terminal commands
 export async function spawnTerminal(ptyId: string, tabId: string, cols: number, rows: number, cwd?: string | null): Promise<void> {
@@ -105,6 +106,32 @@ export async function killTerminal(ptyId: string): Promise<void> {
   return invoke('kill_terminal', { ptyId });
 }
 
+/** Answer an OSC 52 prompt; `remember` stores the answer for the tab or SSH host. */
+export async function respondClipboardRequest(
+  requestId: string,
+  allow: boolean,
+  remember: 'tab' | 'host' | null = null,
+): Promise<void> {
+  return invoke('respond_clipboard_request', { requestId, allow, remember });
+}
+
+/** Tell the backend the active theme's terminal colors (answers OSC 4/10/11/12 queries). */
+export async function setTerminalTheme(colors: TerminalColors): Promise<void> {
+  return invoke('set_terminal_theme', { colors });
+}
+
+export async function listCommandBlocks(ptyId: string): Promise<CommandBlock[]> {
+  return invoke('list_command_blocks', { ptyId });
+}
+
+export async function getCommandBlockOutput(ptyId: string, blockId: number): Promise<string> {
+  return invoke('get_command_block_output', { ptyId, blockId });
+}
+
+export async function getPromptOffsets(ptyId: string): Promise<number[]> {
+  return invoke('get_prompt_offsets', { ptyId });
+}
+
 export async function readClipboardFilePaths(): Promise<string[]> {
   return invoke('read_clipboard_file_paths');
 }
@@ -122,12 +149,49 @@ export async function scrollTerminalTo(ptyId: string, offset: number): Promise<T
   return invoke('scroll_terminal_to', { ptyId, offset });
 }
 
+export async function refreshTerminalFrame(ptyId: string): Promise<TerminalFrame> {
+  return invoke('refresh_terminal_frame', { ptyId });
+}
+
 export async function getTerminalScrollbackInfo(ptyId: string): Promise<ScrollInfo> {
   return invoke('get_terminal_scrollback_info', { ptyId });
 }
 
-export async function searchTerminal(ptyId: string, query: string, caseSensitive: boolean): Promise<SearchResult> {
-  return invoke('search_terminal', { ptyId, query, caseSensitive });
+export async function searchTerminal(ptyId: string, query: string, caseSensitive: boolean, options?: SearchOptions): Promise<SearchResult> {
+  return invoke('search_terminal', { ptyId, query, caseSensitive, options: options ?? null });
+}
+
+/** Case-insensitive substring search (3+ chars) over every tab's saved scrollback, archived tabs included. */
+export async function searchAllScrollback(query: string, limit?: number): Promise<ScrollbackSearchResult[]> {
+  return invoke('search_all_scrollback', { query, limit: limit ?? null });
+}
+
+export async function startTerminalRecording(ptyId: string, tabId: string, path?: string | null): Promise<string> {
+  return invoke('start_terminal_recording', { ptyId, tabId, path: path ?? null });
+}
+
+export async function stopTerminalRecording(ptyId: string): Promise<string | null> {
+  return invoke('stop_terminal_recording', { ptyId });
+}
+
+export async function getTerminalRecording(ptyId: string): Promise<string | null> {
+  return invoke('get_terminal_recording', { ptyId });
+}
+
+export async function startPlayback(playbackId: string, path: string, speed?: number): Promise<PlaybackInfo> {
+  return invoke('start_playback', { playbackId, path, speed: speed ?? null });
+}
+
+export async function setPlaybackPaused(playbackId: string, paused: boolean): Promise<void> {
+  return invoke('set_playback_paused', { playbackId, paused });
+}
+
+export async function setPlaybackSpeed(playbackId: string, speed: number): Promise<void> {
+  return invoke('set_playback_speed', { playbackId, speed });
+}
+
+export async function stopPlayback(playbackId: string): Promise<void> {
+  return invoke('stop_playback', { playbackId });
 }
 
 export async function terminalBracketedPaste(ptyId: string): Promise<boolean> {
@@ -198,6 +262,14 @@ export async function getSavedScrollbackText(tabId: string, lineCount: number):
   return invoke('get_saved_scrollback_text', { tabId, lineCount });
 }
 
+export async function exportTerminal(ptyId: string, tabId: string | null, format: ExportFormat): Promise<string> {
+  return invoke('export_terminal', { ptyId, tabId, format });
+}
+
+export async function exportSavedScrollback(tabId: string, format: ExportFormat): Promise<string> {
+  return invoke('export_saved_scrollback', { tabId, format });
+}
+
 export async function getSavedTerminalSize(tabId: string): Promise<[number, number] | null> {
   return invoke('get_saved_terminal_size', { tabId });
 }
@@ -307,6 +379,10 @@ export async function setTabNotes(workspaceId: string, paneId: string, tabId: st
   return invoke('set_tab_notes', { workspaceId, paneId, tabId, notes });
 }
 
+export async function setTabAutoRecord(workspaceId: string, paneId: string, tabId: string, enabled: boolean): Promise<void> {
+  return invoke('set_tab_auto_record', { workspaceId, paneId, tabId, enabled });
+}
+
 export async function setTabNotesOpen(workspaceId: string, paneId: string, tabId: string, open: boolean): Promise<void> {
   return invoke('set_tab_notes_open', { workspaceId, paneId, tabId, open });
 }
@@ -380,6 +456,30 @@ export async function setTabTriggerVariables(
   return invoke('set_tab_trigger_variables', { workspaceId, paneId, tabId, vars });
 }
 
+export async function setTabTriggerVariable(tabId: string, name: string, value: string | null): Promise<void> {
+  return invoke('set_tab_trigger_variable', { tabId, name, value });
+}
+
+export async function setTabTriggersSuppressed(tabId: string, suppressed: boolean): Promise<void> {
+  return invoke('set_tab_triggers_suppressed', { tabId, suppressed });
+}
+
+export async function enableTabAutoResume(tabId: string, commandTemplate: string, remoteCwdHint: string | null): Promise<void> {
+  return invoke('enable_tab_auto_resume', { tabId, commandTemplate, remoteCwdHint });
+}
+
+export async function replayTabAutoResume(tabId: string): Promise<void> {
+  return invoke('replay_tab_auto_resume', { tabId });
+}
+
+export async function clearTabTriggers(tabId: string): Promise<void> {
+  return invoke('clear_tab_triggers', { tabId });
+}
+
+export async function getTriggerStats(): Promise<Record<string, unknown>> {
+  return invoke('get_trigger_stats');
+}
+
 export async function getAllWorkspaces(): Promise<[string, string][]> {
   return invoke('get_all_workspaces');
 }
@@ -602,6 +702,49 @@ export async function claudeCodeNotifySelection(payload: unknown): Promise<void>
   return invoke('claude_code_notify_selection', { payload });
 }
 
+export async function claudeCodeEditorChanged(tabId: string): Promise<void> {
+  return invoke('claude_code_editor_changed', { tabId });
+}
+
+/** Recorded hook events for a tab or session, oldest first. `since` is unix ms. */
+export async function getHookTimeline(opts: { tabId?: string; sessionId?: string; since?: number; limit?: number }): Promise<HookEvent[]> {
+  return invoke('get_hook_timeline', {
+    tabId: opts.tabId ?? null,
+    sessionId: opts.sessionId ?? null,
+    since: opts.since ?? null,
+    limit: opts.limit ?? null,
+  });
+}
+
+/** Recorded MCP tool calls, most recent first. */
+export async function getMcpAuditLog(opts: { tabId?: string; since?: number; limit?: number } = {}): Promise<McpAuditEntry[]> {
+  return invoke('get_mcp_audit_log', {
+    tabId: opts.tabId ?? null,
+    since: opts.since ?? null,
+    limit: opts.limit ?? null,
+  });
+}
+
+/** Auth tokens the MCP server accepts: local first, then one per SSH host. */
+export async function listMcpTokens(): Promise<McpTokenInfo[]> {
+  return invoke('list_mcp_tokens');
+}
+
+/** Replace the local token (no host) or a host's token; the old one stops working at once. */
+export async function rotateMcpToken(hostKey: string | null = null): Promise<void> {
+  return invoke('rotate_mcp_token', { hostKey });
+}
+
+/** Load and parse a Claude session transcript (over SCP for SSH sessions). */
+export async function readClaudeTranscript(source: TranscriptSource): Promise<ClaudeTranscript> {
+  return invoke('read_claude_transcript', { source });
+}
+
+/** Claude token usage and cost per workspace, tab and session since `since` (unix ms). */
+export async function getClaudeUsage(since?: number): Promise<UsageReport> {
+  return invoke('get_claude_usage', { since: since ?? null });
+}
+
 export async function createDiffTab(
   workspaceId: string,
   paneId: string,
@@ -612,6 +755,24 @@ export async function createDiffTab(
   return invoke('create_diff_tab', { workspaceId, paneId, name, diffContext, afterTabId: afterTabId ?? null });
 }
 
+export async function createPlaybackTab(
+  workspaceId: string,
+  paneId: string,
+  path: string,
+  afterTabId?: string | null,
+): Promise<Tab> {
+  return invoke('create_playback_tab', { workspaceId, paneId, path, afterTabId: afterTabId ?? null });
+}
+
+export async function createTranscriptTab(
+  workspaceId: string,
+  paneId: string,
+  sourceTabId: string,
+  afterTabId?: string | null,
+): Promise<Tab> {
+  return invoke('create_transcript_tab', { workspaceId, paneId, sourceTabId, afterTabId: afterTabId ?? null });
+}
+
 // Archive tab commands
 export async function archiveTab(
   workspaceId: string,
@@ -727,6 +888,20 @@ export interface SshTunnelInfo {
   tunnel_id: string;
   remote_port: number;
   host_key: string;
+  /** MCP token issued to this host. */
+  auth_token: string;
+  /** False while the tunnel supervisor is restarting the forward. */
+  up: boolean;
+}
+
+/** Payload of `ssh-tunnel-status`, emitted when a tunnel goes down or comes back. */
+export interface SshTunnelStatus {
+  host_key: string;
+  up: boolean;
+  remote_port: number;
+  /** The forward came back on another remote port. */
+  port_changed: boolean;
+  error: string | null;
 }
 
 export interface MaitermSkillScripts {
@@ -746,6 +921,11 @@ export async function getSshTunnel(hostKey: string): Promise<SshTunnelInfo | nul
   return invoke('get_ssh_tunnel', { hostKey });
 }
 
+/** Revoke a host's MCP token and close its tunnel. */
+export async function revokeMcpHostToken(hostKey: string): Promise<void> {
+  return invoke('revoke_mcp_host_token', { hostKey });
+}
+
 export async function getMcpPort(): Promise<number | null> {
   return invoke('get_mcp_port');
 }
@@ -762,6 +942,26 @@ export async function getMaitermSkillScripts(): Promise<MaitermSkillScripts> {
   return invoke('get_maiterm_skill_scripts');
 }
 
+// SSH port forward commands
+
+/** Report a tab's SSH session (cleaned args) or its end (null); starts or stops its forwards. */
+export async function syncPortForwards(tabId: string, sshArgs: string | null): Promise<PortForwardStatus[]> {
+  return invoke('sync_port_forwards', { tabId, sshArgs });
+}
+
+/** Attach a forward to a tab, or to the whole workspace when tabId is null. */
+export async function addPortForward(workspaceId: string, tabId: string | null, forward: PortForward): Promise<PortForward> {
+  return invoke('add_port_forward', { workspaceId, tabId, forward });
+}
+
+export async function removePortForward(workspaceId: string, tabId: string | null, forwardId: string): Promise<void> {
+  return invoke('remove_port_forward', { workspaceId, tabId, forwardId });
+}
+
+export async function listPortForwards(): Promise<PortForwardStatus[]> {
+  return invoke('list_port_forwards');
+}
+
 export async function checkFullDiskAccess(): Promise<boolean> {
   return invoke('check_full_disk_access');
 }
@@ -1,4 +1,7 @@
-export type TabType = 'terminal' | 'editor' | 'diff';
+export type TabType = 'terminal' | 'editor' | 'diff' | 'playback' | 'transcript';
+
+/** Agent CLI a hook-tracked session belongs to. */
+export type AgentKind = 'claude' | 'gemini';
 
 export interface EditorFileInfo {
   file_path: string;
@@ -16,12 +19,14 @@ export interface DiffContext {
   tab_name: string;
 }
 
+/** Agent Bridge group membership, persisted on each member tab. */
 export interface AgentBridge {
-  partner_tab_id: string;
-  partner_label: string;
-  partner_session_id?: string | null;
-  /** "caller" | "fork" */
+  group_id: string;
+  group_name: string;
+  /** This member's role in the group, e.g. "frontend" or "qa". */
   role: string;
+  /** This tab's last-known Claude session id. */
+  session_id?: string | null;
   turn: number;
 }
 
@@ -59,6 +64,24 @@ export interface Tab {
   diff_context: DiffContext | null;
   import_highlight?: boolean;
   agent_bridge?: AgentBridge | null;
+  /** OSC 52 permission for this tab; a host policy wins, absent = inherit workspace/global. */
+  osc52_policy?: ClipboardPolicy | null;
+  /** Record to an asciicast file whenever the tab's PTY spawns. */
+  auto_record?: boolean;
+  /** Recording shown by a playback tab. */
+  playback_file?: string | null;
+  /** Claude session shown by a transcript tab. */
+  transcript?: TranscriptSource | null;
+  /** SSH port forwards started whenever the tab is in an SSH session. */
+  port_forwards?: PortForward[];
+}
+
+/** Where a transcript tab finds its Claude session JSONL. */
+export interface TranscriptSource {
+  session_id: string;
+  cwd?: string | null;
+  /** Set for sessions that ran over SSH; fetched by SCP. */
+  ssh_command?: string | null;
 }
 
 export interface Pane {
@@ -103,10 +126,44 @@ export interface Workspace {
   archived_tabs: Tab[];
   import_highlight?: boolean;
   suspended?: boolean;
+  /** SSH port forwards started for every SSH session in the workspace. */
+  port_forwards?: PortForward[];
+  /** OSC 52 permission for the workspace's tabs; absent = inherit global policy. */
+  osc52_policy?: ClipboardPolicy | null;
 }
 
 export type CursorStyle = 'block' | 'underline' | 'bar';
 
+/** ssh -L (local), -R (remote) or -D (dynamic SOCKS) forward. */
+export type PortForwardKind = 'local' | 'remote' | 'dynamic';
+
+export interface PortForward {
+  id: string;
+  kind: PortForwardKind;
+  /** Local port for local/dynamic, remote port for remote. */
+  listen_port: number;
+  /** `host:port` connections go to; absent for dynamic. */
+  target?: string;
+  /** Only start on SSH sessions to this host; absent = any. */
+  host?: string | null;
+  label?: string | null;
+}
+
+export type PortForwardState = 'starting' | 'up' | 'failed';
+
+/** A forward running (or failed) on one SSH destination. */
+export interface PortForwardStatus {
+  forward: PortForward;
+  host_key: string;
+  state: PortForwardState;
+  error: string | null;
+  uptime_secs: number | null;
+  tab_ids: string[];
+}
+
+/** Permission for programs in a terminal to use the clipboard via OSC 52. */
+export type ClipboardPolicy = 'always' | 'ask' | 'never';
+
 export type TriggerActionType = 'notify' | 'send_command' | 'set_tab_state' | 'enable_auto_resume' | 'replay_auto_resume';
 
 export type MatchMode = 'regex' | 'plain_text' | 'variable';
@@ -144,6 +201,15 @@ export interface Trigger {
   user_modified?: boolean;
 }
 
+/** A tab's auto-resume context after a trigger set it in Rust (`tab-auto-resume-changed`). */
+export interface AutoResumeContext {
+  tab_id: string;
+  cwd: string | null;
+  ssh_command: string | null;
+  remote_cwd: string | null;
+  command: string | null;
+}
+
 export interface Preferences {
   ui_font_size: number;
   font_size: number;
@@ -205,6 +271,60 @@ export interface Preferences {
   auto_check_updates: boolean;
   quick_open_show_hidden: boolean;
   quick_open_show_ignored: boolean;
+  osc52_write_policy: ClipboardPolicy;
+  osc52_read_policy: ClipboardPolicy;
+  /** Per-SSH-host overrides (both directions), keyed by lowercase host. */
+  osc52_host_policies?: Record<string, ClipboardPolicy>;
+  osc52_max_bytes: number;
+  prompt_templates?: PromptTemplate[];
+  hook_policy_rules?: HookPolicyRule[];
+  hook_prompt_timeout?: number;
+  claude_model_prices?: ModelPrice[];
+  /** Scope of MCP clients reached through an SSH tunnel. */
+  mcp_remote_scope?: McpScope;
+  /** Per-SSH-host overrides of `mcp_remote_scope`, keyed by lowercase host. */
+  mcp_host_scopes?: Record<string, McpScope>;
+  /** Scope of MCP clients running in a workspace, keyed by workspace ID. */
+  mcp_workspace_scopes?: Record<string, McpScope>;
+  /** Replace MCP auth tokens older than this many hours (0 = never). */
+  mcp_token_rotation_hours?: number;
+  mcp_local_token_rotation_hours?: number;
+}
+
+/** USD per million tokens for Claude models whose id contains `model`. */
+export interface ModelPrice {
+  model: string;
+  input: number;
+  output: number;
+  cache_write: number;
+  cache_read: number;
+}
+
+/** An MCP prompt template, served to Claude sessions as a slash command. */
+export interface PromptTemplate {
+  id: string;
+  name: string;
+  description?: string;
+  template: string;
+}
+
+/** What an MCP client may do; host and workspace scopes narrow each other. */
+export type McpScope = 'read_only' | 'own_tab' | 'workspace' | 'full';
+
+export type HookDecision = 'allow' | 'deny' | 'ask' | 'prompt';
+
+/** A policy rule answering Claude's PreToolUse / PermissionRequest hooks. */
+export interface HookPolicyRule {
+  id: string;
+  /** Tool name with `*` wildcards; empty matches any tool. */
+  tool: string;
+  /** Regex over the Bash command, file path, or JSON input. */
+  pattern?: string;
+  decision: HookDecision;
+  /** Workspace IDs the rule is limited to; empty applies everywhere. */
+  workspaces?: string[];
+  reason?: string;
+  enabled: boolean;
 }
 
 export interface WindowData {
@@ -242,6 +362,42 @@ export interface TerminalFrame {
   total_lines: number;
   alternate_screen: boolean;
   has_selection: boolean;
+  /** False when `ansi` only repaints changed rows (output frames). */
+  full: boolean;
+}
+
+/** Per-read PTY summary (`pty-activity-{ptyId}`) — raw bytes stay in Rust. */
+export interface PtyActivity {
+  bytes: number;
+  redraw: boolean;
+  ssh_drop: boolean;
+}
+
+/** OSC 52 request awaiting the user's answer (policy "ask"). */
+export interface ClipboardRequest {
+  request_id: string;
+  kind: 'read' | 'write';
+  /** SSH destination host when the foreground process is ssh. */
+  host: string | null;
+  /** Payload size for writes; 0 for reads. */
+  bytes: number;
+}
+
+/** One command from OSC 133 shell integration marks. Lines are absolute
+ *  (stable as scrollback rotates); use `prompt_offset` to scroll to it. */
+export interface CommandBlock {
+  id: number;
+  prompt_line: number;
+  command: string | null;
+  output_start: number | null;
+  /** Null while the command is running. */
+  output_end: number | null;
+  started_at: number | null;
+  finished_at: number | null;
+  exit_code: number | null;
+  cwd: string | null;
+  /** `scrollTerminalTo` offset that brings the prompt to the top; null once scrolled out. */
+  prompt_offset: number | null;
 }
 
 export interface ScrollInfo {
@@ -260,7 +416,157 @@ export interface SearchMatch {
 
 export interface SearchResult {
   matches: SearchMatch[];
+  /** Matches in the whole scope, including those outside this page. */
   total_count: number;
+  /** Set when `max_results` was hit — pass back as `cursor` for the next page. */
+  next_cursor: SearchCursor | null;
+}
+
+export interface SearchCursor {
+  line: number;
+  col: number;
+}
+
+/** Grid lines: 0 is the top of the live screen, negative lines are scrollback. */
+export type SearchScope =
+  | { type: 'all' }
+  | { type: 'screen' }
+  | { type: 'scrollback' }
+  | { type: 'lines'; start: number; end: number };
+
+export interface SearchOptions {
+  regex?: boolean;
+  whole_word?: boolean;
+  scope?: SearchScope;
+  /** Default 1000. */
+  max_results?: number;
+  cursor?: SearchCursor | null;
+}
+
+/** A recorded Claude Code hook event (`getHookTimeline`). */
+export interface HookEvent {
+  id: number;
+  session_id: string;
+  tab_id: string | null;
+  /** Hook name: SessionStart, PreToolUse, Notification, ... */
+  event: string;
+  tool_name: string | null;
+  tool_use_id?: string;
+  /** Event-specific fields: tool_input, prompt, notification text, ... */
+  detail: Record<string, unknown> | null;
+  /** Hook policy answer for PreToolUse / PermissionRequest. */
+  decision: string | null;
+  /** Unix time in milliseconds. */
+  ts: number;
+  /** ISO 8601 (UTC). */
+  time: string;
+  /** Set on both halves of a completed PreToolUse → PostToolUse pair. */
+  duration_ms?: number;
+}
+
+/** A recorded MCP tool call (`getMcpAuditLog`). */
+export interface McpAuditEntry {
+  id: number;
+  /** Unix time in milliseconds. */
+  ts: number;
+  /** ISO 8601 (UTC). */
+  time: string;
+  connection_id: string;
+  tab_id: string | null;
+  /** SSH host the call came through, if remote. */
+  host: string | null;
+  tool: string;
+  /** Tab, pane or workspace the call acted on. */
+  target: string | null;
+  /** Fingerprint of the arguments (not cryptographic). */
+  args_digest: string;
+  scope: string | null;
+  status: 'ok' | 'error' | 'denied';
+  error: string | null;
+}
+
+/** An auth token the MCP server accepts (the token itself is never sent). */
+export interface McpTokenInfo {
+  /** SSH host key the token was issued to; null for the local token. */
+  host_key: string | null;
+  /** First characters of the token, to tell rotations apart. */
+  prefix: string;
+  issued_ms: number;
+  last_used_ms: number | null;
+  /** Rotated-out tokens still accepted during their grace period. */
+  retired: number;
+}
+
+/** Token counts read from Claude session transcripts, with estimated cost. */
+export interface TokenUsage {
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationTokens: number;
+  cacheReadTokens: number;
+  /** USD under the `claude_model_prices` preference. */
+  costUsd: number;
+}
+
+export interface TranscriptToolResult {
+  text: string;
+  isError: boolean;
+}
+
+/** One item of a parsed Claude transcript; `sidechain` marks subagent messages. */
+export type TranscriptEntry =
+  | { kind: 'user'; timestamp: string | null; text: string; sidechain: boolean }
+  | { kind: 'assistant'; timestamp: string | null; model: string | null; text: string; sidechain: boolean }
+  | { kind: 'thinking'; timestamp: string | null; text: string; sidechain: boolean }
+  | { kind: 'toolCall'; timestamp: string | null; id: string; name: string; input: unknown; result: TranscriptToolResult | null; sidechain: boolean }
+  | { kind: 'summary'; timestamp: string | null; text: string };
+
+export interface ClaudeTranscript {
+  sessionId: string;
+  /** Local path, or `user@host:path` for a remote session. */
+  location: string;
+  entries: TranscriptEntry[];
+}
+
+/** From `getClaudeUsage`: totals per workspace, tab and session, most expensive first. */
+export interface UsageReport {
+  since: number | null;
+  total: TokenUsage;
+  workspaces: { workspaceId: string; workspaceName: string; usage: TokenUsage }[];
+  /** `tabName` is null once the tab has been closed. */
+  tabs: { tabId: string; tabName: string | null; workspaceId: string | null; usage: TokenUsage }[];
+  sessions: { sessionId: string; tabId: string | null; usage: TokenUsage }[];
+}
+
+/** A hit from `searchAllScrollback` (saved scrollback of every tab). */
+export interface ScrollbackSearchResult {
+  window_id: string;
+  workspace_id: string;
+  workspace_name: string;
+  tab_id: string;
+  tab_name: string;
+  archived: boolean;
+  /** 0-based line within the tab's saved scrollback. */
+  line: number;
+  snippet: string;
+}
+
+/** Full viewport frame from a recording playback (`playback-frame-{id}`). */
+export interface PlaybackFrame extends TerminalFrame {
+  cols: number;
+  rows: number;
+  /** Playback position in seconds. */
+  time: number;
+  finished: boolean;
+}
+
+export type ExportFormat = 'html' | 'text' | 'markdown';
+
+export interface PlaybackInfo {
+  width: number;
+  height: number;
+  /** Seconds, with the recording's idle limit applied. */
+  duration: number;
+  title: string | null;
 }
 
 // OSC events from Rust
@@ -1,8 +1,14 @@
-import type { Trigger, MatchMode } from '$lib/tauri/types';
+import type { AgentKind, Trigger, MatchMode } from '$lib/tauri/types';
 
 /** Shared Claude resume command — used by auto-resume presets and hooks-based auto-resume. */
 export const CLAUDE_RESUME_COMMAND = 'claude --resume %claudeSessionId';
 
+/** Hooks-based auto-resume command per agent CLI. */
+export const AGENT_RESUME_COMMANDS: Record<AgentKind, string> = {
+  claude: CLAUDE_RESUME_COMMAND,
+  gemini: 'gemini --resume %agentSessionId',
+};
+
 /** App-provided default trigger templates. Keyed by stable default_id. */
 export const DEFAULT_TRIGGERS: Record<string, Omit<Trigger, 'id' | 'enabled' | 'workspaces' | 'tabs' | 'default_id'> & { match_mode?: MatchMode }> = {
 };
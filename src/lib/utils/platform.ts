@@ -13,6 +13,14 @@ export function isMac(): boolean {
   return _isMac;
 }
 
+const _isWindows =
+  typeof navigator !== 'undefined' && /Win/.test(navigator.platform);
+
+/** True when running on Windows. */
+export function isWindows(): boolean {
+  return _isWindows;
+}
+
 /** Check the platform action-modifier on a keyboard event (Cmd on mac, Ctrl elsewhere). */
 export function isModKey(e: KeyboardEvent): boolean {
   return _isMac ? e.metaKey : e.ctrlKey;
@@ -0,0 +1,42 @@
+/**
+ * Quote arguments for commands typed into a terminal's shell.
+ */
+
+import { isWindows } from '$lib/utils/platform';
+
+/** Quoting rules: POSIX (bash, zsh, fish, Git Bash, WSL), PowerShell or cmd.exe. */
+export type ShellKind = 'posix' | 'powershell' | 'cmd';
+
+/** The shell local terminals run, from the Windows shell preference. */
+export function localShellKind(windowsShell: string): ShellKind {
+  if (!isWindows()) return 'posix';
+  switch (windowsShell) {
+    case 'powershell':
+    case 'pwsh':
+      return 'powershell';
+    case 'cmd':
+      return 'cmd';
+    default:
+      return 'posix';
+  }
+}
+
+/** Quote `arg` so the shell passes it to the program as one argument. */
+export function quoteShellArg(arg: string, shell: ShellKind): string {
+  switch (shell) {
+    case 'posix':
+      return `'${arg.replace(/'/g, "'\\''")}'`;
+    case 'powershell':
+      // Any single quote, typographic ones included, is escaped by doubling it
+      return `'${arg.replace(/['\u2018\u2019\u201A\u201B]/g, '$&$&')}'`;
+    case 'cmd':
+      // cmd can't continue a line inside quotes; `""` is a literal quote to
+      // the program's argument parser and keeps cmd inside the quoted string.
+      return `"${arg.replace(/\r?\n/g, ' ').replace(/"/g, '""')}"`;
+  }
+}
+
+/** `command` followed by `args`, each quoted for `shell`. */
+export function commandLine(command: string, args: string[] | undefined, shell: ShellKind): string {
+  return [command, ...(args ?? []).map(arg => quoteShellArg(arg, shell))].join(' ');
+}